@@ -0,0 +1,223 @@
+//! Plain-text summaries of the live and history tables for pasting into chat, and the system clipboard.
+
+use std::cmp::Ordering;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+
+use crate::model::{CombatantRow, EncounterSummary, ViewMode};
+use crate::ui::format::format_compact;
+
+/// Kept open for the whole run: on X11 and Wayland the copied text is served by whoever owns
+/// the clipboard, so it would vanish with a dropped handle unless a clipboard manager took it.
+static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+/// Puts `text` on the system clipboard. Blocking; call from a blocking task.
+pub fn copy_text(text: &str) -> Result<()> {
+    let mut guard = CLIPBOARD
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let clipboard = match guard.as_mut() {
+        Some(clipboard) => clipboard,
+        None => guard.insert(arboard::Clipboard::new().context("clipboard unavailable")?),
+    };
+    let result = clipboard
+        .set_text(text)
+        .context("could not write to the clipboard");
+    if result.is_err() {
+        // Start over with a fresh handle next time (e.g. after the display went away).
+        *guard = None;
+    }
+    result
+}
+
+/// The table as a one-liner for chat, in the order the rows are sorted:
+/// "Titan (02:15): Alice (NIN) 12,345 dps — Bob (WAR) 9,876 dps".
+pub fn summary_text(
+    encounter: Option<&EncounterSummary>,
+    rows: &[CombatantRow],
+    mode: ViewMode,
+) -> String {
+    let (unit, rate): (&str, fn(&CombatantRow) -> f64) = match mode {
+        ViewMode::Dps => ("dps", |row| row.encdps),
+        ViewMode::Heal => ("hps", |row| row.enchps),
+    };
+    let ranking = rows
+        .iter()
+        .map(|row| {
+            let job = row.job.trim().to_uppercase();
+            let name = if job.is_empty() {
+                row.name.trim().to_string()
+            } else {
+                format!("{} ({job})", row.name.trim())
+            };
+            format!("{name} {} {unit}", format_grouped(rate(row)))
+        })
+        .collect::<Vec<_>>()
+        .join(" — ");
+    let title = encounter.and_then(|encounter| {
+        let title = if encounter.title.trim().is_empty() {
+            encounter.zone.trim()
+        } else {
+            encounter.title.trim()
+        };
+        let duration = encounter.duration.trim();
+        match (title.is_empty(), duration.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(title.to_string()),
+            (true, false) => Some(duration.to_string()),
+            (false, false) => Some(format!("{title} ({duration})")),
+        }
+    });
+    match title {
+        Some(title) => format!("{title}: {ranking}"),
+        None => ranking,
+    }
+}
+
+/// One combatant as "Alice(NIN): 12.3k dps, 15% crit", or with HPS and overheal in heal mode.
+pub fn combatant_line(row: &CombatantRow, mode: ViewMode) -> String {
+    let name = format!("{}({})", row.name.trim(), row.job.trim().to_uppercase());
+    match mode {
+        ViewMode::Dps => format!(
+            "{name}: {} dps, {} crit",
+            format_compact(row.encdps),
+            percent(&row.crit)
+        ),
+        ViewMode::Heal => format!(
+            "{name}: {} hps, {} overheal",
+            format_compact(row.enchps),
+            percent(&row.overheal_pct)
+        ),
+    }
+}
+
+/// The line for `self_name`'s row; without a match, the line of the combatant with the
+/// highest rate in `mode` with `false`, whatever order the table is sorted in.
+pub fn self_line(
+    rows: &[CombatantRow],
+    self_name: Option<&str>,
+    mode: ViewMode,
+) -> Option<(String, bool)> {
+    let own = self_name.and_then(|name| rows.iter().find(|row| row.name == name));
+    if let Some(row) = own {
+        return Some((combatant_line(row, mode), true));
+    }
+    let rate = |row: &CombatantRow| match mode {
+        ViewMode::Dps => row.encdps,
+        ViewMode::Heal => row.enchps,
+    };
+    rows.iter()
+        .max_by(|a, b| rate(a).partial_cmp(&rate(b)).unwrap_or(Ordering::Equal))
+        .map(|row| (combatant_line(row, mode), false))
+}
+
+/// Whole units with thousands separators: "950", "12,345", "1,500,000".
+fn format_grouped(value: f64) -> String {
+    let digits = format!("{:.0}", value.max(0.0));
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (idx, ch) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+    grouped
+}
+
+/// ACT sends rates as "15%" but sometimes without the sign; blanks read "0%".
+fn percent(value: &str) -> String {
+    let value = value.trim().trim_end_matches('%');
+    if value.is_empty() {
+        "0%".to_string()
+    } else {
+        format!("{value}%")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SortKey;
+
+    fn row(name: &str, job: &str, encdps: f64, crit: &str) -> CombatantRow {
+        CombatantRow {
+            name: name.into(),
+            job: job.into(),
+            encdps,
+            crit: crit.into(),
+            enchps: 850.0,
+            overheal_pct: "31".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lines_use_compact_rates() {
+        let alice = row("Alice", "nin", 12_345.0, "15%");
+        assert_eq!(
+            combatant_line(&alice, ViewMode::Dps),
+            "Alice(NIN): 12.3k dps, 15% crit"
+        );
+        assert_eq!(
+            combatant_line(&alice, ViewMode::Heal),
+            "Alice(NIN): 850 hps, 31% overheal"
+        );
+
+        let encounter = EncounterSummary {
+            title: "Titan".into(),
+            duration: "02:15".into(),
+            ..Default::default()
+        };
+        let rows = [alice, row("Bob", "WAR", 1_500_000.0, "")];
+        assert_eq!(
+            summary_text(Some(&encounter), &rows, ViewMode::Dps),
+            "Titan (02:15): Alice (NIN) 12,345 dps — Bob (WAR) 1,500,000 dps"
+        );
+        assert_eq!(
+            summary_text(None, &rows, ViewMode::Heal),
+            "Alice (NIN) 850 hps — Bob (WAR) 850 hps"
+        );
+    }
+
+    #[test]
+    fn grouping_handles_short_and_rounded_values() {
+        assert_eq!(format_grouped(0.0), "0");
+        assert_eq!(format_grouped(999.6), "1,000");
+        assert_eq!(format_grouped(123_456.0), "123,456");
+    }
+
+    #[test]
+    fn self_line_falls_back_to_the_top_row() {
+        let rows = [
+            row("Alice", "NIN", 2_000.0, "10%"),
+            row("Bob", "WAR", 1_000.0, "5%"),
+        ];
+        let (line, found) = self_line(&rows, Some("Bob"), ViewMode::Dps).expect("line");
+        assert!(found);
+        assert!(line.starts_with("Bob(WAR)"));
+
+        let (line, found) = self_line(&rows, None, ViewMode::Dps).expect("line");
+        assert!(!found);
+        assert!(line.starts_with("Alice(NIN)"));
+        assert!(self_line(&[], Some("Bob"), ViewMode::Dps).is_none());
+    }
+
+    #[test]
+    fn self_line_fallback_ignores_an_ascending_sort() {
+        let mut rows = vec![
+            row("Alice", "NIN", 2_000.0, "10%"),
+            row("Bob", "WAR", 1_000.0, "5%"),
+            row("Cleo", "BRD", 3_000.0, "8%"),
+        ];
+        rows[1].enchps = 1_200.0;
+        SortKey::Rate.sort_rows(ViewMode::Dps, true, &mut rows);
+        assert_eq!(rows[0].name, "Bob");
+
+        let (line, found) = self_line(&rows, Some("Dana"), ViewMode::Dps).expect("line");
+        assert!(!found);
+        assert!(line.starts_with("Cleo(BRD)"));
+        let (line, _) = self_line(&rows, None, ViewMode::Heal).expect("line");
+        assert!(line.starts_with("Bob(WAR)"));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::io::ErrorKind;
@@ -6,9 +7,60 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::history::util::{NeverActivePolicy, ZeroDurationPolicy};
+
 const CONFIG_DIR_ENV: &str = "NEKOMATA_CONFIG_DIR";
 const CONFIG_DIR_NAME: &str = "nekomata";
 const CONFIG_FILE_NAME: &str = "nekomata.config";
+/// Hand-editable alternative to the JSON file; read in preference to it when present.
+const TOML_CONFIG_FILE_NAME: &str = "nekomata.toml";
+
+/// Keys for one action in `key_bindings`: a single key or a list of them.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    pub fn as_slice(&self) -> &[String] {
+        match self {
+            KeyList::One(key) => std::slice::from_ref(key),
+            KeyList::Many(keys) => keys,
+        }
+    }
+}
+
+/// On-disk format of a config file, picked from its extension.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ConfigFormat {
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Json,
+        }
+    }
+
+    fn parse(self, bytes: &[u8]) -> Result<AppConfig> {
+        match self {
+            ConfigFormat::Json => Ok(serde_json::from_slice(bytes)?),
+            ConfigFormat::Toml => Ok(toml::from_str(std::str::from_utf8(bytes)?)?),
+        }
+    }
+
+    fn serialize(self, cfg: &AppConfig) -> Result<Vec<u8>> {
+        match self {
+            ConfigFormat::Json => Ok(serde_json::to_vec_pretty(cfg)?),
+            ConfigFormat::Toml => Ok(toml::to_string_pretty(cfg)?.into_bytes()),
+        }
+    }
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -18,8 +70,130 @@ pub struct AppConfig {
     pub default_decoration: String,
     #[serde(default = "default_mode")]
     pub default_mode: String,
+    /// Start with DPS and HPS columns side by side (`b` toggles); `default_mode` picks the sort.
+    #[serde(default)]
+    pub show_both_rates: bool,
     #[serde(default = "default_dungeon_mode_enabled")]
     pub dungeon_mode_enabled: bool,
+    /// Hide combatants with 0 damage (DPS mode) or 0 healing (heal mode) from the tables.
+    #[serde(default)]
+    pub hide_zero_rows: bool,
+    /// Tab the history panel opens on the first time it is shown in a session.
+    #[serde(default = "default_history_view")]
+    pub default_history_view: String,
+    /// Order of the history Dates lists: "newest" (default) or "oldest".
+    #[serde(default = "default_date_sort")]
+    pub date_sort: String,
+    /// Clock for times shown in history and the error log: "24h" (default) or "12h" (AM/PM).
+    #[serde(default = "default_clock_format")]
+    pub clock_format: String,
+    /// Blank the live combatant table once idle, leaving only the idle overlay.
+    #[serde(default)]
+    pub auto_clear_idle: bool,
+    /// Re-sort live rows at most this often during a pull, in milliseconds (0 = every update).
+    #[serde(default)]
+    pub stable_sort_ms: u64,
+    /// Window of the live "Rolling" DPS column in seconds (0 hides the column).
+    #[serde(default = "default_rolling_dps_secs")]
+    pub rolling_dps_secs: u64,
+    /// Milliseconds between screen redraws (clamped to 16–1000).
+    #[serde(default = "default_redraw_ms")]
+    pub redraw_ms: u64,
+    #[serde(default = "default_escape_behavior")]
+    pub escape_behavior: String,
+    /// Party DPS targets keyed by zone name; the live header colors ENCDPS against them.
+    #[serde(default)]
+    pub dps_thresholds: HashMap<String, f64>,
+    /// Enrage timers in seconds keyed by zone name; the live header counts down towards them.
+    #[serde(default)]
+    pub enrage_secs: HashMap<String, u64>,
+    /// Buff/stance names whose uptime the history detail view reports (case-insensitive).
+    #[serde(default = "default_uptime_buffs")]
+    pub uptime_buffs: Vec<String>,
+    /// Drop leading/trailing frames without damage or healing progress before saving.
+    #[serde(default)]
+    pub trim_idle_frames: bool,
+    /// What to do with `00:00` encounters: "keep" (rates shown as "—"), "discard" or "clamp".
+    #[serde(default = "default_zero_duration_policy")]
+    pub zero_duration_policy: String,
+    /// Encounters that never reported active: "mark" (saved and flagged) or "drop".
+    #[serde(default = "default_never_active_policy")]
+    pub never_active_policy: String,
+    /// Seconds an encounter may stay inactive before it is saved; going active again within
+    /// them continues the same record (0 saves immediately).
+    #[serde(default)]
+    pub encounter_end_grace_secs: u64,
+    /// Encounters shorter than this many seconds are not saved (0 saves everything).
+    #[serde(default)]
+    pub min_encounter_secs: u64,
+    /// Keep fractional seconds ("01:23.4") when computing DPS from durations.
+    #[serde(default)]
+    pub subsecond_durations: bool,
+    /// Delete history older than this many days at startup (0 keeps everything).
+    #[serde(default)]
+    pub max_history_days: u64,
+    /// Start a new dungeon run when pulls in the same dungeon are further apart (0 disables).
+    #[serde(default)]
+    pub dungeon_split_gap_secs: u64,
+    /// Start a new dungeon run after leaving and re-entering the same dungeon.
+    #[serde(default = "default_dungeon_split_on_reentry")]
+    pub dungeon_split_on_reentry: bool,
+    /// Download the dungeon catalog from this URL at startup (cached in the config directory).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dungeon_catalog_url: Option<String>,
+    /// Catalog zones that are never aggregated into dungeon runs (matched ignoring case).
+    #[serde(default)]
+    pub dungeon_exclude_zones: Vec<String>,
+    /// Zones aggregated into dungeon runs in addition to the catalog's (matched like entries).
+    #[serde(default)]
+    pub extra_dungeon_zones: Vec<String>,
+    /// Match zones up to two typos away from a catalog entry when no exact match exists.
+    #[serde(default)]
+    pub fuzzy_zone_match: bool,
+    /// Raw JSON array of messages sent after every (re)connect, replacing the built-in
+    /// handshake `[{"call":"getLanguage"},{"call":"subscribe","events":["CombatData","LogLine"]}]`.
+    /// Invalid values are logged and the default is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscribe_messages: Option<serde_json::Value>,
+    /// IINACT websocket endpoint. When unset, the last URL that connected is used, then the
+    /// built-in default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ws_url: Option<String>,
+    /// Skip certificate checks on `wss://` URLs, for TLS proxies with self-signed certificates.
+    #[serde(default)]
+    pub ws_accept_invalid_certs: bool,
+    /// Seconds to wait before the first IINACT connection attempt.
+    #[serde(default)]
+    pub startup_delay_secs: u64,
+    /// Periodically save the live view so it can be restored after a crash.
+    #[serde(default)]
+    pub persist_snapshot: bool,
+    /// File format of day exports: "json" (default, full records) or "markdown" (results tables).
+    #[serde(default = "default_export_format")]
+    pub export_format: String,
+    /// Remember the history encounter filter between sessions.
+    #[serde(default)]
+    pub persist_history_filter: bool,
+    /// Your character names. The first one present in a fight is treated as "you" (shown
+    /// bold); ACT's "YOU" row is used when none match.
+    #[serde(default)]
+    pub self_names: Vec<String>,
+    /// Combatant table columns in display order; omitted columns are hidden. Empty (and left
+    /// out of the saved file) means the default layout, so columns added later still show up.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub column_order: Vec<String>,
+    /// Built-in color palette: "default", "light", "high-contrast" or "deuteranopia".
+    #[serde(default = "default_palette")]
+    pub palette: String,
+    /// Hex colors by palette element or job code, applied on top of the selected palette.
+    #[serde(default)]
+    pub theme: HashMap<String, String>,
+    /// Live-view keys by action name (see `keys::KeyAction`); unlisted actions keep their keys.
+    #[serde(default)]
+    pub key_bindings: HashMap<String, KeyList>,
+    /// Append a row per saved encounter to a per-day `session-<date>.csv` under `exports/`.
+    #[serde(default)]
+    pub session_summary_csv: bool,
 }
 
 impl Default for AppConfig {
@@ -28,7 +202,46 @@ impl Default for AppConfig {
             idle_seconds: default_idle_seconds(),
             default_decoration: default_decoration(),
             default_mode: default_mode(),
+            default_history_view: default_history_view(),
+            date_sort: default_date_sort(),
+            clock_format: default_clock_format(),
+            show_both_rates: false,
             dungeon_mode_enabled: default_dungeon_mode_enabled(),
+            hide_zero_rows: false,
+            auto_clear_idle: false,
+            stable_sort_ms: 0,
+            rolling_dps_secs: default_rolling_dps_secs(),
+            redraw_ms: default_redraw_ms(),
+            escape_behavior: default_escape_behavior(),
+            dps_thresholds: HashMap::new(),
+            enrage_secs: HashMap::new(),
+            uptime_buffs: default_uptime_buffs(),
+            trim_idle_frames: false,
+            zero_duration_policy: default_zero_duration_policy(),
+            never_active_policy: default_never_active_policy(),
+            encounter_end_grace_secs: 0,
+            min_encounter_secs: 0,
+            subsecond_durations: false,
+            max_history_days: 0,
+            dungeon_split_gap_secs: 0,
+            dungeon_split_on_reentry: default_dungeon_split_on_reentry(),
+            dungeon_exclude_zones: Vec::new(),
+            extra_dungeon_zones: Vec::new(),
+            fuzzy_zone_match: false,
+            dungeon_catalog_url: None,
+            subscribe_messages: None,
+            ws_url: None,
+            ws_accept_invalid_certs: false,
+            startup_delay_secs: 0,
+            persist_snapshot: false,
+            export_format: default_export_format(),
+            persist_history_filter: false,
+            self_names: Vec::new(),
+            column_order: Vec::new(),
+            palette: default_palette(),
+            theme: HashMap::new(),
+            key_bindings: HashMap::new(),
+            session_summary_csv: false,
         }
     }
 }
@@ -45,18 +258,70 @@ fn default_mode() -> String {
     "dps".to_string()
 }
 
+fn default_history_view() -> String {
+    "encounters".to_string()
+}
+
+fn default_date_sort() -> String {
+    "newest".to_string()
+}
+
+fn default_clock_format() -> String {
+    "24h".to_string()
+}
+
+fn default_export_format() -> String {
+    "json".to_string()
+}
+
 fn default_dungeon_mode_enabled() -> bool {
     true
 }
 
+fn default_dungeon_split_on_reentry() -> bool {
+    true
+}
+
+fn default_uptime_buffs() -> Vec<String> {
+    ["Iron Will", "Defiance", "Grit", "Royal Guard"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn default_zero_duration_policy() -> String {
+    ZeroDurationPolicy::default().config_key().to_string()
+}
+
+fn default_never_active_policy() -> String {
+    NeverActivePolicy::default().config_key().to_string()
+}
+
+fn default_rolling_dps_secs() -> u64 {
+    15
+}
+
+fn default_redraw_ms() -> u64 {
+    100
+}
+
+fn default_palette() -> String {
+    "default".to_string()
+}
+
+fn default_escape_behavior() -> String {
+    "back".to_string()
+}
+
 pub fn load() -> Result<AppConfig> {
-    let path = config_path();
-    match fs::read(&path) {
-        Ok(bytes) => {
-            let cfg: AppConfig = serde_json::from_slice(&bytes)
-                .with_context(|| format!("Failed to parse config at {}", path.display()))?;
-            Ok(cfg)
-        }
+    load_from(&config_path())
+}
+
+fn load_from(path: &Path) -> Result<AppConfig> {
+    match fs::read(path) {
+        Ok(bytes) => ConfigFormat::from_path(path)
+            .parse(&bytes)
+            .with_context(|| format!("Failed to parse config at {}", path.display())),
         Err(err) if err.kind() == ErrorKind::NotFound => Ok(AppConfig::default()),
         Err(err) => {
             Err(err).with_context(|| format!("Failed to read config at {}", path.display()))
@@ -64,20 +329,47 @@ pub fn load() -> Result<AppConfig> {
     }
 }
 
+/// Saves to the config file in use, keeping its format.
 pub fn save(cfg: &AppConfig) -> Result<()> {
-    let path = config_path();
+    save_to(cfg, &config_path())
+}
+
+/// Writes `cfg` to `nekomata.toml`, which is read instead of the JSON file from then on.
+/// Returns the path written.
+pub fn save_as_toml(cfg: &AppConfig) -> Result<PathBuf> {
+    let path = config_dir().join(TOML_CONFIG_FILE_NAME);
+    save_to(cfg, &path)?;
+    Ok(path)
+}
+
+fn save_to(cfg: &AppConfig, path: &Path) -> Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("Unable to create config directory {}", parent.display()))?;
     }
-    let data = serde_json::to_vec_pretty(cfg)?;
-    fs::write(&path, data)
+    let data = ConfigFormat::from_path(path)
+        .serialize(cfg)
+        .with_context(|| format!("Failed to serialize config for {}", path.display()))?;
+    fs::write(path, data)
         .with_context(|| format!("Failed to write config to {}", path.display()))?;
     Ok(())
 }
 
+/// Every setting with its default value as pretty JSON, in the layout of the config file.
+/// Optional settings that are unset by default (such as `ws_url`) are left out, as on save.
+pub fn defaults_json() -> Result<String> {
+    serde_json::to_string_pretty(&AppConfig::default()).context("Failed to serialize defaults")
+}
+
+/// The config file in use: `nekomata.toml` when it exists, otherwise the JSON `nekomata.config`.
 pub fn config_path() -> PathBuf {
-    config_dir().join(CONFIG_FILE_NAME)
+    let dir = config_dir();
+    let toml = dir.join(TOML_CONFIG_FILE_NAME);
+    if toml.is_file() {
+        toml
+    } else {
+        dir.join(CONFIG_FILE_NAME)
+    }
 }
 
 pub fn config_dir() -> PathBuf {
@@ -101,3 +393,41 @@ pub fn history_dir() -> PathBuf {
 pub fn history_db_path() -> PathBuf {
     history_dir().join("encounters.sled")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_follows_the_extension() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("a/nekomata.toml")),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("a/nekomata.config")),
+            ConfigFormat::Json
+        );
+    }
+
+    #[test]
+    fn toml_round_trips_and_fills_defaults() {
+        let mut cfg = AppConfig {
+            idle_seconds: 42,
+            ..Default::default()
+        };
+        cfg.dps_thresholds
+            .insert("The Omega Protocol".into(), 120_000.0);
+        cfg.self_names = vec!["Main Name".into()];
+        let text = ConfigFormat::Toml.serialize(&cfg).expect("serialize");
+        let parsed = ConfigFormat::Toml.parse(&text).expect("parse");
+        assert_eq!(parsed.idle_seconds, 42);
+        assert_eq!(parsed.dps_thresholds, cfg.dps_thresholds);
+        assert_eq!(parsed.self_names, cfg.self_names);
+
+        let sparse = b"# hand-written\nidle_seconds = 7\n";
+        let parsed = ConfigFormat::Toml.parse(sparse).expect("parse sparse");
+        assert_eq!(parsed.idle_seconds, 7);
+        assert_eq!(parsed.redraw_ms, AppConfig::default().redraw_ms);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
@@ -12,18 +12,30 @@ use tracing::{debug, info, warn};
 const EMBEDDED_CATALOG: &str = include_str!("../../dungeon-catalog.json");
 const DUNGEON_CATALOG_ENV: &str = "NEKOMATA_DUNGEON_CATALOG";
 
+/// Most single-character edits a reported zone may be from a catalog zone to fuzzy-match it.
+const FUZZY_MAX_DISTANCE: usize = 2;
+
 static DEFAULT_CATALOG_FILENAMES: Lazy<[&str; 1]> = Lazy::new(|| ["dungeon-catalog.json"]);
 
 #[derive(Debug, Deserialize)]
 struct RawCatalog {
+    /// Sorted so duplicates resolve the same way on every load.
+    #[serde(default)]
+    dungeons: BTreeMap<String, Value>,
+    /// Treat "The Aurum Vale" and "Aurum Vale" as the same zone.
     #[serde(default)]
-    dungeons: HashMap<String, Value>,
+    article_insensitive: bool,
 }
 
 /// Lookup helper for determining whether a zone should participate in dungeon aggregation.
 #[derive(Debug, Clone, Default)]
 pub struct DungeonCatalog {
     canonical_by_norm: HashMap<String, String>,
+    /// Boss names from each entry's `boss*` metadata keys, keyed like `canonical_by_norm`.
+    bosses_by_norm: HashMap<String, Vec<String>>,
+    article_insensitive: bool,
+    /// Fall back to the closest known zone within [`FUZZY_MAX_DISTANCE`] edits.
+    fuzzy_match: bool,
 }
 
 impl DungeonCatalog {
@@ -48,6 +60,16 @@ impl DungeonCatalog {
             .context("Failed to load embedded dungeon catalog definition")
     }
 
+    /// Like [`Self::load_default`], but a catalog file that fails to load is an error instead
+    /// of falling back to the embedded copy, so a typo in a hand-edited file is reported.
+    pub fn load_default_strict() -> Result<Self> {
+        match locate_default_file() {
+            Some(path) => Self::load_from_path(&path),
+            None => Self::from_str(EMBEDDED_CATALOG)
+                .context("Failed to load embedded dungeon catalog definition"),
+        }
+    }
+
     /// Load the catalog from the provided path.
     pub fn load_from_path(path: &Path) -> Result<Self> {
         let mut file = File::open(path)
@@ -73,15 +95,41 @@ impl DungeonCatalog {
 
     fn from_raw(raw: RawCatalog) -> Self {
         let mut canonical_by_norm = HashMap::new();
+        let mut bosses_by_norm = HashMap::new();
         let mut duplicates = 0usize;
 
-        for (zone, _metadata) in raw.dungeons {
+        // Visit entries in name order (ignoring stray whitespace) so the first spelling of a
+        // duplicated zone is the same on every load, whatever order the file lists them in.
+        let mut entries: Vec<(String, Value)> = raw.dungeons.into_iter().collect();
+        entries.sort_by(|(a, _), (b, _)| a.trim().cmp(b.trim()));
+        for (zone, metadata) in entries {
             if let Some(normalized) = normalize_zone(&zone) {
-                if canonical_by_norm.contains_key(&normalized) {
+                let bosses = boss_names(&metadata);
+                let variant = toggle_article(&normalized);
+                let kept = if canonical_by_norm.contains_key(&normalized) {
+                    Some(normalized.clone())
+                } else if raw.article_insensitive && canonical_by_norm.contains_key(&variant) {
+                    Some(variant)
+                } else {
+                    None
+                };
+                if let Some(kept) = kept {
                     duplicates += 1;
-                    warn!(zone = %zone, normalized = %normalized, "Duplicate dungeon zone in catalog; keeping first entry");
+                    warn!(zone = %zone, normalized = %normalized, "Duplicate dungeon zone in catalog; keeping the first by name and merging its bosses");
+                    // The spellings name one zone, so bosses listed under either still apply.
+                    if !bosses.is_empty() {
+                        let merged: &mut Vec<String> = bosses_by_norm.entry(kept).or_default();
+                        for boss in bosses {
+                            if !merged.contains(&boss) {
+                                merged.push(boss);
+                            }
+                        }
+                    }
                     continue;
                 }
+                if !bosses.is_empty() {
+                    bosses_by_norm.insert(normalized.clone(), bosses);
+                }
                 canonical_by_norm.insert(normalized, collapse_whitespace(zone.trim()));
             } else {
                 debug!(original = %zone, "Skipping empty/invalid dungeon zone entry");
@@ -97,13 +145,104 @@ impl DungeonCatalog {
 
         info!(count = canonical_by_norm.len(), "Dungeon catalog loaded");
 
-        Self { canonical_by_norm }
+        Self {
+            canonical_by_norm,
+            bosses_by_norm,
+            article_insensitive: raw.article_insensitive,
+            fuzzy_match: false,
+        }
+    }
+
+    /// Adds zones from the config (`extra_dungeon_zones`) as if they were catalog entries
+    /// without metadata. Zones the catalog already knows keep their catalog entry.
+    pub fn with_extra_zones(mut self, zones: &[String]) -> Self {
+        let mut added = 0usize;
+        for zone in zones {
+            let Some(normalized) = normalize_zone(zone) else {
+                continue;
+            };
+            if self.canonical_zone(&normalized).is_some() {
+                continue;
+            }
+            self.canonical_by_norm
+                .insert(normalized, collapse_whitespace(zone.trim()));
+            added += 1;
+        }
+        if added > 0 {
+            info!(added, "Added dungeon zones from config");
+        }
+        self
+    }
+
+    /// Turns on the near-match fallback of [`Self::canonical_zone`] (`fuzzy_zone_match`).
+    pub fn with_fuzzy_match(mut self, enabled: bool) -> Self {
+        self.fuzzy_match = enabled;
+        self
     }
 
     /// Returns the canonical zone name if the provided zone is recognised.
+    ///
+    /// Exact (normalised) matches always win; when the catalog sets `article_insensitive`
+    /// the name is retried with its leading "The " added or removed. With fuzzy matching on,
+    /// a zone within [`FUZZY_MAX_DISTANCE`] edits of exactly one closest entry matches it, and
+    /// failing that the zone is retried without trailing bracketed tags such as " (Hard)".
     pub fn canonical_zone<'a>(&'a self, zone: &str) -> Option<&'a str> {
         let key = normalize_zone(zone)?;
-        self.canonical_by_norm.get(&key).map(|s| s.as_str())
+        if let Some(found) = self.canonical_by_norm.get(&key) {
+            return Some(found.as_str());
+        }
+        if self.article_insensitive {
+            if let Some(found) = self.canonical_by_norm.get(&toggle_article(&key)) {
+                return Some(found.as_str());
+            }
+        }
+        if !self.fuzzy_match {
+            return None;
+        }
+        let found = self.fuzzy_zone(&key).or_else(|| {
+            let base = strip_bracketed_suffixes(&key);
+            if base.is_empty() || base == key {
+                return None;
+            }
+            self.canonical_by_norm
+                .get(base)
+                .map(String::as_str)
+                .or_else(|| self.fuzzy_zone(base))
+        })?;
+        debug!(zone = %zone, matched = %found, "Fuzzy-matched dungeon zone");
+        Some(found)
+    }
+
+    /// The single entry closest to `key` within [`FUZZY_MAX_DISTANCE`] edits; `None` when
+    /// nothing is close enough or two entries are equally close.
+    fn fuzzy_zone(&self, key: &str) -> Option<&str> {
+        let mut best: Option<(usize, &str)> = None;
+        let mut tied = false;
+        for (norm, canonical) in &self.canonical_by_norm {
+            let distance = levenshtein(key, norm);
+            if distance > FUZZY_MAX_DISTANCE {
+                continue;
+            }
+            match best {
+                Some((best_distance, _)) if distance > best_distance => {}
+                Some((best_distance, _)) if distance == best_distance => tied = true,
+                _ => {
+                    best = Some((distance, canonical.as_str()));
+                    tied = false;
+                }
+            }
+        }
+        best.filter(|_| !tied).map(|(_, canonical)| canonical)
+    }
+
+    /// Boss names listed for the zone (`"boss1"`, `"boss_optional3"`, … in the catalog), in
+    /// key order; empty for unknown zones or entries without any.
+    pub fn bosses(&self, zone: &str) -> &[String] {
+        self.canonical_zone(zone)
+            .and_then(normalize_zone)
+            .and_then(|key| self.bosses_by_norm.get(&key))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
     }
 
     /// Returns true when the provided zone exists in the catalog.
@@ -125,6 +264,32 @@ impl DungeonCatalog {
     }
 }
 
+fn boss_names(metadata: &Value) -> Vec<String> {
+    let Some(fields) = metadata.as_object() else {
+        return Vec::new();
+    };
+    let mut entries: Vec<(&String, &str)> = fields
+        .iter()
+        .filter(|(key, _)| key.starts_with("boss"))
+        .filter_map(|(key, value)| Some((key, value.as_str()?.trim())))
+        .filter(|(_, name)| !name.is_empty())
+        .collect();
+    entries.sort_by_key(|(key, _)| boss_order(key));
+    let mut names: Vec<String> = Vec::with_capacity(entries.len());
+    for (_, name) in entries {
+        if !names.iter().any(|known| known.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Sorts `boss2` before `boss10` and `boss_optional3` between `boss2` and `boss4`.
+fn boss_order(key: &str) -> (u32, String) {
+    let digits: String = key.chars().filter(char::is_ascii_digit).collect();
+    (digits.parse().unwrap_or(u32::MAX), key.to_string())
+}
+
 fn locate_default_file() -> Option<PathBuf> {
     if let Some(env_path) = std::env::var_os(DUNGEON_CATALOG_ENV) {
         let candidate = PathBuf::from(env_path);
@@ -153,6 +318,22 @@ fn locate_default_file() -> Option<PathBuf> {
     None
 }
 
+/// Edit distance between two strings, counted in characters.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
 fn normalize_zone(zone: &str) -> Option<String> {
     let collapsed = collapse_whitespace(zone.trim());
     if collapsed.is_empty() {
@@ -161,6 +342,27 @@ fn normalize_zone(zone: &str) -> Option<String> {
     Some(collapsed.to_lowercase())
 }
 
+/// `normalized` without its trailing "(...)" or "[...]" groups: "sastasha (hard)" → "sastasha".
+fn strip_bracketed_suffixes(normalized: &str) -> &str {
+    let mut rest = normalized.trim_end();
+    while let Some(open) = match rest.chars().last() {
+        Some(')') => rest.rfind('('),
+        Some(']') => rest.rfind('['),
+        _ => None,
+    } {
+        rest = rest[..open].trim_end();
+    }
+    rest
+}
+
+/// Adds or removes a leading "the " on an already normalised zone key.
+fn toggle_article(normalized: &str) -> String {
+    match normalized.strip_prefix("the ") {
+        Some(rest) => rest.to_string(),
+        None => format!("the {normalized}"),
+    }
+}
+
 fn collapse_whitespace(input: &str) -> String {
     let mut buf = String::with_capacity(input.len());
     let mut in_whitespace = false;
@@ -210,6 +412,21 @@ mod tests {
         assert_eq!(catalog.len(), 2);
     }
 
+    #[test]
+    fn duplicate_zones_resolve_the_same_way_in_any_order() {
+        for src in [
+            r#"{ "article_insensitive": true, "dungeons": {
+                "The Aurum Vale": { "boss1": "Locksmith" }, "Aurum Vale": {}, " aurum vale": {} } }"#,
+            r#"{ "article_insensitive": true, "dungeons": {
+                " aurum vale": {}, "Aurum Vale": {}, "The Aurum Vale": { "boss1": "Locksmith" } } }"#,
+        ] {
+            let catalog = DungeonCatalog::from_str(src).expect("catalog parse");
+            assert_eq!(catalog.len(), 1);
+            assert_eq!(catalog.canonical_zone("The Aurum Vale"), Some("Aurum Vale"));
+            assert_eq!(catalog.bosses("Aurum Vale"), ["Locksmith"]);
+        }
+    }
+
     #[test]
     fn catalog_allows_trailing_commas() {
         let src = "{ \"dungeons\": { \"Sastasha\": {}, }}";
@@ -217,6 +434,114 @@ mod tests {
         assert!(catalog.is_zone("Sastasha"));
     }
 
+    #[test]
+    fn article_insensitive_matches_zone_reported_with_the() {
+        let catalog = DungeonCatalog::from_str(
+            r#"{ "article_insensitive": true, "dungeons": { "Aurum Vale": {} } }"#,
+        )
+        .expect("catalog parse");
+        assert_eq!(catalog.canonical_zone("The Aurum Vale"), Some("Aurum Vale"));
+        assert_eq!(catalog.canonical_zone("Aurum Vale"), Some("Aurum Vale"));
+    }
+
+    #[test]
+    fn article_insensitive_matches_zone_reported_without_the() {
+        let src = r#"{
+            "article_insensitive": true,
+            "dungeons": { "The Tam-Tara Deepcroft": {}, "Tam-Tara Deepcroft": {}, "Theater": {} }
+        }"#;
+        let catalog = DungeonCatalog::from_str(src).expect("catalog parse");
+        assert_eq!(catalog.len(), 2);
+        assert!(catalog.is_zone("tam-tara deepcroft"));
+        assert!(catalog.is_zone("The Tam-Tara Deepcroft"));
+        assert_eq!(catalog.canonical_zone("Theater"), Some("Theater"));
+
+        let strict = DungeonCatalog::from_str(r#"{ "dungeons": { "Aurum Vale": {} } }"#)
+            .expect("catalog parse");
+        assert!(!strict.is_zone("The Aurum Vale"));
+    }
+
+    #[test]
+    fn boss_names_come_from_boss_keys_in_order() {
+        let src = r#"{
+            "article_insensitive": true,
+            "dungeons": {
+                "Halatali": {
+                    "boss10": "Last",
+                    "boss1": "Firemane",
+                    "boss_optional3": "Pit Peiste",
+                    "boss2": "Thunderclap Guivre",
+                    "level": 20,
+                    "boss4": "firemane"
+                },
+                "Sastasha": {}
+            }
+        }"#;
+        let catalog = DungeonCatalog::from_str(src).expect("catalog parse");
+        assert_eq!(
+            catalog.bosses("The Halatali"),
+            ["Firemane", "Thunderclap Guivre", "Pit Peiste", "Last"]
+        );
+        assert!(catalog.bosses("Sastasha").is_empty());
+        assert!(catalog.bosses("Nowhere").is_empty());
+    }
+
+    #[test]
+    fn extra_zones_join_the_catalog_without_replacing_entries() {
+        let catalog = DungeonCatalog::from_str(r#"{ "dungeons": { "Sastasha": {} } }"#)
+            .unwrap()
+            .with_extra_zones(&[
+                "  The   Omega Protocol ".into(),
+                "SASTASHA".into(),
+                "".into(),
+            ]);
+        assert_eq!(catalog.len(), 2);
+        assert_eq!(
+            catalog.canonical_zone("the omega protocol"),
+            Some("The Omega Protocol")
+        );
+        assert_eq!(catalog.canonical_zone("sastasha"), Some("Sastasha"));
+    }
+
+    #[test]
+    fn fuzzy_match_accepts_near_misses_only_when_enabled() {
+        let catalog = DungeonCatalog::from_str(
+            r#"{ "dungeons": { "The Sunken Temple of Qarn": {}, "Sastasha": {} } }"#,
+        )
+        .unwrap();
+        assert_eq!(catalog.canonical_zone("The Sunken Temple of Qarn."), None);
+
+        let catalog = catalog.with_fuzzy_match(true);
+        assert_eq!(
+            catalog.canonical_zone("The Sunken Temple of Qarn."),
+            Some("The Sunken Temple of Qarn")
+        );
+        assert_eq!(catalog.canonical_zone("Sastasa"), Some("Sastasha"));
+        assert_eq!(catalog.canonical_zone("Sastasha (Hard)"), Some("Sastasha"));
+        assert_eq!(catalog.canonical_zone("Sastasa [Trial]"), Some("Sastasha"));
+        assert_eq!(catalog.canonical_zone("Limsa Lominsa"), None);
+        assert_eq!(catalog.canonical_zone("Limsa Lominsa (Hard)"), None);
+    }
+
+    #[test]
+    fn bracketed_suffixes_are_stripped_from_the_end_only() {
+        assert_eq!(strip_bracketed_suffixes("sastasha (hard)"), "sastasha");
+        assert_eq!(
+            strip_bracketed_suffixes("sastasha (hard) [trial]"),
+            "sastasha"
+        );
+        assert_eq!(strip_bracketed_suffixes("the (odd) keep"), "the (odd) keep");
+        assert_eq!(strip_bracketed_suffixes("(hard)"), "");
+    }
+
+    #[test]
+    fn levenshtein_counts_edits() {
+        assert_eq!(levenshtein("sastasha", "sastasha"), 0);
+        assert_eq!(levenshtein("sastasha", "sastasa"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
     #[test]
     fn collapse_whitespace_collapses_sequences() {
         assert_eq!(collapse_whitespace("A   B"), "A B");
@@ -1,3 +1,4 @@
 pub mod catalog;
+pub mod remote;
 
 pub use catalog::DungeonCatalog;
@@ -0,0 +1,174 @@
+//! Optional download of the dungeon catalog from a URL, cached in the config directory.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use tokio::task;
+use tracing::{info, warn};
+
+use crate::config::config_dir;
+
+use super::DungeonCatalog;
+
+const CATALOG_URL_ENV: &str = "NEKOMATA_DUNGEON_CATALOG_URL";
+const CACHED_CATALOG_FILE_NAME: &str = "dungeon-catalog.json";
+/// Longest startup may wait on the download.
+pub const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+/// Catalogs are a few hundred KiB at most; anything far larger is not a catalog.
+const MAX_CATALOG_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Where a downloaded catalog is kept between launches.
+pub fn cached_catalog_path() -> PathBuf {
+    config_dir().join(CACHED_CATALOG_FILE_NAME)
+}
+
+/// The catalog URL from `NEKOMATA_DUNGEON_CATALOG_URL`, else the config; blank values count as
+/// unset.
+pub fn catalog_url(configured: Option<&str>) -> Option<String> {
+    let non_blank = |url: &str| {
+        let url = url.trim();
+        (!url.is_empty()).then(|| url.to_string())
+    };
+    std::env::var(CATALOG_URL_ENV)
+        .ok()
+        .and_then(|url| non_blank(&url))
+        .or_else(|| configured.and_then(non_blank))
+}
+
+/// Loads the catalog for a session. With a URL, a fresh download (bounded by
+/// [`FETCH_TIMEOUT`]) replaces the cached copy, and the cached copy is used when the download
+/// fails; without one, or without any cache, this is [`DungeonCatalog::load_default`].
+pub async fn load_catalog(url: Option<&str>) -> Result<DungeonCatalog> {
+    let Some(url) = url else {
+        return DungeonCatalog::load_default();
+    };
+    let cache = cached_catalog_path();
+    match fetch_to_cache(url, &cache, FETCH_TIMEOUT).await {
+        Ok(catalog) => {
+            info!(url, zones = catalog.len(), "downloaded dungeon catalog");
+            return Ok(catalog);
+        }
+        Err(err) => warn!(error = ?err, url, "dungeon catalog download failed"),
+    }
+    load_cached(&cache)
+}
+
+/// Loads the cached download when a URL is configured, otherwise the usual locations. Used by
+/// commands that should not touch the network.
+pub fn load_catalog_offline(url: Option<&str>) -> Result<DungeonCatalog> {
+    match url {
+        Some(_) => load_cached(&cached_catalog_path()),
+        None => DungeonCatalog::load_default(),
+    }
+}
+
+/// Loads the catalog again for a reload mid-session, from the same place as
+/// [`load_catalog_offline`]. A file that fails to load is an error here, so the caller can keep
+/// the catalog it already has.
+pub fn reload_catalog(url: Option<&str>) -> Result<DungeonCatalog> {
+    let cache = cached_catalog_path();
+    if url.is_some() && cache.exists() {
+        DungeonCatalog::load_from_path(&cache)
+    } else {
+        DungeonCatalog::load_default_strict()
+    }
+}
+
+fn load_cached(cache: &Path) -> Result<DungeonCatalog> {
+    if cache.exists() {
+        match DungeonCatalog::load_from_path(cache) {
+            Ok(catalog) => return Ok(catalog),
+            Err(err) => warn!(error = ?err, "cached dungeon catalog unreadable"),
+        }
+    }
+    DungeonCatalog::load_default()
+}
+
+async fn fetch_to_cache(url: &str, cache: &Path, timeout: Duration) -> Result<DungeonCatalog> {
+    let request_url = url.to_string();
+    let download = task::spawn_blocking(move || download(&request_url, timeout));
+    let body = tokio::time::timeout(timeout, download)
+        .await
+        .context("timed out")?
+        .context("download task failed")??;
+    let catalog = validate(&body)?;
+    write_atomically(cache, &body)?;
+    Ok(catalog)
+}
+
+fn download(url: &str, timeout: Duration) -> Result<String> {
+    let response = ureq::AgentBuilder::new()
+        .timeout(timeout)
+        .build()
+        .get(url)
+        .call()
+        .with_context(|| format!("GET {url}"))?;
+    let mut body = String::new();
+    response
+        .into_reader()
+        .take(MAX_CATALOG_BYTES + 1)
+        .read_to_string(&mut body)
+        .context("reading the response body")?;
+    if body.len() as u64 > MAX_CATALOG_BYTES {
+        bail!("response is larger than {MAX_CATALOG_BYTES} bytes");
+    }
+    Ok(body)
+}
+
+/// Parses a downloaded catalog, rejecting ones without any dungeons so an error page or an
+/// empty file never replaces a working cache.
+fn validate(body: &str) -> Result<DungeonCatalog> {
+    let catalog = DungeonCatalog::from_str(body).context("downloaded catalog is not valid")?;
+    if catalog.is_empty() {
+        bail!("downloaded catalog lists no dungeons");
+    }
+    Ok(catalog)
+}
+
+fn write_atomically(path: &Path, body: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Unable to create {}", parent.display()))?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, body).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to move {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_empty_and_malformed_catalogs() {
+        assert!(validate(r#"{ "dungeons": { "Sastasha": {} } }"#).is_ok());
+        assert!(validate(r#"{ "dungeons": {} }"#).is_err());
+        assert!(validate("<html>404</html>").is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_download_leaves_the_cache_untouched() {
+        let dir = std::env::temp_dir().join(format!(
+            "nekomata-catalog-{}-{}",
+            std::process::id(),
+            crate::history::now_ms()
+        ));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let cache = dir.join(CACHED_CATALOG_FILE_NAME);
+        let cached = r#"{ "dungeons": { "Sastasha": {} } }"#;
+        fs::write(&cache, cached).expect("seed cache");
+
+        // Nothing listens on port 9 (discard) locally, so the request fails fast.
+        let result = fetch_to_cache("http://127.0.0.1:9/catalog.json", &cache, FETCH_TIMEOUT).await;
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&cache).expect("cache"), cached);
+        assert!(load_cached(&cache)
+            .expect("cached catalog")
+            .is_zone("Sastasha"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum AppErrorKind {
+    Clipboard,
     History,
     Network,
     Storage,
@@ -13,6 +14,7 @@ pub enum AppErrorKind {
 impl AppErrorKind {
     pub fn label(self) -> &'static str {
         match self {
+            AppErrorKind::Clipboard => "Clipboard",
             AppErrorKind::History => "History",
             AppErrorKind::Network => "Network",
             AppErrorKind::Storage => "Storage",
@@ -55,3 +57,10 @@ impl AppError {
         }
     }
 }
+
+/// An error kept in the in-app error log, stamped with wall-clock time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ErrorLogEntry {
+    pub at_ms: u64,
+    pub error: AppError,
+}
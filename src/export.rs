@@ -0,0 +1,525 @@
+//! File exports of recorded history, written under `exports/` in the config directory.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{Local, TimeZone};
+use tracing::warn;
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::config_dir;
+use crate::history::util::{parse_duration_secs, parse_number, resolve_title};
+use crate::history::{EncounterRecord, HistoryStore};
+use crate::model::{resolve_self_name, CombatantRow, EncounterSummary, SortKey, ViewMode};
+
+pub fn exports_dir() -> PathBuf {
+    config_dir().join("exports")
+}
+
+/// File format of exported encounters, from `export_format` in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// The full stored record, frames included.
+    #[default]
+    Json,
+    /// A heading and a results table that pastes cleanly into Discord.
+    Markdown,
+    /// The combatant table with raw numbers, one row per combatant (see [`to_csv`]).
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        match key.as_ref().trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => ExportFormat::Markdown,
+            "csv" => ExportFormat::Csv,
+            _ => ExportFormat::Json,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Outcome of a bulk export; individual failures are counted rather than aborting the run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportReport {
+    pub dir: PathBuf,
+    pub written: usize,
+    pub failed: usize,
+}
+
+/// Writes every encounter of a day as its own file under `<root>/<date>/`.
+///
+/// `progress` is called after each encounter with `(done, total)`.
+pub fn export_day(
+    store: &HistoryStore,
+    date_id: &str,
+    keys: &[Vec<u8>],
+    root: &Path,
+    format: ExportFormat,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<ExportReport> {
+    let dir = root.join(sanitize_file_stem(date_id));
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Unable to create export directory {}", dir.display()))?;
+
+    let mut report = ExportReport {
+        dir,
+        written: 0,
+        failed: 0,
+    };
+    for (index, key) in keys.iter().enumerate() {
+        match export_encounter(store, key, &report.dir, index + 1, format) {
+            Ok(_) => report.written += 1,
+            Err(err) => {
+                warn!(error = ?err, "failed to export encounter");
+                report.failed += 1;
+            }
+        }
+        progress(index + 1, keys.len());
+    }
+    Ok(report)
+}
+
+fn export_encounter(
+    store: &HistoryStore,
+    key: &[u8],
+    dir: &Path,
+    ordinal: usize,
+    format: ExportFormat,
+) -> Result<PathBuf> {
+    let record = store.load_encounter_record(key)?;
+    let path = dir.join(encounter_file_name(&record, ordinal, format));
+    let data = match format {
+        ExportFormat::Json => serde_json::to_vec_pretty(&record)?,
+        ExportFormat::Markdown => encounter_markdown(&record).into_bytes(),
+        ExportFormat::Csv => to_csv(&rows_by_dps(&record), &record.encounter).into_bytes(),
+    };
+    fs::write(&path, data).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Writes the combatant table of the record under `key` to `<dir>/<end time>.csv`.
+pub fn export_encounter_csv(store: &HistoryStore, key: &[u8], dir: &Path) -> Result<PathBuf> {
+    let record = store.load_encounter_record(key)?;
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Unable to create export directory {}", dir.display()))?;
+    let path = dir.join(format!("{}.csv", record.export_stem()));
+    fs::write(&path, to_csv(&rows_by_dps(&record), &record.encounter))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+const TABLE_CSV_HEADER: &str = "encounter,zone,duration_secs,name,job,encdps,damage,share_pct,\
+enchps,healed,crit_pct,dh_pct,deaths";
+
+/// CSV of a combatant table: a header, then one line per row in the given order. Numbers are
+/// written raw (no thousands separators or % signs) so spreadsheets parse them as numbers;
+/// the encounter's title, zone and duration lead every line so several exports can be stacked.
+pub fn to_csv(rows: &[CombatantRow], encounter: &EncounterSummary) -> String {
+    let duration = parse_duration_secs(&encounter.duration)
+        .map(|secs| secs.to_string())
+        .unwrap_or_default();
+    let mut out = String::from(TABLE_CSV_HEADER);
+    out.push('\n');
+    for row in rows {
+        let line = [
+            csv_field(encounter.title.trim()),
+            csv_field(encounter.zone.trim()),
+            duration.clone(),
+            csv_field(&row.name),
+            csv_field(&row.job),
+            csv_number(row.encdps),
+            csv_number(row.damage),
+            csv_number(row.share * 100.0),
+            csv_number(row.enchps),
+            csv_number(row.healed),
+            csv_number(parse_number(&row.crit)),
+            csv_number(parse_number(&row.dh)),
+            csv_number(parse_number(&row.deaths)),
+        ];
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// The record's rows in the stock ENCDPS order.
+fn rows_by_dps(record: &EncounterRecord) -> Vec<CombatantRow> {
+    let mut rows = record.rows.clone();
+    SortKey::Rate.sort_rows(ViewMode::Dps, false, &mut rows);
+    rows
+}
+
+fn encounter_markdown(record: &EncounterRecord) -> String {
+    let rows = rows_by_dps(record);
+    let enc = &record.encounter;
+    format!(
+        "**{}** · {} · {} · {} DPS\n\n{}",
+        escape_markdown_cell(&resolve_title(record)),
+        escape_markdown_cell(&enc.zone),
+        enc.duration,
+        enc.encdps,
+        format_markdown_table(&rows, ViewMode::Dps)
+    )
+}
+
+/// GitHub-flavored Markdown table of `rows` in their given order: name, job, the mode's rate
+/// and share. Columns are padded to line up in plain text too; pipes in names are escaped.
+pub fn format_markdown_table(rows: &[CombatantRow], mode: ViewMode) -> String {
+    let (rate_header, share_header) = match mode {
+        ViewMode::Dps => ("DPS", "Share"),
+        ViewMode::Heal => ("HPS", "Heal share"),
+    };
+    let header = ["Name", "Job", rate_header, share_header].map(str::to_string);
+    let body: Vec<[String; 4]> = rows
+        .iter()
+        .map(|row| {
+            let (rate, share) = match mode {
+                ViewMode::Dps => (&row.encdps_str, &row.share_str),
+                ViewMode::Heal => (&row.enchps_str, &row.heal_share_str),
+            };
+            [
+                escape_markdown_cell(&row.name),
+                escape_markdown_cell(&row.job),
+                escape_markdown_cell(rate),
+                escape_markdown_cell(share),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.clone().map(|cell| cell.width().max(3));
+    for cells in &body {
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = (*width).max(cell.width());
+        }
+    }
+    // Name and job read left to right; the numbers line up on the right.
+    let right_aligned = [false, false, true, true];
+
+    let mut out = String::new();
+    let mut push_line = |cells: &[String; 4]| {
+        out.push('|');
+        for ((cell, width), right) in cells.iter().zip(widths).zip(right_aligned) {
+            let pad = " ".repeat(width - cell.width());
+            if right {
+                out.push_str(&format!(" {pad}{cell} |"));
+            } else {
+                out.push_str(&format!(" {cell}{pad} |"));
+            }
+        }
+        out.push('\n');
+    };
+    push_line(&header);
+    let separator = std::array::from_fn(|idx| {
+        let dashes = "-".repeat(widths[idx] - 1);
+        if right_aligned[idx] {
+            format!("{dashes}:")
+        } else {
+            format!("{dashes}-")
+        }
+    });
+    push_line(&separator);
+    for cells in &body {
+        push_line(cells);
+    }
+    out
+}
+
+fn escape_markdown_cell(text: &str) -> String {
+    text.trim().replace('|', "\\|")
+}
+
+fn encounter_file_name(record: &EncounterRecord, ordinal: usize, format: ExportFormat) -> String {
+    let time = Local
+        .timestamp_millis_opt(record.last_seen_ms as i64)
+        .single()
+        .map(|dt| dt.format("%H%M%S").to_string())
+        .unwrap_or_else(|| "000000".to_string());
+    let title = sanitize_file_stem(&resolve_title(record));
+    format!("{ordinal:03}-{time}-{title}.{}", format.extension())
+}
+
+const SESSION_CSV_HEADER: &str = "timestamp,zone,title,duration,party_dps,my_dps";
+
+/// What a session log row records about a saved encounter. Built up front so the writer task
+/// gets a few strings instead of a copy of the whole record.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionRow {
+    pub first_seen_ms: u64,
+    pub zone: String,
+    pub title: String,
+    pub duration: String,
+    pub party_dps: f64,
+    /// ENCDPS of the combatant `self_names` picks, if any took part.
+    pub my_dps: Option<f64>,
+}
+
+impl SessionRow {
+    pub fn from_record(record: &EncounterRecord, self_names: &[String]) -> Self {
+        let my_dps = resolve_self_name(self_names, &record.rows)
+            .and_then(|name| record.rows.iter().find(|row| row.name == name))
+            .map(|row| row.encdps);
+        Self {
+            first_seen_ms: record.first_seen_ms,
+            zone: record.encounter.zone.trim().to_string(),
+            title: resolve_title(record),
+            duration: record.encounter.duration.trim().to_string(),
+            party_dps: parse_number(&record.encounter.encdps),
+            my_dps,
+        }
+    }
+}
+
+/// The session log for the day an encounter started on: `<dir>/session-<date>.csv`.
+pub fn session_csv_path(dir: &Path, first_seen_ms: u64) -> PathBuf {
+    let date = Local
+        .timestamp_millis_opt(first_seen_ms as i64)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    dir.join(format!("session-{date}.csv"))
+}
+
+/// Appends `row` to its day's session log, writing the header first when the file is new.
+/// "my_dps" is blank when none of `self_names` (or ACT's "YOU") took part.
+pub fn append_session_row(dir: &Path, row: &SessionRow) -> Result<PathBuf> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Unable to create export directory {}", dir.display()))?;
+    let path = session_csv_path(dir, row.first_seen_ms);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Unable to open {}", path.display()))?;
+    let mut text = String::new();
+    if file.metadata()?.len() == 0 {
+        text.push_str(SESSION_CSV_HEADER);
+        text.push('\n');
+    }
+    text.push_str(&session_csv_row(row));
+    text.push('\n');
+    file.write_all(text.as_bytes())
+        .with_context(|| format!("Unable to write {}", path.display()))?;
+    Ok(path)
+}
+
+fn session_csv_row(row: &SessionRow) -> String {
+    let timestamp = Local
+        .timestamp_millis_opt(row.first_seen_ms as i64)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default();
+    [
+        timestamp,
+        csv_field(&row.zone),
+        csv_field(&row.title),
+        csv_field(&row.duration),
+        format!("{:.1}", row.party_dps),
+        row.my_dps
+            .map(|dps| format!("{dps:.1}"))
+            .unwrap_or_default(),
+    ]
+    .join(",")
+}
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Lowercases and replaces anything outside `[a-z0-9]` with dashes so titles are path-safe.
+fn sanitize_file_stem(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for ch in raw.chars().flat_map(char::to_lowercase) {
+        if ch.is_ascii_alphanumeric() {
+            out.push(ch);
+        } else if !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    let trimmed: String = out.trim_matches('-').chars().take(48).collect();
+    if trimmed.is_empty() {
+        "encounter".to_string()
+    } else {
+        trimmed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::history::types::now_ms;
+    use crate::model::EncounterSummary;
+
+    use super::*;
+
+    fn record(title: &str, last_seen_ms: u64) -> EncounterRecord {
+        EncounterRecord::fixture(
+            last_seen_ms,
+            EncounterSummary {
+                title: title.into(),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn session_log_writes_the_header_once_and_appends_rows() {
+        let base = std::env::temp_dir().join(format!("nekomata-session-{}", now_ms()));
+        let mut first = record("Titan, Hard", now_ms());
+        first.encounter.zone = "The Navel".into();
+        first.encounter.duration = "02:15".into();
+        first.encounter.encdps = "45,120.5".into();
+        first.rows = vec![CombatantRow {
+            name: "Alice".into(),
+            encdps: 11_000.25,
+            ..Default::default()
+        }];
+        let second = record("Ifrit", now_ms());
+
+        let names = vec!["alice".to_string()];
+        let first = SessionRow::from_record(&first, &names);
+        assert_eq!(first.my_dps, Some(11_000.25));
+        let path = append_session_row(&base, &first).expect("first row");
+        assert_eq!(
+            append_session_row(&base, &SessionRow::from_record(&second, &names))
+                .expect("second row"),
+            path
+        );
+
+        let text = fs::read_to_string(&path).expect("read log");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], SESSION_CSV_HEADER);
+        assert!(lines[1].ends_with(",The Navel,\"Titan, Hard\",02:15,45120.5,11000.2"));
+        assert!(lines[2].ends_with(",,Ifrit,,0.0,"));
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn sanitize_file_stem_keeps_paths_safe() {
+        assert_eq!(sanitize_file_stem("The Vault (Hard)"), "the-vault-hard");
+        assert_eq!(sanitize_file_stem("../../etc"), "etc");
+        assert_eq!(sanitize_file_stem("???"), "encounter");
+    }
+
+    #[test]
+    fn export_day_continues_past_failures() {
+        let base = std::env::temp_dir().join(format!("nekomata-export-{}", now_ms()));
+        fs::create_dir_all(&base).expect("create temp dir");
+        let store = HistoryStore::open(&base.join("encounters.sled")).expect("open store");
+        let now = now_ms();
+        let first = store
+            .append(&record("Striking Dummy", now))
+            .expect("append");
+        let second = store
+            .append(&record("Sastasha", now + 1_000))
+            .expect("append");
+        let keys = vec![first.as_bytes(), b"missing".to_vec(), second.as_bytes()];
+
+        let mut ticks = Vec::new();
+        let report = export_day(
+            &store,
+            "2025-01-02",
+            &keys,
+            &base.join("exports"),
+            ExportFormat::Json,
+            |done, total| ticks.push((done, total)),
+        )
+        .expect("export day");
+
+        assert_eq!(report.written, 2);
+        assert_eq!(report.failed, 1);
+        assert_eq!(ticks.last(), Some(&(3, 3)));
+        assert_eq!(
+            fs::read_dir(&report.dir).expect("read export dir").count(),
+            2
+        );
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn markdown_table_aligns_columns_and_escapes_pipes() {
+        let row = |name: &str, job: &str, dps: &str, share: &str| CombatantRow {
+            name: name.into(),
+            job: job.into(),
+            encdps_str: dps.into(),
+            share_str: share.into(),
+            ..Default::default()
+        };
+        let rows = vec![
+            row("Tank|Main", "PLD", "12345", "60.1%"),
+            row("Healer", "WHM", "980", "4.8%"),
+        ];
+        assert_eq!(
+            format_markdown_table(&rows, ViewMode::Dps),
+            "\
+| Name       | Job |   DPS | Share |
+| ---------- | --- | ----: | ----: |
+| Tank\\|Main | PLD | 12345 | 60.1% |
+| Healer     | WHM |   980 |  4.8% |
+"
+        );
+    }
+
+    #[test]
+    fn export_format_reads_config_keys() {
+        assert_eq!(
+            ExportFormat::from_config_key("Markdown"),
+            ExportFormat::Markdown
+        );
+        assert_eq!(ExportFormat::from_config_key("md"), ExportFormat::Markdown);
+        assert_eq!(ExportFormat::from_config_key(" CSV "), ExportFormat::Csv);
+        assert_eq!(ExportFormat::from_config_key("bogus"), ExportFormat::Json);
+    }
+
+    #[test]
+    fn table_csv_writes_raw_numbers() {
+        let encounter = EncounterSummary {
+            title: "Ifrit, Hard".into(),
+            zone: "The Bowl of Embers".into(),
+            duration: "01:30".into(),
+            ..Default::default()
+        };
+        let rows = [CombatantRow {
+            name: "Alice".into(),
+            job: "NIN".into(),
+            encdps: 12345.5,
+            encdps_str: "12,345.50".into(),
+            damage: 1111095.0,
+            share: 0.25,
+            crit: "23.5%".into(),
+            dh: "30%".into(),
+            deaths: "1".into(),
+            ..Default::default()
+        }];
+        let csv = to_csv(&rows, &encounter);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], TABLE_CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "\"Ifrit, Hard\",The Bowl of Embers,90,Alice,NIN,12345.5,1111095,25,0,0,23.5,30,1"
+        );
+        assert_eq!(lines.len(), 2);
+    }
+}
@@ -0,0 +1,172 @@
+//! Boss vs trash classification for the pulls of a dungeon run.
+
+use super::types::{DungeonAggregateRecord, EncounterRecord};
+use super::util::parse_duration_secs;
+
+/// Without catalog boss names, a pull must last at least this long to count as a boss.
+const MIN_BOSS_SECS: u64 = 60;
+/// ... and be this many times longer than the run's median pull.
+const BOSS_MEDIAN_FACTOR: f64 = 1.5;
+/// Fewer known durations than this are too few to call anything a boss by length alone.
+const MIN_PULLS_FOR_HEURISTIC: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PullKind {
+    Boss,
+    Trash,
+}
+
+/// The trash pulls leading up to a boss, or trailing after the last one (`boss: None`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PullGroup {
+    pub trash: Vec<usize>,
+    pub boss: Option<usize>,
+}
+
+/// Classifies each pull by matching its title against the catalog's boss names. When no
+/// title matches (no names listed, or a client language the catalog doesn't cover), pulls
+/// that run much longer than the run's median are treated as bosses instead.
+pub fn classify_pulls(
+    titles: &[String],
+    durations: &[Option<u64>],
+    bosses: &[String],
+) -> Vec<PullKind> {
+    let bosses: Vec<String> = bosses.iter().map(|name| name.to_lowercase()).collect();
+    let by_name: Vec<PullKind> = titles
+        .iter()
+        .map(|title| {
+            let title = title.to_lowercase();
+            if bosses.iter().any(|boss| title.contains(boss.as_str())) {
+                PullKind::Boss
+            } else {
+                PullKind::Trash
+            }
+        })
+        .collect();
+    if by_name.contains(&PullKind::Boss) {
+        return by_name;
+    }
+
+    let mut known: Vec<u64> = durations.iter().flatten().copied().collect();
+    if known.len() < MIN_PULLS_FOR_HEURISTIC {
+        return by_name;
+    }
+    known.sort_unstable();
+    let median = known[known.len() / 2] as f64;
+    (0..titles.len())
+        .map(|idx| match durations.get(idx).copied().flatten() {
+            Some(secs) if secs >= MIN_BOSS_SECS && secs as f64 >= median * BOSS_MEDIAN_FACTOR => {
+                PullKind::Boss
+            }
+            _ => PullKind::Trash,
+        })
+        .collect()
+}
+
+/// Classifies the pulls of a loaded run; children still loading count as trash for now.
+pub fn classify_run(
+    record: &DungeonAggregateRecord,
+    child_records: &[Option<EncounterRecord>],
+    bosses: &[String],
+) -> Vec<PullKind> {
+    let durations: Vec<Option<u64>> = (0..record.child_titles.len())
+        .map(|idx| {
+            child_records
+                .get(idx)
+                .and_then(Option::as_ref)
+                .and_then(|child| parse_duration_secs(&child.encounter.duration))
+        })
+        .collect();
+    classify_pulls(&record.child_titles, &durations, bosses)
+}
+
+/// Splits the pulls into checkpoints, each ending at a boss.
+pub fn group_pulls(kinds: &[PullKind]) -> Vec<PullGroup> {
+    let mut groups = Vec::new();
+    let mut current = PullGroup::default();
+    for (idx, kind) in kinds.iter().enumerate() {
+        match kind {
+            PullKind::Trash => current.trash.push(idx),
+            PullKind::Boss => {
+                current.boss = Some(idx);
+                groups.push(std::mem::take(&mut current));
+            }
+        }
+    }
+    if !current.trash.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn catalog_names_mark_bosses_and_group_checkpoints() {
+        let pulls = titles(&[
+            "Shallowtail Reaver",
+            "Chopper",
+            "Bloody Mary",
+            "Captain Madison",
+        ]);
+        let bosses = titles(&["Chopper", "Captain Madison", "Denn the Orcatoothed"]);
+        let kinds = classify_pulls(&pulls, &[None; 4], &bosses);
+        assert_eq!(
+            kinds,
+            [
+                PullKind::Trash,
+                PullKind::Boss,
+                PullKind::Trash,
+                PullKind::Boss
+            ]
+        );
+
+        let mut with_tail = kinds.clone();
+        with_tail.push(PullKind::Trash);
+        let groups = group_pulls(&with_tail);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(
+            groups[0],
+            PullGroup {
+                trash: vec![0],
+                boss: Some(1)
+            }
+        );
+        assert_eq!(
+            groups[2],
+            PullGroup {
+                trash: vec![4],
+                boss: None
+            }
+        );
+    }
+
+    #[test]
+    fn long_pulls_count_as_bosses_without_catalog_names() {
+        let pulls = titles(&["A", "B", "C", "D", "E"]);
+        let durations = [Some(40), Some(150), Some(35), Some(45), None];
+        let kinds = classify_pulls(&pulls, &durations, &[]);
+        assert_eq!(
+            kinds,
+            [
+                PullKind::Trash,
+                PullKind::Boss,
+                PullKind::Trash,
+                PullKind::Trash,
+                PullKind::Trash
+            ]
+        );
+
+        // Too few known durations to judge, and short pulls never qualify.
+        let few = classify_pulls(&pulls[..2], &durations[..2], &[]);
+        assert!(few.iter().all(|kind| *kind == PullKind::Trash));
+        let short = classify_pulls(&pulls[..3], &[Some(10), Some(50), Some(12)], &[]);
+        assert!(short.iter().all(|kind| *kind == PullKind::Trash));
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::history::types::EncounterFrame;
+
+/// Per-combatant fields that may carry active status effects, depending on the server build.
+const BUFF_FIELDS: [&str; 4] = ["Buffs", "buffs", "StatusEffects", "Effects"];
+
+/// Share of frames in which each configured buff was active on a combatant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuffUptime {
+    pub name: String,
+    /// `(buff, 0.0..=1.0)` in the order the buffs were configured.
+    pub uptimes: Vec<(String, f64)>,
+}
+
+/// Computes uptime for `buff_names` from the raw frames.
+///
+/// Only frames where a combatant reports a buff list count towards its total, so recordings
+/// made without buff data simply yield an empty result. Combatants that never had any of the
+/// configured buffs are left out.
+pub fn buff_uptime(frames: &[EncounterFrame], buff_names: &[String]) -> Vec<BuffUptime> {
+    if buff_names.is_empty() {
+        return Vec::new();
+    }
+    let wanted: Vec<String> = buff_names.iter().map(|n| n.trim().to_lowercase()).collect();
+
+    // name -> (frames with buff data, hits per configured buff)
+    let mut tallies: Vec<(String, u32, Vec<u32>)> = Vec::new();
+    for frame in frames {
+        for (combatant, active) in frame_buffs(&frame.raw) {
+            let idx = match tallies.iter().position(|(name, _, _)| *name == combatant) {
+                Some(idx) => idx,
+                None => {
+                    tallies.push((combatant, 0, vec![0; wanted.len()]));
+                    tallies.len() - 1
+                }
+            };
+            let (_, seen, hits) = &mut tallies[idx];
+            *seen += 1;
+            for (slot, buff) in wanted.iter().enumerate() {
+                if active.iter().any(|name| name == buff) {
+                    hits[slot] += 1;
+                }
+            }
+        }
+    }
+
+    tallies
+        .into_iter()
+        .filter(|(_, _, hits)| hits.iter().any(|count| *count > 0))
+        .map(|(name, seen, hits)| BuffUptime {
+            name,
+            uptimes: buff_names
+                .iter()
+                .zip(hits)
+                .map(|(buff, count)| (buff.trim().to_string(), count as f64 / seen as f64))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Lower-cased active buff names per combatant, for combatants that carry buff data.
+fn frame_buffs(raw: &Value) -> HashMap<String, Vec<String>> {
+    let mut out = HashMap::new();
+    let Some(combatants) = raw.get("Combatant").and_then(Value::as_object) else {
+        return out;
+    };
+    for (name, combatant) in combatants {
+        let Some(list) = BUFF_FIELDS
+            .iter()
+            .find_map(|field| combatant.get(*field))
+            .and_then(Value::as_array)
+        else {
+            continue;
+        };
+        let active = list
+            .iter()
+            .filter_map(|entry| match entry {
+                Value::String(name) => Some(name.as_str()),
+                Value::Object(obj) => obj
+                    .get("Name")
+                    .or_else(|| obj.get("name"))
+                    .and_then(Value::as_str),
+                _ => None,
+            })
+            .map(|name| name.trim().to_lowercase())
+            .collect();
+        out.insert(name.clone(), active);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn frame(raw: Value) -> EncounterFrame {
+        EncounterFrame {
+            received_ms: 0,
+            encounter: Default::default(),
+            rows: Vec::new(),
+            raw,
+        }
+    }
+
+    #[test]
+    fn uptime_counts_frames_with_the_buff_active() {
+        let frames = vec![
+            frame(json!({ "Combatant": {
+                "Tank": { "Buffs": ["Iron Will"] },
+                "Healer": { "Buffs": [] },
+            }})),
+            frame(json!({ "Combatant": {
+                "Tank": { "Buffs": [{ "Name": "Iron Will" }, { "Name": "Rampart" }] },
+            }})),
+            frame(json!({ "Combatant": { "Tank": { "Buffs": [] } } })),
+            frame(json!({ "Combatant": { "Tank": { "Buffs": ["iron will"] } } })),
+        ];
+        let names = vec!["Iron Will".to_string(), "Rampart".to_string()];
+        let uptime = buff_uptime(&frames, &names);
+        assert_eq!(
+            uptime,
+            vec![BuffUptime {
+                name: "Tank".into(),
+                uptimes: vec![("Iron Will".into(), 0.75), ("Rampart".into(), 0.25)],
+            }]
+        );
+    }
+
+    #[test]
+    fn uptime_is_empty_without_buff_data() {
+        let frames = vec![frame(
+            json!({ "Combatant": { "Tank": { "encdps": "100" } } }),
+        )];
+        assert!(buff_uptime(&frames, &["Iron Will".to_string()]).is_empty());
+        assert!(buff_uptime(&frames, &[]).is_empty());
+    }
+}
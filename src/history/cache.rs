@@ -0,0 +1,132 @@
+use std::collections::VecDeque;
+
+use super::types::EncounterRecord;
+
+/// Records kept at most; detail views rarely flip between more fights than this.
+pub(crate) const RECORD_CACHE_ENTRIES: usize = 16;
+/// Upper bound on the encoded size of cached records, so frame-heavy pulls can't pile up.
+pub(crate) const RECORD_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Small least-recently-used cache of decoded encounter records, keyed by record key.
+///
+/// Sizes are the stored (CBOR) length of each record, a cheap stand-in for its memory use.
+#[derive(Debug)]
+pub(crate) struct RecordCache {
+    entries: VecDeque<CachedRecord>,
+    max_entries: usize,
+    max_bytes: usize,
+    bytes: usize,
+}
+
+#[derive(Debug)]
+struct CachedRecord {
+    key: Vec<u8>,
+    size: usize,
+    record: EncounterRecord,
+}
+
+impl Default for RecordCache {
+    fn default() -> Self {
+        Self::new(RECORD_CACHE_ENTRIES, RECORD_CACHE_BYTES)
+    }
+}
+
+impl RecordCache {
+    pub(crate) fn new(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            max_entries,
+            max_bytes,
+            bytes: 0,
+        }
+    }
+
+    /// Returns a copy of the cached record and marks it most recently used.
+    pub(crate) fn get(&mut self, key: &[u8]) -> Option<EncounterRecord> {
+        let idx = self.entries.iter().position(|entry| entry.key == key)?;
+        let entry = self.entries.remove(idx)?;
+        let record = entry.record.clone();
+        self.entries.push_back(entry);
+        Some(record)
+    }
+
+    pub(crate) fn insert(&mut self, key: &[u8], size: usize, record: EncounterRecord) {
+        self.remove(key);
+        if size > self.max_bytes || self.max_entries == 0 {
+            return;
+        }
+        self.bytes += size;
+        self.entries.push_back(CachedRecord {
+            key: key.to_vec(),
+            size,
+            record,
+        });
+        while self.entries.len() > self.max_entries || self.bytes > self.max_bytes {
+            let Some(evicted) = self.entries.pop_front() else {
+                break;
+            };
+            self.bytes -= evicted.size;
+        }
+    }
+
+    /// Drops `key` so the next load reads the store again; call after any write to it.
+    pub(crate) fn remove(&mut self, key: &[u8]) {
+        if let Some(idx) = self.entries.iter().position(|entry| entry.key == key) {
+            if let Some(entry) = self.entries.remove(idx) {
+                self.bytes -= entry.size;
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes = 0;
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::EncounterSummary;
+
+    fn record(title: &str) -> EncounterRecord {
+        EncounterRecord::fixture(
+            0,
+            EncounterSummary {
+                title: title.into(),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn evicts_least_recently_used_by_count_and_size() {
+        let mut cache = RecordCache::new(2, 100);
+        cache.insert(b"a", 10, record("A"));
+        cache.insert(b"b", 10, record("B"));
+        assert!(cache.get(b"a").is_some());
+        cache.insert(b"c", 10, record("C"));
+        assert!(cache.get(b"b").is_none());
+        assert_eq!(cache.get(b"a").expect("a").encounter.title, "A");
+
+        cache.insert(b"big", 95, record("Big"));
+        assert_eq!(cache.len(), 1);
+        cache.insert(b"huge", 101, record("Huge"));
+        assert!(cache.get(b"huge").is_none());
+    }
+
+    #[test]
+    fn removed_entries_are_not_served() {
+        let mut cache = RecordCache::default();
+        cache.insert(b"a", 10, record("Old"));
+        cache.remove(b"a");
+        assert!(cache.get(b"a").is_none());
+        cache.insert(b"a", 10, record("New"));
+        assert_eq!(cache.get(b"a").expect("a").encounter.title, "New");
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::history::types::EncounterFrame;
+use crate::history::util::{parse_duration_secs, parse_number};
+use crate::model::CombatantRow;
+
+/// A single death, `ms` after the encounter started. Stored on records from `NetworkDeath`
+/// log lines, or derived from the per-frame `deaths` counters for older records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeathEvent {
+    pub ms: u64,
+    pub name: String,
+}
+
+/// Walks the stored frames and emits an event whenever a combatant's death count rises.
+///
+/// A count that drops (ACT resetting for a new attempt) re-baselines from zero, so any
+/// deaths already reported in that frame are attributed to it.
+pub fn death_timeline(frames: &[EncounterFrame]) -> Vec<DeathEvent> {
+    let Some(first) = frames.first() else {
+        return Vec::new();
+    };
+    let mut counts: HashMap<&str, u64> = HashMap::new();
+    let mut events = Vec::new();
+    for frame in frames {
+        let offset_secs = parse_duration_secs(&frame.encounter.duration)
+            .unwrap_or_else(|| frame.received_ms.saturating_sub(first.received_ms) / 1000);
+        for row in &frame.rows {
+            let current = parse_number(&row.deaths).max(0.0) as u64;
+            let previous = counts.insert(row.name.as_str(), current).unwrap_or(0);
+            let baseline = if current < previous { 0 } else { previous };
+            for _ in baseline..current {
+                events.push(DeathEvent {
+                    ms: offset_secs * 1000,
+                    name: row.name.clone(),
+                });
+            }
+        }
+    }
+    events
+}
+
+/// Longest spread between the party members' last deaths that still counts as one wipe.
+const WIPE_WINDOW_MS: u64 = 30_000;
+
+/// Whether a pull ended in a wipe: every party member's last death falls within
+/// [`WIPE_WINDOW_MS`] of the others. Rows without a job (such as "Limit Break") are not party
+/// members, unless no row has a job at all.
+pub fn is_wipe(rows: &[CombatantRow], events: &[DeathEvent]) -> bool {
+    let with_job: Vec<&str> = rows
+        .iter()
+        .filter(|row| !row.job.trim().is_empty())
+        .map(|row| row.name.as_str())
+        .collect();
+    let party: Vec<&str> = if with_job.is_empty() {
+        rows.iter().map(|row| row.name.as_str()).collect()
+    } else {
+        with_job
+    };
+    if party.is_empty() {
+        return false;
+    }
+    let mut last_deaths = Vec::with_capacity(party.len());
+    for member in party {
+        let last = events
+            .iter()
+            .filter(|event| event.name.trim().eq_ignore_ascii_case(member.trim()))
+            .map(|event| event.ms)
+            .max();
+        match last {
+            Some(ms) => last_deaths.push(ms),
+            None => return false,
+        }
+    }
+    let first = last_deaths.iter().min().copied().unwrap_or(0);
+    let last = last_deaths.iter().max().copied().unwrap_or(0);
+    last - first <= WIPE_WINDOW_MS
+}
+
+/// Groups a timeline by combatant as offsets in whole seconds, keeping first-death order.
+pub fn deaths_by_combatant(events: &[DeathEvent]) -> Vec<(String, Vec<u64>)> {
+    let mut grouped: Vec<(String, Vec<u64>)> = Vec::new();
+    for event in events {
+        let secs = event.ms / 1000;
+        match grouped.iter_mut().find(|(name, _)| *name == event.name) {
+            Some((_, offsets)) => offsets.push(secs),
+            None => grouped.push((event.name.clone(), vec![secs])),
+        }
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::model::{CombatantRow, EncounterSummary};
+
+    use super::*;
+
+    fn frame(received_ms: u64, duration: &str, deaths: &[(&str, &str)]) -> EncounterFrame {
+        EncounterFrame {
+            received_ms,
+            encounter: EncounterSummary {
+                duration: duration.into(),
+                ..Default::default()
+            },
+            rows: deaths
+                .iter()
+                .map(|(name, count)| CombatantRow {
+                    name: (*name).into(),
+                    deaths: (*count).into(),
+                    ..Default::default()
+                })
+                .collect(),
+            raw: Value::Null,
+        }
+    }
+
+    #[test]
+    fn timeline_records_increments_per_combatant() {
+        let frames = vec![
+            frame(1_000, "00:01", &[("Alice", "0"), ("Bob", "0")]),
+            frame(72_000, "01:12", &[("Alice", "1"), ("Bob", "0")]),
+            frame(165_000, "02:45", &[("Alice", "2"), ("Bob", "1")]),
+            frame(170_000, "02:50", &[("Alice", "2"), ("Bob", "1")]),
+        ];
+        let grouped = deaths_by_combatant(&death_timeline(&frames));
+        assert_eq!(
+            grouped,
+            vec![
+                ("Alice".to_string(), vec![72, 165]),
+                ("Bob".to_string(), vec![165]),
+            ]
+        );
+    }
+
+    #[test]
+    fn wipe_needs_every_party_member_dead_together() {
+        let rows: Vec<CombatantRow> = ["Alice", "Bob"]
+            .iter()
+            .map(|name| CombatantRow {
+                name: (*name).into(),
+                job: "NIN".into(),
+                ..Default::default()
+            })
+            .chain(std::iter::once(CombatantRow {
+                name: "Limit Break".into(),
+                ..Default::default()
+            }))
+            .collect();
+        let death = |ms, name: &str| DeathEvent {
+            ms,
+            name: name.into(),
+        };
+
+        assert!(is_wipe(
+            &rows,
+            &[death(90_000, "Alice"), death(95_000, "Bob")]
+        ));
+        // An early death followed by a later raise and wipe still counts.
+        assert!(is_wipe(
+            &rows,
+            &[
+                death(10_000, "Bob"),
+                death(90_000, "Alice"),
+                death(95_000, "Bob")
+            ]
+        ));
+        assert!(!is_wipe(&rows, &[death(90_000, "Alice")]));
+        assert!(!is_wipe(
+            &rows,
+            &[death(10_000, "Alice"), death(95_000, "Bob")]
+        ));
+        assert!(!is_wipe(&[], &[]));
+    }
+
+    #[test]
+    fn timeline_rebaselines_when_counts_reset() {
+        let frames = vec![
+            frame(1_000, "00:30", &[("Alice", "2")]),
+            frame(2_000, "00:05", &[("Alice", "0")]),
+            frame(3_000, "00:40", &[("Alice", "1")]),
+        ];
+        let events = death_timeline(&frames);
+        let offsets: Vec<u64> = events.iter().map(|e| e.ms).collect();
+        assert_eq!(offsets, vec![30_000, 30_000, 40_000]);
+    }
+}
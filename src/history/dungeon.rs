@@ -1,8 +1,13 @@
 use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+
 use crate::dungeon::DungeonCatalog;
-use crate::history::types::{DungeonAggregateRecord, EncounterRecord, SCHEMA_VERSION};
-use crate::history::util::{parse_duration_secs, parse_number, party_signature, resolve_title};
+use crate::history::deaths::is_wipe;
+use crate::history::types::{app_version, DungeonAggregateRecord, EncounterRecord, SCHEMA_VERSION};
+use crate::history::util::{
+    parse_duration_rate_secs, parse_number, party_signature, resolve_title, ZeroDurationPolicy,
+};
 
 #[derive(Debug, Clone)]
 pub enum DungeonZoneState {
@@ -14,12 +19,81 @@ pub enum DungeonZoneState {
 pub struct DungeonRecorderUpdate {
     pub aggregates: Vec<DungeonAggregateRecord>,
     pub zone_state: Option<DungeonZoneState>,
+    /// Totals of the open run after a pull was added to it.
+    pub progress: Option<DungeonProgress>,
+}
+
+/// Running totals of the open dungeon run, for the live HUD.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DungeonProgress {
+    pub zone: String,
+    pub pulls: usize,
+    pub total_damage: f64,
+    /// Summed pull durations in seconds.
+    pub total_duration_secs: f64,
+}
+
+impl DungeonProgress {
+    /// Damage over combat time so far; 0 before any pull had a duration.
+    pub fn encdps(&self) -> f64 {
+        if self.total_duration_secs > 0.0 {
+            self.total_damage / self.total_duration_secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Consecutive complete dungeon runs, of any zone. Each complete run extends the streak; a run
+/// saved as incomplete (cut short or interrupted) resets it to 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DungeonStreak {
+    #[serde(default)]
+    pub current: u32,
+    #[serde(default)]
+    pub longest: u32,
+}
+
+impl DungeonStreak {
+    pub fn record_run(&mut self, incomplete: bool) {
+        if incomplete {
+            self.current = 0;
+        } else {
+            self.current = self.current.saturating_add(1);
+            self.longest = self.longest.max(self.current);
+        }
+    }
+}
+
+/// Controls when a new run starts even though the canonical zone stayed the same.
+#[derive(Debug, Clone, Default)]
+pub struct DungeonOptions {
+    /// Split when consecutive pulls are further apart than this many seconds (0 disables).
+    pub split_gap_secs: u64,
+    /// Split when the player left the dungeon (e.g. back to the overworld) and re-entered it.
+    pub split_on_reentry: bool,
+    pub zero_duration: ZeroDurationPolicy,
+    /// Sum fractional pull durations instead of whole seconds for the run's DPS.
+    pub subsecond_durations: bool,
+    /// Catalog zones treated as ordinary zones: they end an open run and never start one.
+    pub exclude_zones: Vec<String>,
+}
+
+impl DungeonOptions {
+    /// Matches the zone as reported or its catalog name, ignoring case and surrounding spaces.
+    fn excludes(&self, zone: &str, canonical: &str) -> bool {
+        self.exclude_zones.iter().any(|excluded| {
+            let excluded = excluded.trim();
+            excluded.eq_ignore_ascii_case(zone.trim()) || excluded.eq_ignore_ascii_case(canonical)
+        })
+    }
 }
 
 pub struct DungeonRecorder {
     catalog: Option<Arc<DungeonCatalog>>,
     enabled: bool,
     session: Option<DungeonSession>,
+    options: DungeonOptions,
 }
 
 impl DungeonRecorder {
@@ -29,9 +103,61 @@ impl DungeonRecorder {
             catalog,
             enabled: enabled && has_catalog,
             session: None,
+            options: DungeonOptions::default(),
         }
     }
 
+    pub fn with_options(mut self, options: DungeonOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Notes the zone the player is currently in. Leaving the active run's zone arms a split so
+    /// re-entering the same dungeon later starts a fresh run.
+    pub fn observe_zone(&mut self, zone: &str) {
+        let zone = zone.trim();
+        if zone.is_empty() {
+            return;
+        }
+        let Some(session) = self.session.as_mut() else {
+            return;
+        };
+        let same_zone = self
+            .catalog
+            .as_ref()
+            .and_then(|catalog| catalog.canonical_zone(zone))
+            .is_some_and(|canonical| canonical == session.zone);
+        if !same_zone {
+            session.left_zone = true;
+        }
+    }
+
+    /// Swaps in a reloaded catalog. The open run carries on if its zone is still in the new
+    /// catalog; otherwise it is saved as incomplete.
+    pub fn set_catalog(&mut self, catalog: Arc<DungeonCatalog>) -> DungeonRecorderUpdate {
+        let mut update = DungeonRecorderUpdate::default();
+        let canonical = self
+            .session
+            .as_ref()
+            .map(|session| catalog.canonical_zone(&session.zone).map(str::to_string));
+        match canonical {
+            Some(Some(zone)) => {
+                if let Some(session) = self.session.as_mut() {
+                    session.zone = zone;
+                }
+            }
+            Some(None) => {
+                if let Some(aggregate) = self.end_session(true) {
+                    update.aggregates.push(aggregate);
+                    update.zone_state = Some(DungeonZoneState::Inactive);
+                }
+            }
+            None => {}
+        }
+        self.catalog = Some(catalog);
+        update
+    }
+
     pub fn set_enabled(&mut self, enabled: bool) -> DungeonRecorderUpdate {
         let mut update = DungeonRecorderUpdate::default();
         let effective = enabled && self.catalog.is_some();
@@ -62,7 +188,10 @@ impl DungeonRecorder {
         };
 
         let zone = record.encounter.zone.as_str();
-        let Some(canonical_zone) = catalog.canonical_zone(zone) else {
+        let Some(canonical_zone) = catalog
+            .canonical_zone(zone)
+            .filter(|canonical| !self.options.excludes(zone, canonical))
+        else {
             if self.session.is_some() {
                 if let Some(aggregate) = self.end_session(false) {
                     update.zone_state = Some(DungeonZoneState::Inactive);
@@ -74,19 +203,30 @@ impl DungeonRecorder {
         let canonical_zone = canonical_zone.to_string();
 
         if let Some(session) = self.session.as_mut() {
-            if session.zone != canonical_zone {
+            if session.zone != canonical_zone || starts_new_run(&self.options, session, record) {
                 if let Some(aggregate) = self.end_session(false) {
                     update.aggregates.push(aggregate);
                 }
                 update.zone_state = Some(DungeonZoneState::Active(canonical_zone.clone()));
-                self.session = Some(DungeonSession::new(canonical_zone, record, key));
+                self.session = Some(DungeonSession::new(
+                    canonical_zone,
+                    record,
+                    key,
+                    &self.options,
+                ));
             } else {
                 session.append(record, key);
             }
         } else {
             update.zone_state = Some(DungeonZoneState::Active(canonical_zone.clone()));
-            self.session = Some(DungeonSession::new(canonical_zone, record, key));
+            self.session = Some(DungeonSession::new(
+                canonical_zone,
+                record,
+                key,
+                &self.options,
+            ));
         }
+        update.progress = self.session.as_ref().map(DungeonSession::progress);
 
         update
     }
@@ -106,54 +246,120 @@ impl DungeonRecorder {
     }
 }
 
+/// Same-zone split check for back-to-back runs of one dungeon.
+fn starts_new_run(
+    options: &DungeonOptions,
+    session: &DungeonSession,
+    record: &EncounterRecord,
+) -> bool {
+    if options.split_on_reentry && session.left_zone {
+        return true;
+    }
+    if options.split_gap_secs > 0 {
+        let gap_ms = record.first_seen_ms.saturating_sub(session.last_seen_ms);
+        if gap_ms > options.split_gap_secs.saturating_mul(1000) {
+            return true;
+        }
+    }
+    false
+}
+
 struct DungeonSession {
     zone: String,
     started_ms: u64,
     last_seen_ms: u64,
     party_signature: Vec<String>,
-    total_duration_secs: u64,
+    /// Seconds; only fractional when `subsecond_durations` is set.
+    total_duration: f64,
     total_damage: f64,
     total_healed: f64,
+    total_deaths: u64,
+    wipe_count: u64,
     child_keys: Vec<Vec<u8>>,
     child_titles: Vec<String>,
+    left_zone: bool,
+    zero_duration: ZeroDurationPolicy,
+    subsecond_durations: bool,
 }
 
 impl DungeonSession {
-    fn new(zone: String, record: &EncounterRecord, key: Vec<u8>) -> Self {
-        let mut session = Self {
+    fn new(zone: String, record: &EncounterRecord, key: Vec<u8>, options: &DungeonOptions) -> Self {
+        let mut session = Self::empty(zone, record.first_seen_ms, options);
+        session.party_signature = party_signature(&record.rows);
+        session.append(record, key);
+        session
+    }
+
+    fn empty(zone: String, started_ms: u64, options: &DungeonOptions) -> Self {
+        Self {
             zone,
-            started_ms: record.first_seen_ms,
-            last_seen_ms: record.last_seen_ms,
-            party_signature: party_signature(&record.rows),
-            total_duration_secs: 0,
+            started_ms,
+            last_seen_ms: started_ms,
+            party_signature: Vec::new(),
+            total_duration: 0.0,
             total_damage: 0.0,
             total_healed: 0.0,
+            total_deaths: 0,
+            wipe_count: 0,
             child_keys: Vec::new(),
             child_titles: Vec::new(),
-        };
-        session.append(record, key);
-        session
+            left_zone: false,
+            zero_duration: options.zero_duration,
+            subsecond_durations: options.subsecond_durations,
+        }
     }
 
     fn append(&mut self, record: &EncounterRecord, key: Vec<u8>) {
         self.last_seen_ms = record.last_seen_ms;
         self.child_keys.push(key);
         self.child_titles.push(resolve_title(record));
-        if let Some(duration) = parse_duration_secs(&record.encounter.duration) {
-            self.total_duration_secs = self.total_duration_secs.saturating_add(duration);
+        self.add_totals(record);
+    }
+
+    fn add_totals(&mut self, record: &EncounterRecord) {
+        if let Some(duration) =
+            parse_duration_rate_secs(&record.encounter.duration, self.subsecond_durations)
+        {
+            let duration = if duration > 0.0 {
+                duration
+            } else {
+                self.zero_duration.effective_secs(0) as f64
+            };
+            self.total_duration += duration;
         }
         self.total_damage += parse_number(&record.encounter.damage);
         self.total_healed += parse_number(&record.encounter.healed);
+        self.total_deaths += record
+            .rows
+            .iter()
+            .map(|row| parse_number(&row.deaths).max(0.0) as u64)
+            .sum::<u64>();
+        if is_wipe(&record.rows, &record.death_events()) {
+            self.wipe_count += 1;
+        }
+    }
+
+    fn progress(&self) -> DungeonProgress {
+        DungeonProgress {
+            zone: self.zone.clone(),
+            pulls: self.child_keys.len(),
+            total_damage: self.total_damage,
+            total_duration_secs: self.total_duration,
+        }
+    }
+
+    fn encdps(&self) -> f64 {
+        if self.total_duration > 0.0 {
+            self.total_damage / self.total_duration
+        } else {
+            0.0
+        }
     }
 
     fn into_record(mut self, incomplete: bool) -> DungeonAggregateRecord {
         // Avoid duplicates if all child encounters shared the same key somehow
         dedup_keys(&mut self.child_keys, &mut self.child_titles);
-        let total_encdps = if self.total_duration_secs > 0 {
-            self.total_damage / self.total_duration_secs as f64
-        } else {
-            0.0
-        };
+        let total_encdps = self.encdps();
 
         DungeonAggregateRecord {
             version: SCHEMA_VERSION,
@@ -161,17 +367,39 @@ impl DungeonSession {
             started_ms: self.started_ms,
             last_seen_ms: self.last_seen_ms,
             party_signature: self.party_signature,
-            total_duration_secs: self.total_duration_secs,
+            total_duration_secs: self.total_duration as u64,
             total_damage: self.total_damage,
             total_healed: self.total_healed,
             total_encdps,
+            total_deaths: self.total_deaths,
+            wipe_count: self.wipe_count,
             child_keys: self.child_keys,
             child_titles: self.child_titles,
             incomplete,
+            app_version: app_version(),
         }
     }
 }
 
+/// Recomputes a run's totals (damage, healing, duration, DPS, deaths, wipes) from the pulls it
+/// still lists, e.g. after one of them was deleted. Keys and titles are left as they are.
+pub fn recompute_run_totals(
+    record: &mut DungeonAggregateRecord,
+    children: &[EncounterRecord],
+    options: &DungeonOptions,
+) {
+    let mut session = DungeonSession::empty(record.zone.clone(), record.started_ms, options);
+    for child in children {
+        session.add_totals(child);
+    }
+    record.total_duration_secs = session.total_duration as u64;
+    record.total_damage = session.total_damage;
+    record.total_healed = session.total_healed;
+    record.total_encdps = session.encdps();
+    record.total_deaths = session.total_deaths;
+    record.wipe_count = session.wipe_count;
+}
+
 fn dedup_keys(keys: &mut Vec<Vec<u8>>, titles: &mut Vec<String>) {
     let mut seen = Vec::new();
     let mut filtered_keys = Vec::with_capacity(keys.len());
@@ -193,7 +421,7 @@ fn dedup_keys(keys: &mut Vec<Vec<u8>>, titles: &mut Vec<String>) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::history::types::{now_ms, EncounterRecord};
+    use crate::history::types::{EncounterFrame, EncounterRecord};
     use crate::model::{CombatantRow, EncounterSummary};
 
     fn make_record(
@@ -204,29 +432,25 @@ mod tests {
         healed: &str,
     ) -> EncounterRecord {
         EncounterRecord {
-            version: SCHEMA_VERSION,
-            stored_ms: now_ms(),
             first_seen_ms: 100,
-            last_seen_ms: 200,
-            encounter: EncounterSummary {
-                title: title.to_string(),
-                zone: zone.to_string(),
-                duration: duration.to_string(),
-                encdps: String::new(),
-                damage: damage.to_string(),
-                enchps: String::new(),
-                healed: healed.to_string(),
-                is_active: false,
-            },
             rows: vec![CombatantRow {
                 name: "Alice".into(),
                 job: "NIN".into(),
                 ..Default::default()
             }],
-            raw_last: None,
-            snapshots: 1,
-            saw_active: true,
-            frames: Vec::new(),
+            ..EncounterRecord::fixture(
+                200,
+                EncounterSummary {
+                    title: title.to_string(),
+                    zone: zone.to_string(),
+                    duration: duration.to_string(),
+                    encdps: String::new(),
+                    damage: damage.to_string(),
+                    enchps: String::new(),
+                    healed: healed.to_string(),
+                    is_active: false,
+                },
+            )
         }
     }
 
@@ -238,6 +462,38 @@ mod tests {
         Arc::new(catalog)
     }
 
+    #[test]
+    fn streak_grows_with_complete_runs_and_resets_on_incomplete() {
+        let mut streak = DungeonStreak::default();
+        for _ in 0..3 {
+            streak.record_run(false);
+        }
+        assert_eq!(
+            streak,
+            DungeonStreak {
+                current: 3,
+                longest: 3
+            }
+        );
+
+        streak.record_run(true);
+        assert_eq!(
+            streak,
+            DungeonStreak {
+                current: 0,
+                longest: 3
+            }
+        );
+        streak.record_run(false);
+        assert_eq!(
+            streak,
+            DungeonStreak {
+                current: 1,
+                longest: 3
+            }
+        );
+    }
+
     #[test]
     fn recorder_starts_and_updates_session() {
         let catalog = Some(build_catalog());
@@ -254,6 +510,10 @@ mod tests {
         let update = recorder.on_encounter(&second, vec![2]);
         assert!(update.aggregates.is_empty());
         assert!(update.zone_state.is_none());
+        let progress = update.progress.expect("progress");
+        assert_eq!(progress.zone, "Sastasha");
+        assert_eq!(progress.pulls, 2);
+        assert!((progress.encdps() - 25000.0 / 75.0).abs() < 1e-9);
 
         let flush = recorder.flush(false);
         assert_eq!(flush.aggregates.len(), 1);
@@ -264,6 +524,107 @@ mod tests {
         assert!((agg.total_damage - 25000.0).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn reloaded_catalog_keeps_a_run_whose_zone_remains() {
+        let mut recorder = DungeonRecorder::new(Some(build_catalog()), true);
+        recorder.on_encounter(
+            &make_record("Sastasha", "Pull 1", "00:30", "100", "0"),
+            vec![1],
+        );
+
+        let update = recorder.set_catalog(build_catalog());
+        assert!(update.aggregates.is_empty());
+        let update = recorder.on_encounter(
+            &make_record("Sastasha", "Pull 2", "00:30", "100", "0"),
+            vec![2],
+        );
+        assert_eq!(update.progress.expect("progress").pulls, 2);
+
+        let without = DungeonCatalog::from_str(r#"{ "dungeons": { "Copperbell Mines": {} } }"#)
+            .expect("catalog parse");
+        let update = recorder.set_catalog(Arc::new(without));
+        assert_eq!(update.aggregates.len(), 1);
+        assert!(update.aggregates[0].incomplete);
+        assert!(matches!(
+            update.zone_state,
+            Some(DungeonZoneState::Inactive)
+        ));
+    }
+
+    #[test]
+    fn config_zone_starts_a_run() {
+        let catalog = DungeonCatalog::from_str(r#"{ "dungeons": {} }"#)
+            .expect("catalog parse")
+            .with_extra_zones(&["The Omega Protocol".into()]);
+        let mut recorder = DungeonRecorder::new(Some(Arc::new(catalog)), true);
+        let update = recorder.on_encounter(
+            &make_record("the omega protocol", "Omega", "10:00", "100", "0"),
+            vec![1],
+        );
+        assert!(matches!(
+            update.zone_state,
+            Some(DungeonZoneState::Active(ref zone)) if zone == "The Omega Protocol"
+        ));
+        let flush = recorder.flush(false);
+        assert_eq!(flush.aggregates[0].zone, "The Omega Protocol");
+    }
+
+    #[test]
+    fn run_counts_wiped_pulls() {
+        let mut recorder = DungeonRecorder::new(Some(build_catalog()), true);
+        let mut wipe = make_record("Sastasha", "Pull 1", "01:00", "100", "0");
+        wipe.rows.push(CombatantRow {
+            name: "Bob".into(),
+            job: "WHM".into(),
+            ..Default::default()
+        });
+        let frame = |received_ms, duration: &str, deaths: &str| EncounterFrame {
+            received_ms,
+            encounter: EncounterSummary {
+                duration: duration.into(),
+                ..Default::default()
+            },
+            rows: ["Alice", "Bob"]
+                .iter()
+                .map(|name| CombatantRow {
+                    name: (*name).into(),
+                    deaths: deaths.into(),
+                    ..Default::default()
+                })
+                .collect(),
+            raw: serde_json::Value::Null,
+        };
+        wipe.frames = vec![frame(0, "00:01", "0"), frame(60_000, "01:00", "1")];
+        recorder.on_encounter(&wipe, vec![1]);
+        let clear = make_record("Sastasha", "Pull 2", "01:00", "100", "0");
+        recorder.on_encounter(&clear, vec![2]);
+
+        let flush = recorder.flush(false);
+        assert_eq!(flush.aggregates[0].wipe_count, 1);
+        assert!(!flush.aggregates[0].incomplete);
+    }
+
+    #[test]
+    fn run_sums_deaths_across_pulls() {
+        let with_deaths = |title: &str, deaths: &[&str]| {
+            let mut record = make_record("Sastasha", title, "00:30", "1000", "0");
+            record.rows = deaths
+                .iter()
+                .map(|deaths| CombatantRow {
+                    deaths: (*deaths).into(),
+                    ..Default::default()
+                })
+                .collect();
+            record
+        };
+        let mut recorder = DungeonRecorder::new(Some(build_catalog()), true);
+        recorder.on_encounter(&with_deaths("Pull 1", &["1", "0", "2"]), vec![1]);
+        recorder.on_encounter(&with_deaths("Pull 2", &["", "0"]), vec![2]);
+        recorder.on_encounter(&with_deaths("Boss", &["3", "1"]), vec![3]);
+        let flush = recorder.flush(false);
+        assert_eq!(flush.aggregates[0].total_deaths, 7);
+    }
+
     #[test]
     fn recorder_handles_zone_change() {
         let catalog = Some(build_catalog());
@@ -321,4 +682,120 @@ mod tests {
             Some(DungeonZoneState::Inactive)
         ));
     }
+
+    fn split_options() -> DungeonOptions {
+        DungeonOptions {
+            split_gap_secs: 0,
+            split_on_reentry: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn recorder_splits_same_dungeon_after_overworld_reentry() {
+        let mut recorder =
+            DungeonRecorder::new(Some(build_catalog()), true).with_options(split_options());
+        let first = make_record("Sastasha", "Pull 1", "00:30", "1000", "0");
+        recorder.on_encounter(&first, vec![1]);
+        recorder.observe_zone("Sastasha");
+        recorder.observe_zone("Limsa Lominsa Lower Decks");
+        recorder.observe_zone("Sastasha");
+
+        let second = make_record("Sastasha", "Pull 1", "00:25", "900", "0");
+        let update = recorder.on_encounter(&second, vec![2]);
+        assert_eq!(update.aggregates.len(), 1);
+        assert_eq!(update.aggregates[0].child_keys, vec![vec![1]]);
+        assert!(!update.aggregates[0].incomplete);
+
+        let flush = recorder.flush(false);
+        assert_eq!(flush.aggregates.len(), 1);
+        assert_eq!(flush.aggregates[0].child_keys, vec![vec![2]]);
+    }
+
+    #[test]
+    fn recorder_merges_same_dungeon_without_split_signal() {
+        let mut recorder =
+            DungeonRecorder::new(Some(build_catalog()), true).with_options(split_options());
+        let first = make_record("Sastasha", "Pull 1", "00:30", "1000", "0");
+        recorder.on_encounter(&first, vec![1]);
+        recorder.observe_zone("Sastasha");
+        let second = make_record("Sastasha", "Pull 2", "00:25", "900", "0");
+        assert!(recorder
+            .on_encounter(&second, vec![2])
+            .aggregates
+            .is_empty());
+        assert_eq!(recorder.flush(false).aggregates[0].child_keys.len(), 2);
+    }
+
+    #[test]
+    fn recorder_splits_on_large_gap_between_pulls() {
+        let options = DungeonOptions {
+            split_gap_secs: 600,
+            split_on_reentry: false,
+            ..Default::default()
+        };
+        let mut recorder = DungeonRecorder::new(Some(build_catalog()), true).with_options(options);
+        let first = make_record("Sastasha", "Pull 1", "00:30", "1000", "0");
+        recorder.on_encounter(&first, vec![1]);
+
+        let mut close = make_record("Sastasha", "Pull 2", "00:30", "1000", "0");
+        close.first_seen_ms = first.last_seen_ms + 60_000;
+        close.last_seen_ms = close.first_seen_ms + 30_000;
+        assert!(recorder.on_encounter(&close, vec![2]).aggregates.is_empty());
+
+        let mut later = make_record("Sastasha", "Pull 1", "00:30", "1000", "0");
+        later.first_seen_ms = close.last_seen_ms + 900_000;
+        later.last_seen_ms = later.first_seen_ms + 30_000;
+        let update = recorder.on_encounter(&later, vec![3]);
+        assert_eq!(update.aggregates.len(), 1);
+        assert_eq!(update.aggregates[0].child_keys.len(), 2);
+    }
+
+    #[test]
+    fn zero_duration_pulls_follow_the_aggregate_policy() {
+        let run = |policy| {
+            let options = DungeonOptions {
+                zero_duration: policy,
+                ..Default::default()
+            };
+            let mut recorder =
+                DungeonRecorder::new(Some(build_catalog()), true).with_options(options);
+            let instant = make_record("Sastasha", "Trash", "00:00", "500", "0");
+            recorder.on_encounter(&instant, vec![1]);
+            recorder.flush(false).aggregates.remove(0)
+        };
+
+        let kept = run(ZeroDurationPolicy::Keep);
+        assert_eq!(kept.total_duration_secs, 0);
+        assert_eq!(kept.total_encdps, 0.0);
+
+        let clamped = run(ZeroDurationPolicy::Clamp);
+        assert_eq!(clamped.total_duration_secs, 1);
+        assert_eq!(clamped.total_encdps, 500.0);
+    }
+
+    #[test]
+    fn subsecond_durations_keep_fractions_in_the_run_dps() {
+        let run = |subsecond_durations| {
+            let options = DungeonOptions {
+                subsecond_durations,
+                ..Default::default()
+            };
+            let mut recorder =
+                DungeonRecorder::new(Some(build_catalog()), true).with_options(options);
+            let pull = make_record("Sastasha", "Trash", "00:02.5", "1000", "0");
+            recorder.on_encounter(&pull, vec![1]);
+            let pull = make_record("Sastasha", "Boss", "00:02.5", "1000", "0");
+            recorder.on_encounter(&pull, vec![2]);
+            recorder.flush(false).aggregates.remove(0)
+        };
+
+        let whole = run(false);
+        assert_eq!(whole.total_duration_secs, 4);
+        assert_eq!(whole.total_encdps, 500.0);
+
+        let precise = run(true);
+        assert_eq!(precise.total_duration_secs, 5);
+        assert_eq!(precise.total_encdps, 400.0);
+    }
 }
@@ -1,11 +1,19 @@
+pub(crate) mod bosses;
+pub(crate) mod buffs;
+pub(crate) mod cache;
+pub(crate) mod deaths;
 pub(crate) mod dungeon;
+pub(crate) mod rebuild;
 pub mod recorder;
 pub mod store;
 pub mod types;
 pub(crate) mod util;
 
-pub use recorder::{spawn_recorder, RecorderHandle};
+pub(crate) use dungeon::{DungeonProgress, DungeonStreak};
+pub use recorder::{spawn_recorder, RecorderHandle, RecorderOptions};
+pub(crate) use store::local_date_id;
 pub use store::HistoryStore;
+pub(crate) use types::now_ms;
 pub use types::{
     DungeonAggregateRecord, DungeonHistoryDay, DungeonHistoryItem, EncounterRecord, HistoryDay,
     HistoryEncounterItem,
@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+use tracing::warn;
+
+use crate::dungeon::DungeonCatalog;
+
+use super::dungeon::{DungeonOptions, DungeonRecorder};
+use super::store::HistoryStore;
+
+/// Counts gathered by [`rebuild_dungeons`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RebuildReport {
+    pub encounters_scanned: usize,
+    /// Encounters that already belong to a stored dungeon run and were left alone.
+    pub already_grouped: usize,
+    pub unreadable_records: usize,
+    pub runs_built: usize,
+}
+
+impl fmt::Display for RebuildReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Scanned {} encounters.", self.encounters_scanned)?;
+        let rows = [
+            ("Already part of a dungeon run", self.already_grouped),
+            ("Unreadable records (skipped)", self.unreadable_records),
+        ];
+        for (label, count) in rows {
+            writeln!(f, "  {label:<38} {count}")?;
+        }
+        write!(f, "Reconstructed {} dungeon run(s).", self.runs_built)
+    }
+}
+
+/// Replays stored encounters in chronological order through a fresh [`DungeonRecorder`] and
+/// saves the runs it produces.
+///
+/// Encounters already referenced by a stored run are skipped and end any run being rebuilt,
+/// so running this twice (or after a session with dungeon mode on) never duplicates a run.
+pub fn rebuild_dungeons(
+    store: &HistoryStore,
+    catalog: Arc<DungeonCatalog>,
+    options: DungeonOptions,
+) -> Result<RebuildReport> {
+    let grouped: HashSet<Vec<u8>> = store.dungeon_child_keys()?;
+    let mut recorder = DungeonRecorder::new(Some(catalog), true).with_options(options);
+    let mut report = RebuildReport::default();
+    let mut aggregates = Vec::new();
+
+    for entry in store.iter_encounter_records() {
+        report.encounters_scanned += 1;
+        let (key, record) = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn!(error = ?err, "skipping unreadable encounter during rebuild");
+                report.unreadable_records += 1;
+                continue;
+            }
+        };
+        if grouped.contains(&key) {
+            report.already_grouped += 1;
+            aggregates.extend(recorder.flush(false).aggregates);
+            continue;
+        }
+        aggregates.extend(recorder.on_encounter(&record, key).aggregates);
+    }
+    aggregates.extend(recorder.flush(false).aggregates);
+
+    for aggregate in &aggregates {
+        store.append_dungeon(aggregate)?;
+    }
+    report.runs_built = aggregates.len();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::types::{now_ms, EncounterRecord};
+    use crate::model::EncounterSummary;
+
+    fn record(zone: &str, last_seen_ms: u64) -> EncounterRecord {
+        EncounterRecord::fixture(
+            last_seen_ms,
+            EncounterSummary {
+                title: "Pull".into(),
+                zone: zone.into(),
+                duration: "00:30".into(),
+                damage: "3000".into(),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn rebuild_groups_encounters_once() {
+        let dir = std::env::temp_dir().join(format!(
+            "nekomata-rebuild-{}-{}",
+            std::process::id(),
+            now_ms()
+        ));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let store = HistoryStore::open(&dir.join("encounters.sled")).expect("open history");
+        let catalog = Arc::new(
+            DungeonCatalog::from_str(
+                r#"{ "dungeons": { "Sastasha": {}, "The Tam-Tara Deepcroft": {} } }"#,
+            )
+            .expect("catalog"),
+        );
+
+        let base = now_ms();
+        for (offset, zone) in [
+            (0, "Sastasha"),
+            (60_000, "Sastasha"),
+            (120_000, "Limsa Lominsa"),
+            (180_000, "The Tam-Tara Deepcroft"),
+        ] {
+            store.append(&record(zone, base + offset)).expect("append");
+        }
+
+        let report =
+            rebuild_dungeons(&store, catalog.clone(), DungeonOptions::default()).expect("rebuild");
+        assert_eq!(report.encounters_scanned, 4);
+        assert_eq!(report.runs_built, 2);
+        assert_eq!(store.dungeon_child_keys().expect("keys").len(), 3);
+
+        let again = rebuild_dungeons(&store, catalog, DungeonOptions::default()).expect("rerun");
+        assert_eq!(again.already_grouped, 3);
+        assert_eq!(again.runs_built, 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,17 +1,74 @@
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use serde_json::Value;
 use tokio::sync::{mpsc, oneshot, Mutex};
 use tokio::task;
+use tokio::time::{self, Instant};
 
+use crate::config::AppConfig;
 use crate::dungeon::DungeonCatalog;
 use crate::errors::{AppError, AppErrorKind};
+use crate::export;
 use crate::model::{AppEvent, CombatantRow, EncounterSummary};
+use crate::persist;
 
-use super::dungeon::{DungeonRecorder, DungeonRecorderUpdate, DungeonZoneState};
+use super::deaths::DeathEvent;
+use super::dungeon::{
+    DungeonOptions, DungeonRecorder, DungeonRecorderUpdate, DungeonStreak, DungeonZoneState,
+};
 use super::store::HistoryStore;
 use super::types::{DungeonAggregateRecord, EncounterFrame, EncounterRecord, EncounterSnapshot};
-use super::util::{parse_duration_secs, parse_number};
+use super::util::{
+    format_duration_label, parse_duration_millis, parse_duration_secs, parse_number,
+    NeverActivePolicy, ZeroDurationPolicy,
+};
+
+/// Recording policies read from the config file at startup.
+#[derive(Debug, Clone, Default)]
+pub struct RecorderOptions {
+    /// Trim idle frames at the head/tail of an encounter; off keeps the raw frame stream.
+    pub trim_idle_frames: bool,
+    /// How `00:00` encounters are saved; also applied to dungeon aggregates.
+    pub zero_duration: ZeroDurationPolicy,
+    /// Whether pulls that never reported `is_active` are saved (flagged) or dropped.
+    pub never_active: NeverActivePolicy,
+    /// How long an inactive encounter waits for combat to resume before it is saved.
+    pub end_grace: Duration,
+    /// Encounters shorter than this are dropped; 0 keeps everything.
+    pub min_encounter_secs: u64,
+    /// File the dungeon completion streak is kept in; `None` leaves the streak alone.
+    pub streak_path: Option<PathBuf>,
+    /// Directory of the per-day session CSV logs; `None` keeps no log.
+    pub session_csv_dir: Option<PathBuf>,
+    /// Character names used to pick "my dps" for the session log.
+    pub self_names: Vec<String>,
+    pub dungeon: DungeonOptions,
+}
+
+impl From<&AppConfig> for RecorderOptions {
+    fn from(cfg: &AppConfig) -> Self {
+        let zero_duration = ZeroDurationPolicy::from_config_key(&cfg.zero_duration_policy);
+        Self {
+            trim_idle_frames: cfg.trim_idle_frames,
+            zero_duration,
+            never_active: NeverActivePolicy::from_config_key(&cfg.never_active_policy),
+            end_grace: Duration::from_secs(cfg.encounter_end_grace_secs),
+            min_encounter_secs: cfg.min_encounter_secs,
+            streak_path: Some(persist::dungeon_streak_path()),
+            session_csv_dir: cfg.session_summary_csv.then(export::exports_dir),
+            self_names: cfg.self_names.clone(),
+            dungeon: DungeonOptions {
+                split_gap_secs: cfg.dungeon_split_gap_secs,
+                split_on_reentry: cfg.dungeon_split_on_reentry,
+                zero_duration,
+                subsecond_durations: cfg.subsecond_durations,
+                exclude_zones: cfg.dungeon_exclude_zones.clone(),
+            },
+        }
+    }
+}
 
 pub struct RecorderHandle {
     inner: Arc<RecorderInner>,
@@ -23,6 +80,17 @@ struct RecorderInner {
 }
 
 impl RecorderHandle {
+    /// A handle that drops everything sent to it, for running without recording history.
+    pub fn disabled() -> Self {
+        let (tx, _) = mpsc::unbounded_channel();
+        Self {
+            inner: Arc::new(RecorderInner {
+                tx,
+                shutdown: Mutex::new(None),
+            }),
+        }
+    }
+
     pub fn record(&self, snapshot: EncounterSnapshot) {
         let _ = self
             .inner
@@ -43,10 +111,38 @@ impl RecorderHandle {
         let _ = self.inner.tx.send(RecorderMessage::Flush);
     }
 
+    /// Saves the encounter in progress but keeps the open dungeon run going, for a
+    /// deliberate reconnect that should not end the run.
+    pub fn flush_encounter(&self) {
+        let _ = self.inner.tx.send(RecorderMessage::FlushEncounter);
+    }
+
+    pub fn zone_changed(&self, zone: String) {
+        let _ = self.inner.tx.send(RecorderMessage::ZoneChanged(zone));
+    }
+
+    /// Notes a death for the encounter in progress, timestamped on arrival.
+    pub fn combatant_died(&self, name: String) {
+        let _ = self.inner.tx.send(RecorderMessage::Death {
+            name,
+            at_ms: super::types::now_ms(),
+        });
+    }
+
     pub fn set_dungeon_mode_enabled(&self, enabled: bool) {
         let _ = self.inner.tx.send(RecorderMessage::SetDungeonMode(enabled));
     }
 
+    /// Replaces the dungeon catalog mid-session, e.g. after the catalog file was edited.
+    pub fn set_dungeon_catalog(&self, catalog: Arc<DungeonCatalog>) {
+        let _ = self.inner.tx.send(RecorderMessage::SetCatalog(catalog));
+    }
+
+    /// Drops the in-progress encounter without saving it; the rest of that pull is ignored too.
+    pub fn discard_active(&self) {
+        let _ = self.inner.tx.send(RecorderMessage::DiscardActive);
+    }
+
     pub fn cut_dungeon_session(&self) {
         let _ = self.inner.tx.send(RecorderMessage::CutDungeonSession);
     }
@@ -75,8 +171,13 @@ impl Clone for RecorderHandle {
 enum RecorderMessage {
     Snapshot(Box<EncounterSnapshot>),
     Flush,
+    FlushEncounter,
+    ZoneChanged(String),
+    Death { name: String, at_ms: u64 },
     SetDungeonMode(bool),
+    SetCatalog(Arc<DungeonCatalog>),
     CutDungeonSession,
+    DiscardActive,
     Shutdown,
 }
 
@@ -85,22 +186,45 @@ pub fn spawn_recorder(
     event_tx: mpsc::UnboundedSender<AppEvent>,
     dungeon_catalog: Option<Arc<DungeonCatalog>>,
     dungeon_mode_enabled: bool,
+    options: RecorderOptions,
 ) -> RecorderHandle {
     let (tx, mut rx) = mpsc::unbounded_channel();
     let (shutdown_tx, shutdown_rx) = oneshot::channel();
     tokio::spawn(async move {
-        let mut worker =
-            RecorderWorker::new(store, event_tx, dungeon_catalog, dungeon_mode_enabled);
+        let mut worker = RecorderWorker::new(
+            store,
+            event_tx,
+            dungeon_catalog,
+            dungeon_mode_enabled,
+            options,
+        );
         loop {
-            match rx.recv().await {
+            let message = match worker.end_deadline {
+                Some(deadline) => tokio::select! {
+                    message = rx.recv() => message,
+                    () = time::sleep_until(deadline) => {
+                        worker.on_end_grace_elapsed().await;
+                        continue;
+                    }
+                },
+                None => rx.recv().await,
+            };
+            match message {
                 Some(RecorderMessage::Snapshot(snapshot)) => worker.on_snapshot(*snapshot).await,
                 Some(RecorderMessage::Flush) => worker.on_flush().await,
+                Some(RecorderMessage::FlushEncounter) => worker.on_flush_encounter().await,
+                Some(RecorderMessage::ZoneChanged(zone)) => worker.observe_zone(zone),
+                Some(RecorderMessage::Death { name, at_ms }) => worker.on_death(name, at_ms),
                 Some(RecorderMessage::SetDungeonMode(enabled)) => {
                     worker.on_toggle_dungeon_mode(enabled).await;
                 }
+                Some(RecorderMessage::SetCatalog(catalog)) => {
+                    worker.on_set_catalog(catalog).await;
+                }
                 Some(RecorderMessage::CutDungeonSession) => {
                     worker.on_cut_dungeon_session().await;
                 }
+                Some(RecorderMessage::DiscardActive) => worker.on_discard_active(),
                 Some(RecorderMessage::Shutdown) => {
                     worker.on_flush().await;
                     break;
@@ -124,8 +248,17 @@ pub fn spawn_recorder(
 struct RecorderWorker {
     store: Arc<HistoryStore>,
     current: Option<ActiveEncounter>,
+    /// Pull thrown away via `discard_active`, tracked until it ends so it isn't re-recorded.
+    discarded: Option<ActiveEncounter>,
     events: mpsc::UnboundedSender<AppEvent>,
     dungeon: DungeonRecorder,
+    /// Zone seen while an encounter was active; handed to the dungeon recorder only after that
+    /// encounter is finalized so the pull still counts toward the zone it started in.
+    pending_zone: Option<String>,
+    /// When the inactive current encounter gets saved unless combat resumes first; only set
+    /// while waiting out `options.end_grace`.
+    end_deadline: Option<Instant>,
+    options: RecorderOptions,
 }
 
 impl RecorderWorker {
@@ -134,16 +267,50 @@ impl RecorderWorker {
         events: mpsc::UnboundedSender<AppEvent>,
         dungeon_catalog: Option<Arc<DungeonCatalog>>,
         dungeon_mode_enabled: bool,
+        options: RecorderOptions,
     ) -> Self {
         Self {
             store,
             current: None,
+            discarded: None,
             events,
-            dungeon: DungeonRecorder::new(dungeon_catalog, dungeon_mode_enabled),
+            dungeon: DungeonRecorder::new(dungeon_catalog, dungeon_mode_enabled)
+                .with_options(options.dungeon.clone()),
+            pending_zone: None,
+            end_deadline: None,
+            options,
+        }
+    }
+
+    fn observe_zone(&mut self, zone: String) {
+        if self.current.is_some() {
+            self.pending_zone = Some(zone);
+        } else {
+            self.dungeon.observe_zone(&zone);
+        }
+    }
+
+    /// Deaths outside a recorded pull (or during a discarded one) are dropped.
+    fn on_death(&mut self, name: String, at_ms: u64) {
+        if let Some(active) = self.current.as_mut() {
+            active.deaths.push((at_ms, name));
         }
     }
 
     async fn on_snapshot(&mut self, snapshot: EncounterSnapshot) {
+        self.observe_zone(snapshot.encounter.zone.clone());
+        if let Some(discarded) = self.discarded.as_mut() {
+            // Swallow the remainder of a discarded pull until ACT ends it or a new one starts.
+            if !should_rollover(discarded, &snapshot) {
+                let ended = !snapshot.encounter.is_active;
+                discarded.latest_summary = snapshot.encounter;
+                if ended {
+                    self.discarded = None;
+                }
+                return;
+            }
+            self.discarded = None;
+        }
         if self.current.is_none() {
             if !snapshot.encounter.is_active {
                 return;
@@ -165,24 +332,64 @@ impl RecorderWorker {
             self.current = Some(ActiveEncounter::from_snapshot(snapshot));
         }
 
-        if let Some(active) = self.current.as_ref() {
-            if !active.latest_summary.is_active {
+        let Some(active) = self.current.as_ref() else {
+            return;
+        };
+        if active.latest_summary.is_active {
+            self.end_deadline = None;
+        } else if self.options.end_grace.is_zero() {
+            self.flush_active().await;
+        } else {
+            let grace = self.options.end_grace;
+            let deadline = *self
+                .end_deadline
+                .get_or_insert_with(|| Instant::now() + grace);
+            if Instant::now() >= deadline {
                 self.flush_active().await;
             }
         }
     }
 
+    async fn on_end_grace_elapsed(&mut self) {
+        if self.end_deadline.is_some() {
+            self.flush_active().await;
+        }
+    }
+
     async fn on_flush(&mut self) {
+        self.discarded = None;
         self.flush_active().await;
         let update = self.dungeon.flush(true);
         self.handle_dungeon_update(update).await;
     }
 
+    async fn on_flush_encounter(&mut self) {
+        self.discarded = None;
+        self.flush_active().await;
+    }
+
     async fn on_toggle_dungeon_mode(&mut self, enabled: bool) {
         let update = self.dungeon.set_enabled(enabled);
         self.handle_dungeon_update(update).await;
     }
 
+    async fn on_set_catalog(&mut self, catalog: Arc<DungeonCatalog>) {
+        let update = self.dungeon.set_catalog(catalog);
+        self.handle_dungeon_update(update).await;
+    }
+
+    fn on_discard_active(&mut self) {
+        self.end_deadline = None;
+        if let Some(mut active) = self.current.take() {
+            // Only the summary is needed to recognise follow-up snapshots of the same pull.
+            active.frames.clear();
+            self.discarded = Some(active);
+        }
+        if let Some(zone) = self.pending_zone.take() {
+            self.dungeon.observe_zone(&zone);
+        }
+    }
+
     async fn on_cut_dungeon_session(&mut self) {
         self.flush_active().await;
         let update = self.dungeon.flush(false);
@@ -193,52 +400,74 @@ impl RecorderWorker {
         for aggregate in update.aggregates {
             self.persist_dungeon_record(aggregate).await;
         }
-        if let Some(zone_state) = update.zone_state {
-            match zone_state {
-                DungeonZoneState::Active(zone) => {
-                    let _ = self.events.send(AppEvent::DungeonSessionUpdate {
-                        active_zone: Some(zone),
-                    });
-                }
-                DungeonZoneState::Inactive => {
-                    let _ = self
-                        .events
-                        .send(AppEvent::DungeonSessionUpdate { active_zone: None });
-                }
-            }
-        }
+        let event = match (update.zone_state, update.progress) {
+            (_, Some(progress)) => AppEvent::DungeonSessionUpdate {
+                active_zone: Some(progress.zone.clone()),
+                progress: Some(progress),
+            },
+            (Some(DungeonZoneState::Active(zone)), None) => AppEvent::DungeonSessionUpdate {
+                active_zone: Some(zone),
+                progress: None,
+            },
+            (Some(DungeonZoneState::Inactive), None) => AppEvent::DungeonSessionUpdate {
+                active_zone: None,
+                progress: None,
+            },
+            (None, None) => return,
+        };
+        let _ = self.events.send(event);
     }
 
+    /// Saves the active encounter, then replays any zone change seen during it, so the dungeon
+    /// recorder always sees the finished pull before it learns the party moved on.
     async fn flush_active(&mut self) {
+        self.end_deadline = None;
         if let Some(active) = self.current.take() {
-            let store = Arc::clone(&self.store);
-            let record = EncounterRecord::from_active(active);
-            if !record.saw_active && record.rows.is_empty() {
-                return;
+            self.persist_encounter(active).await;
+        }
+        if let Some(zone) = self.pending_zone.take() {
+            self.dungeon.observe_zone(&zone);
+        }
+    }
+
+    async fn persist_encounter(&mut self, mut active: ActiveEncounter) {
+        if self.options.trim_idle_frames {
+            active.trim_idle_frames();
+        }
+        let store = Arc::clone(&self.store);
+        let mut record = EncounterRecord::from_active(active);
+        if !keep_never_active(&record, self.options.never_active) {
+            return;
+        }
+        if !apply_zero_duration_policy(&mut record, self.options.zero_duration) {
+            return;
+        }
+        if !meets_min_duration(&record, self.options.min_encounter_secs) {
+            return;
+        }
+        match task::spawn_blocking(move || store.append(&record).map(|key| (key, record))).await {
+            Ok(Ok((key, record))) => {
+                self.log_session_row(&record).await;
+                let key_bytes = key.as_bytes();
+                let update = self.dungeon.on_encounter(&record, key_bytes);
+                self.handle_dungeon_update(update).await;
             }
-            match task::spawn_blocking(move || store.append(&record).map(|key| (key, record))).await
-            {
-                Ok(Ok((key, record))) => {
-                    let key_bytes = key.as_bytes();
-                    let update = self.dungeon.on_encounter(&record, key_bytes);
-                    self.handle_dungeon_update(update).await;
-                }
-                Ok(Err(err)) => {
-                    let message = format!("Failed to persist encounter history: {err}");
-                    Self::report_error(&self.events, message, AppErrorKind::Storage);
-                }
-                Err(err) => {
-                    let message = format!("History recorder task join error: {err}");
-                    Self::report_error(&self.events, message, AppErrorKind::History);
-                }
+            Ok(Err(err)) => {
+                let message = format!("Failed to persist encounter history: {err}");
+                Self::report_error(&self.events, message, AppErrorKind::Storage);
+            }
+            Err(err) => {
+                let message = format!("History recorder task join error: {err}");
+                Self::report_error(&self.events, message, AppErrorKind::History);
             }
         }
     }
 
     async fn persist_dungeon_record(&self, record: DungeonAggregateRecord) {
         let store = Arc::clone(&self.store);
+        let incomplete = record.incomplete;
         match task::spawn_blocking(move || store.append_dungeon(&record)).await {
-            Ok(Ok(_)) => {}
+            Ok(Ok(_)) => self.update_streak(incomplete).await,
             Ok(Err(err)) => {
                 let message = format!("Failed to persist dungeon aggregate: {err}");
                 Self::report_error(&self.events, message, AppErrorKind::Storage);
@@ -250,6 +479,52 @@ impl RecorderWorker {
         }
     }
 
+    /// Appends a saved encounter to the day's session CSV, if the log is enabled.
+    async fn log_session_row(&self, record: &EncounterRecord) {
+        let Some(dir) = self.options.session_csv_dir.clone() else {
+            return;
+        };
+        let row = export::SessionRow::from_record(record, &self.options.self_names);
+        let append = move || export::append_session_row(&dir, &row);
+        match task::spawn_blocking(append).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => {
+                let message = format!("Failed to append to the session log: {err}");
+                Self::report_error(&self.events, message, AppErrorKind::Storage);
+            }
+            Err(err) => {
+                let message = format!("Session log task join error: {err}");
+                Self::report_error(&self.events, message, AppErrorKind::History);
+            }
+        }
+    }
+
+    /// Counts a saved run toward the completion streak and tells the UI the new value.
+    async fn update_streak(&self, incomplete: bool) {
+        let Some(path) = self.options.streak_path.clone() else {
+            return;
+        };
+        let update = move || -> anyhow::Result<DungeonStreak> {
+            let mut streak = persist::load_dungeon_streak(&path)?;
+            streak.record_run(incomplete);
+            persist::save_dungeon_streak(&path, &streak)?;
+            Ok(streak)
+        };
+        match task::spawn_blocking(update).await {
+            Ok(Ok(streak)) => {
+                let _ = self.events.send(AppEvent::DungeonStreak { streak });
+            }
+            Ok(Err(err)) => {
+                let message = format!("Failed to update the dungeon streak: {err}");
+                Self::report_error(&self.events, message, AppErrorKind::Storage);
+            }
+            Err(err) => {
+                let message = format!("Dungeon streak task join error: {err}");
+                Self::report_error(&self.events, message, AppErrorKind::History);
+            }
+        }
+    }
+
     fn report_error(events: &mpsc::UnboundedSender<AppEvent>, message: String, kind: AppErrorKind) {
         let error = AppError::new(kind, message);
         let _ = events.send(AppEvent::SystemError { error });
@@ -260,11 +535,16 @@ impl RecorderWorker {
 struct ActiveEncounter {
     first_seen_ms: u64,
     last_seen_ms: u64,
+    /// First non-empty zone reported for the pull; the saved record keeps it even if later
+    /// snapshots report another zone.
+    zone: String,
     latest_summary: EncounterSummary,
     latest_rows: Vec<CombatantRow>,
     last_raw: Value,
     saw_active: bool,
     frames: Vec<EncounterFrame>,
+    /// Arrival time and name of each death logged during the pull.
+    deaths: Vec<(u64, String)>,
 }
 
 impl ActiveEncounter {
@@ -280,11 +560,13 @@ impl ActiveEncounter {
         Self {
             first_seen_ms: received_ms,
             last_seen_ms: received_ms,
+            zone: encounter.zone.clone(),
             latest_summary: encounter,
             latest_rows: rows,
             last_raw: raw,
             saw_active: is_active,
             frames: vec![frame],
+            deaths: Vec::new(),
         }
     }
 
@@ -297,12 +579,78 @@ impl ActiveEncounter {
             received_ms,
         } = snapshot;
         let frame = EncounterFrame::new(received_ms, encounter.clone(), rows.clone(), raw.clone());
+        if self.zone.trim().is_empty() {
+            self.zone = encounter.zone.clone();
+        }
         self.latest_summary = encounter;
         self.latest_rows = rows;
         self.last_raw = raw;
         self.frames.push(frame);
         self.saw_active |= self.latest_summary.is_active;
     }
+
+    /// Narrows the frame list (and the first/last seen window) to the span with activity.
+    fn trim_idle_frames(&mut self) {
+        let span = active_frame_span(&self.frames);
+        if span.len() == self.frames.len() {
+            return;
+        }
+        self.frames.truncate(span.end);
+        self.frames.drain(..span.start);
+        if let (Some(first), Some(last)) = (self.frames.first(), self.frames.last()) {
+            self.first_seen_ms = first.received_ms;
+            self.last_seen_ms = last.received_ms;
+        }
+        let secs = self.last_seen_ms.saturating_sub(self.first_seen_ms) / 1000;
+        if secs > 0 {
+            self.rescale_to(secs);
+        }
+    }
+
+    /// Rewrites the duration and the per-second rates so they cover `secs` seconds; the totals
+    /// stay as reported.
+    fn rescale_to(&mut self, secs: u64) {
+        let rate = |total: f64| total / secs as f64;
+        let summary = &mut self.latest_summary;
+        summary.duration = format_duration_label(secs);
+        summary.encdps = format!("{:.2}", rate(parse_number(&summary.damage)));
+        summary.enchps = format!("{:.2}", rate(parse_number(&summary.healed)));
+        for row in &mut self.latest_rows {
+            row.encdps = rate(row.damage);
+            row.encdps_str = format!("{:.2}", row.encdps);
+            row.enchps = rate(row.healed);
+            row.enchps_str = format!("{:.2}", row.enchps);
+        }
+    }
+}
+
+/// Index range of frames where damage or healing totals moved.
+///
+/// A frame counts as active when its totals differ from the previous frame (or are non-zero for
+/// the first frame). Leading and trailing frames without progress fall outside the range; when no
+/// frame shows progress the full range is returned so nothing is discarded.
+fn active_frame_span(frames: &[EncounterFrame]) -> std::ops::Range<usize> {
+    let totals = |frame: &EncounterFrame| {
+        (
+            parse_number(&frame.encounter.damage),
+            parse_number(&frame.encounter.healed),
+        )
+    };
+    let mut first = None;
+    let mut last = None;
+    let mut previous = (0.0, 0.0);
+    for (idx, frame) in frames.iter().enumerate() {
+        let current = totals(frame);
+        if current != previous {
+            first.get_or_insert(idx);
+            last = Some(idx);
+        }
+        previous = current;
+    }
+    match (first, last) {
+        (Some(first), Some(last)) => first..last + 1,
+        _ => 0..frames.len(),
+    }
 }
 
 impl EncounterRecord {
@@ -310,18 +658,36 @@ impl EncounterRecord {
         let ActiveEncounter {
             first_seen_ms,
             last_seen_ms,
-            latest_summary,
+            zone,
+            mut latest_summary,
             latest_rows,
             last_raw,
             saw_active,
             frames,
+            deaths,
         } = active;
+        if !zone.trim().is_empty() {
+            latest_summary.zone = zone;
+        }
         let snapshots = frames.len() as u32;
+        let max_combatants = super::types::max_row_count(&frames, &latest_rows);
         let raw_last = if let Some(frame) = frames.last() {
             Some(frame.raw.clone())
         } else {
             Some(last_raw)
         };
+        // The log reports every death, enemies and the boss included; keep the combatants
+        // on the table, and only deaths inside the (possibly trimmed) pull window.
+        let deaths = deaths
+            .into_iter()
+            .filter(|(at_ms, name)| {
+                *at_ms >= first_seen_ms && latest_rows.iter().any(|row| row.name == *name)
+            })
+            .map(|(at_ms, name)| DeathEvent {
+                ms: at_ms - first_seen_ms,
+                name,
+            })
+            .collect();
 
         Self {
             version: super::types::SCHEMA_VERSION,
@@ -334,6 +700,9 @@ impl EncounterRecord {
             snapshots,
             saw_active,
             frames,
+            app_version: super::types::app_version(),
+            max_combatants,
+            deaths,
         }
     }
 }
@@ -354,6 +723,41 @@ impl EncounterFrame {
     }
 }
 
+/// Records that never went active are always dropped when empty; otherwise the policy decides.
+fn keep_never_active(record: &EncounterRecord, policy: NeverActivePolicy) -> bool {
+    if record.saw_active {
+        return true;
+    }
+    !record.rows.is_empty() && policy == NeverActivePolicy::Mark
+}
+
+/// Whether an encounter lasted at least `min_secs`, by ACT's duration or its wall time,
+/// whichever is longer, so a short ACT timer on a long pull never drops it.
+fn meets_min_duration(record: &EncounterRecord, min_secs: u64) -> bool {
+    let parsed = parse_duration_secs(&record.encounter.duration).unwrap_or(0);
+    parsed.max(record.wall_duration_secs()) >= min_secs
+}
+
+/// Normalises an encounter that ACT reported as lasting `00:00`; returns false to drop it.
+fn apply_zero_duration_policy(record: &mut EncounterRecord, policy: ZeroDurationPolicy) -> bool {
+    // Compare milliseconds so a sub-second pull ("00:00.4") still counts as a real span.
+    if parse_duration_millis(&record.encounter.duration) != Some(0) {
+        return true;
+    }
+    let encounter = &mut record.encounter;
+    match policy {
+        ZeroDurationPolicy::Discard => return false,
+        // The saved rates stay as ACT sent them; the history view shows "—" for them.
+        ZeroDurationPolicy::Keep => {}
+        ZeroDurationPolicy::Clamp => {
+            encounter.duration = "00:01".to_string();
+            encounter.encdps = format!("{:.2}", parse_number(&encounter.damage));
+            encounter.enchps = format!("{:.2}", parse_number(&encounter.healed));
+        }
+    }
+    true
+}
+
 fn should_rollover(active: &ActiveEncounter, incoming: &EncounterSnapshot) -> bool {
     let previous = &active.latest_summary;
     let next = &incoming.encounter;
@@ -410,11 +814,36 @@ mod tests {
     use tokio::sync::mpsc;
 
     use crate::dungeon::DungeonCatalog;
-    use crate::history::types::now_ms;
+    use crate::history::store::TempStore;
     use crate::history::util::parse_number;
 
     use super::*;
 
+    /// A worker over a fresh temp store, keeping its event receiver open for the test. The
+    /// worker drops before the store removes its directory.
+    struct Scaffold {
+        worker: RecorderWorker,
+        store: TempStore,
+        _events: mpsc::UnboundedReceiver<AppEvent>,
+    }
+
+    impl Scaffold {
+        /// `catalog` is dungeon catalog JSON; dungeon mode is on whenever one is given.
+        fn new(tag: &str, catalog: Option<&str>, options: RecorderOptions) -> Self {
+            let store = TempStore::new(tag);
+            let (tx, events) = mpsc::unbounded_channel();
+            let catalog = catalog
+                .map(|json| Arc::new(DungeonCatalog::from_str(json).expect("catalog parse")));
+            let dungeon_mode = catalog.is_some();
+            let worker = RecorderWorker::new(store.shared(), tx, catalog, dungeon_mode, options);
+            Self {
+                worker,
+                store,
+                _events: events,
+            }
+        }
+    }
+
     fn build_snapshot(active: bool, duration: &str, damage: &str) -> EncounterSnapshot {
         let encounter = EncounterSummary {
             title: "Test Encounter".into(),
@@ -480,10 +909,191 @@ mod tests {
         let record = EncounterRecord::from_active(active);
         assert_eq!(record.snapshots, 3);
         assert_eq!(record.frames.len(), 3);
+        assert_eq!(
+            record.app_version.as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
         assert!(record.frames.first().unwrap().encounter.is_active);
         assert!(!record.frames.last().unwrap().encounter.is_active);
     }
 
+    #[test]
+    fn zero_duration_policy_keeps_discards_or_clamps() {
+        let instant = || {
+            let mut record = EncounterRecord::from_active(ActiveEncounter::from_snapshot(
+                build_snapshot(false, "00:00", "4200"),
+            ));
+            record.encounter.encdps = "inf".into();
+            record
+        };
+
+        let mut kept = instant();
+        assert!(apply_zero_duration_policy(
+            &mut kept,
+            ZeroDurationPolicy::Keep
+        ));
+        assert_eq!(kept.encounter.duration, "00:00");
+        assert_eq!(kept.encounter.encdps, "inf");
+
+        assert!(!apply_zero_duration_policy(
+            &mut instant(),
+            ZeroDurationPolicy::Discard
+        ));
+
+        let mut clamped = instant();
+        assert!(apply_zero_duration_policy(
+            &mut clamped,
+            ZeroDurationPolicy::Clamp
+        ));
+        assert_eq!(clamped.encounter.duration, "00:01");
+        assert_eq!(clamped.encounter.encdps, "4200.00");
+
+        let mut normal = EncounterRecord::from_active(ActiveEncounter::from_snapshot(
+            build_snapshot(false, "00:30", "4200"),
+        ));
+        let before = normal.encounter.clone();
+        assert!(apply_zero_duration_policy(
+            &mut normal,
+            ZeroDurationPolicy::Discard
+        ));
+        assert_eq!(normal.encounter, before);
+
+        let mut short = EncounterRecord::from_active(ActiveEncounter::from_snapshot(
+            build_snapshot(false, "00:00.4", "4200"),
+        ));
+        assert!(apply_zero_duration_policy(
+            &mut short,
+            ZeroDurationPolicy::Discard
+        ));
+        assert_eq!(short.encounter.duration, "00:00.4");
+    }
+
+    #[test]
+    fn never_active_records_follow_the_policy() {
+        let residual = EncounterRecord::from_active(ActiveEncounter::from_snapshot(
+            build_snapshot(false, "00:04", "3200"),
+        ));
+        assert!(!residual.saw_active);
+        assert!(keep_never_active(&residual, NeverActivePolicy::Mark));
+        assert!(!keep_never_active(&residual, NeverActivePolicy::Drop));
+
+        let mut empty = residual.clone();
+        empty.rows.clear();
+        assert!(!keep_never_active(&empty, NeverActivePolicy::Mark));
+
+        let real = EncounterRecord::from_active(ActiveEncounter::from_snapshot(build_snapshot(
+            true, "00:04", "3200",
+        )));
+        assert!(keep_never_active(&real, NeverActivePolicy::Drop));
+    }
+
+    #[test]
+    fn short_encounters_are_dropped_below_the_minimum() {
+        let pull = |duration: &str, wall_secs: u64| {
+            let mut record = EncounterRecord::from_active(ActiveEncounter::from_snapshot(
+                build_snapshot(true, duration, "3200"),
+            ));
+            record.first_seen_ms = 1_000;
+            record.last_seen_ms = 1_000 + wall_secs * 1000;
+            record
+        };
+        let stray = pull("00:03", 3);
+        assert_eq!(stray.wall_duration_secs(), 3);
+        assert!(!meets_min_duration(&stray, 10));
+        assert!(meets_min_duration(&stray, 0));
+
+        let fight = pull("00:30", 30);
+        assert_eq!(fight.wall_duration_secs(), 30);
+        assert!(meets_min_duration(&fight, 10));
+
+        // ACT's timer lags the real pull; the wall time alone is enough to keep it.
+        assert!(meets_min_duration(&pull("00:03", 30), 10));
+    }
+
+    fn frames_with_damage(damage: &[&str]) -> Vec<EncounterFrame> {
+        damage
+            .iter()
+            .enumerate()
+            .map(|(idx, dmg)| {
+                let snapshot = build_snapshot(true, "00:01", dmg);
+                EncounterFrame::new(
+                    1_000 * (idx as u64 + 1),
+                    snapshot.encounter,
+                    snapshot.rows,
+                    snapshot.raw,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn trimming_drops_leading_idle_frames() {
+        let frames = frames_with_damage(&["0", "0", "0", "100", "200"]);
+        assert_eq!(active_frame_span(&frames), 3..5);
+    }
+
+    #[test]
+    fn trimming_drops_trailing_idle_frames() {
+        let frames = frames_with_damage(&["100", "200", "300", "300", "300"]);
+        assert_eq!(active_frame_span(&frames), 0..3);
+    }
+
+    #[test]
+    fn trimming_keeps_fully_active_and_fully_idle_encounters() {
+        let active = frames_with_damage(&["100", "200", "300"]);
+        assert_eq!(active_frame_span(&active), 0..3);
+        let idle = frames_with_damage(&["0", "0", "0"]);
+        assert_eq!(active_frame_span(&idle), 0..3);
+    }
+
+    #[test]
+    fn trimmed_encounter_tightens_seen_window() {
+        let mut active = ActiveEncounter::from_snapshot(build_snapshot(true, "00:00", "0"));
+        active.frames = frames_with_damage(&["0", "100", "200", "200"]);
+        active.trim_idle_frames();
+        assert_eq!(active.frames.len(), 2);
+        assert_eq!(active.first_seen_ms, 2_000);
+        assert_eq!(active.last_seen_ms, 3_000);
+    }
+
+    #[test]
+    fn trimmed_encounter_rates_cover_only_the_kept_frames() {
+        let mut active = ActiveEncounter::from_snapshot(build_snapshot(false, "00:06", "300"));
+        active.frames = frames_with_damage(&["0", "0", "100", "200", "300", "300"]);
+        active.trim_idle_frames();
+        assert_eq!(active.last_seen_ms - active.first_seen_ms, 2_000);
+        assert_eq!(active.latest_summary.duration, "00:02");
+        assert_eq!(active.latest_summary.encdps, "150.00");
+        let row = &active.latest_rows[0];
+        assert_eq!(row.encdps, 500.0);
+        assert_eq!(row.encdps_str, "500.00");
+    }
+
+    #[test]
+    fn logged_deaths_are_stored_relative_to_the_pull_start() {
+        let mut active = ActiveEncounter::from_snapshot(build_snapshot(true, "00:00", "0"));
+        active.frames = frames_with_damage(&["0", "100", "200"]);
+        active.latest_rows.push(CombatantRow {
+            name: "Bob".into(),
+            ..Default::default()
+        });
+        // Alice's first death falls before the trimmed start; the dummy is not on the table.
+        active.deaths = vec![
+            (1_500, "Alice".into()),
+            (2_500, "Alice".into()),
+            (3_000, "Striking Dummy".into()),
+            (4_250, "Bob".into()),
+        ];
+        active.trim_idle_frames();
+        let record = EncounterRecord::from_active(active);
+        let offsets: Vec<(u64, &str)> = record
+            .deaths
+            .iter()
+            .map(|death| (death.ms, death.name.as_str()))
+            .collect();
+        assert_eq!(offsets, [(500, "Alice"), (2_250, "Bob")]);
+    }
+
     #[test]
     fn snapshot_activity_detects_idle_state() {
         let idle = EncounterSnapshot::new(
@@ -514,17 +1124,46 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn recorder_aggregates_dungeon_runs_end_to_end() {
-        let base = std::env::temp_dir().join(format!("nekomata-test-{}", now_ms()));
-        std::fs::create_dir_all(&base).expect("create temp history dir");
-        let db_path = base.join("encounters.sled");
-        let store = Arc::new(HistoryStore::open(&db_path).expect("open history"));
+    async fn discarding_active_encounter_skips_persistence() {
+        let mut scaffold = Scaffold::new("discard", None, RecorderOptions::default());
+        let worker = &mut scaffold.worker;
+
+        worker
+            .on_snapshot(build_snapshot(true, "00:05", "500"))
+            .await;
+        worker.on_discard_active();
+        assert!(worker.current.is_none());
 
-        let (tx, _rx) = mpsc::unbounded_channel();
-        let catalog = DungeonCatalog::from_str(r#"{ "dungeons": { "Sastasha": {} } }"#)
-            .expect("catalog parse");
-        let mut worker = RecorderWorker::new(store.clone(), tx, Some(Arc::new(catalog)), true);
+        // The rest of the misfired pull, including its end, is ignored.
+        worker
+            .on_snapshot(build_snapshot(true, "00:06", "600"))
+            .await;
+        assert!(worker.current.is_none());
+        worker
+            .on_snapshot(build_snapshot(false, "00:07", "700"))
+            .await;
+        assert!(worker.current.is_none());
+        assert!(worker.discarded.is_none());
+
+        worker
+            .on_snapshot(build_snapshot(true, "00:01", "100"))
+            .await;
+        let fresh = worker.current.as_ref().expect("new encounter started");
+        assert_eq!(fresh.frames.len(), 1);
+        assert_eq!(fresh.latest_summary.duration, "00:01");
+
+        assert!(scaffold.store.load_dates().expect("load dates").is_empty());
+    }
+
+    #[tokio::test]
+    async fn recorder_aggregates_dungeon_runs_end_to_end() {
+        let mut scaffold = Scaffold::new(
+            "end-to-end",
+            Some(r#"{ "dungeons": { "Sastasha": {} } }"#),
+            RecorderOptions::default(),
+        );
 
+        #[allow(clippy::too_many_arguments)]
         fn snapshot(
             zone: &str,
             title: &str,
@@ -604,11 +1243,12 @@ mod tests {
         ];
 
         for snap in snapshots {
-            worker.on_snapshot(snap).await;
+            scaffold.worker.on_snapshot(snap).await;
         }
 
-        worker.on_flush().await;
+        scaffold.worker.on_flush().await;
 
+        let store = &scaffold.store;
         let days = store.load_dungeon_days().expect("load days");
         assert_eq!(days.len(), 1);
         let day = &days[0];
@@ -628,9 +1268,167 @@ mod tests {
         assert!(!aggregate.incomplete);
         assert!((aggregate.total_damage - 2500.0).abs() < f64::EPSILON);
         assert!((aggregate.total_healed - 450.0).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn inactive_blip_within_grace_keeps_one_encounter() {
+        let options = RecorderOptions {
+            end_grace: Duration::from_secs(30),
+            ..RecorderOptions::default()
+        };
+        let mut scaffold = Scaffold::new("grace", None, options);
+        let (worker, store) = (&mut scaffold.worker, &scaffold.store);
+
+        worker
+            .on_snapshot(build_snapshot(true, "00:05", "500"))
+            .await;
+        worker
+            .on_snapshot(build_snapshot(false, "00:06", "600"))
+            .await;
+        assert!(worker.end_deadline.is_some());
+        worker
+            .on_snapshot(build_snapshot(true, "00:08", "800"))
+            .await;
+        assert!(worker.end_deadline.is_none());
+        worker
+            .on_snapshot(build_snapshot(false, "00:10", "1000"))
+            .await;
+        assert_eq!(store.iter_encounter_records().count(), 0);
+
+        worker.on_end_grace_elapsed().await;
+        let records: Vec<_> = store
+            .iter_encounter_records()
+            .map(|entry| entry.expect("record").1)
+            .collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].frames.len(), 4);
+        assert!(worker.current.is_none());
+    }
+
+    #[tokio::test]
+    async fn zone_change_mid_encounter_keeps_the_first_zone() {
+        let mut scaffold = Scaffold::new(
+            "zone-change",
+            Some(r#"{ "dungeons": { "Sastasha": {} } }"#),
+            RecorderOptions::default(),
+        );
+        let (worker, store) = (&mut scaffold.worker, &scaffold.store);
+
+        let in_zone = |zone: &str, active: bool, duration: &str, damage: &str| {
+            let mut snapshot = build_snapshot(active, duration, damage);
+            snapshot.encounter.zone = zone.into();
+            snapshot
+        };
+        worker
+            .on_snapshot(in_zone("Sastasha", true, "00:10", "1000"))
+            .await;
+        worker.observe_zone("Middle La Noscea".into());
+        worker
+            .on_snapshot(in_zone("Middle La Noscea", true, "00:20", "2000"))
+            .await;
+        worker
+            .on_snapshot(in_zone("Middle La Noscea", false, "00:20", "2000"))
+            .await;
+        worker.on_flush().await;
 
-        drop(worker);
-        drop(store);
-        let _ = std::fs::remove_dir_all(&base);
+        let records: Vec<_> = store
+            .iter_encounter_records()
+            .map(|entry| entry.expect("record").1)
+            .collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].encounter.zone, "Sastasha");
+        assert_eq!(records[0].frames.len(), 3);
+
+        let days = store.load_dungeon_days().expect("load days");
+        assert_eq!(days.len(), 1);
+        let runs = store
+            .load_dungeon_summaries(&days[0].iso_date)
+            .expect("load summaries");
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].child_count, 1);
+    }
+
+    #[tokio::test]
+    async fn excluded_zones_are_recorded_but_never_aggregated() {
+        let options = RecorderOptions {
+            dungeon: DungeonOptions {
+                exclude_zones: vec!["middle la noscea".into()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut scaffold = Scaffold::new(
+            "exclude-zone",
+            Some(r#"{ "dungeons": { "Sastasha": {}, "Middle La Noscea": {} } }"#),
+            options,
+        );
+        let (worker, store) = (&mut scaffold.worker, &scaffold.store);
+
+        let pull = |zone: &str, active: bool, damage: &str| {
+            let mut snapshot = build_snapshot(active, "00:10", damage);
+            snapshot.encounter.zone = zone.into();
+            snapshot
+        };
+        for damage in ["1000", "2000"] {
+            worker
+                .on_snapshot(pull("Middle La Noscea", true, damage))
+                .await;
+            worker
+                .on_snapshot(pull("Middle La Noscea", false, damage))
+                .await;
+        }
+        worker.on_flush().await;
+
+        assert_eq!(store.iter_encounter_records().count(), 2);
+        assert!(store.load_dungeon_days().expect("load days").is_empty());
+    }
+
+    #[tokio::test]
+    async fn flushing_the_encounter_keeps_the_dungeon_run_open() {
+        let mut scaffold = Scaffold::new(
+            "flush-encounter",
+            Some(r#"{ "dungeons": { "Sastasha": {} } }"#),
+            RecorderOptions::default(),
+        );
+        let pull = |active: bool, duration: &str, damage: &str| {
+            let mut snapshot = build_snapshot(active, duration, damage);
+            snapshot.encounter.zone = "Sastasha".into();
+            snapshot
+        };
+        scaffold
+            .worker
+            .on_snapshot(pull(true, "00:20", "2000"))
+            .await;
+        scaffold.worker.on_flush_encounter().await;
+        assert!(scaffold.worker.current.is_none());
+        assert_eq!(scaffold.store.iter_encounter_records().count(), 1);
+        assert!(scaffold
+            .store
+            .load_dungeon_days()
+            .expect("load days")
+            .is_empty());
+
+        scaffold
+            .worker
+            .on_snapshot(pull(true, "00:30", "3000"))
+            .await;
+        scaffold.worker.on_flush().await;
+        let days = scaffold.store.load_dungeon_days().expect("load days");
+        assert_eq!(days.len(), 1);
+        let runs = scaffold
+            .store
+            .load_dungeon_summaries(&days[0].iso_date)
+            .expect("load summaries");
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].child_count, 2);
+    }
+
+    #[tokio::test]
+    async fn disabled_handle_accepts_messages_and_shuts_down() {
+        let handle = RecorderHandle::disabled();
+        handle.record(build_snapshot(true, "00:05", "500"));
+        handle.zone_changed("Sastasha".into());
+        handle.flush();
+        handle.shutdown().await;
     }
 }
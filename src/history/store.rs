@@ -1,19 +1,95 @@
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use anyhow::{Context, Result};
-use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use chrono::{DateTime, Local, TimeZone};
 
 use crate::config;
+use crate::model::ClockFormat;
 
+use super::cache::RecordCache;
+use super::dungeon::{recompute_run_totals, DungeonOptions};
 use super::types::{
-    DateSummaryRecord, DungeonAggregateRecord, DungeonHistoryDay, DungeonHistoryItem,
-    DungeonSummaryRecord, EncounterRecord, EncounterSummaryRecord, HistoryDay,
-    HistoryEncounterItem, HistoryKey, DUNGEON_NAMESPACE, ENCOUNTER_NAMESPACE,
-    META_SCHEMA_VERSION_KEY, SCHEMA_VERSION,
+    format_date_label, format_dungeon_date_label, ContentScale, DateSummaryRecord,
+    DungeonAggregateRecord, DungeonHistoryDay, DungeonHistoryItem, DungeonSummaryRecord,
+    EncounterRecord, EncounterSummaryRecord, HistoryDay, HistoryEncounterItem, HistoryKey,
+    DUNGEON_NAMESPACE, ENCOUNTER_NAMESPACE, META_SCHEMA_VERSION_KEY, SCHEMA_VERSION,
 };
-use super::util::resolve_title;
+use super::util::{format_duration_label, party_signature, resolve_title, signature_jobs};
+
+/// Counts gathered by [`HistoryStore::check_integrity`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IntegrityReport {
+    pub encounters_scanned: usize,
+    pub dungeon_runs_scanned: usize,
+    pub unparseable_records: usize,
+    pub orphaned_summaries: usize,
+    pub missing_summaries: usize,
+    pub dangling_index_entries: usize,
+    pub missing_index_entries: usize,
+    pub dangling_child_keys: usize,
+    pub repaired: bool,
+}
+
+impl IntegrityReport {
+    pub fn issue_count(&self) -> usize {
+        self.unparseable_records
+            + self.orphaned_summaries
+            + self.missing_summaries
+            + self.dangling_index_entries
+            + self.missing_index_entries
+            + self.dangling_child_keys
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.issue_count() == 0
+    }
+}
+
+impl fmt::Display for IntegrityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Scanned {} encounters and {} dungeon runs.",
+            self.encounters_scanned, self.dungeon_runs_scanned
+        )?;
+        let rows = [
+            ("Unparseable records", self.unparseable_records),
+            ("Orphaned or unreadable summaries", self.orphaned_summaries),
+            ("Records missing a summary", self.missing_summaries),
+            (
+                "Day-index entries without a record",
+                self.dangling_index_entries,
+            ),
+            (
+                "Records missing from the day index",
+                self.missing_index_entries,
+            ),
+            (
+                "Dungeon child keys without a record",
+                self.dangling_child_keys,
+            ),
+        ];
+        for (label, count) in rows {
+            writeln!(f, "  {label:<38} {count}")?;
+        }
+        if self.is_clean() {
+            write!(f, "No problems found.")
+        } else if self.repaired {
+            write!(f, "Repaired {} issue(s).", self.issue_count())
+        } else {
+            write!(
+                f,
+                "Found {} issue(s). Run `nekomata doctor --repair` to fix them.",
+                self.issue_count()
+            )
+        }
+    }
+}
 
 /// Thin wrapper around the sled database.
 pub struct HistoryStore {
@@ -26,6 +102,8 @@ pub struct HistoryStore {
     meta: sled::Tree,
     db: sled::Db,
     root: PathBuf,
+    /// Recently loaded encounter records; every write to an encounter key must evict it.
+    record_cache: Mutex<RecordCache>,
 }
 
 impl HistoryStore {
@@ -71,6 +149,7 @@ impl HistoryStore {
             meta,
             db,
             root: path.to_path_buf(),
+            record_cache: Mutex::new(RecordCache::default()),
         };
         store.init_schema()?;
         Ok(store)
@@ -143,9 +222,131 @@ impl HistoryStore {
         self.encounters
             .remove(key.as_bytes())
             .context("Failed to delete encounter record")?;
+        self.with_record_cache(|cache| cache.remove(&key.as_bytes()));
         Ok(())
     }
 
+    /// Deletes encounter and dungeon records stored before `cutoff_ms` (by end time) along
+    /// with their summaries and day-index entries; days left empty disappear from the date
+    /// lists. Encounters still referenced by a kept dungeon run are left alone so the run
+    /// stays complete. Returns how many records were removed.
+    pub fn prune_before(&self, cutoff_ms: u64) -> Result<usize> {
+        let pruned_runs = prune_range(
+            &self.dungeon_runs,
+            &self.dungeon_summaries,
+            DUNGEON_NAMESPACE,
+            cutoff_ms,
+            &HashSet::new(),
+        )?;
+        drop_from_date_index(&self.dungeon_dates, &pruned_runs)?;
+
+        let kept_children = self.dungeon_child_keys()?;
+        let pruned_encounters = prune_range(
+            &self.encounters,
+            &self.encounter_summaries,
+            ENCOUNTER_NAMESPACE,
+            cutoff_ms,
+            &kept_children,
+        )?;
+        drop_from_date_index(&self.date_index, &pruned_encounters)?;
+        self.with_record_cache(|cache| {
+            for key in &pruned_encounters {
+                cache.remove(key);
+            }
+        });
+        Ok(pruned_runs.len() + pruned_encounters.len())
+    }
+
+    /// Deletes one encounter with its summary and day-index entry. Dungeon runs that list it
+    /// as a pull drop it from their child keys so they don't point at a missing record, and
+    /// recount their totals from the pulls left (durations handled as `options` says).
+    pub fn delete_encounter(&self, key: &[u8], options: &DungeonOptions) -> Result<()> {
+        self.encounters
+            .remove(key)
+            .context("Failed to delete encounter record")?;
+        self.encounter_summaries
+            .remove(key)
+            .context("Failed to delete encounter summary")?;
+        self.with_record_cache(|cache| cache.remove(key));
+        drop_from_date_index(&self.date_index, &HashSet::from([key.to_vec()]))?;
+
+        for entry in self.dungeon_runs.iter() {
+            let (run_key, bytes) = entry.context("Failed to iterate dungeon runs")?;
+            let Ok(mut record) = serde_cbor::from_slice::<DungeonAggregateRecord>(bytes.as_ref())
+            else {
+                continue;
+            };
+            let Some(idx) = record.child_keys.iter().position(|child| child == key) else {
+                continue;
+            };
+            record.child_keys.remove(idx);
+            if idx < record.child_titles.len() {
+                record.child_titles.remove(idx);
+            }
+            let children: Vec<EncounterRecord> = record
+                .child_keys
+                .iter()
+                .filter_map(|child| self.load_encounter_record(child).ok())
+                .collect();
+            recompute_run_totals(&mut record, &children, options);
+            let summary = self.build_dungeon_summary(&run_key, &record);
+            let bytes = serde_cbor::to_vec(&record)
+                .context("Failed to serialize dungeon aggregate record")?;
+            let summary_bytes = serde_cbor::to_vec(&summary)
+                .context("Failed to serialize dungeon summary record")?;
+            self.dungeon_runs
+                .insert(&run_key, bytes)
+                .context("Failed to persist dungeon aggregate record")?;
+            self.dungeon_summaries
+                .insert(&run_key, summary_bytes)
+                .context("Failed to persist dungeon summary")?;
+        }
+        Ok(())
+    }
+
+    /// Deletes one dungeon run with its summary and day-index entry. Its pulls stay in the
+    /// encounter history; only pulls that are neither listed on an encounter day nor part of
+    /// another run are deleted with it. Returns how many of those orphans were removed.
+    pub fn delete_dungeon_run(&self, key: &[u8]) -> Result<usize> {
+        let child_keys = match self.load_dungeon_record(key) {
+            Ok(record) => record.child_keys,
+            Err(_) => Vec::new(),
+        };
+        self.dungeon_runs
+            .remove(key)
+            .context("Failed to delete dungeon aggregate record")?;
+        self.dungeon_summaries
+            .remove(key)
+            .context("Failed to delete dungeon summary")?;
+        drop_from_date_index(&self.dungeon_dates, &HashSet::from([key.to_vec()]))?;
+        if child_keys.is_empty() {
+            return Ok(0);
+        }
+
+        let mut referenced = self.dungeon_child_keys()?;
+        for entry in self.date_index.iter() {
+            let (_, value) = entry.context("Failed to iterate history date index")?;
+            if let Ok(record) = serde_cbor::from_slice::<DateSummaryRecord>(value.as_ref()) {
+                referenced.extend(record.encounter_ids);
+            }
+        }
+        let mut removed = 0;
+        for child in child_keys
+            .iter()
+            .filter(|child| !referenced.contains(*child))
+        {
+            self.encounters
+                .remove(child)
+                .context("Failed to delete orphaned encounter record")?;
+            self.encounter_summaries
+                .remove(child)
+                .context("Failed to delete orphaned encounter summary")?;
+            self.with_record_cache(|cache| cache.remove(child));
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
     #[allow(dead_code)]
     pub fn tree(&self, name: &str) -> Result<sled::Tree> {
         self.db
@@ -158,19 +359,14 @@ impl HistoryStore {
         key: &[u8],
         record: &EncounterRecord,
     ) -> EncounterSummaryRecord {
-        let date_time = millis_to_local(record.last_seen_ms);
-        let (date_id, time_label, timestamp_label) = match date_time {
-            Some(dt) => (
-                dt.date_naive().to_string(),
-                dt.format("%H:%M").to_string(),
-                dt.format("%Y-%m-%d %H:%M:%S").to_string(),
-            ),
-            None => (
-                "unknown".to_string(),
-                "--:--".to_string(),
-                "unknown".to_string(),
-            ),
-        };
+        // Stored labels are only a fallback; the views format `last_seen_ms` on the configured
+        // clock.
+        let ms = record.last_seen_ms;
+        let date_id = local_date_id(ms);
+        let time_label = stored_time_label(ms);
+        let timestamp_label = ClockFormat::H24
+            .format_local(ms, true, true)
+            .unwrap_or_else(|| "unknown".to_string());
 
         let base_title = resolve_title(record);
 
@@ -188,6 +384,9 @@ impl HistoryStore {
             zone: record.encounter.zone.clone(),
             snapshots: record.snapshots,
             frames: record.frames.len() as u32,
+            saw_active: record.saw_active,
+            max_combatants: record.combatant_count(),
+            party_signature: party_signature(&record.rows),
         }
     }
 
@@ -196,11 +395,8 @@ impl HistoryStore {
         key: &[u8],
         record: &DungeonAggregateRecord,
     ) -> DungeonSummaryRecord {
-        let start_time = millis_to_local(record.started_ms);
-        let (date_id, started_label) = match start_time {
-            Some(dt) => (dt.date_naive().to_string(), dt.format("%H:%M").to_string()),
-            None => ("unknown".to_string(), "--:--".to_string()),
-        };
+        let date_id = local_date_id(record.started_ms);
+        let started_label = stored_time_label(record.started_ms);
 
         DungeonSummaryRecord {
             key: key.to_vec(),
@@ -212,6 +408,7 @@ impl HistoryStore {
             total_damage: record.total_damage,
             total_healed: record.total_healed,
             total_encdps: record.total_encdps,
+            total_deaths: record.total_deaths,
             child_count: record.child_keys.len(),
             incomplete: record.incomplete,
             party_signature: record.party_signature.clone(),
@@ -361,11 +558,45 @@ impl HistoryStore {
             }
         }
 
-        summaries.sort_by(|a, b| b.last_seen_ms.cmp(&a.last_seen_ms));
+        summaries.sort_by_key(|s| Reverse(s.last_seen_ms));
 
         Ok(build_history_items_from_summaries(summaries))
     }
 
+    /// Encounters across all days whose title or zone contains `query` (ignoring case),
+    /// newest first and at most `limit` of them. A blank query matches nothing. Titles are
+    /// not numbered per day here, since results mix days.
+    pub fn search_encounters(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<HistoryEncounterItem>> {
+        let needle = query.trim().to_lowercase();
+        if needle.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut items = Vec::new();
+        // Keys sort by end time, so walking backwards yields the newest encounters first.
+        for entry in self.encounter_summaries.iter().rev() {
+            if items.len() >= limit {
+                break;
+            }
+            let (_, bytes) = entry.context("Failed to iterate encounter summaries")?;
+            let Ok(summary) = serde_cbor::from_slice::<EncounterSummaryRecord>(bytes.as_ref())
+            else {
+                continue;
+            };
+            let hit = [&summary.base_title, &summary.encounter_title, &summary.zone]
+                .iter()
+                .any(|field| field.to_lowercase().contains(&needle));
+            if hit {
+                let title = summary.base_title.clone();
+                items.push(encounter_item(summary, title, 1));
+            }
+        }
+        Ok(items)
+    }
+
     pub fn load_dungeon_summaries(&self, date_id: &str) -> Result<Vec<DungeonHistoryItem>> {
         let key = date_id.as_bytes();
         let Some(bytes) = self
@@ -392,11 +623,86 @@ impl HistoryStore {
             }
         }
 
-        summaries.sort_by(|a, b| b.last_seen_ms.cmp(&a.last_seen_ms));
+        summaries.sort_by_key(|s| Reverse(s.last_seen_ms));
         Ok(build_dungeon_history_items(summaries))
     }
 
+    /// Date and list label ("Title · HH:MM", on `clock`) of one encounter from the summary
+    /// index.
+    pub fn encounter_label(
+        &self,
+        key: &[u8],
+        clock: ClockFormat,
+    ) -> Result<Option<(String, String)>> {
+        let Some(bytes) = self
+            .encounter_summaries
+            .get(key)
+            .context("Failed to read encounter summary")?
+        else {
+            return Ok(None);
+        };
+        let summary: EncounterSummaryRecord = serde_cbor::from_slice(bytes.as_ref())
+            .context("Failed to deserialize encounter summary")?;
+        let time = clock
+            .format_local(summary.last_seen_ms, false, false)
+            .unwrap_or(summary.time_label);
+        let label = format!("{} · {time}", summary.base_title);
+        Ok(Some((summary.date_id, label)))
+    }
+
+    /// Date and list label ("Zone · HH:MM", on `clock`) of one dungeon run from the summary
+    /// index.
+    pub fn dungeon_label(
+        &self,
+        key: &[u8],
+        clock: ClockFormat,
+    ) -> Result<Option<(String, String)>> {
+        let Some(bytes) = self
+            .dungeon_summaries
+            .get(key)
+            .context("Failed to read dungeon summary")?
+        else {
+            return Ok(None);
+        };
+        let summary: DungeonSummaryRecord = serde_cbor::from_slice(bytes.as_ref())
+            .context("Failed to deserialize dungeon summary record")?;
+        let time = clock
+            .format_local(summary.started_ms, false, false)
+            .unwrap_or(summary.started_label);
+        let label = format!("{} · {time}", summary.zone);
+        Ok(Some((summary.date_id, label)))
+    }
+
+    /// Keys of every encounter that already belongs to a stored dungeon run.
+    pub fn dungeon_child_keys(&self) -> Result<HashSet<Vec<u8>>> {
+        let mut keys = HashSet::new();
+        for entry in self.dungeon_runs.iter() {
+            let (_, bytes) = entry.context("Failed to iterate dungeon runs")?;
+            let record: DungeonAggregateRecord = serde_cbor::from_slice(bytes.as_ref())
+                .context("Failed to deserialize dungeon aggregate record")?;
+            keys.extend(record.child_keys);
+        }
+        Ok(keys)
+    }
+
+    /// Writes the record under `key`, frames included, as pretty JSON to
+    /// `<dir>/<end time>.json` and returns the path. The file deserializes back into an
+    /// [`EncounterRecord`]; exporting the same encounter again overwrites it.
+    pub fn export_encounter(&self, key: &[u8], dir: &Path) -> Result<PathBuf> {
+        let record = self.load_encounter_record(key)?;
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Unable to create export directory {}", dir.display()))?;
+        let path = dir.join(format!("{}.json", record.export_stem()));
+        let data = serde_json::to_vec_pretty(&record).context("Failed to serialize encounter")?;
+        fs::write(&path, data).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Loads an encounter record, serving recently loaded ones from memory.
     pub fn load_encounter_record(&self, key: &[u8]) -> Result<EncounterRecord> {
+        if let Some(record) = self.with_record_cache(|cache| cache.get(key)).flatten() {
+            return Ok(record);
+        }
         let Some(bytes) = self
             .encounters
             .get(key)
@@ -404,7 +710,16 @@ impl HistoryStore {
         else {
             anyhow::bail!("Encounter record not found");
         };
-        serde_cbor::from_slice(bytes.as_ref()).context("Failed to deserialize encounter record")
+        let record: EncounterRecord = serde_cbor::from_slice(bytes.as_ref())
+            .context("Failed to deserialize encounter record")?;
+        let cached = record.clone();
+        self.with_record_cache(|cache| cache.insert(key, bytes.len(), cached));
+        Ok(record)
+    }
+
+    /// Runs `f` on the record cache; a poisoned lock just skips caching.
+    fn with_record_cache<T>(&self, f: impl FnOnce(&mut RecordCache) -> T) -> Option<T> {
+        self.record_cache.lock().ok().map(|mut cache| f(&mut cache))
     }
 
     pub fn load_dungeon_record(&self, key: &[u8]) -> Result<DungeonAggregateRecord> {
@@ -419,6 +734,201 @@ impl HistoryStore {
             .context("Failed to deserialize dungeon aggregate record")
     }
 
+    /// Lazily walks every stored encounter record in key (chronological) order.
+    ///
+    /// Records are decoded one at a time as the iterator advances, so tooling can scan the
+    /// whole database without holding it in memory. Unreadable entries surface as errors
+    /// and do not end the iteration.
+    pub fn iter_encounter_records(
+        &self,
+    ) -> impl Iterator<Item = Result<(Vec<u8>, EncounterRecord)>> + '_ {
+        self.encounters.iter().map(|entry| {
+            let (key, value) = entry.context("Failed to iterate encounter records")?;
+            let record = serde_cbor::from_slice(value.as_ref())
+                .with_context(|| format!("Failed to deserialize encounter record {key:?}"))?;
+            Ok((key.to_vec(), record))
+        })
+    }
+
+    /// Scans every tree for inconsistencies between records, summaries and day indexes.
+    ///
+    /// With `repair` set, unreadable and orphaned entries are removed, dangling dungeon children
+    /// are dropped, and missing summaries/index entries are rebuilt from the surviving records.
+    pub fn check_integrity(&self, repair: bool) -> Result<IntegrityReport> {
+        let mut report = IntegrityReport {
+            repaired: repair,
+            ..Default::default()
+        };
+
+        if repair {
+            self.with_record_cache(RecordCache::clear);
+        }
+        let mut encounter_keys = HashSet::new();
+        let mut unreadable = Vec::new();
+        for entry in self.encounters.iter() {
+            let (key, value) = entry.context("Failed to iterate encounter records")?;
+            report.encounters_scanned += 1;
+            if serde_cbor::from_slice::<EncounterRecord>(value.as_ref()).is_ok() {
+                encounter_keys.insert(key.to_vec());
+            } else {
+                unreadable.push(key);
+            }
+        }
+        report.unparseable_records += unreadable.len();
+        if repair {
+            for key in unreadable {
+                self.encounters
+                    .remove(key)
+                    .context("Failed to remove unreadable encounter record")?;
+            }
+        }
+
+        let mut summaries = Vec::new();
+        let mut orphaned = Vec::new();
+        for entry in self.encounter_summaries.iter() {
+            let (key, value) = entry.context("Failed to iterate encounter summaries")?;
+            match serde_cbor::from_slice::<EncounterSummaryRecord>(value.as_ref()) {
+                Ok(summary) if encounter_keys.contains(key.as_ref()) => summaries.push(summary),
+                _ => orphaned.push(key),
+            }
+        }
+        report.orphaned_summaries += orphaned.len();
+        if repair {
+            for key in orphaned {
+                self.encounter_summaries
+                    .remove(key)
+                    .context("Failed to remove orphaned encounter summary")?;
+            }
+        }
+
+        let summarized: HashSet<Vec<u8>> = summaries.iter().map(|s| s.key.clone()).collect();
+        for key in encounter_keys.difference(&summarized) {
+            report.missing_summaries += 1;
+            if repair {
+                let record = self.load_encounter_record(key)?;
+                let summary = self.build_encounter_summary(key, &record);
+                let bytes = serde_cbor::to_vec(&summary)
+                    .context("Failed to serialize rebuilt encounter summary")?;
+                self.encounter_summaries
+                    .insert(key.as_slice(), bytes)
+                    .context("Failed to persist rebuilt encounter summary")?;
+                summaries.push(summary);
+            }
+        }
+
+        let indexed = check_date_index(&self.date_index, &encounter_keys, repair, &mut report)?;
+        for summary in summaries.iter().filter(|s| !indexed.contains(&s.key)) {
+            report.missing_index_entries += 1;
+            if repair {
+                self.update_date_summary(summary)?;
+            }
+        }
+
+        let mut dungeon_keys = HashSet::new();
+        for entry in self.dungeon_runs.iter() {
+            let (key, value) = entry.context("Failed to iterate dungeon aggregate records")?;
+            report.dungeon_runs_scanned += 1;
+            let Ok(mut record) = serde_cbor::from_slice::<DungeonAggregateRecord>(value.as_ref())
+            else {
+                report.unparseable_records += 1;
+                if repair {
+                    self.dungeon_runs
+                        .remove(&key)
+                        .context("Failed to remove unreadable dungeon record")?;
+                }
+                continue;
+            };
+            dungeon_keys.insert(key.to_vec());
+
+            let dangling = record
+                .child_keys
+                .iter()
+                .filter(|child| !encounter_keys.contains(*child))
+                .count();
+            if dangling == 0 {
+                continue;
+            }
+            report.dangling_child_keys += dangling;
+            if repair {
+                let mut titles = std::mem::take(&mut record.child_titles).into_iter();
+                let mut kept_keys = Vec::with_capacity(record.child_keys.len());
+                for child in std::mem::take(&mut record.child_keys) {
+                    let title = titles.next();
+                    if encounter_keys.contains(&child) {
+                        kept_keys.push(child);
+                        record.child_titles.extend(title);
+                    }
+                }
+                record.child_keys = kept_keys;
+                let bytes = serde_cbor::to_vec(&record)
+                    .context("Failed to serialize repaired dungeon record")?;
+                self.dungeon_runs
+                    .insert(&key, bytes)
+                    .context("Failed to persist repaired dungeon record")?;
+                let summary = self.build_dungeon_summary(&key, &record);
+                let bytes = serde_cbor::to_vec(&summary)
+                    .context("Failed to serialize repaired dungeon summary")?;
+                self.dungeon_summaries
+                    .insert(&key, bytes)
+                    .context("Failed to persist repaired dungeon summary")?;
+            }
+        }
+
+        let mut dungeon_summaries = Vec::new();
+        let mut orphaned = Vec::new();
+        for entry in self.dungeon_summaries.iter() {
+            let (key, value) = entry.context("Failed to iterate dungeon summaries")?;
+            match serde_cbor::from_slice::<DungeonSummaryRecord>(value.as_ref()) {
+                Ok(summary) if dungeon_keys.contains(key.as_ref()) => {
+                    dungeon_summaries.push(summary)
+                }
+                _ => orphaned.push(key),
+            }
+        }
+        report.orphaned_summaries += orphaned.len();
+        if repair {
+            for key in orphaned {
+                self.dungeon_summaries
+                    .remove(key)
+                    .context("Failed to remove orphaned dungeon summary")?;
+            }
+        }
+
+        let summarized: HashSet<Vec<u8>> =
+            dungeon_summaries.iter().map(|s| s.key.clone()).collect();
+        for key in dungeon_keys.difference(&summarized) {
+            report.missing_summaries += 1;
+            if repair {
+                let record = self.load_dungeon_record(key)?;
+                let summary = self.build_dungeon_summary(key, &record);
+                let bytes = serde_cbor::to_vec(&summary)
+                    .context("Failed to serialize rebuilt dungeon summary")?;
+                self.dungeon_summaries
+                    .insert(key.as_slice(), bytes)
+                    .context("Failed to persist rebuilt dungeon summary")?;
+                dungeon_summaries.push(summary);
+            }
+        }
+
+        let indexed = check_date_index(&self.dungeon_dates, &dungeon_keys, repair, &mut report)?;
+        for summary in dungeon_summaries
+            .iter()
+            .filter(|s| !indexed.contains(&s.key))
+        {
+            report.missing_index_entries += 1;
+            if repair {
+                self.update_dungeon_date_summary(summary)?;
+            }
+        }
+
+        if repair {
+            self.db
+                .flush()
+                .context("Failed to flush repaired history database")?;
+        }
+        Ok(report)
+    }
+
     fn init_schema(&self) -> Result<()> {
         match self
             .meta
@@ -429,7 +939,13 @@ impl HistoryStore {
                 let mut arr = [0u8; 4];
                 arr.copy_from_slice(&bytes);
                 let version = u32::from_be_bytes(arr);
-                if version != SCHEMA_VERSION {
+                if version < SCHEMA_VERSION {
+                    // Older records still deserialize (new fields default), so just move the
+                    // marker forward.
+                    self.meta
+                        .insert(META_SCHEMA_VERSION_KEY, &SCHEMA_VERSION.to_be_bytes())
+                        .context("Failed to upgrade history schema version")?;
+                } else if version != SCHEMA_VERSION {
                     eprintln!(
                         "Warning: history schema version mismatch (stored: {}, expected: {})",
                         version, SCHEMA_VERSION
@@ -458,32 +974,121 @@ impl HistoryStore {
     }
 }
 
-fn millis_to_local(ms: u64) -> Option<DateTime<Local>> {
-    let millis = i64::try_from(ms).ok()?;
-    Local.timestamp_millis_opt(millis).single()
+/// Removes records (and their summaries) in `namespace` whose key timestamp is before
+/// `cutoff_ms`, except for keys in `keep`. Returns the removed keys.
+fn prune_range(
+    records: &sled::Tree,
+    summaries: &sled::Tree,
+    namespace: &str,
+    cutoff_ms: u64,
+    keep: &HashSet<Vec<u8>>,
+) -> Result<HashSet<Vec<u8>>> {
+    let start = HistoryKey::prefix(namespace);
+    let end = HistoryKey::new(namespace, cutoff_ms, 0).as_bytes();
+    let mut removed = HashSet::new();
+    for entry in records.range(start..end) {
+        let (key, _) = entry.context("Failed to iterate history records for pruning")?;
+        if !keep.contains(key.as_ref()) {
+            removed.insert(key.to_vec());
+        }
+    }
+    for key in &removed {
+        records
+            .remove(key)
+            .context("Failed to delete pruned history record")?;
+        summaries
+            .remove(key)
+            .context("Failed to delete pruned history summary")?;
+    }
+    Ok(removed)
 }
 
-fn format_date_label(iso_date: &str, encounter_count: usize) -> String {
-    match NaiveDate::parse_from_str(iso_date, "%Y-%m-%d") {
-        Ok(date) => {
-            let weekday = date.format("%a");
-            format!(
-                "{} ({}) · {} encounters",
-                iso_date, weekday, encounter_count
-            )
+/// Removes `keys` from every day-index entry, dropping days that end up empty.
+fn drop_from_date_index(tree: &sled::Tree, keys: &HashSet<Vec<u8>>) -> Result<()> {
+    if keys.is_empty() {
+        return Ok(());
+    }
+    for entry in tree.iter() {
+        let (key, value) = entry.context("Failed to iterate day index")?;
+        let Ok(mut record) = serde_cbor::from_slice::<DateSummaryRecord>(value.as_ref()) else {
+            continue;
+        };
+        let before = record.encounter_ids.len();
+        record.encounter_ids.retain(|id| !keys.contains(id));
+        if record.encounter_ids.len() == before {
+            continue;
+        }
+        if record.encounter_ids.is_empty() {
+            tree.remove(&key)
+                .context("Failed to remove empty day index entry")?;
+        } else {
+            let bytes = serde_cbor::to_vec(&record)
+                .context("Failed to serialize pruned day index entry")?;
+            tree.insert(&key, bytes)
+                .context("Failed to persist pruned day index entry")?;
         }
-        Err(_) => format!("{} · {} encounters", iso_date, encounter_count),
     }
+    Ok(())
 }
 
-fn format_dungeon_date_label(iso_date: &str, run_count: usize) -> String {
-    match NaiveDate::parse_from_str(iso_date, "%Y-%m-%d") {
-        Ok(date) => {
-            let weekday = date.format("%a");
-            format!("{} ({}) · {} runs", iso_date, weekday, run_count)
+/// Drops day-index entries that point at missing records; returns every key still indexed.
+fn check_date_index(
+    tree: &sled::Tree,
+    valid_keys: &HashSet<Vec<u8>>,
+    repair: bool,
+    report: &mut IntegrityReport,
+) -> Result<HashSet<Vec<u8>>> {
+    let mut indexed = HashSet::new();
+    for entry in tree.iter() {
+        let (key, value) = entry.context("Failed to iterate day index")?;
+        let Ok(mut record) = serde_cbor::from_slice::<DateSummaryRecord>(value.as_ref()) else {
+            report.dangling_index_entries += 1;
+            if repair {
+                tree.remove(&key)
+                    .context("Failed to remove unreadable day index entry")?;
+            }
+            continue;
+        };
+        let before = record.encounter_ids.len();
+        record.encounter_ids.retain(|id| valid_keys.contains(id));
+        let removed = before - record.encounter_ids.len();
+        indexed.extend(record.encounter_ids.iter().cloned());
+        if removed == 0 {
+            continue;
+        }
+        report.dangling_index_entries += removed;
+        if repair {
+            if record.encounter_ids.is_empty() {
+                tree.remove(&key)
+                    .context("Failed to remove empty day index entry")?;
+            } else {
+                let bytes = serde_cbor::to_vec(&record)
+                    .context("Failed to serialize repaired day index entry")?;
+                tree.insert(&key, bytes)
+                    .context("Failed to persist repaired day index entry")?;
+            }
         }
-        Err(_) => format!("{} · {} runs", iso_date, run_count),
     }
+    Ok(indexed)
+}
+
+fn millis_to_local(ms: u64) -> Option<DateTime<Local>> {
+    let millis = i64::try_from(ms).ok()?;
+    Local.timestamp_millis_opt(millis).single()
+}
+
+/// 24-hour time of day kept in the summary index for entries whose timestamp can't be read.
+fn stored_time_label(ms: u64) -> String {
+    ClockFormat::H24
+        .format_local(ms, false, false)
+        .unwrap_or_else(|| "--:--".to_string())
+}
+
+/// Day-index id (local `YYYY-MM-DD`) an encounter ending at `ms` is listed under.
+pub(crate) fn local_date_id(ms: u64) -> String {
+    millis_to_local(ms)
+        .map(|dt| dt.date_naive().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
 }
 
 fn build_history_items_from_summaries(
@@ -504,7 +1109,7 @@ fn build_history_items_from_summaries(
 
     let mut occurrence_by_key: HashMap<Vec<u8>, u32> = HashMap::new();
     for entries in chronological.values_mut() {
-        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.sort_by_key(|entry| entry.0);
         for (idx, (_, key)) in entries.iter().enumerate() {
             occurrence_by_key.insert(key.clone(), (idx + 1) as u32);
         }
@@ -520,20 +1125,34 @@ fn build_history_items_from_summaries(
             } else {
                 summary.base_title.clone()
             };
-            HistoryEncounterItem {
-                key: summary.key,
-                display_title,
-                base_title: summary.base_title,
-                occurrence,
-                time_label: summary.time_label,
-                last_seen_ms: summary.last_seen_ms,
-                timestamp_label: summary.timestamp_label,
-                record: None,
-            }
+            encounter_item(summary, display_title, occurrence)
         })
         .collect()
 }
 
+fn encounter_item(
+    summary: EncounterSummaryRecord,
+    display_title: String,
+    occurrence: u32,
+) -> HistoryEncounterItem {
+    HistoryEncounterItem {
+        key: summary.key,
+        display_title,
+        base_title: summary.base_title,
+        occurrence,
+        time_label: summary.time_label,
+        last_seen_ms: summary.last_seen_ms,
+        timestamp_label: summary.timestamp_label,
+        duration: summary.duration,
+        damage: summary.damage,
+        never_active: !summary.saw_active,
+        scale: ContentScale::from_count(summary.max_combatants),
+        jobs: signature_jobs(&summary.party_signature),
+        record: None,
+        buff_uptime: Vec::new(),
+    }
+}
+
 fn build_dungeon_history_items(summaries: Vec<DungeonSummaryRecord>) -> Vec<DungeonHistoryItem> {
     summaries
         .into_iter()
@@ -547,39 +1166,92 @@ fn build_dungeon_history_items(summaries: Vec<DungeonSummaryRecord>) -> Vec<Dung
             DungeonHistoryItem {
                 key: summary.key,
                 zone: summary.zone,
+                started_ms: summary.started_ms,
                 started_label,
                 duration_label,
                 total_damage: summary.total_damage,
                 total_healed: summary.total_healed,
                 total_encdps: summary.total_encdps,
+                total_deaths: summary.total_deaths,
                 child_count: summary.child_count,
                 last_seen_ms: summary.last_seen_ms,
                 incomplete: summary.incomplete,
                 party_signature: summary.party_signature,
                 record: None,
                 child_records: Vec::new(),
+                child_buff_uptime: Vec::new(),
             }
         })
         .collect()
 }
 
-fn format_duration_label(total_secs: u64) -> String {
-    if total_secs == 0 {
-        return "00:00".to_string();
+/// Test scaffold: a store in a fresh `nekomata-{tag}-…` temp directory that is removed,
+/// with everything written under it, when the scaffold drops.
+#[cfg(test)]
+pub(crate) struct TempStore {
+    store: std::sync::Arc<HistoryStore>,
+    dir: PathBuf,
+}
+
+#[cfg(test)]
+impl TempStore {
+    pub(crate) fn new(tag: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "nekomata-{tag}-{}-{}",
+            std::process::id(),
+            super::types::now_ms()
+        ));
+        fs::create_dir_all(&dir).expect("create temp history dir");
+        let store = HistoryStore::open(&dir.join("encounters.sled")).expect("open history");
+        Self {
+            store: std::sync::Arc::new(store),
+            dir,
+        }
+    }
+
+    /// The directory holding the database; put other test output here to have it cleaned up.
+    pub(crate) fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// A handle for code that keeps the store, such as a recorder worker.
+    pub(crate) fn shared(&self) -> std::sync::Arc<HistoryStore> {
+        std::sync::Arc::clone(&self.store)
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TempStore {
+    type Target = HistoryStore;
+
+    fn deref(&self) -> &HistoryStore {
+        &self.store
     }
-    let hours = total_secs / 3600;
-    let minutes = (total_secs % 3600) / 60;
-    let seconds = total_secs % 60;
-    if hours > 0 {
-        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
-    } else {
-        format!("{:02}:{:02}", minutes, seconds)
+}
+
+#[cfg(test)]
+impl Drop for TempStore {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::history::types::now_ms;
+
+    fn make_record(last_seen_ms: u64) -> EncounterRecord {
+        EncounterRecord::fixture(
+            last_seen_ms,
+            crate::model::EncounterSummary {
+                title: "Striking Dummy".into(),
+                duration: "00:30".into(),
+                damage: "30000".into(),
+                ..Default::default()
+            },
+        )
+    }
 
     fn make_summary(key: &[u8], base_title: &str, last_seen: u64) -> EncounterSummaryRecord {
         EncounterSummaryRecord {
@@ -596,6 +1268,9 @@ mod tests {
             zone: "Zone".into(),
             snapshots: 3,
             frames: 3,
+            saw_active: true,
+            max_combatants: 8,
+            party_signature: vec!["Alice|NIN".into()],
         }
     }
 
@@ -612,6 +1287,7 @@ mod tests {
         assert_eq!(items[1].display_title, "Doma Castle (2)");
         assert_eq!(items[2].display_title, "Striking Dummy");
         assert!(items.iter().all(|item| item.record.is_none()));
+        assert_eq!(items[0].scale, Some(ContentScale::Party));
     }
 
     #[test]
@@ -621,7 +1297,7 @@ mod tests {
             make_summary(&[2], "Rubicante", 3_000),
             make_summary(&[3], "Rubicante", 2_000),
         ];
-        summaries.sort_by(|a, b| b.last_seen_ms.cmp(&a.last_seen_ms));
+        summaries.sort_by_key(|s| Reverse(s.last_seen_ms));
 
         let items = build_history_items_from_summaries(summaries);
         assert_eq!(items.len(), 3);
@@ -643,6 +1319,7 @@ mod tests {
             total_damage: 12345.0,
             total_healed: 234.0,
             total_encdps: 98.7,
+            total_deaths: 2,
             child_count: 3,
             incomplete: false,
             party_signature: vec!["Alice|NIN".into()],
@@ -654,4 +1331,314 @@ mod tests {
         assert_eq!(item.child_count, 3);
         assert_eq!(item.zone, "Sastasha");
     }
+
+    #[test]
+    fn iter_encounter_records_yields_records_in_key_order() {
+        let store = TempStore::new("iter");
+        let base = now_ms();
+        let mut keys: Vec<Vec<u8>> = [2_000, 0, 1_000]
+            .into_iter()
+            .map(|offset| store.append(&make_record(base + offset)).expect("append"))
+            .map(|key| key.as_bytes())
+            .collect();
+        keys.sort();
+
+        let scanned: Vec<(Vec<u8>, EncounterRecord)> = store
+            .iter_encounter_records()
+            .collect::<Result<_>>()
+            .expect("scan records");
+        let scanned_keys: Vec<Vec<u8>> = scanned.iter().map(|(key, _)| key.clone()).collect();
+        assert_eq!(scanned_keys, keys);
+        let seen: Vec<u64> = scanned.iter().map(|(_, r)| r.last_seen_ms).collect();
+        assert_eq!(seen, vec![base, base + 1_000, base + 2_000]);
+
+        store
+            .encounters
+            .insert(b"enc-garbage", b"not cbor".to_vec())
+            .expect("insert garbage");
+        let results: Vec<_> = store.iter_encounter_records().collect();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+    }
+
+    #[test]
+    fn encounter_label_reads_the_summary_index() {
+        let store = TempStore::new("label");
+        let key = store.append(&make_record(now_ms())).expect("append");
+        let (date_id, label) = store
+            .encounter_label(&key.as_bytes(), ClockFormat::H24)
+            .expect("lookup")
+            .expect("summary");
+        assert!(!date_id.is_empty());
+        assert!(label.starts_with("Striking Dummy · "));
+        let (_, label_12h) = store
+            .encounter_label(&key.as_bytes(), ClockFormat::H12)
+            .expect("lookup")
+            .expect("summary");
+        assert!(
+            label_12h.ends_with(" AM") || label_12h.ends_with(" PM"),
+            "{label_12h}"
+        );
+        let missing = store.encounter_label(b"missing", ClockFormat::H24);
+        assert_eq!(missing.expect("lookup"), None);
+        let missing = store.dungeon_label(b"missing", ClockFormat::H24);
+        assert_eq!(missing.expect("lookup"), None);
+    }
+
+    #[test]
+    fn exported_encounter_round_trips_through_json() {
+        let store = TempStore::new("export");
+        let mut record = make_record(now_ms());
+        record.deaths = vec![crate::history::deaths::DeathEvent {
+            ms: 12_000,
+            name: "Alice".into(),
+        }];
+        let key = store.append(&record).expect("append");
+        let dir = store.dir().join("exports");
+        let path = store
+            .export_encounter(&key.as_bytes(), &dir)
+            .expect("export");
+        assert_eq!(path.extension().and_then(|ext| ext.to_str()), Some("json"));
+
+        let text = fs::read_to_string(&path).expect("read export");
+        let back: EncounterRecord = serde_json::from_str(&text).expect("parse export");
+        assert_eq!(back.encounter.title, "Striking Dummy");
+        assert_eq!(back.last_seen_ms, record.last_seen_ms);
+        assert_eq!(back.deaths, record.deaths);
+    }
+
+    #[test]
+    fn cached_records_are_evicted_when_removed() {
+        let store = TempStore::new("cache");
+        let key = store.append(&make_record(now_ms())).expect("append");
+        let first = store.load_encounter_record(&key.as_bytes()).expect("load");
+        let again = store
+            .load_encounter_record(&key.as_bytes())
+            .expect("cached");
+        assert_eq!(first.encounter.title, again.encounter.title);
+
+        store.remove(&key).expect("remove");
+        assert!(store.load_encounter_record(&key.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn prune_before_drops_old_records_and_empty_days() {
+        const DAY_MS: u64 = 24 * 60 * 60 * 1_000;
+        let store = TempStore::new("prune");
+        let now = now_ms();
+        let old = store.append(&make_record(now - 40 * DAY_MS)).expect("old");
+        let older = store
+            .append(&make_record(now - 41 * DAY_MS))
+            .expect("older");
+        let child = store
+            .append(&make_record(now - 31 * DAY_MS))
+            .expect("child");
+        let recent = store.append(&make_record(now - DAY_MS)).expect("recent");
+        let dungeon = |started_ms: u64, child_keys: Vec<Vec<u8>>| DungeonAggregateRecord {
+            version: SCHEMA_VERSION,
+            zone: "Sastasha".into(),
+            started_ms,
+            last_seen_ms: started_ms + 60_000,
+            party_signature: Vec::new(),
+            total_duration_secs: 60,
+            total_damage: 60_000.0,
+            total_healed: 0.0,
+            total_encdps: 1_000.0,
+            total_deaths: 0,
+            wipe_count: 0,
+            child_titles: vec!["Striking Dummy".into(); child_keys.len()],
+            child_keys,
+            incomplete: false,
+            app_version: None,
+        };
+        store
+            .append_dungeon(&dungeon(now - 40 * DAY_MS, Vec::new()))
+            .expect("old run");
+        // A run that ends inside the window keeps its older child encounter.
+        store
+            .append_dungeon(&dungeon(now - 30 * DAY_MS - 30_000, vec![child.as_bytes()]))
+            .expect("kept run");
+
+        let removed = store.prune_before(now - 30 * DAY_MS).expect("prune");
+        assert_eq!(removed, 3);
+
+        assert!(store.load_encounter_record(&old.as_bytes()).is_err());
+        assert!(store.load_encounter_record(&older.as_bytes()).is_err());
+        assert!(store.load_encounter_record(&child.as_bytes()).is_ok());
+        assert!(store.load_encounter_record(&recent.as_bytes()).is_ok());
+        let listed: usize = store
+            .load_dates()
+            .expect("dates")
+            .iter()
+            .map(|day| day.encounter_ids.len())
+            .sum();
+        assert_eq!(listed, 2);
+        let runs: usize = store
+            .load_dungeon_days()
+            .expect("dungeon days")
+            .iter()
+            .map(|day| day.run_ids.len())
+            .sum();
+        assert_eq!(runs, 1);
+        assert!(store.check_integrity(false).expect("check").is_clean());
+    }
+
+    #[test]
+    fn deleting_records_updates_the_day_index_and_runs() {
+        let store = TempStore::new("delete");
+        let now = now_ms();
+        let kept = store.append(&make_record(now)).expect("kept");
+        let mut pull_record = make_record(now + 1_000);
+        pull_record.encounter.damage = "60000".into();
+        let pull = store.append(&pull_record).expect("pull");
+        let orphan = HistoryKey::new(ENCOUNTER_NAMESPACE, now + 2_000, 0).as_bytes();
+        store
+            .encounters
+            .insert(
+                orphan.as_slice(),
+                serde_cbor::to_vec(&make_record(now + 2_000)).expect("cbor"),
+            )
+            .expect("insert orphan");
+        let run = store
+            .append_dungeon(&DungeonAggregateRecord {
+                version: SCHEMA_VERSION,
+                zone: "Sastasha".into(),
+                started_ms: now,
+                last_seen_ms: now + 3_000,
+                party_signature: Vec::new(),
+                total_duration_secs: 90,
+                total_damage: 120_000.0,
+                total_healed: 0.0,
+                total_encdps: 1_333.3,
+                total_deaths: 2,
+                wipe_count: 1,
+                child_keys: vec![kept.as_bytes(), pull.as_bytes(), orphan.clone()],
+                child_titles: vec!["Striking Dummy".into(); 3],
+                incomplete: false,
+                app_version: None,
+            })
+            .expect("append dungeon");
+
+        let options = DungeonOptions::default();
+        store
+            .delete_encounter(&pull.as_bytes(), &options)
+            .expect("delete pull");
+        assert!(store.load_encounter_record(&pull.as_bytes()).is_err());
+        let days = store.load_dates().expect("dates");
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].encounter_ids, vec![kept.as_bytes()]);
+        let record = store.load_dungeon_record(&run.as_bytes()).expect("run");
+        assert_eq!(record.child_keys, vec![kept.as_bytes(), orphan.clone()]);
+        assert_eq!(record.child_titles.len(), 2);
+        // The run's totals only count the two pulls left.
+        assert_eq!(record.total_duration_secs, 60);
+        assert_eq!(record.total_damage, 60_000.0);
+        assert_eq!(record.total_encdps, 1_000.0);
+        assert_eq!(record.total_deaths, 0);
+        assert_eq!(record.wipe_count, 0);
+
+        let removed = store
+            .delete_dungeon_run(&run.as_bytes())
+            .expect("delete run");
+        assert_eq!(removed, 1);
+        assert!(store.load_dungeon_days().expect("dungeon days").is_empty());
+        assert!(store.load_encounter_record(&kept.as_bytes()).is_ok());
+        assert!(store.load_encounter_record(&orphan).is_err());
+
+        store
+            .delete_encounter(&kept.as_bytes(), &options)
+            .expect("delete kept");
+        assert!(store.load_dates().expect("dates").is_empty());
+        assert!(store.check_integrity(false).expect("check").is_clean());
+    }
+
+    #[test]
+    fn search_matches_title_or_zone_newest_first() {
+        let store = TempStore::new("search");
+        let now = now_ms();
+        let record = |title: &str, zone: &str, at: u64| {
+            let mut record = make_record(at);
+            record.encounter.title = title.into();
+            record.encounter.zone = zone.into();
+            record
+        };
+        store
+            .append(&record("Titan", "The Navel", now - 2 * 86_400_000))
+            .expect("old titan");
+        let newest = store
+            .append(&record("Titan", "The Navel", now))
+            .expect("new titan");
+        store
+            .append(&record("Ifrit", "The Bowl of Embers", now - 1_000))
+            .expect("ifrit");
+
+        assert!(store.search_encounters("  ", 10).expect("blank").is_empty());
+        let titans = store.search_encounters("titan", 10).expect("search");
+        assert_eq!(titans.len(), 2);
+        assert_eq!(titans[0].key, newest.as_bytes());
+        assert!(titans[0].last_seen_ms > titans[1].last_seen_ms);
+        assert_eq!(titans[0].display_title, "Titan");
+
+        let by_zone = store.search_encounters("EMBERS", 10).expect("zone");
+        assert_eq!(by_zone.len(), 1);
+        assert_eq!(store.search_encounters("the", 2).expect("limit").len(), 2);
+    }
+
+    #[test]
+    fn integrity_check_reports_and_repairs_orphans() {
+        let store = TempStore::new("doctor");
+        let now = now_ms();
+        let kept = store.append(&make_record(now)).expect("append kept");
+        let lost = store
+            .append(&make_record(now + 1_000))
+            .expect("append lost");
+        store
+            .append_dungeon(&DungeonAggregateRecord {
+                version: SCHEMA_VERSION,
+                zone: "Sastasha".into(),
+                started_ms: now,
+                last_seen_ms: now + 1_000,
+                party_signature: Vec::new(),
+                total_duration_secs: 60,
+                total_damage: 60_000.0,
+                total_healed: 0.0,
+                total_encdps: 1_000.0,
+                total_deaths: 0,
+                wipe_count: 0,
+                child_keys: vec![kept.as_bytes(), lost.as_bytes()],
+                child_titles: vec!["Striking Dummy".into(), "Striking Dummy".into()],
+                incomplete: false,
+                app_version: None,
+            })
+            .expect("append dungeon");
+
+        store.remove(&lost).expect("remove record");
+        store
+            .encounters
+            .insert(
+                HistoryKey::new(ENCOUNTER_NAMESPACE, now + 2_000, 0).as_bytes(),
+                b"not cbor".to_vec(),
+            )
+            .expect("insert garbage");
+
+        let report = store.check_integrity(false).expect("check");
+        assert_eq!(report.encounters_scanned, 2);
+        assert_eq!(report.unparseable_records, 1);
+        assert_eq!(report.orphaned_summaries, 1);
+        assert_eq!(report.dangling_index_entries, 1);
+        assert_eq!(report.dangling_child_keys, 1);
+        assert!(!report.is_clean());
+
+        let repaired = store.check_integrity(true).expect("repair");
+        assert_eq!(repaired.issue_count(), report.issue_count());
+        assert!(store.check_integrity(false).expect("recheck").is_clean());
+
+        let days = store.load_dungeon_days().expect("dungeon days");
+        let runs = store
+            .load_dungeon_summaries(&days[0].iso_date)
+            .expect("dungeon runs");
+        assert_eq!(runs[0].child_count, 1);
+        let record = store.load_dungeon_record(&runs[0].key).expect("run");
+        assert_eq!(record.child_keys, vec![kept.as_bytes()]);
+    }
 }
@@ -1,16 +1,27 @@
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use chrono::{Local, NaiveDate, TimeZone};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::model::{CombatantRow, EncounterSummary};
+use crate::parse::{parse_abilities, AbilityStat};
+
+use super::buffs::BuffUptime;
+use super::deaths::{death_timeline, DeathEvent};
+use super::util::parse_number;
 
 pub(crate) const ENCOUNTER_NAMESPACE: &str = "enc";
 pub(crate) const DUNGEON_NAMESPACE: &str = "dun";
 pub(crate) const KEY_SEPARATOR: u8 = 0x1F;
-pub(crate) const SCHEMA_VERSION: u32 = 2;
+pub(crate) const SCHEMA_VERSION: u32 = 4;
 pub(crate) const META_SCHEMA_VERSION_KEY: &[u8] = b"schema/version";
 
+/// Version stamped onto newly saved records.
+pub(crate) fn app_version() -> Option<String> {
+    Some(env!("CARGO_PKG_VERSION").to_string())
+}
+
 /// Snapshot prepared for persistence; keeps the raw payload around for future use.
 #[derive(Debug, Clone)]
 pub struct EncounterSnapshot {
@@ -57,7 +68,6 @@ impl HistoryKey {
         decode_key(bytes)
     }
 
-    #[allow(dead_code)]
     pub fn prefix(namespace: &str) -> Vec<u8> {
         let mut buf = Vec::with_capacity(namespace.len() + 1);
         buf.extend_from_slice(namespace.as_bytes());
@@ -83,6 +93,218 @@ pub struct EncounterRecord {
     pub saw_active: bool,
     #[serde(default)]
     pub frames: Vec<EncounterFrame>,
+    /// Nekomata version that saved the record; `None` for records predating the field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_version: Option<String>,
+    /// Most combatants seen in a single frame; 0 for records predating the field.
+    #[serde(default)]
+    pub max_combatants: u32,
+    /// Deaths from `NetworkDeath` log lines in the order they arrived; empty for records
+    /// saved before schema 3.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deaths: Vec<DeathEvent>,
+}
+
+impl EncounterRecord {
+    /// File name stem for single-encounter exports: the local end time, `20250101-203015`.
+    pub fn export_stem(&self) -> String {
+        let ended_ms = if self.last_seen_ms > 0 {
+            self.last_seen_ms
+        } else {
+            self.stored_ms
+        };
+        Local
+            .timestamp_millis_opt(ended_ms as i64)
+            .single()
+            .map(|dt| dt.format("%Y%m%d-%H%M%S").to_string())
+            .unwrap_or_else(|| ended_ms.to_string())
+    }
+
+    /// The logged deaths, or for records without any, the ones the frame counters imply.
+    pub fn death_events(&self) -> Vec<DeathEvent> {
+        if self.deaths.is_empty() {
+            death_timeline(&self.frames)
+        } else {
+            self.deaths.clone()
+        }
+    }
+
+    /// `combatant`'s skill breakdown from the latest stored payload that has one (totals are
+    /// cumulative), falling back to `raw_last`. `None` when no payload carried abilities.
+    pub fn abilities_for(&self, combatant: &str) -> Option<Vec<AbilityStat>> {
+        self.frames
+            .iter()
+            .rev()
+            .map(|frame| &frame.raw)
+            .chain(self.raw_last.as_ref())
+            .find_map(|raw| parse_abilities(raw, combatant))
+    }
+
+    /// Test fixture: a current-schema record of one active snapshot that ended (and was
+    /// stored) at `last_seen_ms` after 30 s, with no rows, frames or deaths.
+    #[cfg(test)]
+    pub(crate) fn fixture(last_seen_ms: u64, encounter: EncounterSummary) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            stored_ms: last_seen_ms,
+            first_seen_ms: last_seen_ms.saturating_sub(30_000),
+            last_seen_ms,
+            encounter,
+            rows: Vec::new(),
+            raw_last: None,
+            snapshots: 1,
+            saw_active: true,
+            frames: Vec::new(),
+            app_version: None,
+            max_combatants: 0,
+            deaths: Vec::new(),
+        }
+    }
+
+    /// `max_combatants`, or the largest row count among the stored frames for older records.
+    pub fn combatant_count(&self) -> u32 {
+        if self.max_combatants > 0 {
+            self.max_combatants
+        } else {
+            max_row_count(&self.frames, &self.rows)
+        }
+    }
+
+    pub fn content_scale(&self) -> Option<ContentScale> {
+        ContentScale::from_count(self.combatant_count())
+    }
+
+    /// Whole seconds between the first and the last snapshot of the pull.
+    pub fn wall_duration_secs(&self) -> u64 {
+        self.last_seen_ms.saturating_sub(self.first_seen_ms) / 1000
+    }
+
+    pub fn frame_coverage(&self) -> Option<FrameCoverage> {
+        FrameCoverage::from_frames(
+            self.frames.len(),
+            self.last_seen_ms.saturating_sub(self.first_seen_ms),
+        )
+    }
+}
+
+/// Party DPS between consecutive frames: the damage total's increase over the time between
+/// their arrival. Frames arriving in the same millisecond are skipped and a falling total (a
+/// counter reset) counts as zero. Empty with fewer than two frames.
+pub(crate) fn dps_series(frames: &[EncounterFrame]) -> Vec<f64> {
+    let points: Vec<(u64, f64)> = frames
+        .iter()
+        .map(|frame| (frame.received_ms, frame_damage(frame)))
+        .collect();
+    points
+        .windows(2)
+        .filter_map(|pair| {
+            let [(prev_ms, prev), (ms, damage)] = pair else {
+                return None;
+            };
+            let elapsed_ms = ms.checked_sub(*prev_ms).filter(|elapsed| *elapsed > 0)?;
+            Some(((damage - prev) / (elapsed_ms as f64 / 1_000.0)).max(0.0))
+        })
+        .collect()
+}
+
+/// Total damage of a frame from its encounter line, or the sum of its rows when that is blank.
+fn frame_damage(frame: &EncounterFrame) -> f64 {
+    if frame.encounter.damage.trim().is_empty() {
+        frame.rows.iter().map(|row| row.damage).sum()
+    } else {
+        parse_number(&frame.encounter.damage)
+    }
+}
+
+pub(crate) fn max_row_count(frames: &[EncounterFrame], rows: &[CombatantRow]) -> u32 {
+    frames
+        .iter()
+        .map(|frame| frame.rows.len())
+        .chain(std::iter::once(rows.len()))
+        .max()
+        .unwrap_or(0) as u32
+}
+
+/// Size of the content an encounter was fought in, inferred from its combatant count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentScale {
+    Solo,
+    /// Light or full party, up to eight combatants.
+    Party,
+    /// More than eight combatants.
+    Alliance,
+}
+
+impl ContentScale {
+    pub const PARTY_MAX: u32 = 8;
+
+    /// `None` when nothing was recorded.
+    pub fn from_count(count: u32) -> Option<Self> {
+        match count {
+            0 => None,
+            1 => Some(ContentScale::Solo),
+            2..=Self::PARTY_MAX => Some(ContentScale::Party),
+            _ => Some(ContentScale::Alliance),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ContentScale::Solo => "solo",
+            ContentScale::Party => "party",
+            ContentScale::Alliance => "alliance",
+        }
+    }
+}
+
+/// How densely an encounter's frames cover its wall-clock span. Gaps from connection
+/// hiccups leave sparse frames, which makes timelines and derived metrics less reliable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameCoverage {
+    /// Frames received as a percentage of the frames expected, capped at 100.
+    pub score: u8,
+}
+
+impl FrameCoverage {
+    /// IINACT pushes CombatData about once a second during combat.
+    const EXPECTED_FRAMES_PER_SEC: f64 = 1.0;
+    /// Spans shorter than this are too short to judge.
+    const MIN_SPAN_MS: u64 = 5_000;
+    pub const GOOD: u8 = 80;
+    pub const POOR: u8 = 50;
+
+    /// `None` without frames or when the span is too short to judge.
+    pub fn from_frames(frames: usize, wall_ms: u64) -> Option<Self> {
+        if frames == 0 || wall_ms < Self::MIN_SPAN_MS {
+            return None;
+        }
+        // A span of N seconds fully covered holds N + 1 frames (both ends included).
+        let expected = wall_ms as f64 / 1000.0 * Self::EXPECTED_FRAMES_PER_SEC + 1.0;
+        let ratio = (frames as f64 / expected).min(1.0);
+        Some(Self {
+            score: (ratio * 100.0).round() as u8,
+        })
+    }
+
+    /// `Some(true)` for good coverage, `Some(false)` for poor, `None` in between.
+    pub fn verdict(self) -> Option<bool> {
+        if self.score >= Self::GOOD {
+            Some(true)
+        } else if self.score < Self::POOR {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self.verdict() {
+            Some(true) => "good",
+            Some(false) => "poor, metrics may be unreliable",
+            None => "fair",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,9 +318,17 @@ pub struct DungeonAggregateRecord {
     pub total_damage: f64,
     pub total_healed: f64,
     pub total_encdps: f64,
+    /// Deaths summed over every pull; 0 for runs recorded before this was tracked.
+    #[serde(default)]
+    pub total_deaths: u64,
+    /// Pulls that ended with the whole party dead; 0 for runs saved before schema 4.
+    #[serde(default)]
+    pub wipe_count: u64,
     pub child_keys: Vec<Vec<u8>>,
     pub child_titles: Vec<String>,
     pub incomplete: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -119,7 +349,22 @@ pub struct HistoryEncounterItem {
     pub last_seen_ms: u64,
     pub timestamp_label: String,
     #[serde(default)]
+    pub duration: String,
+    #[serde(default)]
+    pub damage: String,
+    #[serde(default)]
+    pub never_active: bool,
+    /// `None` for index entries written before combatant counts were tracked.
+    #[serde(default)]
+    pub scale: Option<ContentScale>,
+    /// Jobs that took part, for the history job filter.
+    #[serde(default)]
+    pub jobs: Vec<String>,
+    #[serde(default)]
     pub record: Option<EncounterRecord>,
+    /// Uptime of the configured buffs in `record`, worked out once when it loads.
+    #[serde(skip)]
+    pub buff_uptime: Vec<BuffUptime>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -135,6 +380,22 @@ pub struct HistoryDay {
     pub encounters_loaded: bool,
 }
 
+impl HistoryDay {
+    /// Drops `key` from the day's index and loaded list and relabels it; returns false when
+    /// the day did not contain it.
+    pub fn remove_encounter(&mut self, key: &[u8]) -> bool {
+        let before = self.encounter_ids.len();
+        self.encounter_ids.retain(|id| id != key);
+        if self.encounter_ids.len() == before {
+            return false;
+        }
+        self.encounters.retain(|item| item.key != key);
+        self.encounter_count = self.encounter_ids.len();
+        self.label = format_date_label(&self.iso_date, self.encounter_count);
+        true
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncounterSummaryRecord {
     pub key: Vec<u8>,
@@ -150,6 +411,19 @@ pub struct EncounterSummaryRecord {
     pub zone: String,
     pub snapshots: u32,
     pub frames: u32,
+    /// Summaries written before this flag existed were only ever saved for active pulls.
+    #[serde(default = "default_saw_active")]
+    pub saw_active: bool,
+    /// See [`EncounterRecord::max_combatants`]; 0 when unknown.
+    #[serde(default)]
+    pub max_combatants: u32,
+    /// `name|job` per combatant; empty for summaries written before it was stored.
+    #[serde(default)]
+    pub party_signature: Vec<String>,
+}
+
+fn default_saw_active() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -164,6 +438,8 @@ pub struct DungeonSummaryRecord {
     pub total_damage: f64,
     pub total_healed: f64,
     pub total_encdps: f64,
+    #[serde(default)]
+    pub total_deaths: u64,
     pub child_count: usize,
     pub incomplete: bool,
     pub party_signature: Vec<String>,
@@ -173,11 +449,15 @@ pub struct DungeonSummaryRecord {
 pub struct DungeonHistoryItem {
     pub key: Vec<u8>,
     pub zone: String,
+    #[serde(default)]
+    pub started_ms: u64,
     pub started_label: String,
     pub duration_label: String,
     pub total_damage: f64,
     pub total_healed: f64,
     pub total_encdps: f64,
+    #[serde(default)]
+    pub total_deaths: u64,
     pub child_count: usize,
     pub last_seen_ms: u64,
     pub incomplete: bool,
@@ -186,6 +466,9 @@ pub struct DungeonHistoryItem {
     pub record: Option<DungeonAggregateRecord>,
     #[serde(default, skip_serializing, skip_deserializing)]
     pub child_records: Vec<Option<EncounterRecord>>,
+    /// Buff uptime of each loaded child record, filled alongside `child_records`.
+    #[serde(default, skip_serializing, skip_deserializing)]
+    pub child_buff_uptime: Vec<Vec<BuffUptime>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -201,6 +484,22 @@ pub struct DungeonHistoryDay {
     pub runs_loaded: bool,
 }
 
+impl DungeonHistoryDay {
+    /// Drops `key` from the day's index and loaded runs and relabels it; returns false when
+    /// the day did not contain it.
+    pub fn remove_run(&mut self, key: &[u8]) -> bool {
+        let before = self.run_ids.len();
+        self.run_ids.retain(|id| id != key);
+        if self.run_ids.len() == before {
+            return false;
+        }
+        self.runs.retain(|run| run.key != key);
+        self.run_count = self.run_ids.len();
+        self.label = format_dungeon_date_label(&self.iso_date, self.run_count);
+        true
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DateSummaryRecord {
     pub date_id: String,
@@ -208,6 +507,29 @@ pub struct DateSummaryRecord {
     pub encounter_ids: Vec<Vec<u8>>,
 }
 
+pub(crate) fn format_date_label(iso_date: &str, encounter_count: usize) -> String {
+    match NaiveDate::parse_from_str(iso_date, "%Y-%m-%d") {
+        Ok(date) => {
+            let weekday = date.format("%a");
+            format!(
+                "{} ({}) · {} encounters",
+                iso_date, weekday, encounter_count
+            )
+        }
+        Err(_) => format!("{} · {} encounters", iso_date, encounter_count),
+    }
+}
+
+pub(crate) fn format_dungeon_date_label(iso_date: &str, run_count: usize) -> String {
+    match NaiveDate::parse_from_str(iso_date, "%Y-%m-%d") {
+        Ok(date) => {
+            let weekday = date.format("%a");
+            format!("{} ({}) · {} runs", iso_date, weekday, run_count)
+        }
+        Err(_) => format!("{} · {} runs", iso_date, run_count),
+    }
+}
+
 pub(crate) fn encode_key(namespace: &str, timestamp_ms: u64, discriminator: u64) -> Vec<u8> {
     let mut buf = Vec::with_capacity(namespace.len() + 1 + 8 + 1 + 8);
     buf.extend_from_slice(namespace.as_bytes());
@@ -252,6 +574,83 @@ pub(crate) fn now_ms() -> u64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn abilities_fall_back_to_the_last_payload_without_frames() {
+        let mut record = EncounterRecord::fixture(0, EncounterSummary::default());
+        assert!(record.abilities_for("Alpha").is_none());
+        record.raw_last = Some(serde_json::json!({
+            "type": "CombatData",
+            "Combatant": {"Alpha": {"abilities": {"Fire": {"damage": 10}}}}
+        }));
+        let abilities = record
+            .abilities_for("Alpha")
+            .expect("abilities from raw_last");
+        assert_eq!(abilities.len(), 1);
+        assert!(record.abilities_for("Beta").is_none());
+    }
+
+    #[test]
+    fn content_scale_follows_the_largest_frame() {
+        let row = CombatantRow::default;
+        let frame = |count: usize| EncounterFrame {
+            received_ms: 0,
+            encounter: EncounterSummary::default(),
+            rows: (0..count).map(|_| row()).collect(),
+            raw: Value::Null,
+        };
+        assert_eq!(max_row_count(&[frame(4), frame(24), frame(9)], &[]), 24);
+        assert_eq!(max_row_count(&[], &[row()]), 1);
+        assert_eq!(ContentScale::from_count(0), None);
+        assert_eq!(ContentScale::from_count(1), Some(ContentScale::Solo));
+        assert_eq!(ContentScale::from_count(8), Some(ContentScale::Party));
+        assert_eq!(ContentScale::from_count(9), Some(ContentScale::Alliance));
+    }
+
+    #[test]
+    fn dps_series_uses_damage_deltas_between_frames() {
+        let frame = |received_ms: u64, damage: &str| EncounterFrame {
+            received_ms,
+            encounter: EncounterSummary {
+                damage: damage.into(),
+                ..Default::default()
+            },
+            rows: Vec::new(),
+            raw: Value::Null,
+        };
+        assert!(dps_series(&[frame(0, "0")]).is_empty());
+        let frames = [
+            frame(0, "0"),
+            frame(1_000, "1,000"),
+            frame(1_000, "1,200"),
+            frame(3_000, "5,000"),
+            frame(4_000, "100"),
+        ];
+        assert_eq!(dps_series(&frames), vec![1_000.0, 1_900.0, 0.0]);
+    }
+
+    #[test]
+    fn frame_coverage_scores_density_against_wall_time() {
+        let good = FrameCoverage::from_frames(61, 60_000).expect("coverage");
+        assert_eq!(good.score, 100);
+        assert_eq!(good.verdict(), Some(true));
+
+        let fair = FrameCoverage::from_frames(40, 60_000).expect("coverage");
+        assert_eq!(fair.score, 66);
+        assert_eq!(fair.verdict(), None);
+
+        let poor = FrameCoverage::from_frames(12, 60_000).expect("coverage");
+        assert_eq!(poor.verdict(), Some(false));
+
+        assert_eq!(
+            FrameCoverage::from_frames(500, 60_000)
+                .expect("capped")
+                .score,
+            100
+        );
+        assert!(FrameCoverage::from_frames(0, 60_000).is_none());
+        assert!(FrameCoverage::from_frames(3, 2_000).is_none());
+    }
+
     #[test]
     fn key_roundtrip() {
         let key = HistoryKey::new("enc", 12345, 42);
@@ -2,6 +2,12 @@ use crate::history::types::EncounterRecord;
 use crate::model::CombatantRow;
 
 pub(crate) fn parse_duration_secs(s: &str) -> Option<u64> {
+    parse_duration_millis(s).map(|ms| ms / 1000)
+}
+
+/// Parses `[[h:]m:]s` durations into milliseconds. The seconds part may carry a fraction
+/// ("01:23.4", "90.5"); digits past millisecond precision are ignored.
+pub(crate) fn parse_duration_millis(s: &str) -> Option<u64> {
     if s.trim().is_empty() {
         return None;
     }
@@ -9,20 +15,141 @@ pub(crate) fn parse_duration_secs(s: &str) -> Option<u64> {
     if parts.is_empty() || parts.len() > 3 {
         return None;
     }
-    let mut value = 0u64;
-    let mut multiplier = 1u64;
-    while let Some(part) = parts.pop() {
-        let part = part.trim();
-        if part.is_empty() || part.contains('-') {
+    let last = parts.pop()?;
+    let (seconds, fraction) = match last.trim().split_once('.') {
+        Some((seconds, fraction)) => (seconds, Some(fraction)),
+        None => (last, None),
+    };
+    let mut value = parse_whole(seconds)?.saturating_mul(1000);
+    if let Some(fraction) = fraction {
+        if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
             return None;
         }
-        let parsed = part.parse::<u64>().ok()?;
-        value += parsed.saturating_mul(multiplier);
+        let millis: String = fraction.chars().chain("00".chars()).take(3).collect();
+        value += millis.parse::<u64>().ok()?;
+    }
+    let mut multiplier = 60_000u64;
+    while let Some(part) = parts.pop() {
+        value = value.saturating_add(parse_whole(part)?.saturating_mul(multiplier));
         multiplier = multiplier.saturating_mul(60);
     }
     Some(value)
 }
 
+/// Duration in seconds for rate math: keeps the fraction when `subsecond` is set, otherwise
+/// truncates to whole seconds like the rest of the history code.
+pub(crate) fn parse_duration_rate_secs(s: &str, subsecond: bool) -> Option<f64> {
+    let millis = parse_duration_millis(s)?;
+    Some(if subsecond {
+        millis as f64 / 1000.0
+    } else {
+        (millis / 1000) as f64
+    })
+}
+
+/// Formats whole seconds the way ACT reports durations: `mm:ss`, or `hh:mm:ss` past an hour.
+pub(crate) fn format_duration_label(total_secs: u64) -> String {
+    if total_secs == 0 {
+        return "00:00".to_string();
+    }
+    let hours = total_secs / 3600;
+    let minutes = (total_secs % 3600) / 60;
+    let seconds = total_secs % 60;
+    if hours > 0 {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+fn parse_whole(part: &str) -> Option<u64> {
+    let part = part.trim();
+    if part.is_empty() || part.contains('-') {
+        return None;
+    }
+    part.parse::<u64>().ok()
+}
+
+/// Lower-case hex form of a store key, used where keys are persisted as text.
+pub(crate) fn key_to_hex(key: &[u8]) -> String {
+    key.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub(crate) fn key_from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok())
+        .collect()
+}
+
+/// What to do with an encounter whose reported duration is `00:00`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ZeroDurationPolicy {
+    /// Save it as reported; the history view shows its per-second rates as "—".
+    #[default]
+    Keep,
+    /// Don't save it at all.
+    Discard,
+    /// Treat it as lasting one second so rates stay finite.
+    Clamp,
+}
+
+impl ZeroDurationPolicy {
+    pub fn config_key(self) -> &'static str {
+        match self {
+            ZeroDurationPolicy::Keep => "keep",
+            ZeroDurationPolicy::Discard => "discard",
+            ZeroDurationPolicy::Clamp => "clamp",
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        match key.as_ref().to_ascii_lowercase().as_str() {
+            "discard" => ZeroDurationPolicy::Discard,
+            "clamp" => ZeroDurationPolicy::Clamp,
+            _ => ZeroDurationPolicy::Keep,
+        }
+    }
+
+    /// Duration used for aggregate math; only `Clamp` lifts zero to one second.
+    pub fn effective_secs(self, secs: u64) -> u64 {
+        match self {
+            ZeroDurationPolicy::Clamp => secs.max(1),
+            ZeroDurationPolicy::Keep | ZeroDurationPolicy::Discard => secs,
+        }
+    }
+}
+
+/// What to do with a finished encounter whose snapshots never reported `is_active`
+/// (e.g. residual DoT ticks picked up after a wipe).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum NeverActivePolicy {
+    /// Save it but flag it as never active in the history list.
+    #[default]
+    Mark,
+    /// Don't save it.
+    Drop,
+}
+
+impl NeverActivePolicy {
+    pub fn config_key(self) -> &'static str {
+        match self {
+            NeverActivePolicy::Mark => "mark",
+            NeverActivePolicy::Drop => "drop",
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        match key.as_ref().to_ascii_lowercase().as_str() {
+            "drop" => NeverActivePolicy::Drop,
+            _ => NeverActivePolicy::Mark,
+        }
+    }
+}
+
 pub(crate) fn parse_number(s: &str) -> f64 {
     let mut buf = String::with_capacity(s.len());
     for ch in s.chars() {
@@ -46,6 +173,20 @@ pub(crate) fn party_signature(rows: &[CombatantRow]) -> Vec<String> {
     entries
 }
 
+/// Distinct jobs (upper-cased, sorted) in a [`party_signature`]; combatants without a job
+/// are skipped.
+pub(crate) fn signature_jobs(signature: &[String]) -> Vec<String> {
+    let mut jobs: Vec<String> = signature
+        .iter()
+        .filter_map(|entry| entry.rsplit_once('|'))
+        .map(|(_, job)| job.trim().to_uppercase())
+        .filter(|job| !job.is_empty())
+        .collect();
+    jobs.sort_unstable();
+    jobs.dedup();
+    jobs
+}
+
 pub(crate) fn resolve_title(record: &EncounterRecord) -> String {
     let primary = record.encounter.title.trim();
     if !primary.is_empty() {
@@ -72,6 +213,28 @@ mod tests {
         assert_eq!(parse_duration_secs("--:--"), None);
     }
 
+    #[test]
+    fn parse_duration_accepts_fractional_seconds() {
+        assert_eq!(parse_duration_millis("01:23.4"), Some(83_400));
+        assert_eq!(parse_duration_millis("90.5"), Some(90_500));
+        assert_eq!(parse_duration_millis("0:00.1234"), Some(123));
+        assert_eq!(parse_duration_millis("01:30"), Some(90_000));
+        assert_eq!(parse_duration_secs("01:23.4"), Some(83));
+        assert_eq!(parse_duration_millis("01.5:30"), None);
+        assert_eq!(parse_duration_millis("01:30."), None);
+        assert_eq!(parse_duration_rate_secs("90.5", true), Some(90.5));
+        assert_eq!(parse_duration_rate_secs("90.5", false), Some(90.0));
+    }
+
+    #[test]
+    fn hex_keys_round_trip() {
+        let key = vec![0x00, 0x1f, 0xab, 0xff];
+        assert_eq!(key_to_hex(&key), "001fabff");
+        assert_eq!(key_from_hex("001fabff"), Some(key));
+        assert_eq!(key_from_hex("abc"), None);
+        assert_eq!(key_from_hex("zz"), None);
+    }
+
     #[test]
     fn parse_number_handles_commas_and_percent() {
         assert_eq!(parse_number("12,345.6"), 12345.6);
@@ -101,20 +264,23 @@ mod tests {
         assert_eq!(sig, vec!["Alice|NIN".to_string(), "Bob|WHM".to_string()]);
     }
 
+    #[test]
+    fn signature_jobs_skips_blank_jobs_and_dedups() {
+        let sig = vec![
+            "Alice|sge".to_string(),
+            "Bob|WHM".to_string(),
+            "Carbuncle|".to_string(),
+            "Cid|SGE".to_string(),
+        ];
+        assert_eq!(
+            signature_jobs(&sig),
+            vec!["SGE".to_string(), "WHM".to_string()]
+        );
+    }
+
     #[test]
     fn resolve_title_prefers_encounter_title_then_zone() {
-        let mut record = EncounterRecord {
-            version: 1,
-            stored_ms: 0,
-            first_seen_ms: 0,
-            last_seen_ms: 0,
-            encounter: Default::default(),
-            rows: Vec::new(),
-            raw_last: None,
-            snapshots: 0,
-            saw_active: false,
-            frames: Vec::new(),
-        };
+        let mut record = EncounterRecord::fixture(0, Default::default());
         record.encounter.title = "Boss Fight".into();
         assert_eq!(resolve_title(&record), "Boss Fight");
         record.encounter.title = "".into();
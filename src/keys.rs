@@ -0,0 +1,421 @@
+//! Remappable keys for the live view, loaded from `key_bindings` in the config.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use crossterm::event::KeyCode;
+
+use crate::config::KeyList;
+
+/// Something a live-view key press does. History panel keys are not remappable.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum KeyAction {
+    /// Step back out of the open overlay or view; quits from the plain live view.
+    Quit,
+    ToggleHistory,
+    ToggleIdle,
+    CycleDecoration,
+    CycleMode,
+    ToggleBothRates,
+    OpenSettings,
+    SettingsPrev,
+    SettingsNext,
+    SettingsDecrease,
+    SettingsIncrease,
+    SettingsEdit,
+    Filter,
+    SortPrev,
+    SortNext,
+    SortDirection,
+    Reconnect,
+    ErrorLog,
+    Copy,
+    CopySelf,
+    Legend,
+    RoleFilter,
+    Throughput,
+    Discard,
+    CutDungeonRun,
+    ReloadCatalog,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 26] = [
+        KeyAction::Quit,
+        KeyAction::ToggleHistory,
+        KeyAction::ToggleIdle,
+        KeyAction::CycleDecoration,
+        KeyAction::CycleMode,
+        KeyAction::ToggleBothRates,
+        KeyAction::OpenSettings,
+        KeyAction::SettingsPrev,
+        KeyAction::SettingsNext,
+        KeyAction::SettingsDecrease,
+        KeyAction::SettingsIncrease,
+        KeyAction::SettingsEdit,
+        KeyAction::Filter,
+        KeyAction::SortPrev,
+        KeyAction::SortNext,
+        KeyAction::SortDirection,
+        KeyAction::Reconnect,
+        KeyAction::ErrorLog,
+        KeyAction::Copy,
+        KeyAction::CopySelf,
+        KeyAction::Legend,
+        KeyAction::RoleFilter,
+        KeyAction::Throughput,
+        KeyAction::Discard,
+        KeyAction::CutDungeonRun,
+        KeyAction::ReloadCatalog,
+    ];
+
+    pub fn config_key(self) -> &'static str {
+        match self {
+            KeyAction::Quit => "quit",
+            KeyAction::ToggleHistory => "toggle_history",
+            KeyAction::ToggleIdle => "toggle_idle",
+            KeyAction::CycleDecoration => "cycle_decoration",
+            KeyAction::CycleMode => "cycle_mode",
+            KeyAction::ToggleBothRates => "toggle_both_rates",
+            KeyAction::OpenSettings => "open_settings",
+            KeyAction::SettingsPrev => "settings_prev",
+            KeyAction::SettingsNext => "settings_next",
+            KeyAction::SettingsDecrease => "settings_decrease",
+            KeyAction::SettingsIncrease => "settings_increase",
+            KeyAction::SettingsEdit => "settings_edit",
+            KeyAction::Filter => "filter",
+            KeyAction::SortPrev => "sort_prev",
+            KeyAction::SortNext => "sort_next",
+            KeyAction::SortDirection => "sort_direction",
+            KeyAction::Reconnect => "reconnect",
+            KeyAction::ErrorLog => "error_log",
+            KeyAction::Copy => "copy",
+            KeyAction::CopySelf => "copy_self",
+            KeyAction::Legend => "legend",
+            KeyAction::RoleFilter => "role_filter",
+            KeyAction::Throughput => "throughput",
+            KeyAction::Discard => "discard",
+            KeyAction::CutDungeonRun => "cut_dungeon_run",
+            KeyAction::ReloadCatalog => "reload_catalog",
+        }
+    }
+
+    /// Resolved before the history panel sees the key, so these also work while it is open.
+    fn is_global(self) -> bool {
+        matches!(
+            self,
+            KeyAction::Quit | KeyAction::ToggleHistory | KeyAction::ToggleIdle
+        )
+    }
+
+    fn from_config_key(key: &str) -> Option<Self> {
+        let key = key.trim().to_ascii_lowercase().replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|action| action.config_key() == key)
+    }
+
+    /// The stock keys, unchanged from before bindings were configurable.
+    fn default_keys(self) -> Vec<KeyCode> {
+        let keys: &[KeyCode] = match self {
+            KeyAction::Quit => &[KeyCode::Char('q'), KeyCode::Esc],
+            KeyAction::ToggleHistory => &[KeyCode::Char('h')],
+            KeyAction::ToggleIdle => &[KeyCode::Char('i')],
+            KeyAction::CycleDecoration => &[KeyCode::Char('d')],
+            KeyAction::CycleMode => &[KeyCode::Char('m')],
+            KeyAction::ToggleBothRates => &[KeyCode::Char('b')],
+            KeyAction::OpenSettings => &[KeyCode::Char('s')],
+            KeyAction::SettingsPrev => &[KeyCode::Up],
+            KeyAction::SettingsNext => &[KeyCode::Down],
+            KeyAction::SettingsDecrease => &[KeyCode::Left],
+            KeyAction::SettingsIncrease => &[KeyCode::Right],
+            KeyAction::SettingsEdit => &[KeyCode::Enter],
+            KeyAction::Filter => &[KeyCode::Char('/')],
+            KeyAction::SortPrev => &[KeyCode::Char('<')],
+            KeyAction::SortNext => &[KeyCode::Char('>')],
+            KeyAction::SortDirection => &[KeyCode::Char('~')],
+            KeyAction::Reconnect => &[KeyCode::Char('r')],
+            KeyAction::ErrorLog => &[KeyCode::Char('e')],
+            KeyAction::Copy => &[KeyCode::Char('c')],
+            KeyAction::CopySelf => &[KeyCode::Char('C')],
+            KeyAction::Legend => &[KeyCode::Char('l')],
+            KeyAction::RoleFilter => &[KeyCode::Char('f')],
+            KeyAction::Throughput => &[KeyCode::Char('t')],
+            KeyAction::Discard => &[KeyCode::Char('x')],
+            KeyAction::CutDungeonRun => &[KeyCode::Char('D')],
+            KeyAction::ReloadCatalog => &[KeyCode::Char('R')],
+        };
+        keys.to_vec()
+    }
+}
+
+/// Keys the history panel handles itself (the history `match` in `main`; keep the two in sync).
+/// Global actions can't take them, or the panel would never see the key.
+const HISTORY_KEYS: [KeyCode; 32] = [
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Enter,
+    KeyCode::Backspace,
+    KeyCode::Tab,
+    KeyCode::Char('c'),
+    KeyCode::Char('e'),
+    KeyCode::Char('E'),
+    KeyCode::Char('m'),
+    KeyCode::Char('M'),
+    KeyCode::Char('<'),
+    KeyCode::Char('>'),
+    KeyCode::Char('~'),
+    KeyCode::Char('['),
+    KeyCode::Char(']'),
+    KeyCode::Char('D'),
+    KeyCode::Char('f'),
+    KeyCode::Char('/'),
+    KeyCode::Char('j'),
+    KeyCode::Char('u'),
+    KeyCode::Char('U'),
+    KeyCode::Char('t'),
+    KeyCode::Char('T'),
+    KeyCode::Char('g'),
+    KeyCode::Char('o'),
+    KeyCode::Char('r'),
+    KeyCode::Char('x'),
+    KeyCode::Char('v'),
+];
+
+/// Which keys trigger each [`KeyAction`]. The default is the stock layout.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyBindings {
+    bindings: Vec<(KeyAction, Vec<KeyCode>)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            bindings: KeyAction::ALL
+                .into_iter()
+                .map(|action| (action, action.default_keys()))
+                .collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// The stock bindings with the actions listed in the config rebound. Unknown actions,
+    /// unreadable key names and a key bound to two actions are errors.
+    pub fn from_config(configured: &HashMap<String, KeyList>) -> Result<Self> {
+        let mut bindings = Self::default();
+        for (name, keys) in configured {
+            let Some(action) = KeyAction::from_config_key(name) else {
+                bail!("unknown action `{name}` in key_bindings");
+            };
+            let keys = keys
+                .as_slice()
+                .iter()
+                .map(|key| parse_key(key))
+                .collect::<Result<Vec<_>>>()?;
+            if keys.is_empty() {
+                bail!("`{name}` in key_bindings needs at least one key");
+            }
+            if let Some((_, slot)) = bindings.bindings.iter_mut().find(|(a, _)| *a == action) {
+                *slot = keys;
+            }
+        }
+        bindings.check_conflicts()?;
+        Ok(bindings)
+    }
+
+    fn check_conflicts(&self) -> Result<()> {
+        let mut seen: HashMap<KeyCode, KeyAction> = HashMap::new();
+        for (action, keys) in &self.bindings {
+            for key in keys {
+                if action.is_global() && HISTORY_KEYS.contains(key) {
+                    bail!(
+                        "key `{}` for `{}` in key_bindings is used by the history panel",
+                        key_label(*key),
+                        action.config_key()
+                    );
+                }
+                if let Some(other) = seen.insert(*key, *action).filter(|other| other != action) {
+                    bail!(
+                        "key `{}` is bound to both `{}` and `{}` in key_bindings",
+                        key_label(*key),
+                        other.config_key(),
+                        action.config_key()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The action bound to `code`, if any.
+    pub fn action(&self, code: KeyCode) -> Option<KeyAction> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&code))
+            .map(|(action, _)| *action)
+    }
+
+    /// The first key bound to `action`, as shown in key hints (arrows as ↑ ↓ ← →).
+    pub fn label(&self, action: KeyAction) -> String {
+        let key = self
+            .bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .and_then(|(_, keys)| keys.first());
+        match key {
+            Some(KeyCode::Up) => "↑".to_string(),
+            Some(KeyCode::Down) => "↓".to_string(),
+            Some(KeyCode::Left) => "←".to_string(),
+            Some(KeyCode::Right) => "→".to_string(),
+            Some(key) => key_label(*key),
+            None => String::new(),
+        }
+    }
+}
+
+/// Reads a key as written in the config: a single character (`"q"`, `"D"`) or a name such as
+/// `"Esc"`, `"Enter"`, `"Up"`, `"PageDown"`, `"Space"` or `"F5"` (names ignore case).
+fn parse_key(key: &str) -> Result<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(ch));
+    }
+    let name = key.trim().to_ascii_lowercase();
+    let code = match name.as_str() {
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" | "return" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "insert" => KeyCode::Insert,
+        "delete" => KeyCode::Delete,
+        _ => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            Some(n @ 1..=12) => KeyCode::F(n),
+            _ => bail!("unknown key `{key}` in key_bindings"),
+        },
+    };
+    Ok(code)
+}
+
+/// How a key is written in hints and error messages; the inverse of [`parse_key`].
+pub fn key_label(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(ch) => ch.to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::Insert => "Insert".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configured(pairs: &[(&str, &[&str])]) -> HashMap<String, KeyList> {
+        pairs
+            .iter()
+            .map(|(action, keys)| {
+                (
+                    action.to_string(),
+                    KeyList::Many(keys.iter().map(|key| key.to_string()).collect()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn defaults_match_the_stock_keys() {
+        let bindings = KeyBindings::default();
+        assert_eq!(bindings.action(KeyCode::Char('q')), Some(KeyAction::Quit));
+        assert_eq!(bindings.action(KeyCode::Esc), Some(KeyAction::Quit));
+        assert_eq!(
+            bindings.action(KeyCode::Char('D')),
+            Some(KeyAction::CutDungeonRun)
+        );
+        assert_eq!(bindings.action(KeyCode::Char('z')), None);
+        assert_eq!(bindings.label(KeyAction::Quit), "q");
+        assert!(bindings.check_conflicts().is_ok());
+    }
+
+    #[test]
+    fn config_rebinds_actions_and_keeps_the_rest() {
+        let bindings = KeyBindings::from_config(&configured(&[
+            ("settings_prev", &["k", "Up"]),
+            ("settings-next", &["j", "DOWN"]),
+            ("quit", &["F10"]),
+        ]))
+        .expect("valid bindings");
+        assert_eq!(
+            bindings.action(KeyCode::Char('k')),
+            Some(KeyAction::SettingsPrev)
+        );
+        assert_eq!(
+            bindings.action(KeyCode::Down),
+            Some(KeyAction::SettingsNext)
+        );
+        assert_eq!(bindings.action(KeyCode::F(10)), Some(KeyAction::Quit));
+        assert_eq!(bindings.action(KeyCode::Char('q')), None);
+        assert_eq!(
+            bindings.action(KeyCode::Char('m')),
+            Some(KeyAction::CycleMode)
+        );
+    }
+
+    #[test]
+    fn conflicts_and_unknown_names_are_rejected() {
+        let err = KeyBindings::from_config(&configured(&[("cycle_mode", &["d"])]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`d`"), "{err}");
+        assert!(
+            err.contains("cycle_decoration") && err.contains("cycle_mode"),
+            "{err}"
+        );
+
+        // Global keys would shadow the history panel's own keys.
+        let err = KeyBindings::from_config(&configured(&[("toggle_idle", &["g"])]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("history panel"), "{err}");
+        assert!(KeyBindings::from_config(&configured(&[("quit", &["r"])])).is_err());
+        assert!(KeyBindings::from_config(&configured(&[("legend", &["g"])])).is_ok());
+
+        assert!(KeyBindings::from_config(&configured(&[("fly", &["f"])])).is_err());
+        assert!(KeyBindings::from_config(&configured(&[("quit", &["Hyper"])])).is_err());
+        assert!(KeyBindings::from_config(&configured(&[("quit", &[])])).is_err());
+    }
+
+    #[test]
+    fn key_names_round_trip() {
+        for key in ["q", "D", "Space", "Esc", "PageUp", "F5", "~"] {
+            assert_eq!(key_label(parse_key(key).expect(key)), key);
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use std::env;
 use std::fs::{create_dir_all, OpenOptions};
+use std::io;
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use std::{io, sync::Arc};
 
 use anyhow::{bail, Context, Result};
 use crossterm::event::{
@@ -14,39 +16,51 @@ use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
 use ratatui::backend::CrosstermBackend;
+use ratatui::layout::Rect;
 use ratatui::Terminal;
 use tokio::sync::{mpsc, RwLock};
 use tokio::task;
 
+mod clipboard;
 mod config;
 mod dungeon;
 mod errors;
+mod export;
 mod history;
+mod keys;
 mod model;
 mod parse;
+mod persist;
+mod push;
 mod theme;
 mod ui;
 mod ui_history;
 mod ui_idle;
 mod ws_client;
 
+use errors::{AppError, AppErrorKind};
 use history::HistoryStore;
+use keys::{KeyAction, KeyBindings};
 use model::{
-    AppEvent, AppSettings, AppState, DungeonPanelLevel, HistoryPanelLevel, HistoryView,
-    SettingsField, WS_URL_DEFAULT,
+    AppEvent, AppSettings, AppState, ClockFormat, ConfirmAction, DateSort, DungeonPanelLevel,
+    HistoryPanelLevel, HistoryView, RecentKind, SettingsField, SEARCH_RESULT_LIMIT,
 };
 use tracing::level_filters::LevelFilter;
-use tracing::warn;
+use tracing::{info, warn};
+use ui::{FooterTarget, SettingsClick};
 
 const HISTORY_LIST_OFFSET: u16 = 4;
 
+#[allow(clippy::enum_variant_names)]
 enum HistoryTask {
+    LoadDates,
     LoadEncounters { date_id: String },
     LoadEncounterDetail { key: Vec<u8> },
     LoadDungeonDays,
     LoadDungeonRuns { date_id: String },
     LoadDungeonRunDetail { key: Vec<u8> },
     LoadDungeonEncounter { key: Vec<u8> },
+    SearchEncounters { query: String },
 }
 
 #[tokio::main]
@@ -54,6 +68,17 @@ async fn main() -> Result<()> {
     let cli = parse_cli()?;
     init_tracing(&cli)?;
 
+    match cli.command {
+        CliCommand::Doctor { repair } => return run_doctor(repair),
+        CliCommand::RebuildDungeons => return run_rebuild_dungeons(),
+        CliCommand::Config {
+            print_defaults,
+            print_path,
+            to_toml,
+        } => return run_config(print_defaults, print_path, to_toml),
+        CliCommand::Run => {}
+    }
+
     // Shared app state
     let state = Arc::new(RwLock::new(AppState::default()));
 
@@ -61,47 +86,126 @@ async fn main() -> Result<()> {
     let (tx, mut rx) = mpsc::unbounded_channel::<AppEvent>();
     let event_tx = tx.clone();
 
-    // Dungeon catalog (optional; disable dungeon mode if unavailable)
-    let dungeon_catalog = match dungeon::DungeonCatalog::load_default() {
-        Ok(catalog) => Some(Arc::new(catalog)),
-        Err(err) => {
-            warn!(error = ?err, "Dungeon catalog unavailable; dungeon mode disabled");
-            None
-        }
-    };
-
     // Load persisted configuration into state
-    let app_cfg = match config::load() {
+    let mut app_cfg = match config::load() {
         Ok(c) => c,
         Err(err) => {
             eprintln!("Failed to load config: {err:?}. Using defaults.");
             config::AppConfig::default()
         }
     };
+    let key_bindings = Arc::new(
+        KeyBindings::from_config(&app_cfg.key_bindings)
+            .context("invalid `key_bindings` in the config")?,
+    );
+
+    // Dungeon catalog (optional; disable dungeon mode if unavailable)
+    let catalog_url = dungeon::remote::catalog_url(app_cfg.dungeon_catalog_url.as_deref());
+    let dungeon_catalog = match dungeon::remote::load_catalog(catalog_url.as_deref()).await {
+        Ok(catalog) => Some(Arc::new(configure_catalog(catalog, &app_cfg))),
+        Err(err) => {
+            warn!(error = ?err, "Dungeon catalog unavailable; dungeon mode disabled");
+            None
+        }
+    };
+    if theme::Theme::by_name(&app_cfg.palette).is_none() {
+        warn!(palette = %app_cfg.palette, "unknown palette, using the default");
+    }
+    if HistoryView::parse_config_key(&app_cfg.default_history_view).is_none() {
+        warn!(
+            view = %app_cfg.default_history_view,
+            "unknown default_history_view, using encounters"
+        );
+    }
+    theme::install_overrides(&app_cfg.theme);
     {
         let mut s = state.write().await;
         s.apply_settings(AppSettings::from(app_cfg.clone()));
+        s.dungeon_catalog = dungeon_catalog.clone();
+        s.key_bindings = Arc::clone(&key_bindings);
+        s.recording_disabled = cli.no_record;
+        s.show_both_rates = app_cfg.show_both_rates;
+        s.dungeon_streak = persist::load_dungeon_streak(&persist::dungeon_streak_path())
+            .unwrap_or_else(|err| {
+                warn!(error = ?err, "ignoring unreadable dungeon streak");
+                Default::default()
+            });
+        // Later opens keep whichever tab was last shown this session.
+        s.history.view = HistoryView::from_config_key(&app_cfg.default_history_view);
+        s.history.date_sort = DateSort::from_config_key(&app_cfg.date_sort);
         // Initialize disconnected_since since the app starts disconnected
         // This must happen after settings are loaded so idle_duration() works correctly
         if s.disconnected_since.is_none() {
             s.disconnected_since = Some(Instant::now());
         }
+        if app_cfg.persist_snapshot {
+            match persist::load_recent_snapshot(&persist::snapshot_path(), history::now_ms()) {
+                Ok(Some(snapshot)) => snapshot.restore_into(&mut s),
+                Ok(None) => {}
+                Err(err) => warn!(error = ?err, "ignoring unreadable live snapshot"),
+            }
+        }
     }
 
     // History persistence (sled-backed)
     let history_store = Arc::new(history::HistoryStore::open_default()?);
-    let history_recorder = history::spawn_recorder(
-        history_store.clone(),
-        tx.clone(),
-        dungeon_catalog.clone(),
-        app_cfg.dungeon_mode_enabled,
-    );
+    // `--no-record` leaves the store untouched, retention included.
+    if app_cfg.max_history_days > 0 && !cli.no_record {
+        let cutoff = history::now_ms().saturating_sub(app_cfg.max_history_days * 86_400_000);
+        match history_store.prune_before(cutoff) {
+            Ok(0) => {}
+            Ok(removed) => info!(
+                removed,
+                days = app_cfg.max_history_days,
+                "pruned old history"
+            ),
+            Err(err) => warn!(error = ?err, "failed to prune old history"),
+        }
+    }
+    let history_recorder = if cli.no_record {
+        info!("--no-record: history recording disabled");
+        history::RecorderHandle::disabled()
+    } else {
+        history::spawn_recorder(
+            history_store.clone(),
+            tx.clone(),
+            dungeon_catalog.clone(),
+            app_cfg.dungeon_mode_enabled,
+            history::RecorderOptions::from(&app_cfg),
+        )
+    };
 
     // Spawn WS client task (auto-connect and subscribe)
-    let ws_url = WS_URL_DEFAULT.to_string();
+    let last_ws_url = persist::load_ui_state(&persist::ui_state_path())
+        .unwrap_or_default()
+        .last_ws_url;
+    let (ws_url, ws_url_source) = ws_client::resolve_url(
+        cli.ws_url.as_deref(),
+        app_cfg.ws_url.as_deref(),
+        last_ws_url.as_deref(),
+    );
+    ws_client::validate_url(&ws_url)
+        .with_context(|| format!("invalid websocket URL from {}", ws_url_source.description()))?;
+    info!(url = %ws_url, source = ?ws_url_source, "using websocket url");
+    // Remembered once the URL connects, unless it is already the stored one.
+    let mut unsaved_ws_url =
+        (last_ws_url.as_deref() != Some(ws_url.as_str())).then(|| ws_url.clone());
+    let ws_options = ws_client::ClientOptions {
+        url: ws_url,
+        accept_invalid_certs: cli.accept_invalid_certs || app_cfg.ws_accept_invalid_certs,
+        ..ws_client::ClientOptions::from(&app_cfg)
+    };
     let history_tx = history_recorder.clone();
     let ws_tx = tx.clone();
-    tokio::spawn(async move { ws_client::run(ws_url, ws_tx, history_tx).await });
+    // Capacity 1: repeated `r` presses before the client reacts collapse into one reconnect.
+    let (reconnect_tx, reconnect_rx) = mpsc::channel::<()>(1);
+    tokio::spawn(async move { ws_client::run(ws_options, ws_tx, history_tx, reconnect_rx).await });
+
+    // Optional push feed for third-party overlays
+    let mut push_server = match cli.push_ws {
+        Some(addr) => Some(push::PushServer::spawn(addr).await?),
+        None => None,
+    };
 
     // TUI init
     enable_raw_mode()?;
@@ -111,96 +215,233 @@ async fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // App loop
-    let tick = Duration::from_millis(100);
     let mut last_draw = Instant::now();
+    let mut last_persist = Instant::now();
+    let mut persisted: Option<persist::LiveSnapshot> = None;
     let mut running = true;
 
     while running {
         // Drain any incoming WS events into state
+        let mut drained = false;
         while let Ok(evt) = rx.try_recv() {
+            if matches!(evt, AppEvent::Connected) {
+                if let Some(url) = unsaved_ws_url.take() {
+                    update_ui_state(move |ui_state| ui_state.last_ws_url = Some(url));
+                }
+            }
             let mut s = state.write().await;
             s.apply(evt);
+            drained = true;
         }
 
-        // Draw at most every tick interval or immediately on first loop
+        if let Some(server) = push_server.as_mut().filter(|_| drained) {
+            server.publish(push::PushFrame::capture(
+                &*state.read().await,
+                Instant::now(),
+            ));
+        }
+
+        // Draw at most every redraw interval (re-read so settings changes apply at once)
+        let tick = state.read().await.settings.redraw_interval();
         if last_draw.elapsed() >= tick {
             let s = state.read().await.clone_snapshot();
             terminal.draw(|f| ui::draw(f, &s))?;
             last_draw = Instant::now();
+            let capacity = ui_history::detail_table_capacity();
+            if s.history.visible && s.history.detail_capacity != capacity {
+                state.write().await.history.detail_capacity = capacity;
+            }
+        }
+
+        if app_cfg.persist_snapshot && last_persist.elapsed() >= persist::SNAPSHOT_INTERVAL {
+            last_persist = Instant::now();
+            let snapshot = persist::LiveSnapshot::capture(&*state.read().await, history::now_ms());
+            let changed = persisted
+                .as_ref()
+                .map_or(true, |previous| !previous.same_view(&snapshot));
+            if changed {
+                let to_write = snapshot.clone();
+                task::spawn_blocking(move || {
+                    if let Err(err) = persist::save_snapshot(&persist::snapshot_path(), &to_write) {
+                        warn!(error = ?err, "failed to persist live snapshot");
+                    }
+                });
+                persisted = Some(snapshot);
+            }
         }
 
         // Non-blocking input with small timeout so we keep redrawing
         if event::poll(Duration::from_millis(10))? {
             match event::read()? {
-                Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => {
-                        let mut s = state.write().await;
-                        if s.show_settings {
-                            s.show_settings = false;
-                        } else if s.history.visible {
-                            s.history.visible = false;
-                            s.history.reset();
-                        } else {
-                            running = false;
+                Event::Key(key)
+                    if key.kind == KeyEventKind::Press
+                        && state.read().await.pending_confirm.is_some() =>
+                {
+                    let action = state.write().await.pending_confirm.take();
+                    let confirmed = matches!(
+                        key.code,
+                        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter
+                    );
+                    if let Some(action) = action.filter(|_| confirmed) {
+                        match action {
+                            ConfirmAction::DiscardEncounter => history_recorder.discard_active(),
+                            ConfirmAction::DeleteHistoryEntry { kind, key, .. } => {
+                                let mut s = state.write().await;
+                                let date_id = match kind {
+                                    RecentKind::Encounter => {
+                                        s.history.current_day().map(|day| day.iso_date.clone())
+                                    }
+                                    RecentKind::Dungeon => s
+                                        .history
+                                        .current_dungeon_day()
+                                        .map(|day| day.iso_date.clone()),
+                                };
+                                let epoch = s.history_begin_load();
+                                drop(s);
+                                spawn_history_delete(
+                                    kind,
+                                    key,
+                                    date_id,
+                                    epoch,
+                                    history::RecorderOptions::from(&app_cfg).dungeon,
+                                    history_store.clone(),
+                                    event_tx.clone(),
+                                );
+                            }
                         }
                     }
-                    KeyCode::Char('h') => {
-                        let should_load = {
-                            let mut s = state.write().await;
-                            if s.toggle_history() {
-                                s.history_set_loading();
-                                true
-                            } else {
-                                false
+                }
+                Event::Key(key)
+                    if key.kind == KeyEventKind::Press
+                        && state.read().await.live_filter_editing =>
+                {
+                    let mut s = state.write().await;
+                    match key.code {
+                        KeyCode::Enter => s.live_finish_filter(true),
+                        KeyCode::Esc => s.live_finish_filter(false),
+                        KeyCode::Backspace => s.live_filter_pop(),
+                        KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            s.live_filter_push(ch);
+                        }
+                        _ => {}
+                    }
+                }
+                Event::Key(key)
+                    if key.kind == KeyEventKind::Press
+                        && state.read().await.history.filter_editing =>
+                {
+                    let mut s = state.write().await;
+                    let finished = match key.code {
+                        KeyCode::Enter => Some(true),
+                        KeyCode::Esc => Some(false),
+                        KeyCode::Backspace => {
+                            s.history_filter_pop();
+                            None
+                        }
+                        KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            s.history_filter_push(ch);
+                            None
+                        }
+                        _ => None,
+                    };
+                    if let Some(keep) = finished {
+                        s.history_finish_filter(keep);
+                        if app_cfg.persist_history_filter {
+                            remember_history_filter(s.history.filter.clone());
+                        }
+                    }
+                }
+                Event::Key(key)
+                    if key.kind == KeyEventKind::Press
+                        && state.read().await.history.search_editing =>
+                {
+                    let mut s = state.write().await;
+                    let finished = match key.code {
+                        KeyCode::Enter => Some(true),
+                        KeyCode::Esc => Some(false),
+                        KeyCode::Backspace => {
+                            s.history_search_pop();
+                            None
+                        }
+                        KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            s.history_search_push(ch);
+                            None
+                        }
+                        _ => None,
+                    };
+                    if let Some(epoch) = finished.and_then(|submit| s.history_finish_search(submit))
+                    {
+                        let query = s.history.search_query.clone();
+                        spawn_history_task(
+                            HistoryTask::SearchEncounters { query },
+                            epoch,
+                            history_store.clone(),
+                            event_tx.clone(),
+                        );
+                    }
+                }
+                Event::Key(key)
+                    if key.kind == KeyEventKind::Press
+                        && state.read().await.settings_entry.is_some() =>
+                {
+                    let updated = {
+                        let mut s = state.write().await;
+                        let finished = match key.code {
+                            KeyCode::Enter => Some(true),
+                            KeyCode::Esc => Some(false),
+                            KeyCode::Backspace => {
+                                s.settings_entry_pop();
+                                None
+                            }
+                            KeyCode::Char(ch) => {
+                                s.settings_entry_push(ch);
+                                None
                             }
+                            _ => None,
                         };
-                        if should_load {
-                            let store = history_store.clone();
-                            let tx = event_tx.clone();
-                            tokio::spawn(async move {
-                                match task::spawn_blocking(move || store.load_dates()).await {
-                                    Ok(Ok(days)) => {
-                                        let _ = tx.send(AppEvent::HistoryDatesLoaded { days });
-                                    }
-                                    Ok(Err(err)) => {
-                                        let _ = tx.send(AppEvent::HistoryError {
-                                            message: err.to_string(),
-                                        });
-                                    }
-                                    Err(err) => {
-                                        let _ = tx.send(AppEvent::HistoryError {
-                                            message: format!("History load failed: {err}"),
-                                        });
-                                    }
-                                }
-                            });
-                            let store_dungeon = history_store.clone();
-                            let tx_dungeon = event_tx.clone();
-                            tokio::spawn(async move {
-                                match task::spawn_blocking(move || {
-                                    store_dungeon.load_dungeon_days()
-                                })
-                                .await
-                                {
-                                    Ok(Ok(days)) => {
-                                        let _ =
-                                            tx_dungeon.send(AppEvent::DungeonDatesLoaded { days });
-                                    }
-                                    Ok(Err(err)) => {
-                                        let _ = tx_dungeon.send(AppEvent::HistoryError {
-                                            message: format!("Failed to load dungeon days: {err}"),
-                                        });
-                                    }
-                                    Err(err) => {
-                                        let _ = tx_dungeon.send(AppEvent::HistoryError {
-                                            message: format!("History load failed: {err}"),
-                                        });
-                                    }
-                                }
-                            });
+                        let changed =
+                            finished.is_some_and(|commit| s.settings_finish_entry(commit));
+                        changed.then(|| s.settings.clone())
+                    };
+                    if let Some(settings) = updated {
+                        save_settings(&settings, &mut app_cfg, &history_recorder);
+                    }
+                }
+                Event::Key(key)
+                    if key.kind == KeyEventKind::Press
+                        && state.read().await.history.show_recent =>
+                {
+                    let mut s = state.write().await;
+                    match key.code {
+                        KeyCode::Up => s.history_recent_move(-1),
+                        KeyCode::Down => s.history_recent_move(1),
+                        KeyCode::Enter => s.history_jump_recent(),
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('v') => {
+                            s.history_toggle_recent();
                         }
+                        _ => {}
+                    }
+                    if let Some((task, epoch)) = determine_history_task(&mut s) {
+                        spawn_history_task(task, epoch, history_store.clone(), event_tx.clone());
                     }
-                    KeyCode::Char('i') => {
+                    remember_recent_if_changed(&mut s);
+                }
+                Event::Key(key) if key.kind == KeyEventKind::Press => match key_bindings
+                    .action(key.code)
+                {
+                    Some(KeyAction::Quit) => {
+                        let mut s = state.write().await;
+                        if !s.navigate_back() {
+                            running = false;
+                        }
+                    }
+                    Some(KeyAction::ToggleHistory) => {
+                        let load_epoch = toggle_history_panel(&mut *state.write().await, &app_cfg);
+                        if let Some(epoch) = load_epoch {
+                            spawn_history_dates(epoch, &history_store, &event_tx);
+                        }
+                    }
+                    Some(KeyAction::ToggleIdle) => {
                         let mut s = state.write().await;
                         if !s.history.visible {
                             let now = Instant::now();
@@ -209,76 +450,261 @@ async fn main() -> Result<()> {
                             }
                         }
                     }
-                    _ => {
+                    action => {
                         let mut pending_task = None;
+                        let mut refresh_epoch = None;
+                        let mut day_export = None;
+                        let mut encounter_export = None;
+                        let mut recent_lookup = Vec::new();
                         let history_active = {
                             let mut s = state.write().await;
                             if s.history.visible {
+                                // Mirrored by `HISTORY_KEYS` in keys.rs.
                                 match key.code {
+                                    KeyCode::Char('e') => {
+                                        day_export = s.history_day_export_target();
+                                        encounter_export = s
+                                            .history_encounter_export_target()
+                                            .map(|key| (key, export::ExportFormat::Json));
+                                    }
+                                    KeyCode::Char('c') => match s.history_copy_text() {
+                                        Some((text, count)) if count > 0 => {
+                                            let done = format!("Copied the ranking ({count} rows)");
+                                            spawn_copy(text, done, &event_tx);
+                                        }
+                                        Some(_) => {
+                                            s.notice =
+                                                Some(("Nothing to copy".into(), Instant::now()));
+                                        }
+                                        None => {}
+                                    },
+                                    KeyCode::Char('E') => {
+                                        encounter_export = s
+                                            .history_encounter_export_target()
+                                            .map(|key| (key, export::ExportFormat::Csv));
+                                    }
                                     KeyCode::Up => s.history_move_selection(-1),
                                     KeyCode::Down => s.history_move_selection(1),
-                                    KeyCode::PageUp => s.history_move_selection(-5),
-                                    KeyCode::PageDown => s.history_move_selection(5),
-                                    KeyCode::Left | KeyCode::Backspace => s.history_back(),
+                                    KeyCode::PageUp => s.history_scroll(-5),
+                                    KeyCode::PageDown => s.history_scroll(5),
+                                    KeyCode::Left | KeyCode::Backspace => {
+                                        s.history_back();
+                                    }
                                     KeyCode::Right | KeyCode::Enter => s.history_enter(),
                                     KeyCode::Char('m') | KeyCode::Char('M') => {
                                         s.history_toggle_mode()
                                     }
+                                    KeyCode::Char('<') => s.cycle_sort(-1),
+                                    KeyCode::Char('>') => s.cycle_sort(1),
+                                    KeyCode::Char('~') => s.toggle_sort_direction(),
+                                    KeyCode::Char('[') => s.history_move_detail_row(-1),
+                                    KeyCode::Char(']') => s.history_move_detail_row(1),
+                                    KeyCode::Char('D') => s.history_toggle_deaths(),
+                                    KeyCode::Char('f') => s.role_filter = s.role_filter.next(),
+                                    KeyCode::Char('/') => {
+                                        s.history_start_filter();
+                                        s.history_start_search();
+                                    }
+                                    KeyCode::Char('j') => s.history_cycle_job_filter(),
+                                    KeyCode::Char('u') | KeyCode::Char('U') => {
+                                        s.history_toggle_buffs()
+                                    }
                                     KeyCode::Tab => s.history_toggle_view(),
                                     KeyCode::Char('t') | KeyCode::Char('T') => {
                                         s.history_toggle_view()
                                     }
+                                    KeyCode::Char('g') => s.history_toggle_boss_groups(),
+                                    KeyCode::Char('o') => s.history_toggle_date_sort(),
+                                    KeyCode::Char('r') => refresh_epoch = s.history_refresh(),
+                                    KeyCode::Char('x') => {
+                                        s.request_history_delete();
+                                    }
+                                    KeyCode::Char('v') => {
+                                        let opened = s.history_toggle_recent();
+                                        if opened {
+                                            recent_lookup = s.history_recent_unresolved();
+                                        }
+                                    }
                                     _ => {}
                                 }
                                 pending_task = determine_history_task(&mut s);
+                                remember_recent_if_changed(&mut s);
                                 true
                             } else {
                                 false
                             }
                         };
 
-                        if let Some(task) = pending_task {
-                            spawn_history_task(task, history_store.clone(), event_tx.clone());
+                        if let Some(epoch) = refresh_epoch {
+                            spawn_history_dates(epoch, &history_store, &event_tx);
+                        }
+
+                        if let Some((task, epoch)) = pending_task {
+                            spawn_history_task(
+                                task,
+                                epoch,
+                                history_store.clone(),
+                                event_tx.clone(),
+                            );
+                        }
+
+                        if !recent_lookup.is_empty() {
+                            spawn_recent_lookup(
+                                recent_lookup,
+                                ClockFormat::from_config_key(&app_cfg.clock_format),
+                                history_store.clone(),
+                                event_tx.clone(),
+                            );
+                        }
+
+                        if let Some((date_id, keys)) = day_export {
+                            spawn_day_export(
+                                date_id,
+                                keys,
+                                export::ExportFormat::from_config_key(&app_cfg.export_format),
+                                history_store.clone(),
+                                event_tx.clone(),
+                            );
+                        }
+
+                        if let Some((key, format)) = encounter_export {
+                            spawn_encounter_export(
+                                key,
+                                format,
+                                history_store.clone(),
+                                event_tx.clone(),
+                            );
                         }
 
                         if history_active {
                             continue;
                         }
 
-                        match key.code {
-                            KeyCode::Char('D') if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                        match action {
+                            Some(KeyAction::CutDungeonRun) => {
                                 history_recorder.cut_dungeon_session();
                             }
-                            KeyCode::Char('d') => {
+                            Some(KeyAction::ReloadCatalog) => {
+                                state.write().await.notice =
+                                    Some(("Reloading dungeon catalog…".into(), Instant::now()));
+                                spawn_catalog_reload(
+                                    catalog_url.clone(),
+                                    app_cfg.clone(),
+                                    history_recorder.clone(),
+                                    &event_tx,
+                                );
+                            }
+                            Some(KeyAction::CycleDecoration) => {
                                 let mut s = state.write().await;
                                 s.decoration = s.decoration.next();
                             }
-                            KeyCode::Char('m') => {
+                            Some(KeyAction::CycleMode) => {
                                 let mut s = state.write().await;
                                 s.mode = s.mode.next();
                                 s.resort_rows();
                             }
-                            KeyCode::Char('s') => {
+                            Some(KeyAction::ToggleBothRates) => {
+                                let mut s = state.write().await;
+                                s.show_both_rates = !s.show_both_rates;
+                            }
+                            Some(KeyAction::Filter) => state.write().await.live_start_filter(),
+                            Some(KeyAction::SortPrev) => state.write().await.cycle_sort(-1),
+                            Some(KeyAction::SortNext) => state.write().await.cycle_sort(1),
+                            Some(KeyAction::SortDirection) => {
+                                state.write().await.toggle_sort_direction()
+                            }
+                            Some(KeyAction::Reconnect) => {
+                                let accepted = state.write().await.request_reconnect();
+                                if accepted {
+                                    let _ = reconnect_tx.try_send(());
+                                }
+                            }
+                            Some(KeyAction::ErrorLog) => {
+                                let mut s = state.write().await;
+                                s.show_error_log = !s.show_error_log;
+                            }
+                            Some(KeyAction::Copy) => {
+                                let mut s = state.write().await;
+                                if s.show_error_log {
+                                    s.clear_errors();
+                                } else {
+                                    let rows = model::visible_live_rows(
+                                        &s.rows,
+                                        s.role_filter,
+                                        &s.live_filter,
+                                        s.settings.hide_zero_rows,
+                                        s.mode,
+                                    );
+                                    if rows.is_empty() {
+                                        s.notice =
+                                            Some(("Nothing to copy yet".into(), Instant::now()));
+                                    } else {
+                                        let text = clipboard::summary_text(
+                                            s.encounter.as_ref(),
+                                            &rows,
+                                            s.mode,
+                                        );
+                                        let done =
+                                            format!("Copied the ranking ({} rows)", rows.len());
+                                        spawn_copy(text, done, &event_tx);
+                                    }
+                                }
+                            }
+                            Some(KeyAction::CopySelf) => {
+                                let mut s = state.write().await;
+                                let self_name = s.settings.resolve_self(&s.rows);
+                                match clipboard::self_line(&s.rows, self_name, s.mode) {
+                                    Some((line, true)) => {
+                                        spawn_copy(line, "Copied your line".into(), &event_tx);
+                                    }
+                                    Some((line, false)) => {
+                                        let done =
+                                            "You're not in this fight (set \"self_names\"); \
+                                                    copied the top line instead";
+                                        spawn_copy(line, done.into(), &event_tx);
+                                    }
+                                    None => {
+                                        s.notice =
+                                            Some(("Nothing to copy yet".into(), Instant::now()));
+                                    }
+                                }
+                            }
+                            Some(KeyAction::Legend) => {
+                                let mut s = state.write().await;
+                                s.show_legend = !s.show_legend;
+                            }
+                            Some(KeyAction::RoleFilter) => {
+                                let mut s = state.write().await;
+                                s.role_filter = s.role_filter.next();
+                            }
+                            Some(KeyAction::Throughput) => {
+                                let mut s = state.write().await;
+                                s.show_throughput = !s.show_throughput;
+                            }
+                            Some(KeyAction::Discard) => {
+                                state.write().await.request_discard_encounter();
+                            }
+                            Some(KeyAction::OpenSettings) => {
                                 let mut s = state.write().await;
                                 s.show_settings = !s.show_settings;
                                 if s.show_settings {
                                     s.settings_cursor = SettingsField::default();
                                 }
                             }
-                            KeyCode::Up => {
+                            Some(KeyAction::SettingsPrev) => {
                                 let mut s = state.write().await;
                                 if s.show_settings {
                                     s.prev_setting();
                                 }
                             }
-                            KeyCode::Down => {
+                            Some(KeyAction::SettingsNext) => {
                                 let mut s = state.write().await;
                                 if s.show_settings {
                                     s.next_setting();
                                 }
                             }
-                            KeyCode::Left | KeyCode::Right => {
-                                let forward = matches!(key.code, KeyCode::Right);
+                            Some(KeyAction::SettingsDecrease | KeyAction::SettingsIncrease) => {
+                                let forward = action == Some(KeyAction::SettingsIncrease);
                                 let updated = {
                                     let mut s = state.write().await;
                                     if s.show_settings && s.adjust_selected_setting(forward) {
@@ -288,26 +714,47 @@ async fn main() -> Result<()> {
                                     }
                                 };
                                 if let Some(settings) = updated {
-                                    let app_cfg: config::AppConfig = settings.into();
-                                    if let Err(err) = config::save(&app_cfg) {
-                                        eprintln!("Failed to save config: {err:?}");
-                                    }
-                                    history_recorder
-                                        .set_dungeon_mode_enabled(app_cfg.dungeon_mode_enabled);
+                                    save_settings(&settings, &mut app_cfg, &history_recorder);
                                 }
                             }
+                            Some(KeyAction::SettingsEdit) => {
+                                state.write().await.settings_begin_entry();
+                            }
                             _ => {}
                         }
                     }
                 },
                 Event::Key(_) => {}
+                Event::Mouse(mouse) if !state.read().await.history.visible => {
+                    let size = terminal.size()?;
+                    let outcome = handle_live_mouse(mouse, size, &mut *state.write().await);
+                    match outcome {
+                        LiveMouseOutcome::None => {}
+                        LiveMouseOutcome::SettingsChanged(settings) => {
+                            save_settings(&settings, &mut app_cfg, &history_recorder);
+                        }
+                        LiveMouseOutcome::ToggleHistory => {
+                            let load_epoch =
+                                toggle_history_panel(&mut *state.write().await, &app_cfg);
+                            if let Some(epoch) = load_epoch {
+                                spawn_history_dates(epoch, &history_store, &event_tx);
+                            }
+                        }
+                    }
+                }
                 Event::Mouse(mouse) => {
                     handle_history_mouse(mouse, &state).await;
                     let mut s = state.write().await;
                     if s.history.visible {
-                        if let Some(task) = determine_history_task(&mut s) {
-                            spawn_history_task(task, history_store.clone(), event_tx.clone());
+                        if let Some((task, epoch)) = determine_history_task(&mut s) {
+                            spawn_history_task(
+                                task,
+                                epoch,
+                                history_store.clone(),
+                                event_tx.clone(),
+                            );
                         }
+                        remember_recent_if_changed(&mut s);
                     }
                 }
                 _ => {}
@@ -330,8 +777,35 @@ async fn main() -> Result<()> {
 #[derive(Debug, Default)]
 struct CliArgs {
     debug: Option<DebugTarget>,
+    /// Address for the outbound overlay WebSocket feed.
+    push_ws: Option<SocketAddr>,
+    /// IINACT websocket URL, overriding the config and the last-good URL.
+    ws_url: Option<String>,
+    /// Skip TLS certificate checks for `wss://` URLs (`--accept-invalid-certs`).
+    accept_invalid_certs: bool,
+    /// Show the live meter without writing anything to history (`--no-record`).
+    no_record: bool,
+    command: CliCommand,
+}
+
+/// Subcommands run to completion without starting the TUI.
+#[derive(Debug, Default, PartialEq, Eq)]
+enum CliCommand {
+    #[default]
+    Run,
+    Doctor {
+        repair: bool,
+    },
+    RebuildDungeons,
+    Config {
+        print_defaults: bool,
+        print_path: bool,
+        to_toml: bool,
+    },
 }
 
+const SUBCOMMANDS: &[&str] = &["doctor", "rebuild-dungeons", "config"];
+
 #[derive(Debug)]
 enum DebugTarget {
     Default,
@@ -341,6 +815,11 @@ enum DebugTarget {
 fn parse_cli() -> Result<CliArgs> {
     let mut args = env::args().skip(1).peekable();
     let mut debug = None;
+    let mut push_ws = None;
+    let mut ws_url = None;
+    let mut no_record = false;
+    let mut accept_invalid_certs = false;
+    let mut command = CliCommand::Run;
 
     while let Some(arg) = args.next() {
         if arg == "--debug" {
@@ -348,7 +827,7 @@ fn parse_cli() -> Result<CliArgs> {
                 bail!("`--debug` specified more than once");
             }
             if let Some(next) = args.peek() {
-                if !next.starts_with('-') {
+                if !next.starts_with('-') && !SUBCOMMANDS.contains(&next.as_str()) {
                     let path = args
                         .next()
                         .map(PathBuf::from)
@@ -367,12 +846,127 @@ fn parse_cli() -> Result<CliArgs> {
             } else {
                 debug = Some(DebugTarget::Path(PathBuf::from(rest)));
             }
+        } else if arg == "--push-ws" {
+            let Some(addr) = args.next() else {
+                bail!("`--push-ws` needs an address such as 127.0.0.1:10502");
+            };
+            push_ws = Some(parse_push_addr(&addr)?);
+        } else if let Some(addr) = arg.strip_prefix("--push-ws=") {
+            push_ws = Some(parse_push_addr(addr)?);
+        } else if arg == "--ws-url" {
+            let Some(url) = args.next() else {
+                bail!("`--ws-url` needs a URL such as ws://127.0.0.1:10501/ws");
+            };
+            ws_url = Some(parse_ws_url(&url)?);
+        } else if let Some(url) = arg.strip_prefix("--ws-url=") {
+            ws_url = Some(parse_ws_url(url)?);
+        } else if arg == "--no-record" {
+            no_record = true;
+        } else if arg == "--accept-invalid-certs" {
+            accept_invalid_certs = true;
+        } else if arg == "doctor" && command == CliCommand::Run {
+            command = CliCommand::Doctor { repair: false };
+        } else if arg == "--repair" && matches!(command, CliCommand::Doctor { .. }) {
+            command = CliCommand::Doctor { repair: true };
+        } else if arg == "rebuild-dungeons" && command == CliCommand::Run {
+            command = CliCommand::RebuildDungeons;
+        } else if arg == "config" && command == CliCommand::Run {
+            command = CliCommand::Config {
+                print_defaults: false,
+                print_path: false,
+                to_toml: false,
+            };
+        } else if arg == "--print-defaults" || arg == "--config-path" || arg == "--to-toml" {
+            let CliCommand::Config {
+                print_defaults,
+                print_path,
+                to_toml,
+            } = &mut command
+            else {
+                bail!("unknown argument: {arg}");
+            };
+            match arg.as_str() {
+                "--print-defaults" => *print_defaults = true,
+                "--config-path" => *print_path = true,
+                _ => *to_toml = true,
+            }
         } else {
             bail!("unknown argument: {arg}");
         }
     }
 
-    Ok(CliArgs { debug })
+    Ok(CliArgs {
+        debug,
+        push_ws,
+        ws_url,
+        accept_invalid_certs,
+        no_record,
+        command,
+    })
+}
+
+/// `--ws-url` is validated here too so an explicitly empty value isn't mistaken for "unset".
+fn parse_ws_url(url: &str) -> Result<String> {
+    let url = url.trim();
+    if url.is_empty() {
+        bail!("`--ws-url` needs a URL such as ws://127.0.0.1:10501/ws");
+    }
+    ws_client::validate_url(url).context("invalid `--ws-url`")?;
+    Ok(url.to_string())
+}
+
+fn parse_push_addr(addr: &str) -> Result<SocketAddr> {
+    addr.parse()
+        .with_context(|| format!("invalid `--push-ws` address `{addr}` (expected host:port)"))
+}
+
+fn run_doctor(repair: bool) -> Result<()> {
+    let store = HistoryStore::open_default()?;
+    println!(
+        "Checking history database at {}",
+        config::history_db_path().display()
+    );
+    let report = store.check_integrity(repair)?;
+    println!("{report}");
+    Ok(())
+}
+
+fn run_config(print_defaults: bool, print_path: bool, to_toml: bool) -> Result<()> {
+    if !print_defaults && !print_path && !to_toml {
+        bail!("`config` needs `--print-defaults`, `--config-path` or `--to-toml`");
+    }
+    if to_toml {
+        let cfg = config::load()?;
+        let path = config::save_as_toml(&cfg)?;
+        println!("Wrote {}", path.display());
+    }
+    if print_path {
+        println!("{}", config::config_path().display());
+    }
+    if print_defaults {
+        println!("{}", config::defaults_json()?);
+    }
+    Ok(())
+}
+
+fn run_rebuild_dungeons() -> Result<()> {
+    let cfg = config::load().unwrap_or_else(|err| {
+        eprintln!("Failed to load config: {err:?}. Using defaults.");
+        config::AppConfig::default()
+    });
+    let catalog_url = dungeon::remote::catalog_url(cfg.dungeon_catalog_url.as_deref());
+    let catalog = dungeon::remote::load_catalog_offline(catalog_url.as_deref())
+        .context("a dungeon catalog is required to rebuild dungeon runs")?;
+    let catalog = configure_catalog(catalog, &cfg);
+    let options = history::RecorderOptions::from(&cfg).dungeon;
+    let store = HistoryStore::open_default()?;
+    println!(
+        "Rebuilding dungeon runs in {}",
+        config::history_db_path().display()
+    );
+    let report = history::rebuild::rebuild_dungeons(&store, Arc::new(catalog), options)?;
+    println!("{report}");
+    Ok(())
 }
 
 fn init_tracing(cli: &CliArgs) -> Result<()> {
@@ -414,15 +1008,103 @@ fn init_tracing(cli: &CliArgs) -> Result<()> {
     Ok(())
 }
 
+/// Opens or closes the history panel as `h` does; returns the load epoch when it opened.
+fn toggle_history_panel(s: &mut AppState, app_cfg: &config::AppConfig) -> Option<u64> {
+    if !s.toggle_history() {
+        return None;
+    }
+    let epoch = s.history_begin_load();
+    match persist::load_ui_state(&persist::ui_state_path()) {
+        Ok(ui_state) => {
+            if app_cfg.persist_history_filter {
+                s.history_restore_filter(ui_state.history_filter);
+            }
+            s.history_restore_recent(ui_state.recent_history);
+        }
+        Err(err) => warn!(error = ?err, "ignoring unreadable UI state"),
+    }
+    Some(epoch)
+}
+
+/// Follow-up work for a live-view click that needs more than the state lock.
+enum LiveMouseOutcome {
+    None,
+    SettingsChanged(Box<AppSettings>),
+    ToggleHistory,
+}
+
+/// Clicks and wheel turns outside history: the settings popup takes them while it is open,
+/// otherwise the footer's key hints act like their keys. Anything else is ignored.
+fn handle_live_mouse(mouse: MouseEvent, screen: Rect, s: &mut AppState) -> LiveMouseOutcome {
+    if s.pending_confirm.is_some() || s.show_legend || s.show_error_log {
+        return LiveMouseOutcome::None;
+    }
+    if s.show_settings {
+        if s.settings_entry.is_some() {
+            return LiveMouseOutcome::None;
+        }
+        let click = ui::settings_click(&s.clone_snapshot(), screen, mouse.column, mouse.row);
+        let forward = match (mouse.kind, click) {
+            (MouseEventKind::Down(MouseButton::Left), Some(SettingsClick::Select(field))) => {
+                s.settings_cursor = field;
+                return LiveMouseOutcome::None;
+            }
+            (MouseEventKind::Down(MouseButton::Left), Some(SettingsClick::Adjust(forward))) => {
+                forward
+            }
+            (MouseEventKind::ScrollUp, Some(_)) => true,
+            (MouseEventKind::ScrollDown, Some(_)) => false,
+            _ => return LiveMouseOutcome::None,
+        };
+        return if s.adjust_selected_setting(forward) {
+            LiveMouseOutcome::SettingsChanged(Box::new(s.settings.clone()))
+        } else {
+            LiveMouseOutcome::None
+        };
+    }
+
+    let on_footer = mouse.row + 1 == screen.height && s.error.is_none();
+    if !on_footer || mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+        return LiveMouseOutcome::None;
+    }
+    let target = ui::footer_target_at(
+        &s.clone_snapshot(),
+        usize::from(screen.width),
+        usize::from(mouse.column),
+    );
+    match target {
+        Some(FooterTarget::Mode) => {
+            s.mode = s.mode.next();
+            s.resort_rows();
+        }
+        Some(FooterTarget::Decoration) => s.decoration = s.decoration.next(),
+        Some(FooterTarget::Settings) => {
+            s.show_settings = true;
+            s.settings_cursor = SettingsField::default();
+        }
+        Some(FooterTarget::History) => return LiveMouseOutcome::ToggleHistory,
+        None => {}
+    }
+    LiveMouseOutcome::None
+}
+
 async fn handle_history_mouse(mouse: MouseEvent, state: &Arc<RwLock<AppState>>) {
     let mut s = state.write().await;
     if !s.history.visible || s.history.loading {
         return;
     }
+    if s.history.show_recent {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => s.history_recent_move(1),
+            MouseEventKind::ScrollUp => s.history_recent_move(-1),
+            _ => {}
+        }
+        return;
+    }
 
     match mouse.kind {
-        MouseEventKind::ScrollDown => s.history_move_selection(1),
-        MouseEventKind::ScrollUp => s.history_move_selection(-1),
+        MouseEventKind::ScrollDown => s.history_scroll(1),
+        MouseEventKind::ScrollUp => s.history_scroll(-1),
         MouseEventKind::Down(MouseButton::Left) => {
             let index = mouse.row.saturating_sub(HISTORY_LIST_OFFSET) as usize;
             match s.history.view {
@@ -435,15 +1117,25 @@ async fn handle_history_mouse(mouse: MouseEvent, state: &Arc<RwLock<AppState>>)
                         s.history_enter();
                     }
                     HistoryPanelLevel::Encounters => {
-                        if let Some(day) = s.history.current_day() {
-                            if !day.encounters.is_empty() {
-                                let max_index = day.encounters.len().saturating_sub(1);
-                                s.history.selected_encounter = index.min(max_index);
-                                s.history_enter();
-                            }
+                        let list_offset = HISTORY_LIST_OFFSET + ui_history::DAY_TOTAL_ROWS;
+                        if mouse.row < list_offset {
+                            return;
+                        }
+                        let index = (mouse.row - list_offset) as usize;
+                        let visible = s.history.visible_encounter_indices();
+                        if let Some(last) = visible.last().copied() {
+                            s.history.selected_encounter =
+                                visible.get(index).copied().unwrap_or(last);
+                            s.history_enter();
                         }
                     }
-                    HistoryPanelLevel::EncounterDetail => {}
+                    HistoryPanelLevel::SearchResults => {
+                        if let Some(last) = s.history.search_results.len().checked_sub(1) {
+                            s.history.search_selected = index.min(last);
+                            s.history_enter();
+                        }
+                    }
+                    HistoryPanelLevel::EncounterDetail | HistoryPanelLevel::Abilities => {}
                 },
                 HistoryView::Dungeons => match s.history.dungeon_level {
                     DungeonPanelLevel::Dates => {
@@ -480,7 +1172,8 @@ async fn handle_history_mouse(mouse: MouseEvent, state: &Arc<RwLock<AppState>>)
     }
 }
 
-fn determine_history_task(state: &mut AppState) -> Option<HistoryTask> {
+/// Picks the store load the current history view still needs, with the epoch to tag it with.
+fn determine_history_task(state: &mut AppState) -> Option<(HistoryTask, u64)> {
     if state.history.loading {
         return None;
     }
@@ -490,7 +1183,7 @@ fn determine_history_task(state: &mut AppState) -> Option<HistoryTask> {
 
     match state.history.view {
         HistoryView::Encounters => match state.history.level {
-            HistoryPanelLevel::Dates => {}
+            HistoryPanelLevel::Dates | HistoryPanelLevel::SearchResults => {}
             HistoryPanelLevel::Encounters => {
                 if let Some(day) = state.history.current_day() {
                     if !day.encounters_loaded && !day.encounter_ids.is_empty() {
@@ -501,7 +1194,7 @@ fn determine_history_task(state: &mut AppState) -> Option<HistoryTask> {
                     }
                 }
             }
-            HistoryPanelLevel::EncounterDetail => {
+            HistoryPanelLevel::EncounterDetail | HistoryPanelLevel::Abilities => {
                 if let Some(enc) = state.history.current_encounter() {
                     if enc.record.is_none() {
                         task = Some(HistoryTask::LoadEncounterDetail {
@@ -560,19 +1253,329 @@ fn determine_history_task(state: &mut AppState) -> Option<HistoryTask> {
         },
     }
 
-    if blocking {
-        state.history_set_loading();
+    let epoch = if blocking {
+        state.history_begin_load()
+    } else {
+        state.history_epoch
+    };
+
+    task.map(|task| (task, epoch))
+}
+
+/// Writes changed settings back to the config file and pushes the ones the recorder uses.
+fn save_settings(
+    settings: &AppSettings,
+    app_cfg: &mut config::AppConfig,
+    recorder: &history::RecorderHandle,
+) {
+    settings.write_to_config(app_cfg);
+    if let Err(err) = config::save(app_cfg) {
+        eprintln!("Failed to save config: {err:?}");
     }
+    recorder.set_dungeon_mode_enabled(app_cfg.dungeon_mode_enabled);
+}
 
-    task
+/// Saves the history filter to the UI-state file without blocking the UI loop.
+fn remember_history_filter(filter: String) {
+    update_ui_state(move |ui_state| ui_state.history_filter = filter);
+}
+
+/// Saves the recently viewed list once opening an encounter or run has changed it.
+fn remember_recent_if_changed(state: &mut AppState) {
+    if std::mem::take(&mut state.history.recent_dirty) {
+        let recent = state.history.recent.clone();
+        update_ui_state(move |ui_state| ui_state.recent_history = recent);
+    }
+}
+
+type UiStateUpdate = Box<dyn FnOnce(&mut persist::UiState) + Send>;
+
+/// Changes waiting to be written to the UI-state file, in the order they were made.
+static UI_STATE_UPDATES: Mutex<Vec<UiStateUpdate>> = Mutex::new(Vec::new());
+/// Held across each load-modify-save so two writes never read the same file contents and
+/// drop each other's change.
+static UI_STATE_FILE: Mutex<()> = Mutex::new(());
+
+fn update_ui_state(update: impl FnOnce(&mut persist::UiState) + Send + 'static) {
+    UI_STATE_UPDATES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(Box::new(update));
+    task::spawn_blocking(|| {
+        let _file = UI_STATE_FILE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Whichever task gets here first writes everything queued so far.
+        let updates = std::mem::take(
+            &mut *UI_STATE_UPDATES
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        if updates.is_empty() {
+            return;
+        }
+        let path = persist::ui_state_path();
+        let mut ui_state = persist::load_ui_state(&path).unwrap_or_default();
+        for update in updates {
+            update(&mut ui_state);
+        }
+        if let Err(err) = persist::save_ui_state(&path, &ui_state) {
+            warn!(error = ?err, "failed to save UI state");
+        }
+    });
+}
+
+/// Looks up dates and titles for the recently viewed list; unreadable entries show as missing.
+fn spawn_recent_lookup(
+    entries: Vec<(RecentKind, Vec<u8>)>,
+    clock: ClockFormat,
+    store: Arc<HistoryStore>,
+    tx: mpsc::UnboundedSender<AppEvent>,
+) {
+    task::spawn_blocking(move || {
+        for (kind, key) in entries {
+            let result = match kind {
+                RecentKind::Encounter => store.encounter_label(&key, clock),
+                RecentKind::Dungeon => store.dungeon_label(&key, clock),
+            };
+            let found = result.unwrap_or_else(|err| {
+                warn!(error = ?err, "failed to resolve recently viewed entry");
+                None
+            });
+            let _ = tx.send(AppEvent::HistoryRecentResolved { kind, key, found });
+        }
+    });
+}
+
+fn spawn_day_export(
+    date_id: String,
+    keys: Vec<Vec<u8>>,
+    format: export::ExportFormat,
+    store: Arc<HistoryStore>,
+    tx: mpsc::UnboundedSender<AppEvent>,
+) {
+    let notice = |message: String| AppEvent::Notice { message };
+    let _ = tx.send(notice(format!(
+        "Exporting {} encounters from {date_id}…",
+        keys.len()
+    )));
+    tokio::spawn(async move {
+        let progress_tx = tx.clone();
+        let progress_date = date_id.clone();
+        let result = task::spawn_blocking(move || {
+            export::export_day(
+                &store,
+                &progress_date,
+                &keys,
+                &export::exports_dir(),
+                format,
+                |done, total| {
+                    if done % 10 == 0 && done < total {
+                        let _ = progress_tx
+                            .send(notice(format!("Exporting {progress_date}… {done}/{total}")));
+                    }
+                },
+            )
+        })
+        .await;
+        let message = match result {
+            Ok(Ok(report)) if report.failed == 0 => format!(
+                "Exported {} encounters to {}",
+                report.written,
+                report.dir.display()
+            ),
+            Ok(Ok(report)) => format!(
+                "Exported {} of {} encounters to {} ({} failed)",
+                report.written,
+                report.written + report.failed,
+                report.dir.display(),
+                report.failed
+            ),
+            Ok(Err(err)) => format!("Export of {date_id} failed: {err}"),
+            Err(err) => format!("Export of {date_id} failed: {err}"),
+        };
+        let _ = tx.send(notice(message));
+    });
+}
+
+/// Writes one encounter under the exports directory, as its combatant table for
+/// [`export::ExportFormat::Csv`] and as the full JSON record otherwise, then reports the path
+/// or error.
+fn spawn_encounter_export(
+    key: Vec<u8>,
+    format: export::ExportFormat,
+    store: Arc<HistoryStore>,
+    tx: mpsc::UnboundedSender<AppEvent>,
+) {
+    tokio::spawn(async move {
+        let result = task::spawn_blocking(move || {
+            let dir = export::exports_dir();
+            match format {
+                export::ExportFormat::Csv => export::export_encounter_csv(&store, &key, &dir),
+                _ => store.export_encounter(&key, &dir),
+            }
+        })
+        .await;
+        let message = match result {
+            Ok(Ok(path)) => format!("Exported encounter to {}", path.display()),
+            Ok(Err(err)) => format!("Encounter export failed: {err:#}"),
+            Err(err) => format!("Encounter export failed: {err}"),
+        };
+        let _ = tx.send(AppEvent::Notice { message });
+    });
+}
+
+/// Copies `text` off the UI thread and reports `done` as a toast. Without a usable clipboard
+/// (e.g. a headless session) the failure goes to the error log instead.
+fn spawn_copy(text: String, done: String, tx: &mpsc::UnboundedSender<AppEvent>) {
+    let tx = tx.clone();
+    task::spawn_blocking(move || {
+        let event = match clipboard::copy_text(&text) {
+            Ok(()) => AppEvent::Notice { message: done },
+            Err(err) => AppEvent::SystemError {
+                error: AppError::new(AppErrorKind::Clipboard, format!("copy failed: {err:#}")),
+            },
+        };
+        let _ = tx.send(event);
+    });
+}
+
+/// Applies the catalog settings from the config (extra zones, fuzzy matching) to a loaded catalog.
+fn configure_catalog(
+    catalog: dungeon::DungeonCatalog,
+    cfg: &config::AppConfig,
+) -> dungeon::DungeonCatalog {
+    catalog
+        .with_extra_zones(&cfg.extra_dungeon_zones)
+        .with_fuzzy_match(cfg.fuzzy_zone_match)
+}
+
+/// Reloads the dungeon catalog from disk and hands it to the recorder and the UI. A catalog
+/// that fails to load leaves the current one in place.
+fn spawn_catalog_reload(
+    url: Option<String>,
+    cfg: config::AppConfig,
+    recorder: history::RecorderHandle,
+    tx: &mpsc::UnboundedSender<AppEvent>,
+) {
+    let tx = tx.clone();
+    task::spawn_blocking(move || {
+        let message = match dungeon::remote::reload_catalog(url.as_deref()) {
+            Ok(catalog) => {
+                let catalog = configure_catalog(catalog, &cfg);
+                let zones = catalog.len();
+                let catalog = Arc::new(catalog);
+                recorder.set_dungeon_catalog(Arc::clone(&catalog));
+                let _ = tx.send(AppEvent::DungeonCatalogReloaded { catalog });
+                format!("Reloaded dungeon catalog ({zones} zones)")
+            }
+            Err(err) => {
+                warn!(error = ?err, "dungeon catalog reload failed");
+                format!("Catalog reload failed: {err:#}")
+            }
+        };
+        let _ = tx.send(AppEvent::Notice { message });
+    });
+}
+
+/// Deletes an encounter or dungeon run, then reloads its day so repeated titles are
+/// renumbered.
+fn spawn_history_delete(
+    kind: RecentKind,
+    key: Vec<u8>,
+    date_id: Option<String>,
+    epoch: u64,
+    options: history::dungeon::DungeonOptions,
+    store: Arc<HistoryStore>,
+    tx: mpsc::UnboundedSender<AppEvent>,
+) {
+    tokio::spawn(async move {
+        let key_for_block = key.clone();
+        let store_for_block = store.clone();
+        let result = task::spawn_blocking(move || match kind {
+            RecentKind::Encounter => store_for_block
+                .delete_encounter(&key_for_block, &options)
+                .map(|()| 0),
+            RecentKind::Dungeon => store_for_block.delete_dungeon_run(&key_for_block),
+        })
+        .await;
+        let orphans = match result {
+            Ok(Ok(orphans)) => orphans,
+            Ok(Err(err)) => {
+                let _ = tx.send(AppEvent::HistoryError {
+                    epoch,
+                    message: format!("Delete failed: {err}"),
+                });
+                return;
+            }
+            Err(err) => {
+                let _ = tx.send(AppEvent::HistoryError {
+                    epoch,
+                    message: format!("Delete failed: {err}"),
+                });
+                return;
+            }
+        };
+        let message = match (kind, orphans) {
+            (RecentKind::Encounter, _) => "Deleted encounter".to_string(),
+            (RecentKind::Dungeon, 0) => "Deleted dungeon run".to_string(),
+            (RecentKind::Dungeon, n) => format!("Deleted dungeon run and {n} unlisted pull(s)"),
+        };
+        let _ = tx.send(AppEvent::HistoryEntryDeleted { epoch, kind, key });
+        let _ = tx.send(AppEvent::Notice { message });
+        if let Some(date_id) = date_id {
+            let task = match kind {
+                RecentKind::Encounter => HistoryTask::LoadEncounters { date_id },
+                RecentKind::Dungeon => HistoryTask::LoadDungeonRuns { date_id },
+            };
+            spawn_history_task(task, epoch, store, tx);
+        }
+    });
+}
+
+/// Loads both date lists, as done when the panel opens or is refreshed.
+fn spawn_history_dates(
+    epoch: u64,
+    store: &Arc<HistoryStore>,
+    tx: &mpsc::UnboundedSender<AppEvent>,
+) {
+    spawn_history_task(HistoryTask::LoadDates, epoch, store.clone(), tx.clone());
+    spawn_history_task(
+        HistoryTask::LoadDungeonDays,
+        epoch,
+        store.clone(),
+        tx.clone(),
+    );
 }
 
 fn spawn_history_task(
     task: HistoryTask,
+    epoch: u64,
     store: Arc<HistoryStore>,
     tx: mpsc::UnboundedSender<AppEvent>,
 ) {
     match task {
+        HistoryTask::LoadDates => {
+            tokio::spawn(async move {
+                match task::spawn_blocking(move || store.load_dates()).await {
+                    Ok(Ok(days)) => {
+                        let _ = tx.send(AppEvent::HistoryDatesLoaded { epoch, days });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            epoch,
+                            message: err.to_string(),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            epoch,
+                            message: format!("History load failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
         HistoryTask::LoadEncounters { date_id } => {
             let tx_enc = tx.clone();
             let store_clone = store.clone();
@@ -585,17 +1588,20 @@ fn spawn_history_task(
                 match result {
                     Ok(Ok(encounters)) => {
                         let _ = tx_enc.send(AppEvent::HistoryEncountersLoaded {
+                            epoch,
                             date_id,
                             encounters,
                         });
                     }
                     Ok(Err(err)) => {
                         let _ = tx_enc.send(AppEvent::HistoryError {
+                            epoch,
                             message: err.to_string(),
                         });
                     }
                     Err(err) => {
                         let _ = tx_enc.send(AppEvent::HistoryError {
+                            epoch,
                             message: format!("History load failed: {err}"),
                         });
                     }
@@ -612,15 +1618,18 @@ fn spawn_history_task(
                         .await;
                 match result {
                     Ok(Ok(record)) => {
-                        let _ = tx_detail.send(AppEvent::HistoryEncounterLoaded { key, record });
+                        let _ =
+                            tx_detail.send(AppEvent::HistoryEncounterLoaded { epoch, key, record });
                     }
                     Ok(Err(err)) => {
                         let _ = tx_detail.send(AppEvent::HistoryError {
+                            epoch,
                             message: err.to_string(),
                         });
                     }
                     Err(err) => {
                         let _ = tx_detail.send(AppEvent::HistoryError {
+                            epoch,
                             message: format!("History load failed: {err}"),
                         });
                     }
@@ -634,15 +1643,17 @@ fn spawn_history_task(
                 let result = task::spawn_blocking(move || store_clone.load_dungeon_days()).await;
                 match result {
                     Ok(Ok(days)) => {
-                        let _ = tx_days.send(AppEvent::DungeonDatesLoaded { days });
+                        let _ = tx_days.send(AppEvent::DungeonDatesLoaded { epoch, days });
                     }
                     Ok(Err(err)) => {
                         let _ = tx_days.send(AppEvent::HistoryError {
+                            epoch,
                             message: format!("Failed to load dungeon days: {err}"),
                         });
                     }
                     Err(err) => {
                         let _ = tx_days.send(AppEvent::HistoryError {
+                            epoch,
                             message: format!("History load failed: {err}"),
                         });
                     }
@@ -660,15 +1671,21 @@ fn spawn_history_task(
                 .await;
                 match result {
                     Ok(Ok(runs)) => {
-                        let _ = tx_runs.send(AppEvent::DungeonRunsLoaded { date_id, runs });
+                        let _ = tx_runs.send(AppEvent::DungeonRunsLoaded {
+                            epoch,
+                            date_id,
+                            runs,
+                        });
                     }
                     Ok(Err(err)) => {
                         let _ = tx_runs.send(AppEvent::HistoryError {
+                            epoch,
                             message: format!("Failed to load dungeon runs: {err}"),
                         });
                     }
                     Err(err) => {
                         let _ = tx_runs.send(AppEvent::HistoryError {
+                            epoch,
                             message: format!("History load failed: {err}"),
                         });
                     }
@@ -689,6 +1706,7 @@ fn spawn_history_task(
                     Ok(Ok(record)) => {
                         let child_keys = record.child_keys.clone();
                         let _ = tx_run.send(AppEvent::DungeonRunLoaded {
+                            epoch,
                             key: key.clone(),
                             record: record.clone(),
                         });
@@ -705,6 +1723,7 @@ fn spawn_history_task(
                                     .await;
                                     if let Ok(Ok(child_record)) = res {
                                         let _ = tx_child.send(AppEvent::DungeonEncounterLoaded {
+                                            epoch,
                                             key: child_key,
                                             record: child_record,
                                         });
@@ -715,11 +1734,38 @@ fn spawn_history_task(
                     }
                     Ok(Err(err)) => {
                         let _ = tx_run.send(AppEvent::HistoryError {
+                            epoch,
                             message: format!("Failed to load dungeon run: {err}"),
                         });
                     }
                     Err(err) => {
                         let _ = tx_run.send(AppEvent::HistoryError {
+                            epoch,
+                            message: format!("History load failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::SearchEncounters { query } => {
+            tokio::spawn(async move {
+                let result = task::spawn_blocking(move || {
+                    store.search_encounters(&query, SEARCH_RESULT_LIMIT)
+                })
+                .await;
+                match result {
+                    Ok(Ok(results)) => {
+                        let _ = tx.send(AppEvent::HistorySearchLoaded { epoch, results });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            epoch,
+                            message: format!("Search failed: {err}"),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            epoch,
                             message: format!("History load failed: {err}"),
                         });
                     }
@@ -736,15 +1782,21 @@ fn spawn_history_task(
                         .await;
                 match result {
                     Ok(Ok(record)) => {
-                        let _ = tx_encounter.send(AppEvent::DungeonEncounterLoaded { key, record });
+                        let _ = tx_encounter.send(AppEvent::DungeonEncounterLoaded {
+                            epoch,
+                            key,
+                            record,
+                        });
                     }
                     Ok(Err(err)) => {
                         let _ = tx_encounter.send(AppEvent::HistoryError {
+                            epoch,
                             message: format!("Failed to load dungeon encounter: {err}"),
                         });
                     }
                     Err(err) => {
                         let _ = tx_encounter.send(AppEvent::HistoryError {
+                            epoch,
                             message: format!("History load failed: {err}"),
                         });
                     }
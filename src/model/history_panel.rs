@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::history::util::{key_from_hex, key_to_hex};
 use crate::history::{DungeonHistoryDay, DungeonHistoryItem, HistoryDay, HistoryEncounterItem};
 
-use super::ViewMode;
+use super::{visible_detail_rows, CombatantRow, RoleFilter, SortKey, ViewMode};
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum HistoryPanelLevel {
@@ -10,6 +11,10 @@ pub enum HistoryPanelLevel {
     Dates,
     Encounters,
     EncounterDetail,
+    /// Per-skill breakdown of the combatant picked in the encounter detail table.
+    Abilities,
+    /// Encounters from every day matching the search typed with `/` on the Dates list.
+    SearchResults,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -19,6 +24,59 @@ pub enum HistoryView {
     Dungeons,
 }
 
+impl HistoryView {
+    /// The view a config key names, or `None` for an unknown key.
+    pub fn parse_config_key<S: AsRef<str>>(key: S) -> Option<Self> {
+        match key.as_ref().trim().to_ascii_lowercase().as_str() {
+            "encounters" => Some(HistoryView::Encounters),
+            "dungeons" => Some(HistoryView::Dungeons),
+            _ => None,
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        Self::parse_config_key(key).unwrap_or_default()
+    }
+}
+
+/// Order of the Dates lists. The store returns newest first; oldest-first reverses it.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum DateSort {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+}
+
+impl DateSort {
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        match key.as_ref().trim().to_ascii_lowercase().as_str() {
+            "oldest" | "oldest_first" | "ascending" | "asc" => DateSort::OldestFirst,
+            _ => DateSort::NewestFirst,
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            DateSort::NewestFirst => DateSort::OldestFirst,
+            DateSort::OldestFirst => DateSort::NewestFirst,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DateSort::NewestFirst => "newest first",
+            DateSort::OldestFirst => "oldest first",
+        }
+    }
+
+    /// Puts newest-first `days` from the store into this order.
+    pub fn apply<T>(self, days: &mut [T]) {
+        if self == DateSort::OldestFirst {
+            days.reverse();
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum DungeonPanelLevel {
     #[default]
@@ -28,6 +86,64 @@ pub enum DungeonPanelLevel {
     EncounterDetail,
 }
 
+/// How many recently opened encounters/runs the quick list keeps.
+pub const RECENT_CAPACITY: usize = 8;
+
+/// Most encounters a history search lists.
+pub const SEARCH_RESULT_LIMIT: usize = 200;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecentKind {
+    Encounter,
+    Dungeon,
+}
+
+/// Where a recent entry lives, looked up from the store when the quick list opens.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum RecentTarget {
+    #[default]
+    Pending,
+    Found {
+        date_id: String,
+        title: String,
+    },
+    /// The key is no longer in the store (deleted or pruned).
+    Missing,
+}
+
+/// An entry of the "recently viewed" quick list; persisted in the UI-state file.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecentEntry {
+    pub kind: RecentKind,
+    /// Store key in hex.
+    pub key: String,
+    #[serde(skip)]
+    pub target: RecentTarget,
+}
+
+impl RecentEntry {
+    pub fn new(kind: RecentKind, key: &[u8]) -> Self {
+        Self {
+            kind,
+            key: key_to_hex(key),
+            target: RecentTarget::Pending,
+        }
+    }
+
+    pub fn key_bytes(&self) -> Option<Vec<u8>> {
+        key_from_hex(&self.key)
+    }
+}
+
+/// A quick-list jump waiting for the day (and its entries) to finish loading.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingJump {
+    pub kind: RecentKind,
+    pub key: Vec<u8>,
+    pub date_id: String,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HistoryPanel {
     pub visible: bool,
@@ -53,6 +169,65 @@ pub struct HistoryPanel {
     pub detail_mode: ViewMode,
     #[serde(default)]
     pub dungeon_detail_mode: ViewMode,
+    #[serde(default)]
+    pub show_deaths: bool,
+    #[serde(default)]
+    pub show_buffs: bool,
+    /// Run detail collapses trash pulls between bosses.
+    #[serde(default)]
+    pub dungeon_group_bosses: bool,
+    /// Order of both Dates lists; kept across `reset`.
+    #[serde(default)]
+    pub date_sort: DateSort,
+    /// Case-insensitive substring the encounter list is narrowed to (empty shows everything).
+    #[serde(default)]
+    pub filter: String,
+    #[serde(default)]
+    pub filter_editing: bool,
+    /// Job the encounter list is narrowed to, cycled with `j`; combines with `filter`.
+    #[serde(default)]
+    pub job_filter: Option<String>,
+    /// Set when `filter` came from the saved UI state rather than this session's typing.
+    #[serde(default)]
+    pub filter_restored: bool,
+    /// Rows scrolled off the top of the encounter detail table.
+    #[serde(default)]
+    pub detail_scroll: usize,
+    /// Rows the detail table had room for at the last draw (0 before the first), so scrolling
+    /// stops at the same last full page the renderer clamps to.
+    #[serde(skip)]
+    pub detail_capacity: usize,
+    /// Combatant under the cursor in the encounter detail table, in displayed order.
+    #[serde(default)]
+    pub detail_row: usize,
+    #[serde(default)]
+    pub ability_selected: usize,
+    /// Recently opened encounters and runs, most recent first. Kept across `reset`.
+    #[serde(default)]
+    pub recent: Vec<RecentEntry>,
+    #[serde(default)]
+    pub show_recent: bool,
+    #[serde(default)]
+    pub recent_selected: usize,
+    /// Set when `recent` changed and the UI-state file should be rewritten.
+    #[serde(skip)]
+    pub recent_dirty: bool,
+    #[serde(skip)]
+    pub pending_jump: Option<PendingJump>,
+    /// Date ids to select again once a refresh reloads the date lists.
+    #[serde(skip)]
+    pub reselect_day: Option<String>,
+    #[serde(skip)]
+    pub reselect_dungeon_day: Option<String>,
+    /// Text of the search across all encounters (kept so `/` can refine it).
+    pub search_query: String,
+    /// True while the search is being typed.
+    pub search_editing: bool,
+    /// Matches of the last search, newest first.
+    pub search_results: Vec<HistoryEncounterItem>,
+    pub search_selected: usize,
+    /// Set when the open encounter detail came from the search results, so back returns there.
+    pub from_search: bool,
 }
 
 impl Default for HistoryPanel {
@@ -73,6 +248,30 @@ impl Default for HistoryPanel {
             error: None,
             detail_mode: ViewMode::Dps,
             dungeon_detail_mode: ViewMode::Dps,
+            show_deaths: false,
+            show_buffs: false,
+            dungeon_group_bosses: false,
+            date_sort: DateSort::default(),
+            filter: String::new(),
+            filter_editing: false,
+            job_filter: None,
+            filter_restored: false,
+            detail_scroll: 0,
+            detail_capacity: 0,
+            detail_row: 0,
+            ability_selected: 0,
+            recent: Vec::new(),
+            show_recent: false,
+            recent_selected: 0,
+            recent_dirty: false,
+            pending_jump: None,
+            reselect_day: None,
+            reselect_dungeon_day: None,
+            search_query: String::new(),
+            search_editing: false,
+            search_results: Vec::new(),
+            search_selected: 0,
+            from_search: false,
         }
     }
 }
@@ -90,6 +289,26 @@ impl HistoryPanel {
         self.error = None;
         self.detail_mode = ViewMode::Dps;
         self.dungeon_detail_mode = ViewMode::Dps;
+        self.show_deaths = false;
+        self.show_buffs = false;
+        self.dungeon_group_bosses = false;
+        self.filter.clear();
+        self.filter_editing = false;
+        self.filter_restored = false;
+        self.job_filter = None;
+        self.detail_scroll = 0;
+        self.detail_row = 0;
+        self.ability_selected = 0;
+        self.show_recent = false;
+        self.recent_selected = 0;
+        self.pending_jump = None;
+        self.reselect_day = None;
+        self.reselect_dungeon_day = None;
+        self.search_query.clear();
+        self.search_editing = false;
+        self.search_results.clear();
+        self.search_selected = 0;
+        self.from_search = false;
         for day in &mut self.days {
             day.encounters.clear();
             day.encounters_loaded = false;
@@ -104,11 +323,194 @@ impl HistoryPanel {
         self.days.get(self.selected_day)
     }
 
+    pub fn current_search_result(&self) -> Option<&HistoryEncounterItem> {
+        self.search_results.get(self.search_selected)
+    }
+
     pub fn current_encounter(&self) -> Option<&HistoryEncounterItem> {
         self.current_day()
             .and_then(|day| day.encounters.get(self.selected_encounter))
     }
 
+    /// Rows of the open encounter as its detail table shows them: sorted by `sort` (in the
+    /// detail mode), narrowed by `filter` and, with `hide_zero`, without rows that put nothing
+    /// into the detail mode's metric. Empty until the record is loaded.
+    pub fn detail_rows(
+        &self,
+        filter: RoleFilter,
+        sort: SortKey,
+        ascending: bool,
+        hide_zero: bool,
+    ) -> Vec<CombatantRow> {
+        let Some(record) = self.current_encounter().and_then(|enc| enc.record.as_ref()) else {
+            return Vec::new();
+        };
+        visible_detail_rows(
+            &record.rows,
+            self.detail_mode,
+            sort,
+            ascending,
+            filter,
+            hide_zero,
+        )
+    }
+
+    /// The combatant under the detail table cursor, clamped to the rows currently shown.
+    pub fn detail_combatant(
+        &self,
+        filter: RoleFilter,
+        sort: SortKey,
+        ascending: bool,
+        hide_zero: bool,
+    ) -> Option<CombatantRow> {
+        let rows = self.detail_rows(filter, sort, ascending, hide_zero);
+        let last = rows.len().checked_sub(1)?;
+        rows.into_iter().nth(self.detail_row.min(last))
+    }
+
+    pub fn encounter_matches(&self, item: &HistoryEncounterItem) -> bool {
+        let needle = self.filter.trim().to_lowercase();
+        let text_matches = needle.is_empty()
+            || item.display_title.to_lowercase().contains(&needle)
+            || item.base_title.to_lowercase().contains(&needle)
+            || item.scale.is_some_and(|scale| scale.label() == needle);
+        text_matches
+            && self
+                .job_filter
+                .as_ref()
+                .map_or(true, |job| item.jobs.contains(job))
+    }
+
+    /// Jobs seen across the current day's encounters, sorted, for cycling the job filter.
+    pub fn day_jobs(&self) -> Vec<String> {
+        let mut jobs: Vec<String> = self
+            .current_day()
+            .map(|day| {
+                day.encounters
+                    .iter()
+                    .flat_map(|enc| enc.jobs.iter().cloned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        jobs.sort_unstable();
+        jobs.dedup();
+        jobs
+    }
+
+    /// Indexes into the current day's encounters that pass the filter, in list order.
+    pub fn visible_encounter_indices(&self) -> Vec<usize> {
+        self.current_day()
+            .map(|day| {
+                day.encounters
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, item)| self.encounter_matches(item))
+                    .map(|(idx, _)| idx)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Moves the encounter selection onto a visible entry if the filter hid the current one.
+    pub fn snap_encounter_selection(&mut self) {
+        let visible = self.visible_encounter_indices();
+        // With nothing visible keep the old position so widening the filter again restores it.
+        if let Some(first) = visible.first().copied() {
+            if !visible.contains(&self.selected_encounter) {
+                self.selected_encounter = first;
+            }
+        }
+    }
+
+    /// Moves `key` to the front of the recent list, dropping duplicates and the oldest entries.
+    pub fn remember_recent(&mut self, kind: RecentKind, key: &[u8]) {
+        let entry = RecentEntry::new(kind, key);
+        let existing = self
+            .recent
+            .iter()
+            .position(|recent| recent.kind == kind && recent.key == entry.key);
+        let entry = match existing {
+            Some(0) => return,
+            Some(idx) => self.recent.remove(idx),
+            None => entry,
+        };
+        self.recent.insert(0, entry);
+        self.recent.truncate(RECENT_CAPACITY);
+        self.recent_dirty = true;
+    }
+
+    /// Removes a deleted encounter or run from the loaded lists and the recent list. A day
+    /// left empty is dropped, and if it was open the view steps back to its Dates list.
+    pub fn forget_entry(&mut self, kind: RecentKind, key: &[u8]) {
+        match kind {
+            RecentKind::Encounter => {
+                if let Some(idx) = self
+                    .days
+                    .iter_mut()
+                    .position(|day| day.remove_encounter(key))
+                {
+                    if self.days[idx].encounter_ids.is_empty() {
+                        self.days.remove(idx);
+                        if idx == self.selected_day {
+                            self.level = HistoryPanelLevel::Dates;
+                            self.selected_encounter = 0;
+                        } else if idx < self.selected_day {
+                            self.selected_day -= 1;
+                        }
+                        self.selected_day =
+                            self.selected_day.min(self.days.len().saturating_sub(1));
+                    } else if idx == self.selected_day {
+                        let len = self.days[idx].encounters.len();
+                        self.selected_encounter =
+                            self.selected_encounter.min(len.saturating_sub(1));
+                    }
+                }
+                self.search_results.retain(|item| item.key != key);
+                self.search_selected = self
+                    .search_selected
+                    .min(self.search_results.len().saturating_sub(1));
+                // Runs that listed it as a pull were rewritten by the store.
+                for day in &mut self.dungeon_days {
+                    day.runs_loaded = false;
+                }
+            }
+            RecentKind::Dungeon => {
+                if let Some(idx) = self
+                    .dungeon_days
+                    .iter_mut()
+                    .position(|day| day.remove_run(key))
+                {
+                    if self.dungeon_days[idx].run_ids.is_empty() {
+                        self.dungeon_days.remove(idx);
+                        if idx == self.dungeon_selected_day {
+                            self.dungeon_level = DungeonPanelLevel::Dates;
+                            self.dungeon_selected_run = 0;
+                        } else if idx < self.dungeon_selected_day {
+                            self.dungeon_selected_day -= 1;
+                        }
+                        self.dungeon_selected_day = self
+                            .dungeon_selected_day
+                            .min(self.dungeon_days.len().saturating_sub(1));
+                    } else if idx == self.dungeon_selected_day {
+                        let len = self.dungeon_days[idx].runs.len();
+                        self.dungeon_selected_run =
+                            self.dungeon_selected_run.min(len.saturating_sub(1));
+                    }
+                }
+            }
+        }
+        let hex = key_to_hex(key);
+        let before = self.recent.len();
+        self.recent
+            .retain(|entry| !(entry.kind == kind && entry.key == hex));
+        if self.recent.len() != before {
+            self.recent_dirty = true;
+            self.recent_selected = self
+                .recent_selected
+                .min(self.recent.len().saturating_sub(1));
+        }
+    }
+
     pub fn find_day_mut(&mut self, date_id: &str) -> Option<&mut HistoryDay> {
         self.days.iter_mut().find(|day| day.iso_date == date_id)
     }
@@ -1,13 +1,24 @@
 pub const WS_URL_DEFAULT: &str = "ws://127.0.0.1:10501/ws";
 
 mod history_panel;
+mod rate;
+mod rolling;
 mod settings;
 mod state;
+mod timer;
 mod types;
 mod view;
 
-pub use history_panel::{DungeonPanelLevel, HistoryPanel, HistoryPanelLevel, HistoryView};
-pub use settings::{AppSettings, SettingsField};
-pub use state::{AppSnapshot, AppState};
-pub use types::{known_jobs, AppEvent, CombatantRow, EncounterSummary};
-pub use view::{Decoration, IdleScene, ViewMode};
+pub use history_panel::{
+    DateSort, DungeonPanelLevel, HistoryPanel, HistoryPanelLevel, HistoryView, PendingJump,
+    RecentEntry, RecentKind, RecentTarget, SEARCH_RESULT_LIMIT,
+};
+pub use rate::RateCounter;
+pub use rolling::RollingDps;
+pub use settings::{resolve_self_name, AppSettings, EscapeBehavior, SettingsField};
+pub use state::{AppSnapshot, AppState, ConfirmAction};
+pub use types::{
+    known_jobs, visible_detail_rows, visible_live_rows, AppEvent, CombatantRow, ConnectionState,
+    EncounterSummary,
+};
+pub use view::{ClockFormat, ColumnId, Decoration, IdleScene, Role, RoleFilter, SortKey, ViewMode};
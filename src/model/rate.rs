@@ -0,0 +1,47 @@
+use std::time::{Duration, Instant};
+
+/// Length of the window the message rate is averaged over.
+pub const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Windowed message counter: bump it per message and it yields a per-second rate once a
+/// window has elapsed. Costs one increment and one clock read per message.
+#[derive(Clone, Debug, Default)]
+pub struct RateCounter {
+    window_start: Option<Instant>,
+    count: u32,
+}
+
+impl RateCounter {
+    /// Counts one message; returns the rate for the window that just closed, if any.
+    pub fn record(&mut self, now: Instant) -> Option<f64> {
+        let start = *self.window_start.get_or_insert(now);
+        self.count += 1;
+        let elapsed = now.saturating_duration_since(start);
+        if elapsed < RATE_WINDOW {
+            return None;
+        }
+        let rate = self.count as f64 / elapsed.as_secs_f64();
+        self.window_start = Some(now);
+        self.count = 0;
+        Some(rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_reports_once_per_window() {
+        let start = Instant::now();
+        let mut counter = RateCounter::default();
+        for ms in (0..1_000).step_by(50) {
+            assert_eq!(counter.record(start + Duration::from_millis(ms)), None);
+        }
+        let rate = counter
+            .record(start + Duration::from_millis(1_000))
+            .expect("window closed");
+        assert!((rate - 21.0).abs() < f64::EPSILON);
+        assert_eq!(counter.record(start + Duration::from_millis(1_100)), None);
+    }
+}
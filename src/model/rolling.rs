@@ -0,0 +1,104 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::CombatantRow;
+
+/// Damage per combatant over the last `window_ms`, from the cumulative totals of successive
+/// CombatData updates. Keeps one sample at or before the window start so the rate always
+/// spans the full window once enough data has arrived.
+#[derive(Clone, Debug, Default)]
+pub struct RollingDps {
+    samples: VecDeque<(u64, HashMap<String, f64>)>,
+}
+
+impl RollingDps {
+    /// Adds the totals of an update received at `wall_ms` and drops samples that fell out of
+    /// the window. Totals going backwards mean ACT started a new encounter, so the history
+    /// is restarted from this update.
+    pub fn record(&mut self, wall_ms: u64, rows: &[CombatantRow], window_ms: u64) {
+        let totals: HashMap<String, f64> = rows
+            .iter()
+            .map(|row| (row.name.clone(), row.damage))
+            .collect();
+        let went_back = self.samples.back().is_some_and(|(last_ms, last)| {
+            wall_ms < *last_ms
+                || last
+                    .iter()
+                    .any(|(name, damage)| totals.get(name).is_some_and(|now| now < damage))
+        });
+        if went_back {
+            self.samples.clear();
+        }
+        self.samples.push_back((wall_ms, totals));
+
+        let cutoff = wall_ms.saturating_sub(window_ms);
+        while self.samples.get(1).is_some_and(|(ms, _)| *ms <= cutoff) {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Per-second damage between the oldest kept sample and the newest one, keyed by
+    /// combatant name. Empty until two updates at least a second apart have been seen.
+    pub fn rates(&self) -> HashMap<String, f64> {
+        let (Some((first_ms, first)), Some((last_ms, last))) =
+            (self.samples.front(), self.samples.back())
+        else {
+            return HashMap::new();
+        };
+        let elapsed_ms = last_ms.saturating_sub(*first_ms);
+        if elapsed_ms < 1_000 {
+            return HashMap::new();
+        }
+        let secs = elapsed_ms as f64 / 1_000.0;
+        last.iter()
+            .map(|(name, damage)| {
+                let start = first.get(name).copied().unwrap_or(0.0);
+                (name.clone(), ((damage - start) / secs).max(0.0))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(damage: &[(&str, f64)]) -> Vec<CombatantRow> {
+        damage
+            .iter()
+            .map(|(name, damage)| CombatantRow {
+                name: (*name).into(),
+                damage: *damage,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rate_covers_only_the_window() {
+        let mut rolling = RollingDps::default();
+        rolling.record(0, &rows(&[("A", 0.0)]), 10_000);
+        assert!(rolling.rates().is_empty());
+
+        // A steady 100 DPS, then a burst of 1000 DPS for the last 10 seconds.
+        rolling.record(20_000, &rows(&[("A", 2_000.0)]), 10_000);
+        rolling.record(30_000, &rows(&[("A", 12_000.0), ("B", 500.0)]), 10_000);
+        let rates = rolling.rates();
+        assert_eq!(rates["A"], 1_000.0);
+        assert_eq!(rates["B"], 50.0);
+    }
+
+    #[test]
+    fn falling_totals_restart_the_window() {
+        let mut rolling = RollingDps::default();
+        rolling.record(0, &rows(&[("A", 0.0)]), 15_000);
+        rolling.record(5_000, &rows(&[("A", 5_000.0)]), 15_000);
+        rolling.record(6_000, &rows(&[("A", 100.0)]), 15_000);
+        assert!(rolling.rates().is_empty());
+        rolling.record(8_000, &rows(&[("A", 300.0)]), 15_000);
+        assert_eq!(rolling.rates()["A"], 100.0);
+    }
+}
@@ -1,55 +1,164 @@
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
 use crate::config::AppConfig;
+use crate::theme::Theme;
 
-use super::{Decoration, ViewMode};
+use super::{ClockFormat, ColumnId, CombatantRow, Decoration, ViewMode};
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum SettingsField {
     #[default]
     IdleTimeout,
+    RedrawRate,
     DefaultDecoration,
     DefaultMode,
     DungeonMode,
+    HideZeroRows,
+    EscapeBehavior,
+    Theme,
 }
 
 impl SettingsField {
     pub fn next(self) -> Self {
         match self {
-            SettingsField::IdleTimeout => SettingsField::DefaultDecoration,
+            SettingsField::IdleTimeout => SettingsField::RedrawRate,
+            SettingsField::RedrawRate => SettingsField::DefaultDecoration,
             SettingsField::DefaultDecoration => SettingsField::DefaultMode,
             SettingsField::DefaultMode => SettingsField::DungeonMode,
-            SettingsField::DungeonMode => SettingsField::IdleTimeout,
+            SettingsField::DungeonMode => SettingsField::HideZeroRows,
+            SettingsField::HideZeroRows => SettingsField::EscapeBehavior,
+            SettingsField::EscapeBehavior => SettingsField::Theme,
+            SettingsField::Theme => SettingsField::IdleTimeout,
         }
     }
 
     pub fn prev(self) -> Self {
         match self {
-            SettingsField::IdleTimeout => SettingsField::DungeonMode,
-            SettingsField::DefaultDecoration => SettingsField::IdleTimeout,
+            SettingsField::IdleTimeout => SettingsField::Theme,
+            SettingsField::RedrawRate => SettingsField::IdleTimeout,
+            SettingsField::DefaultDecoration => SettingsField::RedrawRate,
             SettingsField::DefaultMode => SettingsField::DefaultDecoration,
             SettingsField::DungeonMode => SettingsField::DefaultMode,
+            SettingsField::HideZeroRows => SettingsField::DungeonMode,
+            SettingsField::EscapeBehavior => SettingsField::HideZeroRows,
+            SettingsField::Theme => SettingsField::EscapeBehavior,
+        }
+    }
+
+    /// Accepted range for fields that take a typed number; `None` for fields that only cycle.
+    pub fn numeric_range(self) -> Option<RangeInclusive<u64>> {
+        match self {
+            SettingsField::IdleTimeout => Some(0..=IDLE_SECONDS_MAX),
+            SettingsField::RedrawRate => Some(REDRAW_MS_MIN..=REDRAW_MS_MAX),
+            _ => None,
+        }
+    }
+}
+
+/// Upper bound for a typed idle timeout (one hour).
+pub const IDLE_SECONDS_MAX: u64 = 3600;
+
+/// Fastest screen redraw interval in milliseconds (about 60 fps).
+pub const REDRAW_MS_MIN: u64 = 16;
+/// Slowest screen redraw interval in milliseconds.
+pub const REDRAW_MS_MAX: u64 = 1000;
+
+/// What `q`/Esc does while a nested view (history drill-down) is open.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum EscapeBehavior {
+    // Pop one level at a time: detail → list → dates → close history → quit
+    #[default]
+    Back,
+    // Close the whole history panel in one press (pre-0.5 behaviour)
+    Close,
+}
+
+impl EscapeBehavior {
+    pub fn next(self) -> Self {
+        match self {
+            EscapeBehavior::Back => EscapeBehavior::Close,
+            EscapeBehavior::Close => EscapeBehavior::Back,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        self.next()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EscapeBehavior::Back => "Step back",
+            EscapeBehavior::Close => "Close view",
+        }
+    }
+
+    pub fn config_key(self) -> &'static str {
+        match self {
+            EscapeBehavior::Back => "back",
+            EscapeBehavior::Close => "close",
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        match key.as_ref().to_ascii_lowercase().as_str() {
+            "close" => EscapeBehavior::Close,
+            _ => EscapeBehavior::Back,
         }
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AppSettings {
     pub idle_seconds: u64,
+    /// Milliseconds between screen redraws, kept within `REDRAW_MS_MIN..=REDRAW_MS_MAX`.
+    pub redraw_ms: u64,
     pub default_decoration: Decoration,
     pub default_mode: ViewMode,
     pub dungeon_mode_enabled: bool,
+    /// Leave out rows with nothing in the current mode's metric (display only).
+    pub hide_zero_rows: bool,
+    pub escape_behavior: EscapeBehavior,
+    pub dps_thresholds: HashMap<String, f64>,
+    pub enrage_secs: HashMap<String, u64>,
+    pub column_order: Vec<ColumnId>,
+    pub uptime_buffs: Vec<String>,
+    pub subsecond_durations: bool,
+    pub self_names: Vec<String>,
+    /// Blank the live table (display only) while idle; the next pull brings it back.
+    pub auto_clear_idle: bool,
+    /// Minimum milliseconds between live re-sorts during a pull (0 re-sorts every update).
+    pub stable_sort_ms: u64,
+    /// Seconds the live rolling DPS is measured over (0 turns it off).
+    pub rolling_dps_secs: u64,
+    pub clock_format: ClockFormat,
+    pub theme: Theme,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             idle_seconds: 5,
+            redraw_ms: 100,
             default_decoration: Decoration::Underline,
             default_mode: ViewMode::Dps,
             dungeon_mode_enabled: true,
+            hide_zero_rows: false,
+            escape_behavior: EscapeBehavior::Back,
+            dps_thresholds: HashMap::new(),
+            enrage_secs: HashMap::new(),
+            column_order: ColumnId::DEFAULT_ORDER.to_vec(),
+            uptime_buffs: AppConfig::default().uptime_buffs,
+            subsecond_durations: false,
+            self_names: Vec::new(),
+            auto_clear_idle: false,
+            stable_sort_ms: 0,
+            rolling_dps_secs: 15,
+            clock_format: ClockFormat::H24,
+            theme: Theme::default(),
         }
     }
 }
@@ -62,26 +171,193 @@ impl AppSettings {
             Some(Duration::from_secs(self.idle_seconds))
         }
     }
+
+    /// Interval between screen redraws.
+    pub fn redraw_interval(&self) -> Duration {
+        Duration::from_millis(self.redraw_ms.clamp(REDRAW_MS_MIN, REDRAW_MS_MAX))
+    }
+
+    /// How long the live row order is held during a pull, if stable sorting is on.
+    pub fn stable_sort_window(&self) -> Option<Duration> {
+        (self.stable_sort_ms > 0).then(|| Duration::from_millis(self.stable_sort_ms))
+    }
+
+    /// Length of the rolling DPS window in milliseconds, if the rolling column is on.
+    pub fn rolling_dps_window_ms(&self) -> Option<u64> {
+        (self.rolling_dps_secs > 0).then(|| self.rolling_dps_secs.saturating_mul(1_000))
+    }
+
+    /// Looks up the party DPS target for a zone, ignoring case and surrounding whitespace.
+    pub fn dps_threshold_for(&self, zone: &str) -> Option<f64> {
+        zone_lookup(&self.dps_thresholds, zone).filter(|threshold| *threshold > 0.0)
+    }
+
+    /// Looks up the enrage timer for a zone, matched the same way as DPS thresholds.
+    pub fn enrage_secs_for(&self, zone: &str) -> Option<u64> {
+        zone_lookup(&self.enrage_secs, zone).filter(|secs| *secs > 0)
+    }
+
+    /// Name of the row that is "you" in `rows`; see [`resolve_self_name`].
+    pub fn resolve_self<'a>(&self, rows: &'a [CombatantRow]) -> Option<&'a str> {
+        resolve_self_name(&self.self_names, rows)
+    }
+}
+
+/// Name of the row that is "you" in `rows`: the first of `self_names` (in config order) that
+/// appears, else ACT's own "YOU" row. Names match ignoring case and surrounding spaces.
+pub fn resolve_self_name<'a>(self_names: &[String], rows: &'a [CombatantRow]) -> Option<&'a str> {
+    let find = |name: &str| {
+        let name = name.trim();
+        rows.iter()
+            .find(|row| !name.is_empty() && row.name.trim().eq_ignore_ascii_case(name))
+            .map(|row| row.name.as_str())
+    };
+    self_names
+        .iter()
+        .find_map(|name| find(name))
+        .or_else(|| find(ACT_SELF_NAME))
+}
+
+/// ACT's placeholder name for the local player when no character name is set.
+const ACT_SELF_NAME: &str = "YOU";
+
+fn zone_lookup<T: Copy>(map: &HashMap<String, T>, zone: &str) -> Option<T> {
+    let zone = zone.trim();
+    if zone.is_empty() {
+        return None;
+    }
+    map.iter()
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(zone))
+        .map(|(_, value)| *value)
 }
 
 impl From<AppConfig> for AppSettings {
     fn from(value: AppConfig) -> Self {
         Self {
             idle_seconds: value.idle_seconds,
+            redraw_ms: value.redraw_ms.clamp(REDRAW_MS_MIN, REDRAW_MS_MAX),
             default_decoration: Decoration::from_config_key(&value.default_decoration),
             default_mode: ViewMode::from_config_key(&value.default_mode),
             dungeon_mode_enabled: value.dungeon_mode_enabled,
+            hide_zero_rows: value.hide_zero_rows,
+            escape_behavior: EscapeBehavior::from_config_key(&value.escape_behavior),
+            dps_thresholds: value.dps_thresholds,
+            enrage_secs: value.enrage_secs,
+            column_order: ColumnId::resolve_order(&value.column_order),
+            uptime_buffs: value.uptime_buffs,
+            subsecond_durations: value.subsecond_durations,
+            self_names: value.self_names,
+            auto_clear_idle: value.auto_clear_idle,
+            stable_sort_ms: value.stable_sort_ms,
+            rolling_dps_secs: value.rolling_dps_secs,
+            clock_format: ClockFormat::from_config_key(&value.clock_format),
+            theme: Theme::from_config_key(&value.palette),
         }
     }
 }
 
-impl From<AppSettings> for AppConfig {
-    fn from(value: AppSettings) -> Self {
-        AppConfig {
-            idle_seconds: value.idle_seconds,
-            default_decoration: value.default_decoration.config_key().to_string(),
-            default_mode: value.default_mode.config_key().to_string(),
-            dungeon_mode_enabled: value.dungeon_mode_enabled,
-        }
+impl AppSettings {
+    /// Writes the settings back onto a loaded config so keys that only live in the file survive
+    /// a save from the settings pane.
+    pub fn write_to_config(&self, cfg: &mut AppConfig) {
+        cfg.idle_seconds = self.idle_seconds;
+        cfg.redraw_ms = self.redraw_ms;
+        cfg.default_decoration = self.default_decoration.config_key().to_string();
+        cfg.default_mode = self.default_mode.config_key().to_string();
+        cfg.dungeon_mode_enabled = self.dungeon_mode_enabled;
+        cfg.hide_zero_rows = self.hide_zero_rows;
+        cfg.escape_behavior = self.escape_behavior.config_key().to_string();
+        cfg.dps_thresholds = self.dps_thresholds.clone();
+        cfg.enrage_secs = self.enrage_secs.clone();
+        cfg.column_order = if self.column_order == ColumnId::DEFAULT_ORDER {
+            Vec::new()
+        } else {
+            self.column_order
+                .iter()
+                .map(|column| column.config_key().to_string())
+                .collect()
+        };
+        cfg.uptime_buffs = self.uptime_buffs.clone();
+        cfg.subsecond_durations = self.subsecond_durations;
+        cfg.self_names = self.self_names.clone();
+        cfg.auto_clear_idle = self.auto_clear_idle;
+        cfg.stable_sort_ms = self.stable_sort_ms;
+        cfg.rolling_dps_secs = self.rolling_dps_secs;
+        cfg.clock_format = self.clock_format.config_key().to_string();
+        cfg.palette = self.theme.config_key().to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dps_threshold_lookup_ignores_case_and_missing_zones() {
+        let mut settings = AppSettings::default();
+        settings
+            .dps_thresholds
+            .insert("The Omega Protocol (Ultimate)".into(), 95_000.0);
+
+        assert_eq!(
+            settings.dps_threshold_for("the omega protocol (ultimate) "),
+            Some(95_000.0)
+        );
+        assert_eq!(settings.dps_threshold_for("Sastasha"), None);
+        assert_eq!(settings.dps_threshold_for(""), None);
+    }
+
+    #[test]
+    fn enrage_lookup_skips_unset_and_zero_entries() {
+        let mut settings = AppSettings::default();
+        settings
+            .enrage_secs
+            .insert("Eden's Promise: Eternity".into(), 600);
+        settings.enrage_secs.insert("Sastasha".into(), 0);
+
+        assert_eq!(
+            settings.enrage_secs_for("EDEN'S PROMISE: ETERNITY"),
+            Some(600)
+        );
+        assert_eq!(settings.enrage_secs_for("Sastasha"), None);
+        assert_eq!(settings.enrage_secs_for("Copperbell Mines"), None);
+    }
+
+    #[test]
+    fn resolve_self_picks_the_owned_name_present_in_the_fight() {
+        let row = |name: &str| CombatantRow {
+            name: name.into(),
+            ..Default::default()
+        };
+        let rows = vec![row("Y'shtola Rhul"), row("Alt Character"), row("YOU")];
+        let mut settings = AppSettings::default();
+        assert_eq!(settings.resolve_self(&rows), Some("YOU"));
+
+        settings.self_names = vec![
+            "Main Character".into(),
+            " alt character ".into(),
+            "Y'shtola Rhul".into(),
+        ];
+        assert_eq!(settings.resolve_self(&rows), Some("Alt Character"));
+        assert_eq!(settings.resolve_self(&rows[..1]), Some("Y'shtola Rhul"));
+        assert_eq!(settings.resolve_self(&[row("Stranger")]), None);
+    }
+
+    #[test]
+    fn default_column_order_is_not_pinned_in_the_saved_config() {
+        let mut cfg = AppConfig::default();
+        AppSettings::default().write_to_config(&mut cfg);
+        assert!(cfg.column_order.is_empty());
+        assert_eq!(
+            AppSettings::from(cfg.clone()).column_order,
+            ColumnId::DEFAULT_ORDER
+        );
+
+        let settings = AppSettings {
+            column_order: vec![ColumnId::Name, ColumnId::Encdps],
+            ..AppSettings::default()
+        };
+        settings.write_to_config(&mut cfg);
+        assert_eq!(cfg.column_order, ["name", "encdps"]);
     }
 }
@@ -1,37 +1,144 @@
-use std::cmp::Ordering;
-use std::time::Instant;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
+use tracing::debug;
 
-use crate::errors::AppError;
+use crate::clipboard;
+use crate::dungeon::DungeonCatalog;
+use crate::errors::{AppError, AppErrorKind, ErrorLogEntry};
+use crate::history::buffs::buff_uptime;
+use crate::history::{local_date_id, DungeonProgress, DungeonStreak};
+use crate::keys::KeyBindings;
 
+use super::history_panel::RECENT_CAPACITY;
+use super::settings::{REDRAW_MS_MAX, REDRAW_MS_MIN};
+use super::timer::CombatTimer;
 use super::{
-    AppEvent, AppSettings, CombatantRow, Decoration, DungeonPanelLevel, EncounterSummary,
-    HistoryPanel, HistoryPanelLevel, HistoryView, IdleScene, SettingsField, ViewMode,
+    visible_detail_rows, AppEvent, AppSettings, CombatantRow, ConnectionState, Decoration,
+    DungeonPanelLevel, EncounterSummary, EscapeBehavior, HistoryPanel, HistoryPanelLevel,
+    HistoryView, IdleScene, PendingJump, RecentEntry, RecentKind, RecentTarget, RoleFilter,
+    RollingDps, SettingsField, SortKey, ViewMode,
 };
 
+/// Number of recent errors kept for the error log panel.
+const ERROR_LOG_CAPACITY: usize = 50;
+
+/// How long a toast from `AppEvent::Notice` stays on screen.
+const NOTICE_TTL: Duration = Duration::from_secs(4);
+
+/// A reported message rate older than this reads as zero (the socket went quiet).
+const MESSAGE_RATE_STALE: Duration = Duration::from_secs(3);
+
+/// How long the "Combat Start!" flash stays up after a pull begins.
+const COMBAT_START_FLASH: Duration = Duration::from_millis(1500);
+
+/// Longest number accepted while typing a setting value.
+const SETTINGS_ENTRY_MAX_DIGITS: usize = 6;
+
+/// Milliseconds one ←/→ press moves the redraw interval.
+const REDRAW_MS_STEP: i64 = 10;
+
+/// Destructive actions that wait for a y/n confirmation before running.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ConfirmAction {
+    DiscardEncounter,
+    /// Delete a saved encounter or dungeon run; `title` names it in the prompt.
+    DeleteHistoryEntry {
+        kind: RecentKind,
+        key: Vec<u8>,
+        title: String,
+    },
+}
+
+impl ConfirmAction {
+    pub fn prompt(&self) -> String {
+        match self {
+            ConfirmAction::DiscardEncounter => {
+                "Discard the current encounter without saving it?".to_string()
+            }
+            ConfirmAction::DeleteHistoryEntry {
+                kind: RecentKind::Encounter,
+                title,
+                ..
+            } => format!("Delete \"{title}\" from history?"),
+            ConfirmAction::DeleteHistoryEntry {
+                kind: RecentKind::Dungeon,
+                title,
+                ..
+            } => format!("Delete the run \"{title}\"? Its pulls stay in the encounter list."),
+        }
+    }
+}
+
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct AppSnapshot {
-    pub connected: bool,
+    /// True until the first successful connection, so startup doesn't read as an outage.
+    pub waiting_for_server: bool,
+    pub connection: ConnectionState,
     pub last_update_ms: u128,
     pub encounter: Option<EncounterSummary>,
     pub rows: Vec<CombatantRow>,
+    /// Row name resolved as "you" for the current rows (see [`AppSettings::resolve_self`]).
+    pub self_name: Option<String>,
     pub decoration: Decoration,
     pub mode: ViewMode,
+    /// Show the other mode's rate column next to `mode`'s (`b` toggles).
+    pub show_both_rates: bool,
+    /// DPS over the last `rolling_dps_secs` by combatant name; `None` when the window is off.
+    pub rolling_dps: Option<HashMap<String, f64>>,
     pub is_idle: bool,
     pub idle_scene: IdleScene,
     pub settings: AppSettings,
     pub show_settings: bool,
+    pub show_legend: bool,
     pub settings_cursor: SettingsField,
+    /// Digits typed for the selected numeric setting while in entry mode.
+    pub settings_entry: Option<String>,
     pub history: HistoryPanel,
     pub show_idle_overlay: bool,
     pub error: Option<AppError>,
     pub dungeon_active_zone: Option<String>,
+    /// Totals of the open dungeon run, shown as a HUD on the live view.
+    pub dungeon_progress: Option<DungeonProgress>,
+    pub dungeon_streak: DungeonStreak,
+    /// Boss names for grouping dungeon run pulls; `None` when the catalog failed to load.
+    #[serde(skip)]
+    pub dungeon_catalog: Option<Arc<DungeonCatalog>>,
+    /// Live-view keys, for the footer hints.
+    #[serde(skip)]
+    pub key_bindings: Arc<KeyBindings>,
+    /// Started with `--no-record`: nothing is written to history.
+    pub recording_disabled: bool,
+    pub pending_confirm: Option<ConfirmAction>,
+    pub role_filter: RoleFilter,
+    /// Order of the live and history detail tables (`<`/`>` cycle it, `~` reverses it).
+    pub sort_key: SortKey,
+    pub sort_ascending: bool,
+    /// Wall-clock seconds since the current pull started, frozen once it ends.
+    pub combat_elapsed_secs: Option<u64>,
+    pub combat_running: bool,
+    /// True for a moment after the encounter goes active, hidden while history is open.
+    pub combat_start_flash: bool,
+    pub notice: Option<String>,
+    pub show_throughput: bool,
+    /// Websocket messages per second; `None` until the first rate window closes.
+    pub message_rate: Option<f64>,
+    /// Most recent first.
+    pub error_log: Vec<ErrorLogEntry>,
+    pub show_error_log: bool,
+    /// Live table filter on name or job (`/` edits it, Esc clears it).
+    pub live_filter: String,
+    pub live_filter_editing: bool,
 }
 
 #[derive(Clone, Debug)]
 pub struct AppState {
-    pub connected: bool,
+    pub has_connected: bool,
+    pub connection: ConnectionState,
+    /// A connect attempt is running right now, as opposed to waiting out the retry backoff.
+    pub connect_in_flight: bool,
     pub last_update: Option<Instant>,
     pub last_active: Option<Instant>,
     pub connected_since: Option<Instant>,
@@ -40,20 +147,56 @@ pub struct AppState {
     pub rows: Vec<CombatantRow>,
     pub decoration: Decoration,
     pub mode: ViewMode,
+    /// Show the other mode's rate column next to `mode`'s (`b` toggles).
+    pub show_both_rates: bool,
     pub idle_scene: IdleScene,
     pub settings: AppSettings,
     pub show_settings: bool,
+    pub show_legend: bool,
     pub settings_cursor: SettingsField,
+    /// Digits typed for the selected numeric setting while in entry mode.
+    pub settings_entry: Option<String>,
     pub history: HistoryPanel,
+    /// Bumped whenever a blocking history load starts or the panel closes; store results
+    /// tagged with an older epoch are dropped so out-of-order loads cannot win.
+    pub history_epoch: u64,
     pub show_idle_overlay: bool,
     pub error: Option<AppError>,
     pub dungeon_active_zone: Option<String>,
+    /// Totals of the open dungeon run, shown as a HUD on the live view.
+    pub dungeon_progress: Option<DungeonProgress>,
+    /// Consecutive complete dungeon runs, loaded at startup and updated as runs are saved.
+    pub dungeon_streak: DungeonStreak,
+    pub dungeon_catalog: Option<Arc<DungeonCatalog>>,
+    pub key_bindings: Arc<KeyBindings>,
+    pub recording_disabled: bool,
+    pub pending_confirm: Option<ConfirmAction>,
+    /// Narrows the displayed combatant rows; recording always sees every row.
+    pub role_filter: RoleFilter,
+    /// Order of the live and history detail tables (`<`/`>` cycle it, `~` reverses it).
+    pub sort_key: SortKey,
+    pub sort_ascending: bool,
+    pub combat_timer: CombatTimer,
+    pub combat_started_at: Option<Instant>,
+    /// When the live rows were last put in true sorted order (see `stable_sort_ms`).
+    pub last_resort: Option<Instant>,
+    pub rolling_dps: RollingDps,
+    pub notice: Option<(String, Instant)>,
+    pub show_throughput: bool,
+    pub message_rate: Option<(f64, Instant)>,
+    pub error_log: VecDeque<ErrorLogEntry>,
+    pub show_error_log: bool,
+    /// Live table filter on name or job (`/` edits it, Esc clears it).
+    pub live_filter: String,
+    pub live_filter_editing: bool,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
-            connected: false,
+            has_connected: false,
+            connection: ConnectionState::Connecting,
+            connect_in_flight: false,
             last_update: None,
             last_active: None,
             connected_since: None,
@@ -62,32 +205,97 @@ impl Default for AppState {
             rows: Vec::new(),
             decoration: Decoration::default(),
             mode: ViewMode::default(),
+            show_both_rates: false,
             idle_scene: IdleScene::default(),
             settings: AppSettings::default(),
             show_settings: false,
+            show_legend: false,
             settings_cursor: SettingsField::default(),
+            settings_entry: None,
             history: HistoryPanel::default(),
+            history_epoch: 0,
             show_idle_overlay: true,
             error: None,
             dungeon_active_zone: None,
+            dungeon_progress: None,
+            dungeon_streak: DungeonStreak::default(),
+            dungeon_catalog: None,
+            key_bindings: Arc::default(),
+            recording_disabled: false,
+            pending_confirm: None,
+            role_filter: RoleFilter::default(),
+            sort_key: SortKey::default(),
+            sort_ascending: false,
+            combat_timer: CombatTimer::default(),
+            combat_started_at: None,
+            last_resort: None,
+            rolling_dps: RollingDps::default(),
+            notice: None,
+            show_throughput: false,
+            message_rate: None,
+            error_log: VecDeque::new(),
+            show_error_log: false,
+            live_filter: String::new(),
+            live_filter_editing: false,
+        }
+    }
+}
+
+/// When an event is applied: the monotonic instant drives timers, the wall clock stamps the
+/// error log.
+#[derive(Clone, Copy, Debug)]
+pub struct EventTime {
+    pub instant: Instant,
+    pub wall_ms: u64,
+}
+
+impl EventTime {
+    pub fn now() -> Self {
+        Self {
+            instant: Instant::now(),
+            wall_ms: crate::history::now_ms(),
         }
     }
 }
 
 impl AppState {
     pub fn apply(&mut self, evt: AppEvent) {
+        self.apply_at(evt, EventTime::now());
+    }
+
+    /// The whole state transition for one event, with the clock passed in: no I/O and no
+    /// reads of the current time, so tests can drive it synchronously.
+    pub fn apply_at(&mut self, evt: AppEvent, at: EventTime) {
+        if let Some(epoch) = evt.history_epoch() {
+            if epoch != self.history_epoch {
+                debug!(
+                    epoch,
+                    current = self.history_epoch,
+                    "dropping stale history result"
+                );
+                return;
+            }
+        }
         match evt {
             AppEvent::Connected => {
-                self.connected = true;
-                let now = Instant::now();
+                self.has_connected = true;
+                self.connection = ConnectionState::Connected;
+                self.connect_in_flight = false;
+                let now = at.instant;
                 self.last_update = Some(now);
                 self.last_active = None;
                 self.connected_since = Some(now);
                 self.disconnected_since = None;
             }
             AppEvent::Disconnected => {
-                self.connected = false;
-                let now = Instant::now();
+                // The client reports every failed attempt; keep the retry count on screen
+                // through the backoff wait instead of flashing back to "Disconnected".
+                if !matches!(self.connection, ConnectionState::Reconnecting { .. }) {
+                    self.connection = ConnectionState::Disconnected;
+                }
+                self.connect_in_flight = false;
+                let now = at.instant;
+                self.combat_timer.observe(false, now);
                 self.last_update = None;
                 self.last_active = None;
                 // Reset disconnected_since if we were previously connected (to restart idle timer)
@@ -103,11 +311,39 @@ impl AppState {
                 }
                 // Otherwise, keep the existing disconnected_since (preserves startup time)
             }
+            AppEvent::Connecting => {
+                self.connection = ConnectionState::Connecting;
+                self.connect_in_flight = true;
+            }
+            AppEvent::Reconnecting { attempt } => {
+                self.connection = ConnectionState::Reconnecting { attempt };
+                self.connect_in_flight = true;
+            }
             AppEvent::CombatData { encounter, rows } => {
-                let now = Instant::now();
+                let now = at.instant;
+                self.combat_timer.observe(encounter.is_active, now);
+                let was_active = self.encounter.as_ref().is_some_and(|enc| enc.is_active);
+                if encounter.is_active && !was_active {
+                    self.combat_started_at = Some(now);
+                    self.rolling_dps.reset();
+                }
+                if let Some(window_ms) = self.settings.rolling_dps_window_ms() {
+                    self.rolling_dps.record(at.wall_ms, &rows, window_ms);
+                }
+                let throttled = encounter.is_active
+                    && was_active
+                    && self.settings.stable_sort_window().is_some_and(|window| {
+                        self.last_resort
+                            .is_some_and(|at| now.saturating_duration_since(at) < window)
+                    });
                 self.encounter = Some(encounter);
-                self.rows = rows;
+                let previous = std::mem::replace(&mut self.rows, rows);
                 self.resort_rows();
+                if throttled {
+                    keep_previous_order(&mut self.rows, &previous);
+                } else {
+                    self.last_resort = Some(now);
+                }
                 self.last_update = Some(now);
                 self.idle_scene = IdleScene::Status;
                 if self
@@ -119,10 +355,17 @@ impl AppState {
                     self.last_active = Some(now);
                 }
             }
-            AppEvent::HistoryDatesLoaded { days } => {
+            AppEvent::HistoryDatesLoaded { mut days, .. } => {
                 self.history.loading = false;
                 self.history.error = None;
+                self.history.date_sort.apply(&mut days);
                 self.history.days = days;
+                if let Some(date_id) = self.history.reselect_day.take() {
+                    if let Some(idx) = self.history.days.iter().position(|d| d.iso_date == date_id)
+                    {
+                        self.history.selected_day = idx;
+                    }
+                }
                 if self.history.selected_day >= self.history.days.len() {
                     self.history.selected_day = 0;
                 }
@@ -137,6 +380,7 @@ impl AppState {
             AppEvent::HistoryEncountersLoaded {
                 date_id,
                 encounters,
+                ..
             } => {
                 if let Some(day) = self.history.find_day_mut(&date_id) {
                     day.encounters = encounters;
@@ -148,24 +392,49 @@ impl AppState {
                         self.history.selected_encounter = new_len.saturating_sub(1);
                     }
                 }
+                if self.history.level == HistoryPanelLevel::Encounters {
+                    self.history.snap_encounter_selection();
+                }
                 self.history.loading = false;
+                self.apply_pending_jump();
             }
-            AppEvent::HistoryEncounterLoaded { key, record } => {
+            AppEvent::HistoryEncounterLoaded { key, record, .. } => {
                 if let Some(item) = self.history.find_encounter_mut(&key) {
+                    item.buff_uptime = buff_uptime(&record.frames, &self.settings.uptime_buffs);
                     item.record = Some(record);
                 }
                 self.history.loading = false;
             }
-            AppEvent::DungeonDatesLoaded { days } => {
+            AppEvent::DungeonDatesLoaded { mut days, .. } => {
+                self.history.date_sort.apply(&mut days);
                 self.history.dungeon_days = days;
+                if let Some(date_id) = self.history.reselect_dungeon_day.take() {
+                    if let Some(idx) = self
+                        .history
+                        .dungeon_days
+                        .iter()
+                        .position(|d| d.iso_date == date_id)
+                    {
+                        self.history.dungeon_selected_day = idx;
+                    }
+                }
                 if self.history.dungeon_selected_day >= self.history.dungeon_days.len() {
                     self.history.dungeon_selected_day = 0;
                 }
                 self.history.dungeon_selected_run = 0;
                 self.history.dungeon_selected_child = 0;
                 self.history.loading = false;
+                let dungeon_jump = self
+                    .history
+                    .pending_jump
+                    .as_ref()
+                    .is_some_and(|jump| jump.kind == RecentKind::Dungeon);
+                if self.history.dungeon_days.is_empty() && dungeon_jump {
+                    self.history.pending_jump = None;
+                }
+                self.apply_pending_jump();
             }
-            AppEvent::DungeonRunsLoaded { date_id, runs } => {
+            AppEvent::DungeonRunsLoaded { date_id, runs, .. } => {
                 if let Some(day) = self.history.find_dungeon_day_mut(&date_id) {
                     day.runs = runs;
                     day.runs_loaded = true;
@@ -175,16 +444,18 @@ impl AppState {
                     }
                 }
                 self.history.loading = false;
+                self.apply_pending_jump();
             }
-            AppEvent::DungeonRunLoaded { key, record } => {
+            AppEvent::DungeonRunLoaded { key, record, .. } => {
                 if let Some(run) = self.history.find_dungeon_run_mut(&key) {
                     let child_count = record.child_keys.len();
                     run.record = Some(record);
                     run.child_records = vec![None; child_count];
+                    run.child_buff_uptime = vec![Vec::new(); child_count];
                 }
                 self.history.loading = false;
             }
-            AppEvent::DungeonEncounterLoaded { key, record } => {
+            AppEvent::DungeonEncounterLoaded { key, record, .. } => {
                 'outer: for day in &mut self.history.dungeon_days {
                     for run in &mut day.runs {
                         if let Some(rec) = run.record.as_ref() {
@@ -193,9 +464,15 @@ impl AppState {
                                 .iter()
                                 .position(|child_key| child_key.as_slice() == key.as_slice())
                             {
-                                if run.child_records.len() < rec.child_keys.len() {
-                                    run.child_records.resize(rec.child_keys.len(), None);
+                                let count = rec.child_keys.len();
+                                if run.child_records.len() < count {
+                                    run.child_records.resize(count, None);
                                 }
+                                if run.child_buff_uptime.len() < count {
+                                    run.child_buff_uptime.resize(count, Vec::new());
+                                }
+                                run.child_buff_uptime[idx] =
+                                    buff_uptime(&record.frames, &self.settings.uptime_buffs);
                                 run.child_records[idx] = Some(record);
                                 break 'outer;
                             }
@@ -204,16 +481,53 @@ impl AppState {
                 }
                 self.history.loading = false;
             }
-            AppEvent::DungeonSessionUpdate { active_zone } => {
+            AppEvent::DungeonSessionUpdate {
+                active_zone,
+                progress,
+            } => {
                 self.dungeon_active_zone = active_zone;
+                self.dungeon_progress = progress;
             }
-            AppEvent::HistoryError { message } => {
+            AppEvent::DungeonCatalogReloaded { catalog } => self.dungeon_catalog = Some(catalog),
+            AppEvent::DungeonStreak { streak } => self.dungeon_streak = streak,
+            AppEvent::HistoryError { message, .. } => {
                 self.history.loading = false;
+                self.log_error(
+                    AppError::new(AppErrorKind::History, message.clone()),
+                    at.wall_ms,
+                );
                 self.history.error = Some(message);
             }
             AppEvent::SystemError { error } => {
+                self.log_error(error.clone(), at.wall_ms);
                 self.error = Some(error);
             }
+            AppEvent::Notice { message } => {
+                self.notice = Some((message, at.instant));
+            }
+            AppEvent::HistorySearchLoaded { results, .. } => {
+                self.history.search_results = results;
+                self.history.search_selected = 0;
+                self.history.loading = false;
+            }
+            AppEvent::HistoryEntryDeleted { kind, key, .. } => {
+                self.history.loading = false;
+                self.history.forget_entry(kind, &key);
+            }
+            AppEvent::HistoryRecentResolved { kind, key, found } => {
+                let target = match found {
+                    Some((date_id, title)) => RecentTarget::Found { date_id, title },
+                    None => RecentTarget::Missing,
+                };
+                for entry in &mut self.history.recent {
+                    if entry.kind == kind && entry.key_bytes().as_deref() == Some(key.as_slice()) {
+                        entry.target = target.clone();
+                    }
+                }
+            }
+            AppEvent::MessageRate { per_sec } => {
+                self.message_rate = Some((per_sec, at.instant));
+            }
         }
     }
 
@@ -223,45 +537,126 @@ impl AppState {
             .last_update
             .map(|instant| now.saturating_duration_since(instant).as_millis())
             .unwrap_or(0);
+        let is_idle = self.is_idle_at(now);
+        // Display-only: the rows stay in state so the next pull (or history) is unaffected.
+        let cleared = is_idle && self.settings.auto_clear_idle;
+        let rows = if cleared {
+            Vec::new()
+        } else {
+            self.rows.clone()
+        };
         AppSnapshot {
-            connected: self.connected,
+            waiting_for_server: !self.has_connected,
+            connection: self.connection,
             last_update_ms,
             encounter: self.encounter.clone(),
-            rows: self.rows.clone(),
+            self_name: self.settings.resolve_self(&rows).map(str::to_string),
+            rows,
             decoration: self.decoration,
             mode: self.mode,
-            is_idle: self.is_idle_at(now),
+            show_both_rates: self.show_both_rates,
+            rolling_dps: self
+                .settings
+                .rolling_dps_window_ms()
+                .map(|_| self.rolling_dps.rates()),
+            is_idle,
             idle_scene: self.idle_scene,
             settings: self.settings.clone(),
             show_settings: self.show_settings,
+            show_legend: self.show_legend,
             settings_cursor: self.settings_cursor,
+            settings_entry: self.settings_entry.clone(),
             history: self.history.clone(),
-            show_idle_overlay: self.show_idle_overlay,
+            show_idle_overlay: self.show_idle_overlay || cleared,
             error: self.error.clone(),
             dungeon_active_zone: self.dungeon_active_zone.clone(),
+            dungeon_progress: self.dungeon_progress.clone(),
+            dungeon_streak: self.dungeon_streak,
+            dungeon_catalog: self.dungeon_catalog.clone(),
+            key_bindings: Arc::clone(&self.key_bindings),
+            recording_disabled: self.recording_disabled,
+            pending_confirm: self.pending_confirm.clone(),
+            role_filter: self.role_filter,
+            sort_key: self.sort_key,
+            sort_ascending: self.sort_ascending,
+            combat_elapsed_secs: self.combat_timer.elapsed(now).map(|d| d.as_secs()),
+            combat_running: self.combat_timer.is_running(),
+            combat_start_flash: !self.history.visible
+                && self
+                    .combat_started_at
+                    .is_some_and(|at| now.saturating_duration_since(at) < COMBAT_START_FLASH),
+            notice: self
+                .notice
+                .as_ref()
+                .filter(|(_, at)| now.saturating_duration_since(*at) < NOTICE_TTL)
+                .map(|(message, _)| message.clone()),
+            show_throughput: self.show_throughput,
+            message_rate: self.message_rate.map(|(rate, at)| {
+                if now.saturating_duration_since(at) < MESSAGE_RATE_STALE {
+                    rate
+                } else {
+                    0.0
+                }
+            }),
+            error_log: self.error_log.iter().rev().cloned().collect(),
+            show_error_log: self.show_error_log,
+            live_filter: self.live_filter.clone(),
+            live_filter_editing: self.live_filter_editing,
         }
     }
 
     pub fn resort_rows(&mut self) {
-        match self.mode {
-            ViewMode::Dps => {
-                self.rows.sort_by(|a, b| {
-                    b.encdps
-                        .partial_cmp(&a.encdps)
-                        .unwrap_or(Ordering::Equal)
-                        .then_with(|| a.name.cmp(&b.name))
-                });
-            }
-            ViewMode::Heal => {
-                self.rows.sort_by(|a, b| {
-                    b.enchps
-                        .partial_cmp(&a.enchps)
-                        .unwrap_or(Ordering::Equal)
-                        .then_with(|| a.name.cmp(&b.name))
-                });
-            }
-        }
+        self.sort_key
+            .sort_rows(self.mode, self.sort_ascending, &mut self.rows);
+    }
+
+    /// Moves the table sort to the next (`delta > 0`) or previous key, starting it in that
+    /// key's natural direction.
+    pub fn cycle_sort(&mut self, delta: i32) {
+        self.sort_key = if delta < 0 {
+            self.sort_key.prev()
+        } else {
+            self.sort_key.next()
+        };
+        self.sort_ascending = self.sort_key.default_ascending();
+        self.after_sort_change();
     }
+
+    pub fn toggle_sort_direction(&mut self) {
+        self.sort_ascending = !self.sort_ascending;
+        self.after_sort_change();
+    }
+
+    fn after_sort_change(&mut self) {
+        self.resort_rows();
+        self.last_resort = Some(Instant::now());
+        self.history.detail_row = 0;
+        let direction = if self.sort_ascending {
+            "ascending"
+        } else {
+            "descending"
+        };
+        let mode = if self.history.visible {
+            self.history.detail_mode
+        } else {
+            self.mode
+        };
+        self.notice = Some((
+            format!("Sorted by {} ({direction})", self.sort_key.label(mode)),
+            Instant::now(),
+        ));
+    }
+}
+
+/// Puts rows seen in `previous` back in their old positions; newcomers follow in the order they
+/// already have.
+fn keep_previous_order(rows: &mut [CombatantRow], previous: &[CombatantRow]) {
+    rows.sort_by_key(|row| {
+        previous
+            .iter()
+            .position(|old| old.name == row.name)
+            .unwrap_or(usize::MAX)
+    });
 }
 
 impl AppState {
@@ -269,8 +664,8 @@ impl AppState {
         let Some(threshold) = self.settings.idle_duration() else {
             return false;
         };
-        
-        if !self.connected {
+
+        if !self.connection.is_connected() {
             // When disconnected, check if we've been disconnected long enough
             if let Some(disconnected) = self.disconnected_since {
                 return now.saturating_duration_since(disconnected) >= threshold;
@@ -278,7 +673,7 @@ impl AppState {
             // If we don't have a disconnected timestamp yet, we're not idle
             return false;
         }
-        
+
         // When connected, check for active encounters
         if self
             .encounter
@@ -288,7 +683,7 @@ impl AppState {
         {
             return false;
         }
-        
+
         // Check time since last active encounter
         if let Some(active) = self.last_active {
             if now.saturating_duration_since(active) >= threshold {
@@ -296,12 +691,12 @@ impl AppState {
             }
             return false;
         }
-        
+
         // Check time since connection
         if let Some(since) = self.connected_since {
             return now.saturating_duration_since(since) >= threshold;
         }
-        
+
         false
     }
 
@@ -322,9 +717,28 @@ impl AppState {
         }
     }
 
+    /// Steps the redraw interval by `delta` milliseconds within the allowed range.
+    pub fn adjust_redraw_ms(&mut self, delta: i64) -> bool {
+        let current = self.settings.redraw_ms;
+        let adjusted = current
+            .saturating_add_signed(delta)
+            .clamp(REDRAW_MS_MIN, REDRAW_MS_MAX);
+        if adjusted != current {
+            self.settings.redraw_ms = adjusted;
+            true
+        } else {
+            false
+        }
+    }
+
     pub fn adjust_selected_setting(&mut self, forward: bool) -> bool {
         match self.settings_cursor {
             SettingsField::IdleTimeout => self.adjust_idle_seconds(if forward { 1 } else { -1 }),
+            SettingsField::RedrawRate => self.adjust_redraw_ms(if forward {
+                REDRAW_MS_STEP
+            } else {
+                -REDRAW_MS_STEP
+            }),
             SettingsField::DefaultDecoration => {
                 let changed = self.cycle_default_decoration(forward);
                 if changed {
@@ -340,15 +754,78 @@ impl AppState {
                 changed
             }
             SettingsField::DungeonMode => {
-                let before = self.settings.dungeon_mode_enabled;
-                let after = if forward { !before } else { !before };
-                if after != before {
-                    self.settings.dungeon_mode_enabled = after;
-                    true
+                self.settings.dungeon_mode_enabled = !self.settings.dungeon_mode_enabled;
+                true
+            }
+            SettingsField::HideZeroRows => {
+                self.settings.hide_zero_rows = !self.settings.hide_zero_rows;
+                true
+            }
+            SettingsField::EscapeBehavior => {
+                self.settings.escape_behavior = if forward {
+                    self.settings.escape_behavior.next()
                 } else {
-                    false
-                }
-            } // Placeholder for future settings fields
+                    self.settings.escape_behavior.prev()
+                };
+                true
+            }
+            SettingsField::Theme => {
+                self.settings.theme = if forward {
+                    self.settings.theme.next()
+                } else {
+                    self.settings.theme.prev()
+                };
+                true
+            }
+        }
+    }
+
+    /// Starts typing a value for the selected setting; returns false for fields that only cycle.
+    pub fn settings_begin_entry(&mut self) -> bool {
+        if !self.show_settings || self.settings_cursor.numeric_range().is_none() {
+            return false;
+        }
+        self.settings_entry = Some(String::new());
+        true
+    }
+
+    pub fn settings_entry_push(&mut self, ch: char) {
+        if let Some(entry) = self.settings_entry.as_mut() {
+            if ch.is_ascii_digit() && entry.len() < SETTINGS_ENTRY_MAX_DIGITS {
+                entry.push(ch);
+            }
+        }
+    }
+
+    pub fn settings_entry_pop(&mut self) {
+        if let Some(entry) = self.settings_entry.as_mut() {
+            entry.pop();
+        }
+    }
+
+    /// Leaves entry mode. With `commit`, the typed value is clamped into the field's range
+    /// and applied; returns whether the setting changed. An empty entry changes nothing.
+    pub fn settings_finish_entry(&mut self, commit: bool) -> bool {
+        let Some(entry) = self.settings_entry.take() else {
+            return false;
+        };
+        let (Some(range), true) = (self.settings_cursor.numeric_range(), commit) else {
+            return false;
+        };
+        let Ok(value) = entry.parse::<u64>() else {
+            return false;
+        };
+        let value = value.clamp(*range.start(), *range.end());
+        match self.settings_cursor {
+            SettingsField::IdleTimeout if self.settings.idle_seconds != value => {
+                self.settings.idle_seconds = value;
+                true
+            }
+            SettingsField::RedrawRate if self.settings.redraw_ms != value => {
+                self.settings.redraw_ms = value;
+                true
+            }
+            _ => false,
         }
     }
 
@@ -398,8 +875,7 @@ impl AppState {
 
     pub fn toggle_history(&mut self) -> bool {
         if self.history.visible {
-            self.history.visible = false;
-            self.history.reset();
+            self.close_history();
             false
         } else {
             self.history.visible = true;
@@ -423,10 +899,46 @@ impl AppState {
         self.history.error = None;
     }
 
+    /// Marks the panel as loading under a fresh epoch and returns it for tagging the spawned
+    /// load; results from earlier loads are ignored from here on.
+    pub fn history_begin_load(&mut self) -> u64 {
+        self.history_epoch += 1;
+        self.history_set_loading();
+        self.history_epoch
+    }
+
+    /// Reloads both date lists from the store, dropping loaded encounters and runs so they
+    /// are fetched again on navigation. Both views return to their Dates level with the same
+    /// days selected where they still exist. Returns the load epoch to spawn the loads with.
+    pub fn history_refresh(&mut self) -> Option<u64> {
+        if !self.history.visible || self.history.loading {
+            return None;
+        }
+        self.history.reselect_day = self.history.current_day().map(|d| d.iso_date.clone());
+        self.history.reselect_dungeon_day = self
+            .history
+            .current_dungeon_day()
+            .map(|d| d.iso_date.clone());
+        self.history.level = HistoryPanelLevel::Dates;
+        self.history.dungeon_level = DungeonPanelLevel::Dates;
+        self.history.selected_encounter = 0;
+        self.history.dungeon_selected_run = 0;
+        self.history.dungeon_selected_child = 0;
+        self.history.detail_scroll = 0;
+        Some(self.history_begin_load())
+    }
+
+    fn close_history(&mut self) {
+        self.history.visible = false;
+        self.history.reset();
+        self.history_epoch += 1;
+    }
+
     pub fn history_move_selection(&mut self, delta: i32) {
         if !self.history.visible || self.history.loading {
             return;
         }
+        self.history.detail_scroll = 0;
         match self.history.view {
             HistoryView::Encounters => match self.history.level {
                 HistoryPanelLevel::Dates => {
@@ -451,20 +963,39 @@ impl AppState {
                     }
                 }
                 HistoryPanelLevel::Encounters | HistoryPanelLevel::EncounterDetail => {
-                    if let Some(day) = self.history.current_day() {
-                        if day.encounters.is_empty() {
-                            return;
-                        }
-                        let len = day.encounters.len() as i32;
-                        let current = self.history.selected_encounter as i32;
-                        let mut next = current + delta;
-                        if next < 0 {
-                            next = 0;
-                        } else if next >= len {
-                            next = len - 1;
-                        }
-                        self.history.selected_encounter = next as usize;
+                    let visible = self.history.visible_encounter_indices();
+                    if visible.is_empty() {
+                        return;
+                    }
+                    let len = visible.len() as i32;
+                    let current = visible
+                        .iter()
+                        .position(|idx| *idx == self.history.selected_encounter)
+                        .unwrap_or(0) as i32;
+                    let mut next = current + delta;
+                    if next < 0 {
+                        next = 0;
+                    } else if next >= len {
+                        next = len - 1;
+                    }
+                    self.history.selected_encounter = visible[next as usize];
+                    self.history.detail_row = 0;
+                }
+                HistoryPanelLevel::Abilities => {
+                    let len = self.history_ability_count() as i64;
+                    if len == 0 {
+                        return;
                     }
+                    let next = self.history.ability_selected as i64 + delta as i64;
+                    self.history.ability_selected = next.clamp(0, len - 1) as usize;
+                }
+                HistoryPanelLevel::SearchResults => {
+                    let len = self.history.search_results.len() as i64;
+                    if len == 0 {
+                        return;
+                    }
+                    let next = self.history.search_selected as i64 + delta as i64;
+                    self.history.search_selected = next.clamp(0, len - 1) as usize;
                 }
             },
             HistoryView::Dungeons => match self.history.dungeon_level {
@@ -575,6 +1106,256 @@ impl AppState {
         }
     }
 
+    /// Starts typing a live table filter; keeps the current query so it can be refined.
+    pub fn live_start_filter(&mut self) {
+        if !self.history.visible {
+            self.live_filter_editing = true;
+        }
+    }
+
+    pub fn live_filter_push(&mut self, ch: char) {
+        self.live_filter.push(ch);
+    }
+
+    pub fn live_filter_pop(&mut self) {
+        self.live_filter.pop();
+    }
+
+    /// Leaves live filter typing; `keep` false clears the filter as well.
+    pub fn live_finish_filter(&mut self, keep: bool) {
+        self.live_filter_editing = false;
+        if !keep {
+            self.live_filter.clear();
+        }
+    }
+
+    /// Starts typing an encounter filter; only available on the encounter list.
+    pub fn history_start_filter(&mut self) {
+        if self.history.visible
+            && self.history.view == HistoryView::Encounters
+            && self.history.level == HistoryPanelLevel::Encounters
+        {
+            self.history.filter_editing = true;
+        }
+    }
+
+    pub fn history_filter_push(&mut self, ch: char) {
+        self.history.filter.push(ch);
+        self.history.filter_restored = false;
+        self.history.snap_encounter_selection();
+    }
+
+    pub fn history_filter_pop(&mut self) {
+        self.history.filter.pop();
+        self.history.filter_restored = false;
+        self.history.snap_encounter_selection();
+    }
+
+    /// Leaves filter typing; `keep` false clears the filter as well.
+    pub fn history_finish_filter(&mut self, keep: bool) {
+        self.history.filter_editing = false;
+        if !keep {
+            self.history.filter.clear();
+            self.history.filter_restored = false;
+        }
+        self.history.snap_encounter_selection();
+    }
+
+    /// Starts typing a search across every day's encounters, from the Dates list or to refine
+    /// the current results.
+    pub fn history_start_search(&mut self) {
+        if self.history.visible
+            && self.history.view == HistoryView::Encounters
+            && matches!(
+                self.history.level,
+                HistoryPanelLevel::Dates | HistoryPanelLevel::SearchResults
+            )
+        {
+            self.history.search_editing = true;
+        }
+    }
+
+    pub fn history_search_push(&mut self, ch: char) {
+        self.history.search_query.push(ch);
+    }
+
+    pub fn history_search_pop(&mut self) {
+        self.history.search_query.pop();
+    }
+
+    /// Leaves search typing. Submitting a non-blank query shows the results level and returns
+    /// the load epoch to run the search under; cancelling drops the query and any results.
+    pub fn history_finish_search(&mut self, submit: bool) -> Option<u64> {
+        self.history.search_editing = false;
+        if !submit || self.history.search_query.trim().is_empty() {
+            self.history.search_query.clear();
+            self.history.search_results.clear();
+            self.history.level = HistoryPanelLevel::Dates;
+            return None;
+        }
+        self.history.level = HistoryPanelLevel::SearchResults;
+        self.history.search_results.clear();
+        self.history.search_selected = 0;
+        Some(self.history_begin_load())
+    }
+
+    /// Steps the encounter list's job filter through the jobs seen on the selected day, then
+    /// back to showing every encounter.
+    pub fn history_cycle_job_filter(&mut self) {
+        if !self.history.visible
+            || self.history.view != HistoryView::Encounters
+            || self.history.level != HistoryPanelLevel::Encounters
+        {
+            return;
+        }
+        let jobs = self.history.day_jobs();
+        let next = match &self.history.job_filter {
+            None => jobs.first(),
+            Some(current) => jobs.iter().find(|job| *job > current),
+        };
+        self.history.job_filter = next.cloned();
+        self.history.snap_encounter_selection();
+    }
+
+    /// Applies a filter saved from an earlier session when the panel opens.
+    pub fn history_restore_filter(&mut self, filter: String) {
+        if filter.trim().is_empty() {
+            return;
+        }
+        self.history.filter = filter;
+        self.history.filter_restored = true;
+    }
+
+    fn history_in_encounter_detail(&self) -> bool {
+        if !self.history.visible {
+            return false;
+        }
+        match self.history.view {
+            HistoryView::Encounters => self.history.level == HistoryPanelLevel::EncounterDetail,
+            HistoryView::Dungeons => {
+                self.history.dungeon_level == DungeonPanelLevel::EncounterDetail
+            }
+        }
+    }
+
+    /// Scrolls the combatant table of an open encounter detail; elsewhere moves the selection.
+    pub fn history_scroll(&mut self, delta: i32) {
+        if !self.history_in_encounter_detail() {
+            self.history_move_selection(delta);
+            return;
+        }
+        let max = self
+            .history_detail_row_count()
+            .saturating_sub(self.history.detail_capacity.max(1));
+        let next = self.history.detail_scroll as i64 + delta as i64;
+        self.history.detail_scroll = next.clamp(0, max as i64) as usize;
+    }
+
+    fn history_detail_row_count(&self) -> usize {
+        let rows = match self.history.view {
+            HistoryView::Encounters => self
+                .history
+                .current_encounter()
+                .and_then(|enc| enc.record.as_ref())
+                .map(|record| &record.rows),
+            HistoryView::Dungeons => self.history.current_dungeon_run().and_then(|run| {
+                run.child_records
+                    .get(self.history.dungeon_selected_child)
+                    .and_then(|child| child.as_ref())
+                    .map(|record| &record.rows)
+            }),
+        };
+        rows.map(|rows| {
+            visible_detail_rows(
+                rows,
+                self.history.detail_mode,
+                self.sort_key,
+                self.sort_ascending,
+                self.role_filter,
+                self.settings.hide_zero_rows,
+            )
+            .len()
+        })
+        .unwrap_or(0)
+    }
+
+    /// Moves the combatant cursor of an open encounter detail (Encounters view).
+    pub fn history_move_detail_row(&mut self, delta: i32) {
+        if !self.history.visible
+            || self.history.view != HistoryView::Encounters
+            || self.history.level != HistoryPanelLevel::EncounterDetail
+        {
+            return;
+        }
+        let len = self.history_detail_row_count() as i64;
+        if len == 0 {
+            return;
+        }
+        let current = (self.history.detail_row as i64).min(len - 1);
+        self.history.detail_row = (current + delta as i64).clamp(0, len - 1) as usize;
+    }
+
+    fn history_ability_count(&self) -> usize {
+        let Some(record) = self
+            .history
+            .current_encounter()
+            .and_then(|enc| enc.record.as_ref())
+        else {
+            return 0;
+        };
+        self.history
+            .detail_combatant(
+                self.role_filter,
+                self.sort_key,
+                self.sort_ascending,
+                self.settings.hide_zero_rows,
+            )
+            .and_then(|row| record.abilities_for(&row.name))
+            .map_or(0, |abilities| abilities.len())
+    }
+
+    pub fn history_toggle_deaths(&mut self) {
+        if self.history_in_encounter_detail() {
+            self.history.show_deaths = !self.history.show_deaths;
+        }
+    }
+
+    pub fn history_toggle_buffs(&mut self) {
+        if self.history_in_encounter_detail() {
+            self.history.show_buffs = !self.history.show_buffs;
+        }
+    }
+
+    pub fn history_toggle_boss_groups(&mut self) {
+        if self.history.visible
+            && self.history.view == HistoryView::Dungeons
+            && self.history.dungeon_level == DungeonPanelLevel::RunDetail
+        {
+            self.history.dungeon_group_bosses = !self.history.dungeon_group_bosses;
+        }
+    }
+
+    /// Flips the order of both Dates lists, keeping the same days selected. Only on the
+    /// Dates level, where the order is visible.
+    pub fn history_toggle_date_sort(&mut self) {
+        let on_dates = match self.history.view {
+            HistoryView::Encounters => self.history.level == HistoryPanelLevel::Dates,
+            HistoryView::Dungeons => self.history.dungeon_level == DungeonPanelLevel::Dates,
+        };
+        if !self.history.visible || self.history.loading || !on_dates {
+            return;
+        }
+        self.history.date_sort = self.history.date_sort.toggled();
+        self.history.days.reverse();
+        self.history.dungeon_days.reverse();
+        let mirror = |idx: usize, len: usize| len.saturating_sub(1).saturating_sub(idx);
+        self.history.selected_day = mirror(self.history.selected_day, self.history.days.len());
+        self.history.dungeon_selected_day = mirror(
+            self.history.dungeon_selected_day,
+            self.history.dungeon_days.len(),
+        );
+    }
+
     pub fn history_toggle_view(&mut self) {
         if !self.history.visible {
             return;
@@ -598,6 +1379,7 @@ impl AppState {
         if !self.history.visible || self.history.loading {
             return;
         }
+        self.history.detail_scroll = 0;
         match self.history.view {
             HistoryView::Encounters => match self.history.level {
                 HistoryPanelLevel::Dates => {
@@ -612,13 +1394,48 @@ impl AppState {
                             self.history.selected_encounter = 0;
                         }
                     }
+                    self.history.snap_encounter_selection();
                 }
                 HistoryPanelLevel::Encounters => {
-                    if self.history.current_encounter().is_some() {
+                    if let Some(key) = self
+                        .history
+                        .current_encounter()
+                        .filter(|enc| self.history.encounter_matches(enc))
+                        .map(|enc| enc.key.clone())
+                    {
                         self.history.level = HistoryPanelLevel::EncounterDetail;
+                        self.history.from_search = false;
+                        self.history.remember_recent(RecentKind::Encounter, &key);
+                    }
+                }
+                HistoryPanelLevel::EncounterDetail => {
+                    if self
+                        .history
+                        .detail_combatant(
+                            self.role_filter,
+                            self.sort_key,
+                            self.sort_ascending,
+                            self.settings.hide_zero_rows,
+                        )
+                        .is_some()
+                    {
+                        self.history.level = HistoryPanelLevel::Abilities;
+                        self.history.ability_selected = 0;
                     }
                 }
-                HistoryPanelLevel::EncounterDetail => {}
+                HistoryPanelLevel::Abilities => {}
+                HistoryPanelLevel::SearchResults => {
+                    let Some(item) = self.history.current_search_result() else {
+                        return;
+                    };
+                    self.history.pending_jump = Some(PendingJump {
+                        kind: RecentKind::Encounter,
+                        key: item.key.clone(),
+                        date_id: local_date_id(item.last_seen_ms),
+                    });
+                    self.history.from_search = true;
+                    self.apply_pending_jump();
+                }
             },
             HistoryView::Dungeons => match self.history.dungeon_level {
                 DungeonPanelLevel::Dates => {
@@ -635,9 +1452,14 @@ impl AppState {
                     }
                 }
                 DungeonPanelLevel::Runs => {
-                    if self.history.current_dungeon_run().is_some() {
+                    if let Some(key) = self
+                        .history
+                        .current_dungeon_run()
+                        .map(|run| run.key.clone())
+                    {
                         self.history.dungeon_level = DungeonPanelLevel::RunDetail;
                         self.history.dungeon_selected_child = 0;
+                        self.history.remember_recent(RecentKind::Dungeon, &key);
                     }
                 }
                 DungeonPanelLevel::RunDetail => {
@@ -645,7 +1467,6 @@ impl AppState {
                         if let Some(record) = run.record.as_ref() {
                             if !record.child_keys.is_empty() {
                                 self.history.dungeon_level = DungeonPanelLevel::EncounterDetail;
-                                self.history.dungeon_selected_child = 0;
                             }
                         }
                     }
@@ -655,24 +1476,175 @@ impl AppState {
         }
     }
 
-    pub fn history_back(&mut self) {
+    /// Opens or closes the recently viewed quick list. Returns `true` when it opened.
+    pub fn history_toggle_recent(&mut self) -> bool {
         if !self.history.visible {
+            return false;
+        }
+        self.history.show_recent = !self.history.show_recent;
+        self.history.recent_selected = 0;
+        self.history.show_recent
+    }
+
+    pub fn history_recent_move(&mut self, delta: i32) {
+        let len = self.history.recent.len() as i32;
+        if len == 0 {
             return;
         }
-        match self.history.view {
-            HistoryView::Encounters => match self.history.level {
-                HistoryPanelLevel::EncounterDetail => {
-                    self.history.level = HistoryPanelLevel::Encounters;
-                }
-                HistoryPanelLevel::Encounters => {
-                    self.history.level = HistoryPanelLevel::Dates;
-                    self.history.selected_encounter = 0;
-                }
-                HistoryPanelLevel::Dates => {}
-            },
-            HistoryView::Dungeons => match self.history.dungeon_level {
-                DungeonPanelLevel::EncounterDetail => {
-                    self.history.dungeon_level = DungeonPanelLevel::RunDetail;
+        let next = (self.history.recent_selected as i32 + delta).clamp(0, len - 1);
+        self.history.recent_selected = next as usize;
+    }
+
+    /// Recent entries whose date and title still need a store lookup.
+    pub fn history_recent_unresolved(&self) -> Vec<(RecentKind, Vec<u8>)> {
+        self.history
+            .recent
+            .iter()
+            .filter(|entry| entry.target == RecentTarget::Pending)
+            .filter_map(|entry| Some((entry.kind, entry.key_bytes()?)))
+            .collect()
+    }
+
+    /// Replaces the recent list with the one saved in the UI-state file.
+    pub fn history_restore_recent(&mut self, recent: Vec<RecentEntry>) {
+        self.history.recent = recent;
+        self.history.recent.truncate(RECENT_CAPACITY);
+    }
+
+    /// Jumps to the selected quick-list entry, loading its day first when needed.
+    pub fn history_jump_recent(&mut self) {
+        let Some(entry) = self.history.recent.get(self.history.recent_selected) else {
+            return;
+        };
+        match &entry.target {
+            RecentTarget::Found { date_id, .. } => {
+                let Some(key) = entry.key_bytes() else {
+                    return;
+                };
+                self.history.pending_jump = Some(PendingJump {
+                    kind: entry.kind,
+                    key,
+                    date_id: date_id.clone(),
+                });
+                self.history.show_recent = false;
+                self.history.loading = false;
+                self.history.detail_scroll = 0;
+                self.apply_pending_jump();
+            }
+            RecentTarget::Missing => {
+                self.notice = Some((
+                    "That entry is no longer in history".to_string(),
+                    Instant::now(),
+                ));
+            }
+            RecentTarget::Pending => {}
+        }
+    }
+
+    /// Advances a pending quick-list jump as far as the loaded data allows; the follow-up
+    /// loads are issued by the usual history task logic and call back in here.
+    fn apply_pending_jump(&mut self) {
+        let Some(jump) = self.history.pending_jump.clone() else {
+            return;
+        };
+        match jump.kind {
+            RecentKind::Encounter => {
+                self.history.view = HistoryView::Encounters;
+                let Some(day_idx) = self
+                    .history
+                    .days
+                    .iter()
+                    .position(|day| day.iso_date == jump.date_id)
+                else {
+                    self.history.pending_jump = None;
+                    return;
+                };
+                self.history.selected_day = day_idx;
+                self.history.level = HistoryPanelLevel::Encounters;
+                let day = &self.history.days[day_idx];
+                if !day.encounters_loaded && !day.encounter_ids.is_empty() {
+                    return;
+                }
+                self.history.pending_jump = None;
+                let Some(idx) = day.encounters.iter().position(|enc| enc.key == jump.key) else {
+                    return;
+                };
+                if !self.history.encounter_matches(&day.encounters[idx]) {
+                    self.history.filter.clear();
+                    self.history.filter_restored = false;
+                    self.history.job_filter = None;
+                }
+                self.history.selected_encounter = idx;
+                self.history.level = HistoryPanelLevel::EncounterDetail;
+                self.history
+                    .remember_recent(RecentKind::Encounter, &jump.key);
+            }
+            RecentKind::Dungeon => {
+                self.history.view = HistoryView::Dungeons;
+                if self.history.dungeon_days.is_empty() {
+                    // The dungeon dates load lazily when the view opens at the Dates level.
+                    self.history.dungeon_level = DungeonPanelLevel::Dates;
+                    return;
+                }
+                let Some(day_idx) = self
+                    .history
+                    .dungeon_days
+                    .iter()
+                    .position(|day| day.iso_date == jump.date_id)
+                else {
+                    self.history.pending_jump = None;
+                    return;
+                };
+                self.history.dungeon_selected_day = day_idx;
+                self.history.dungeon_level = DungeonPanelLevel::Runs;
+                let day = &self.history.dungeon_days[day_idx];
+                if !day.runs_loaded && !day.run_ids.is_empty() {
+                    return;
+                }
+                self.history.pending_jump = None;
+                let Some(idx) = day.runs.iter().position(|run| run.key == jump.key) else {
+                    return;
+                };
+                self.history.dungeon_selected_run = idx;
+                self.history.dungeon_selected_child = 0;
+                self.history.dungeon_level = DungeonPanelLevel::RunDetail;
+                self.history.remember_recent(RecentKind::Dungeon, &jump.key);
+            }
+        }
+    }
+
+    /// Steps the history panel up one level. Returns `false` when already at the top level.
+    pub fn history_back(&mut self) -> bool {
+        if !self.history.visible {
+            return false;
+        }
+        self.history.detail_scroll = 0;
+        match self.history.view {
+            HistoryView::Encounters => match self.history.level {
+                HistoryPanelLevel::Abilities => {
+                    self.history.level = HistoryPanelLevel::EncounterDetail;
+                }
+                HistoryPanelLevel::EncounterDetail => {
+                    self.history.level = if self.history.from_search {
+                        HistoryPanelLevel::SearchResults
+                    } else {
+                        HistoryPanelLevel::Encounters
+                    };
+                    self.history.from_search = false;
+                    self.history.detail_row = 0;
+                }
+                HistoryPanelLevel::Encounters => {
+                    self.history.level = HistoryPanelLevel::Dates;
+                    self.history.selected_encounter = 0;
+                }
+                HistoryPanelLevel::SearchResults => {
+                    self.history.level = HistoryPanelLevel::Dates;
+                }
+                HistoryPanelLevel::Dates => return false,
+            },
+            HistoryView::Dungeons => match self.history.dungeon_level {
+                DungeonPanelLevel::EncounterDetail => {
+                    self.history.dungeon_level = DungeonPanelLevel::RunDetail;
                 }
                 DungeonPanelLevel::RunDetail => {
                     self.history.dungeon_level = DungeonPanelLevel::Runs;
@@ -682,8 +1654,1235 @@ impl AppState {
                     self.history.dungeon_level = DungeonPanelLevel::Dates;
                     self.history.dungeon_selected_run = 0;
                 }
-                DungeonPanelLevel::Dates => {}
+                DungeonPanelLevel::Dates => return false,
             },
         }
+        true
+    }
+
+    fn log_error(&mut self, error: AppError, at_ms: u64) {
+        if self.error_log.len() >= ERROR_LOG_CAPACITY {
+            self.error_log.pop_front();
+        }
+        self.error_log.push_back(ErrorLogEntry { at_ms, error });
+    }
+
+    /// Empties the error log and dismisses the footer error.
+    pub fn clear_errors(&mut self) {
+        self.error_log.clear();
+        self.error = None;
+    }
+
+    /// The day under the cursor at the encounter Dates level, as `(date_id, encounter keys)`.
+    pub fn history_day_export_target(&self) -> Option<(String, Vec<Vec<u8>>)> {
+        if !self.history.visible
+            || self.history.view != HistoryView::Encounters
+            || self.history.level != HistoryPanelLevel::Dates
+        {
+            return None;
+        }
+        let day = self.history.current_day()?;
+        if day.encounter_ids.is_empty() {
+            return None;
+        }
+        Some((day.iso_date.clone(), day.encounter_ids.clone()))
+    }
+
+    /// Key of the encounter open in the detail view, for a single-encounter export.
+    pub fn history_encounter_export_target(&self) -> Option<Vec<u8>> {
+        if !self.history.visible
+            || self.history.view != HistoryView::Encounters
+            || self.history.level != HistoryPanelLevel::EncounterDetail
+        {
+            return None;
+        }
+        self.history.current_encounter().map(|enc| enc.key.clone())
+    }
+
+    /// The encounter open in the detail view as a chat one-liner, in the detail table's
+    /// order and mode, with the number of rows it lists.
+    pub fn history_copy_text(&self) -> Option<(String, usize)> {
+        if !self.history.visible
+            || self.history.view != HistoryView::Encounters
+            || self.history.level != HistoryPanelLevel::EncounterDetail
+        {
+            return None;
+        }
+        let record = self.history.current_encounter()?.record.as_ref()?;
+        let rows = self.history.detail_rows(
+            self.role_filter,
+            self.sort_key,
+            self.sort_ascending,
+            self.settings.hide_zero_rows,
+        );
+        let text =
+            clipboard::summary_text(Some(&record.encounter), &rows, self.history.detail_mode);
+        Some((text, rows.len()))
+    }
+
+    /// Asks to delete the encounter or dungeon run under the cursor in a history list;
+    /// returns false when no list entry is selected.
+    pub fn request_history_delete(&mut self) -> bool {
+        if !self.history.visible || self.history.loading {
+            return false;
+        }
+        let target = match self.history.view {
+            HistoryView::Encounters if self.history.level == HistoryPanelLevel::Encounters => {
+                self.history.current_encounter().map(|enc| {
+                    (
+                        RecentKind::Encounter,
+                        enc.key.clone(),
+                        enc.display_title.clone(),
+                    )
+                })
+            }
+            HistoryView::Dungeons if self.history.dungeon_level == DungeonPanelLevel::Runs => {
+                self.history.current_dungeon_run().map(|run| {
+                    (
+                        RecentKind::Dungeon,
+                        run.key.clone(),
+                        format!(
+                            "{} · {}",
+                            run.zone,
+                            self.settings
+                                .clock_format
+                                .format_local(run.started_ms, false, false)
+                                .unwrap_or_else(|| run.started_label.clone())
+                        ),
+                    )
+                })
+            }
+            _ => None,
+        };
+        let Some((kind, key, title)) = target else {
+            return false;
+        };
+        self.pending_confirm = Some(ConfirmAction::DeleteHistoryEntry { kind, key, title });
+        true
+    }
+
+    /// Handles `r`: returns whether the client should reconnect now. Refused only while a
+    /// connect attempt is running; during the backoff wait it cuts the wait short.
+    pub fn request_reconnect(&mut self) -> bool {
+        let accepted = !self.connect_in_flight;
+        let message = if accepted {
+            "Reconnecting…"
+        } else {
+            "Already connecting…"
+        };
+        self.notice = Some((message.into(), Instant::now()));
+        accepted
+    }
+
+    /// Asks to discard the in-progress encounter; returns false when nothing is being recorded.
+    pub fn request_discard_encounter(&mut self) -> bool {
+        let active = self.encounter.as_ref().is_some_and(|enc| enc.is_active);
+        if active {
+            self.pending_confirm = Some(ConfirmAction::DiscardEncounter);
+        }
+        active
+    }
+
+    /// Performs the least destructive "back" action for `q`/Esc, innermost layer first.
+    ///
+    /// Returns `false` when nothing is left to close, which the caller treats as a quit.
+    pub fn navigate_back(&mut self) -> bool {
+        if self.pending_confirm.take().is_some() {
+            return true;
+        }
+        if self.show_legend {
+            self.show_legend = false;
+            return true;
+        }
+        if self.show_error_log {
+            self.show_error_log = false;
+            return true;
+        }
+        if self.show_settings {
+            self.show_settings = false;
+            return true;
+        }
+        if !self.history.visible && !self.live_filter.is_empty() {
+            self.live_finish_filter(false);
+            return true;
+        }
+        if self.history.visible {
+            if self.settings.escape_behavior == EscapeBehavior::Back && self.history_back() {
+                return true;
+            }
+            self.close_history();
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::HistoryEncounterItem;
+    use crate::model::settings::IDLE_SECONDS_MAX;
+
+    /// A history list entry keyed by `key` and titled `title`, with no record loaded.
+    fn history_item(key: &[u8], title: &str) -> HistoryEncounterItem {
+        HistoryEncounterItem {
+            key: key.to_vec(),
+            display_title: title.into(),
+            base_title: title.into(),
+            occurrence: 1,
+            time_label: String::new(),
+            last_seen_ms: 0,
+            timestamp_label: String::new(),
+            duration: String::new(),
+            damage: String::new(),
+            never_active: false,
+            scale: None,
+            jobs: Vec::new(),
+            record: None,
+            buff_uptime: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn live_filter_is_typed_kept_and_cleared_by_back() {
+        let mut state = AppState::default();
+        state.live_start_filter();
+        assert!(state.live_filter_editing);
+        for ch in "sge".chars() {
+            state.live_filter_push(ch);
+        }
+        state.live_filter_pop();
+        state.live_finish_filter(true);
+        assert!(!state.live_filter_editing);
+        assert_eq!(state.live_filter, "sg");
+
+        let row = CombatantRow {
+            name: "Alice".into(),
+            job: "SGE".into(),
+            ..Default::default()
+        };
+        assert!(row.matches_query(&state.live_filter));
+        assert!(row.matches_query(" ALI "));
+        assert!(!row.matches_query("whm"));
+
+        assert!(state.navigate_back());
+        assert!(state.live_filter.is_empty());
+        assert!(!state.navigate_back());
+    }
+
+    #[test]
+    fn navigate_back_pops_one_level_at_a_time() {
+        let mut state = AppState::default();
+        state.history.visible = true;
+        state.history.level = HistoryPanelLevel::EncounterDetail;
+
+        assert!(state.navigate_back());
+        assert_eq!(state.history.level, HistoryPanelLevel::Encounters);
+        assert!(state.navigate_back());
+        assert_eq!(state.history.level, HistoryPanelLevel::Dates);
+        assert!(state.navigate_back());
+        assert!(!state.history.visible);
+        assert!(!state.navigate_back());
+    }
+
+    #[test]
+    fn navigate_back_close_behavior_closes_history_at_once() {
+        let mut state = AppState::default();
+        state.settings.escape_behavior = EscapeBehavior::Close;
+        state.history.visible = true;
+        state.history.view = HistoryView::Dungeons;
+        state.history.dungeon_level = DungeonPanelLevel::EncounterDetail;
+
+        assert!(state.navigate_back());
+        assert!(!state.history.visible);
+        assert!(!state.navigate_back());
+    }
+
+    #[test]
+    fn waiting_for_server_clears_after_first_connection() {
+        let mut state = AppState::default();
+        assert!(state.clone_snapshot().waiting_for_server);
+
+        state.apply(AppEvent::Disconnected);
+        assert!(state.clone_snapshot().waiting_for_server);
+
+        state.apply(AppEvent::Connected);
+        state.apply(AppEvent::Disconnected);
+        let snapshot = state.clone_snapshot();
+        assert!(!snapshot.connection.is_connected());
+        assert!(!snapshot.waiting_for_server);
+    }
+
+    #[test]
+    fn connection_state_follows_client_events() {
+        let mut state = AppState::default();
+        assert_eq!(
+            state.clone_snapshot().connection,
+            ConnectionState::Connecting
+        );
+        state.apply(AppEvent::Connected);
+        assert_eq!(
+            state.clone_snapshot().connection,
+            ConnectionState::Connected
+        );
+        state.apply(AppEvent::Disconnected);
+        assert_eq!(
+            state.clone_snapshot().connection,
+            ConnectionState::Disconnected
+        );
+        state.apply(AppEvent::Reconnecting { attempt: 1 });
+        state.apply(AppEvent::Reconnecting { attempt: 2 });
+        state.apply(AppEvent::Disconnected);
+        assert_eq!(
+            state.clone_snapshot().connection,
+            ConnectionState::Reconnecting { attempt: 2 }
+        );
+
+        state.apply(AppEvent::Connected);
+        assert_eq!(
+            state.clone_snapshot().connection,
+            ConnectionState::Connected
+        );
+    }
+
+    #[test]
+    fn reconnect_is_refused_only_while_an_attempt_is_running() {
+        let mut state = AppState::default();
+        state.apply(AppEvent::Connecting);
+        assert!(!state.request_reconnect());
+        state.apply(AppEvent::Disconnected);
+        assert!(state.request_reconnect());
+
+        // Later failures keep "Reconnecting" on screen through the wait; `r` still skips it.
+        state.apply(AppEvent::Reconnecting { attempt: 2 });
+        assert!(!state.request_reconnect());
+        assert_eq!(state.notice.as_ref().unwrap().0, "Already connecting…");
+        state.apply(AppEvent::Disconnected);
+        assert_eq!(
+            state.connection,
+            ConnectionState::Reconnecting { attempt: 2 }
+        );
+        assert!(state.request_reconnect());
+        assert_eq!(state.notice.as_ref().unwrap().0, "Reconnecting…");
+
+        state.apply(AppEvent::Connected);
+        assert!(state.request_reconnect());
+    }
+
+    #[test]
+    fn discard_confirm_requires_active_encounter_and_cancels_on_back() {
+        let mut state = AppState::default();
+        assert!(!state.request_discard_encounter());
+        assert!(state.pending_confirm.is_none());
+
+        state.encounter = Some(EncounterSummary {
+            is_active: true,
+            ..Default::default()
+        });
+        assert!(state.request_discard_encounter());
+        assert_eq!(state.pending_confirm, Some(ConfirmAction::DiscardEncounter));
+
+        assert!(state.navigate_back());
+        assert!(state.pending_confirm.is_none());
+        assert!(!state.navigate_back());
+    }
+
+    #[test]
+    fn navigate_back_closes_legend_before_settings() {
+        let mut state = AppState {
+            show_settings: true,
+            show_legend: true,
+            ..Default::default()
+        };
+        assert!(state.navigate_back());
+        assert!(!state.show_legend);
+        assert!(state.show_settings);
+        assert!(state.navigate_back());
+        assert!(!state.show_settings);
+    }
+
+    #[test]
+    fn combat_start_flash_fires_on_inactive_to_active_transition() {
+        let active = |is_active| AppEvent::CombatData {
+            encounter: EncounterSummary {
+                is_active,
+                ..Default::default()
+            },
+            rows: Vec::new(),
+        };
+        let mut state = AppState::default();
+        state.apply(active(false));
+        assert!(!state.clone_snapshot().combat_start_flash);
+
+        state.apply(active(true));
+        assert!(state.clone_snapshot().combat_start_flash);
+
+        state.history.visible = true;
+        assert!(!state.clone_snapshot().combat_start_flash);
+        state.history.visible = false;
+
+        let started = state.combat_started_at;
+        state.apply(active(true));
+        assert_eq!(state.combat_started_at, started);
+
+        state.combat_started_at = Some(Instant::now() - COMBAT_START_FLASH);
+        assert!(!state.clone_snapshot().combat_start_flash);
+    }
+
+    #[test]
+    fn history_filter_narrows_selection_and_clears_on_escape() {
+        use crate::history::HistoryDay;
+
+        let item = |title: &str| history_item(title.as_bytes(), title);
+        let mut state = AppState::default();
+        state.history.visible = true;
+        state.history.level = HistoryPanelLevel::Encounters;
+        state.history.days = vec![HistoryDay {
+            iso_date: "2025-01-01".into(),
+            label: "2025-01-01".into(),
+            encounter_count: 3,
+            encounters: vec![item("Titan"), item("Ifrit"), item("Titan (2)")],
+            encounter_ids: Vec::new(),
+            encounters_loaded: true,
+        }];
+
+        state.history_start_filter();
+        assert!(state.history.filter_editing);
+        for ch in "tit".chars() {
+            state.history_filter_push(ch);
+        }
+        assert_eq!(state.history.visible_encounter_indices(), vec![0, 2]);
+        state.history_move_selection(1);
+        assert_eq!(state.history.selected_encounter, 2);
+
+        state.history_filter_push('x');
+        assert!(state.history.visible_encounter_indices().is_empty());
+        state.history_filter_pop();
+        state.history_finish_filter(true);
+        assert_eq!(state.history.filter, "tit");
+        assert_eq!(state.history.selected_encounter, 2);
+
+        state.history_start_filter();
+        state.history_finish_filter(false);
+        assert!(state.history.filter.is_empty());
+        assert_eq!(state.history.visible_encounter_indices().len(), 3);
+    }
+
+    #[test]
+    fn job_filter_cycles_through_the_days_jobs() {
+        use crate::history::HistoryDay;
+
+        let item = |title: &str, jobs: &[&str]| HistoryEncounterItem {
+            jobs: jobs.iter().map(|job| job.to_string()).collect(),
+            ..history_item(title.as_bytes(), title)
+        };
+        let mut state = AppState::default();
+        state.history.visible = true;
+        state.history.level = HistoryPanelLevel::Encounters;
+        state.history.days = vec![HistoryDay {
+            iso_date: "2025-01-01".into(),
+            label: "2025-01-01".into(),
+            encounter_count: 3,
+            encounters: vec![
+                item("Titan", &["SGE", "WAR"]),
+                item("Ifrit", &["WHM"]),
+                item("Garuda", &["SGE"]),
+            ],
+            encounter_ids: Vec::new(),
+            encounters_loaded: true,
+        }];
+
+        state.history_cycle_job_filter();
+        assert_eq!(state.history.job_filter.as_deref(), Some("SGE"));
+        assert_eq!(state.history.visible_encounter_indices(), vec![0, 2]);
+
+        state.history.filter = "gar".into();
+        assert_eq!(state.history.visible_encounter_indices(), vec![2]);
+        state.history.filter.clear();
+
+        state.history_cycle_job_filter();
+        assert_eq!(state.history.job_filter.as_deref(), Some("WAR"));
+        state.history_cycle_job_filter();
+        assert_eq!(state.history.visible_encounter_indices(), vec![1]);
+        state.history_cycle_job_filter();
+        assert!(state.history.job_filter.is_none());
+        assert_eq!(state.history.visible_encounter_indices().len(), 3);
+    }
+
+    #[test]
+    fn detail_scroll_is_clamped_and_reset_when_switching_encounters() {
+        use crate::history::{EncounterRecord, HistoryDay};
+
+        let record = EncounterRecord {
+            rows: vec![CombatantRow::default(); 12],
+            ..EncounterRecord::fixture(0, EncounterSummary::default())
+        };
+        let item = |title: &str| HistoryEncounterItem {
+            record: Some(record.clone()),
+            ..history_item(title.as_bytes(), title)
+        };
+        let mut state = AppState::default();
+        state.history.visible = true;
+        state.history.level = HistoryPanelLevel::Encounters;
+        state.history.days = vec![HistoryDay {
+            iso_date: "2025-01-01".into(),
+            label: "2025-01-01".into(),
+            encounter_count: 2,
+            encounters: vec![item("Titan"), item("Ifrit")],
+            encounter_ids: Vec::new(),
+            encounters_loaded: true,
+        }];
+
+        // Outside the detail view paging still moves the list selection.
+        state.history_scroll(5);
+        assert_eq!(state.history.selected_encounter, 1);
+        assert_eq!(state.history.detail_scroll, 0);
+
+        state.history_enter();
+        state.history_scroll(5);
+        assert_eq!(state.history.detail_scroll, 5);
+        state.history_scroll(50);
+        assert_eq!(state.history.detail_scroll, 11);
+        state.history_scroll(-3);
+        assert_eq!(state.history.detail_scroll, 8);
+
+        // Once drawn, the last full page is the limit, like the renderer's clamp.
+        state.history.detail_capacity = 5;
+        state.history_scroll(50);
+        assert_eq!(state.history.detail_scroll, 7);
+        state.history_scroll(-1);
+        assert_eq!(state.history.detail_scroll, 6);
+
+        state.history_move_selection(-1);
+        assert_eq!(state.history.selected_encounter, 0);
+        assert_eq!(state.history.detail_scroll, 0);
+    }
+
+    #[test]
+    fn detail_cursor_opens_the_ability_breakdown() {
+        use crate::history::{EncounterRecord, HistoryDay};
+
+        let row = |name: &str, encdps: f64| CombatantRow {
+            name: name.into(),
+            encdps,
+            ..CombatantRow::default()
+        };
+        let record = EncounterRecord {
+            rows: vec![row("Alpha", 100.0), row("Beta", 300.0)],
+            raw_last: Some(serde_json::json!({
+                "type": "CombatData",
+                "Combatant": {
+                    "Alpha": {"abilities": {"Fire": {"damage": 10}}},
+                    "Beta": {"abilities": {"Stone": {"damage": 30}, "Aero": {"damage": 5}}}
+                }
+            })),
+            ..EncounterRecord::fixture(0, EncounterSummary::default())
+        };
+        let mut state = AppState::default();
+        state.history.visible = true;
+        state.history.level = HistoryPanelLevel::EncounterDetail;
+        state.history.days = vec![HistoryDay {
+            iso_date: "2025-01-01".into(),
+            label: "2025-01-01".into(),
+            encounter_count: 1,
+            encounters: vec![HistoryEncounterItem {
+                record: Some(record),
+                ..history_item(b"Titan", "Titan")
+            }],
+            encounter_ids: Vec::new(),
+            encounters_loaded: true,
+        }];
+
+        // Rows follow the table order, so the top DPS comes first.
+        let selected = state.history.detail_combatant(
+            state.role_filter,
+            state.sort_key,
+            state.sort_ascending,
+            state.settings.hide_zero_rows,
+        );
+        assert_eq!(selected.map(|r| r.name).as_deref(), Some("Beta"));
+        let (text, count) = state.history_copy_text().expect("copy text");
+        assert_eq!(count, 2);
+        assert_eq!(text, "Beta 300 dps — Alpha 100 dps");
+        state.history_move_detail_row(5);
+        assert_eq!(state.history.detail_row, 1);
+
+        state.history_move_detail_row(-1);
+        state.history_enter();
+        assert_eq!(state.history.level, HistoryPanelLevel::Abilities);
+        state.history_move_selection(5);
+        assert_eq!(state.history.ability_selected, 1);
+
+        state.history_back();
+        assert_eq!(state.history.level, HistoryPanelLevel::EncounterDetail);
+        assert_eq!(state.history.detail_row, 0);
+    }
+
+    #[test]
+    fn recent_list_dedupes_caps_and_jumps_back() {
+        use crate::history::HistoryDay;
+
+        let item = |key: u8| history_item(&[key], &format!("Pull {key}"));
+        let mut state = AppState::default();
+        state.history.visible = true;
+        state.history.days = vec![HistoryDay {
+            iso_date: "2025-01-01".into(),
+            label: "2025-01-01".into(),
+            encounter_count: 3,
+            encounters: Vec::new(),
+            encounter_ids: vec![vec![1], vec![2], vec![3]],
+            encounters_loaded: false,
+        }];
+
+        for key in 0..RECENT_CAPACITY as u8 + 2 {
+            state.history.remember_recent(RecentKind::Encounter, &[key]);
+        }
+        state.history.remember_recent(RecentKind::Encounter, &[3]);
+        assert_eq!(state.history.recent.len(), RECENT_CAPACITY);
+        assert_eq!(state.history.recent[0].key, "03");
+        assert_eq!(state.history.recent[1].key, "09");
+        assert!(state.history.recent_dirty);
+
+        assert!(state.history_toggle_recent());
+        assert_eq!(state.history_recent_unresolved().len(), RECENT_CAPACITY);
+        state.apply(AppEvent::HistoryRecentResolved {
+            kind: RecentKind::Encounter,
+            key: vec![3],
+            found: Some(("2025-01-01".into(), "Pull 3 · 12:00".into())),
+        });
+
+        // The day is not loaded yet, so the jump waits on the encounter list.
+        state.history_jump_recent();
+        assert!(!state.history.show_recent);
+        assert_eq!(state.history.level, HistoryPanelLevel::Encounters);
+        assert!(state.history.pending_jump.is_some());
+
+        state.apply(AppEvent::HistoryEncountersLoaded {
+            epoch: state.history_epoch,
+            date_id: "2025-01-01".into(),
+            encounters: vec![item(1), item(2), item(3)],
+        });
+        assert!(state.history.pending_jump.is_none());
+        assert_eq!(state.history.level, HistoryPanelLevel::EncounterDetail);
+        assert_eq!(state.history.selected_encounter, 2);
+    }
+
+    #[test]
+    fn numeric_settings_accept_typed_values() {
+        let mut state = AppState {
+            show_settings: true,
+            ..Default::default()
+        };
+        state.settings_cursor = SettingsField::DefaultMode;
+        assert!(!state.settings_begin_entry());
+
+        state.settings_cursor = SettingsField::IdleTimeout;
+        assert!(state.settings_begin_entry());
+        for ch in "4x2".chars() {
+            state.settings_entry_push(ch);
+        }
+        assert_eq!(state.settings_entry.as_deref(), Some("42"));
+        assert!(state.settings_finish_entry(true));
+        assert_eq!(state.settings.idle_seconds, 42);
+        assert!(state.settings_entry.is_none());
+
+        // Out-of-range values clamp; cancelling or committing nothing leaves the value alone.
+        state.settings_begin_entry();
+        "999999"
+            .chars()
+            .for_each(|ch| state.settings_entry_push(ch));
+        assert!(state.settings_finish_entry(true));
+        assert_eq!(state.settings.idle_seconds, IDLE_SECONDS_MAX);
+        state.settings_begin_entry();
+        state.settings_entry_push('7');
+        assert!(!state.settings_finish_entry(false));
+        state.settings_begin_entry();
+        assert!(!state.settings_finish_entry(true));
+        assert_eq!(state.settings.idle_seconds, IDLE_SECONDS_MAX);
+    }
+
+    #[test]
+    fn redraw_rate_steps_and_clamps() {
+        let mut state = AppState {
+            show_settings: true,
+            settings_cursor: SettingsField::RedrawRate,
+            ..Default::default()
+        };
+        assert!(state.adjust_selected_setting(true));
+        assert_eq!(state.settings.redraw_ms, 110);
+
+        state.settings.redraw_ms = 20;
+        assert!(state.adjust_selected_setting(false));
+        assert_eq!(state.settings.redraw_ms, REDRAW_MS_MIN);
+        assert!(!state.adjust_selected_setting(false));
+
+        assert!(state.settings_begin_entry());
+        "5000".chars().for_each(|ch| state.settings_entry_push(ch));
+        assert!(state.settings_finish_entry(true));
+        assert_eq!(state.settings.redraw_ms, REDRAW_MS_MAX);
+        assert_eq!(
+            state.settings.redraw_interval(),
+            Duration::from_millis(REDRAW_MS_MAX)
+        );
+    }
+
+    #[test]
+    fn stale_history_results_are_ignored() {
+        use crate::history::HistoryDay;
+
+        let day = |iso: &str| HistoryDay {
+            iso_date: iso.into(),
+            label: iso.into(),
+            encounter_count: 0,
+            encounters: Vec::new(),
+            encounter_ids: Vec::new(),
+            encounters_loaded: false,
+        };
+        let mut state = AppState::default();
+        assert!(state.toggle_history());
+        let first = state.history_begin_load();
+        let second = state.history_begin_load();
+        assert!(second > first);
+
+        state.apply(AppEvent::HistoryDatesLoaded {
+            epoch: first,
+            days: vec![day("2025-01-01")],
+        });
+        assert!(state.history.loading);
+        assert!(state.history.days.is_empty());
+
+        state.apply(AppEvent::HistoryDatesLoaded {
+            epoch: second,
+            days: vec![day("2025-01-02")],
+        });
+        assert!(!state.history.loading);
+        assert_eq!(state.history.days[0].iso_date, "2025-01-02");
+
+        // Results still in flight when the panel closes are dropped as well.
+        let pending = state.history_begin_load();
+        assert!(!state.toggle_history());
+        state.apply(AppEvent::HistoryError {
+            epoch: pending,
+            message: "late failure".into(),
+        });
+        assert!(state.error_log.is_empty());
+    }
+
+    #[test]
+    fn error_log_is_capped_and_clearable() {
+        let mut state = AppState::default();
+        for idx in 0..ERROR_LOG_CAPACITY + 5 {
+            state.apply(AppEvent::SystemError {
+                error: AppError::new(AppErrorKind::Storage, format!("failure {idx}")),
+            });
+        }
+        state.apply(AppEvent::HistoryError {
+            epoch: state.history_epoch,
+            message: "load failed".into(),
+        });
+
+        let snapshot = state.clone_snapshot();
+        assert_eq!(snapshot.error_log.len(), ERROR_LOG_CAPACITY);
+        assert_eq!(snapshot.error_log[0].error.kind(), AppErrorKind::History);
+        assert_eq!(snapshot.error_log[1].error.summary_line(), "failure 54");
+
+        state.clear_errors();
+        assert!(state.error_log.is_empty());
+        assert!(state.error.is_none());
+    }
+
+    #[test]
+    fn boss_grouping_only_toggles_in_run_detail() {
+        let mut state = AppState::default();
+        state.history.visible = true;
+        state.history.view = HistoryView::Dungeons;
+        state.history_toggle_boss_groups();
+        assert!(!state.history.dungeon_group_bosses);
+
+        state.history.dungeon_level = DungeonPanelLevel::RunDetail;
+        state.history_toggle_boss_groups();
+        assert!(state.history.dungeon_group_bosses);
+        state.history_toggle_boss_groups();
+        assert!(!state.history.dungeon_group_bosses);
+    }
+
+    #[test]
+    fn history_reopens_on_the_last_view_of_the_session() {
+        let mut state = AppState::default();
+        state.history.view = HistoryView::from_config_key("Dungeons");
+
+        assert!(state.toggle_history());
+        assert_eq!(state.history.view, HistoryView::Dungeons);
+        state.history_toggle_view();
+        assert!(!state.toggle_history());
+
+        assert!(state.toggle_history());
+        assert_eq!(state.history.view, HistoryView::Encounters);
+        assert_eq!(
+            HistoryView::from_config_key("bogus"),
+            HistoryView::Encounters
+        );
+    }
+
+    #[test]
+    fn date_sort_toggle_keeps_the_selected_day() {
+        let mut state = AppState::default();
+        state.history.date_sort = crate::model::DateSort::OldestFirst;
+        state.toggle_history();
+        let epoch = state.history_begin_load();
+        let day = |iso: &str| crate::history::HistoryDay {
+            iso_date: iso.into(),
+            label: iso.into(),
+            encounter_count: 0,
+            encounters: Vec::new(),
+            encounter_ids: Vec::new(),
+            encounters_loaded: false,
+        };
+        state.apply(AppEvent::HistoryDatesLoaded {
+            epoch,
+            days: vec![day("2025-01-03"), day("2025-01-02"), day("2025-01-01")],
+        });
+        assert_eq!(state.history.days[0].iso_date, "2025-01-01");
+
+        state.history.selected_day = 1;
+        state.history_toggle_date_sort();
+        assert_eq!(state.history.date_sort, crate::model::DateSort::NewestFirst);
+        assert_eq!(state.history.days[0].iso_date, "2025-01-03");
+        assert_eq!(state.history.current_day().unwrap().iso_date, "2025-01-02");
+
+        state.history.selected_day = 0;
+        state.history_toggle_date_sort();
+        assert_eq!(state.history.current_day().unwrap().iso_date, "2025-01-03");
+    }
+
+    #[test]
+    fn refresh_reloads_dates_and_keeps_the_selected_day() {
+        let day = |iso: &str| crate::history::HistoryDay {
+            iso_date: iso.into(),
+            label: iso.into(),
+            encounter_count: 0,
+            encounters: Vec::new(),
+            encounter_ids: Vec::new(),
+            encounters_loaded: false,
+        };
+        let mut state = AppState::default();
+        state.toggle_history();
+        let epoch = state.history_begin_load();
+        state.apply(AppEvent::HistoryDatesLoaded {
+            epoch,
+            days: vec![day("2025-01-02"), day("2025-01-01")],
+        });
+        state.history.selected_day = 1;
+        state.history.level = HistoryPanelLevel::Encounters;
+
+        let refresh = state.history_refresh().expect("refresh starts");
+        assert!(state.history.loading);
+        assert_eq!(state.history.level, HistoryPanelLevel::Dates);
+        assert!(state.history_refresh().is_none());
+
+        state.apply(AppEvent::HistoryDatesLoaded {
+            epoch: refresh,
+            days: vec![day("2025-01-03"), day("2025-01-02"), day("2025-01-01")],
+        });
+        assert_eq!(state.history.current_day().unwrap().iso_date, "2025-01-01");
+    }
+
+    #[test]
+    fn deleting_an_encounter_updates_the_day_and_drops_it_when_empty() {
+        use crate::history::HistoryDay;
+
+        let item = |key: u8| HistoryEncounterItem {
+            base_title: "Pull".into(),
+            ..history_item(&[key], &format!("Pull {key}"))
+        };
+        let day = |iso: &str, keys: &[u8]| HistoryDay {
+            iso_date: iso.into(),
+            label: iso.into(),
+            encounter_count: keys.len(),
+            encounters: keys.iter().map(|key| item(*key)).collect(),
+            encounter_ids: keys.iter().map(|key| vec![*key]).collect(),
+            encounters_loaded: true,
+        };
+        let mut state = AppState::default();
+        state.toggle_history();
+        let epoch = state.history_begin_load();
+        state.apply(AppEvent::HistoryDatesLoaded {
+            epoch,
+            days: vec![day("2025-01-02", &[1, 2]), day("2025-01-01", &[3])],
+        });
+        assert!(
+            !state.request_history_delete(),
+            "nothing to delete on Dates"
+        );
+
+        state.history.level = HistoryPanelLevel::Encounters;
+        state.history.selected_encounter = 1;
+        state.history.remember_recent(RecentKind::Encounter, &[2]);
+        assert!(state.request_history_delete());
+        let Some(ConfirmAction::DeleteHistoryEntry { kind, key, title }) =
+            state.pending_confirm.take()
+        else {
+            panic!("expected a delete confirmation");
+        };
+        assert_eq!(
+            (kind, key.as_slice(), title.as_str()),
+            (RecentKind::Encounter, &[2u8][..], "Pull 2")
+        );
+
+        let epoch = state.history_begin_load();
+        state.apply(AppEvent::HistoryEntryDeleted { epoch, kind, key });
+        let current = state.history.current_day().expect("day kept");
+        assert_eq!(current.encounter_ids, vec![vec![1]]);
+        assert!(current.label.ends_with("1 encounters"));
+        assert_eq!(state.history.selected_encounter, 0);
+        assert!(state.history.recent.is_empty());
+        assert_eq!(state.history.level, HistoryPanelLevel::Encounters);
+
+        state.apply(AppEvent::HistoryEntryDeleted {
+            epoch,
+            kind: RecentKind::Encounter,
+            key: vec![1],
+        });
+        assert_eq!(state.history.level, HistoryPanelLevel::Dates);
+        assert_eq!(state.history.days.len(), 1);
+        assert_eq!(state.history.current_day().unwrap().iso_date, "2025-01-01");
+    }
+
+    #[test]
+    fn loading_a_record_works_out_its_buff_uptime_once() {
+        use crate::history::types::EncounterFrame;
+        use crate::history::{EncounterRecord, HistoryDay};
+
+        let frame = |buffs: serde_json::Value| EncounterFrame {
+            received_ms: 0,
+            encounter: EncounterSummary::default(),
+            rows: Vec::new(),
+            raw: serde_json::json!({ "Combatant": { "Tank": { "Buffs": buffs } } }),
+        };
+        let record = EncounterRecord {
+            frames: vec![
+                frame(serde_json::json!(["Iron Will"])),
+                frame(serde_json::json!([])),
+            ],
+            ..EncounterRecord::fixture(0, EncounterSummary::default())
+        };
+        let mut state = AppState::default();
+        state.settings.uptime_buffs = vec!["Iron Will".into()];
+        state.toggle_history();
+        let epoch = state.history_begin_load();
+        state.apply(AppEvent::HistoryDatesLoaded {
+            epoch,
+            days: vec![HistoryDay {
+                iso_date: "2025-01-01".into(),
+                label: "2025-01-01".into(),
+                encounter_count: 1,
+                encounters: vec![history_item(b"Titan", "Titan")],
+                encounter_ids: Vec::new(),
+                encounters_loaded: true,
+            }],
+        });
+        state.apply(AppEvent::HistoryEncounterLoaded {
+            epoch,
+            key: b"Titan".to_vec(),
+            record,
+        });
+
+        let item = &state.history.current_day().expect("day").encounters[0];
+        assert!(item.record.is_some());
+        assert_eq!(item.buff_uptime.len(), 1);
+        assert_eq!(item.buff_uptime[0].uptimes, vec![("Iron Will".into(), 0.5)]);
+    }
+
+    #[test]
+    fn search_results_open_the_encounter_and_back_returns_to_them() {
+        use crate::history::{local_date_id, HistoryDay};
+
+        let at = 1_735_732_800_000;
+        let date_id = local_date_id(at);
+        let item = |key: u8| HistoryEncounterItem {
+            last_seen_ms: at,
+            ..history_item(&[key], "Titan")
+        };
+        let mut state = AppState::default();
+        state.toggle_history();
+        let epoch = state.history_begin_load();
+        state.apply(AppEvent::HistoryDatesLoaded {
+            epoch,
+            days: vec![HistoryDay {
+                iso_date: date_id.clone(),
+                label: date_id.clone(),
+                encounter_count: 2,
+                encounters: vec![item(1), item(2)],
+                encounter_ids: vec![vec![1], vec![2]],
+                encounters_loaded: true,
+            }],
+        });
+
+        state.history_start_search();
+        assert!(state.history.search_editing);
+        assert_eq!(state.history_finish_search(true), None, "blank query");
+        assert_eq!(state.history.level, HistoryPanelLevel::Dates);
+
+        state.history_start_search();
+        for ch in "titan".chars() {
+            state.history_search_push(ch);
+        }
+        let epoch = state.history_finish_search(true).expect("search runs");
+        assert_eq!(state.history.level, HistoryPanelLevel::SearchResults);
+        assert!(state.history.loading);
+        state.apply(AppEvent::HistorySearchLoaded {
+            epoch,
+            results: vec![item(2), item(1)],
+        });
+        assert!(!state.history.loading);
+
+        state.history_move_selection(1);
+        state.history_enter();
+        assert_eq!(state.history.level, HistoryPanelLevel::EncounterDetail);
+        assert_eq!(state.history.current_encounter().unwrap().key, vec![1]);
+
+        assert!(state.history_back());
+        assert_eq!(state.history.level, HistoryPanelLevel::SearchResults);
+        assert_eq!(state.history.search_selected, 1);
+        assert!(state.history_back());
+        assert_eq!(state.history.level, HistoryPanelLevel::Dates);
+    }
+
+    #[test]
+    fn dungeon_progress_follows_session_updates() {
+        let mut state = AppState::default();
+        let progress = DungeonProgress {
+            zone: "Sastasha".into(),
+            pulls: 3,
+            total_damage: 90_000.0,
+            total_duration_secs: 90.0,
+        };
+        state.apply(AppEvent::DungeonSessionUpdate {
+            active_zone: Some("Sastasha".into()),
+            progress: Some(progress.clone()),
+        });
+        assert_eq!(state.clone_snapshot().dungeon_progress, Some(progress));
+
+        state.apply(AppEvent::DungeonSessionUpdate {
+            active_zone: None,
+            progress: None,
+        });
+        assert!(state.clone_snapshot().dungeon_progress.is_none());
+        assert!(state.dungeon_active_zone.is_none());
+    }
+
+    #[test]
+    fn auto_clear_blanks_the_idle_table_until_the_next_pull() {
+        let row = CombatantRow {
+            name: "Tank".into(),
+            ..Default::default()
+        };
+        let mut state = AppState::default();
+        state.settings.idle_seconds = 1;
+        state.show_idle_overlay = false;
+        state.apply(AppEvent::Connected);
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary::default(),
+            rows: vec![row.clone()],
+        });
+        let long_ago = Instant::now()
+            .checked_sub(Duration::from_secs(10))
+            .expect("instant in the past");
+        state.connected_since = Some(long_ago);
+        state.last_active = Some(long_ago);
+
+        let snapshot = state.clone_snapshot();
+        assert!(snapshot.is_idle);
+        assert_eq!(snapshot.rows.len(), 1);
+        assert!(!snapshot.show_idle_overlay);
+
+        state.settings.auto_clear_idle = true;
+        let snapshot = state.clone_snapshot();
+        assert!(snapshot.rows.is_empty());
+        assert!(snapshot.show_idle_overlay);
+        assert_eq!(state.rows.len(), 1);
+
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary {
+                is_active: true,
+                ..Default::default()
+            },
+            rows: vec![row],
+        });
+        let snapshot = state.clone_snapshot();
+        assert!(!snapshot.is_idle);
+        assert_eq!(snapshot.rows.len(), 1);
+    }
+
+    fn at(base: Instant, secs: u64) -> EventTime {
+        EventTime {
+            instant: base + Duration::from_secs(secs),
+            wall_ms: 1_700_000_000_000 + secs * 1000,
+        }
+    }
+
+    fn combat(active: bool, rows: &[(&str, f64)]) -> AppEvent {
+        AppEvent::CombatData {
+            encounter: EncounterSummary {
+                is_active: active,
+                ..Default::default()
+            },
+            rows: rows
+                .iter()
+                .map(|(name, encdps)| CombatantRow {
+                    name: (*name).into(),
+                    encdps: *encdps,
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn apply_combat_data_replaces_and_sorts_rows() {
+        let base = Instant::now();
+        let mut state = AppState::default();
+        state.apply_at(AppEvent::Connected, at(base, 0));
+        state.apply_at(
+            combat(true, &[("Healer", 900.0), ("Tank", 1500.0)]),
+            at(base, 1),
+        );
+        let names: Vec<_> = state.rows.iter().map(|row| row.name.as_str()).collect();
+        assert_eq!(names, ["Tank", "Healer"]);
+        assert_eq!(state.last_update, Some(base + Duration::from_secs(1)));
+        assert_eq!(state.combat_started_at, Some(base + Duration::from_secs(1)));
+
+        state.apply_at(combat(true, &[("Bard", 2000.0)]), at(base, 2));
+        assert_eq!(state.rows.len(), 1);
+        assert_eq!(state.combat_started_at, Some(base + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn apply_encounter_end_freezes_the_timer_and_starts_idling() {
+        let base = Instant::now();
+        let mut state = AppState::default();
+        state.settings.idle_seconds = 5;
+        state.apply_at(AppEvent::Connected, at(base, 0));
+        state.apply_at(combat(true, &[("Tank", 1000.0)]), at(base, 10));
+        state.apply_at(combat(true, &[("Tank", 1000.0)]), at(base, 40));
+        state.apply_at(combat(false, &[("Tank", 1000.0)]), at(base, 42));
+
+        let later = base + Duration::from_secs(100);
+        assert!(!state.combat_timer.is_running());
+        assert_eq!(
+            state.combat_timer.elapsed(later),
+            Some(Duration::from_secs(32))
+        );
+        assert_eq!(state.last_active, Some(base + Duration::from_secs(40)));
+        assert!(!state.is_idle_at(base + Duration::from_secs(44)));
+        assert!(state.is_idle_at(base + Duration::from_secs(45)));
+    }
+
+    #[test]
+    fn apply_history_dates_populates_days_in_the_configured_order() {
+        let day = |iso: &str| crate::history::HistoryDay {
+            iso_date: iso.into(),
+            label: iso.into(),
+            encounter_count: 1,
+            encounters: Vec::new(),
+            encounter_ids: Vec::new(),
+            encounters_loaded: false,
+        };
+        let mut state = AppState::default();
+        state.toggle_history();
+        state.history.date_sort = crate::model::DateSort::OldestFirst;
+        let epoch = state.history_begin_load();
+        state.apply_at(
+            AppEvent::HistoryDatesLoaded {
+                epoch,
+                days: vec![day("2025-01-02"), day("2025-01-01")],
+            },
+            at(Instant::now(), 0),
+        );
+        assert!(!state.history.loading);
+        let dates: Vec<_> = state
+            .history
+            .days
+            .iter()
+            .map(|day| day.iso_date.as_str())
+            .collect();
+        assert_eq!(dates, ["2025-01-01", "2025-01-02"]);
+    }
+
+    #[test]
+    fn apply_error_events_fill_the_log_with_the_event_time() {
+        let base = Instant::now();
+        let mut state = AppState::default();
+        state.toggle_history();
+        let epoch = state.history_begin_load();
+        state.apply_at(
+            AppEvent::HistoryError {
+                epoch,
+                message: "store locked".into(),
+            },
+            at(base, 1),
+        );
+        state.apply_at(
+            AppEvent::SystemError {
+                error: AppError::new(AppErrorKind::Network, "refused"),
+            },
+            at(base, 2),
+        );
+
+        assert!(!state.history.loading);
+        assert_eq!(state.history.error.as_deref(), Some("store locked"));
+        assert_eq!(
+            state.error.as_ref().map(AppError::kind),
+            Some(AppErrorKind::Network)
+        );
+        let stamps: Vec<_> = state.error_log.iter().map(|entry| entry.at_ms).collect();
+        assert_eq!(stamps, [1_700_000_001_000, 1_700_000_002_000]);
+    }
+
+    #[test]
+    fn stable_sort_holds_the_order_within_the_window() {
+        let base = Instant::now();
+        let mut state = AppState::default();
+        state.settings.stable_sort_ms = 1500;
+        let names = |state: &AppState| -> Vec<String> {
+            state.rows.iter().map(|row| row.name.clone()).collect()
+        };
+        state.apply_at(AppEvent::Connected, at(base, 0));
+        state.apply_at(
+            combat(true, &[("Bard", 900.0), ("Tank", 1000.0)]),
+            at(base, 1),
+        );
+        state.apply_at(
+            combat(true, &[("Bard", 1200.0), ("Tank", 1000.0)]),
+            at(base, 1),
+        );
+        assert_eq!(names(&state), ["Tank", "Bard"]);
+
+        state.apply_at(
+            combat(
+                true,
+                &[("Bard", 1200.0), ("Tank", 1000.0), ("Ninja", 2000.0)],
+            ),
+            at(base, 2),
+        );
+        assert_eq!(names(&state), ["Tank", "Bard", "Ninja"]);
+
+        state.apply_at(
+            combat(
+                true,
+                &[("Bard", 1200.0), ("Tank", 1000.0), ("Ninja", 2000.0)],
+            ),
+            at(base, 3),
+        );
+        assert_eq!(names(&state), ["Ninja", "Bard", "Tank"]);
+
+        state.apply_at(
+            combat(
+                false,
+                &[("Bard", 1300.0), ("Tank", 1000.0), ("Ninja", 1250.0)],
+            ),
+            at(base, 3),
+        );
+        assert_eq!(names(&state), ["Bard", "Ninja", "Tank"]);
     }
 }
@@ -0,0 +1,68 @@
+use std::time::{Duration, Instant};
+
+/// Wall-clock combat timer that ticks between IINACT snapshots and freezes when combat ends.
+#[derive(Clone, Debug, Default)]
+pub struct CombatTimer {
+    started: Option<Instant>,
+    frozen: Option<Duration>,
+}
+
+impl CombatTimer {
+    /// Feeds the active flag of the latest snapshot; an inactive → active edge starts a new pull.
+    pub fn observe(&mut self, active: bool, now: Instant) {
+        match (active, self.started) {
+            (true, None) => {
+                self.started = Some(now);
+                self.frozen = None;
+            }
+            (false, Some(started)) => {
+                self.frozen = Some(now.saturating_duration_since(started));
+                self.started = None;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    pub fn elapsed(&self, now: Instant) -> Option<Duration> {
+        match self.started {
+            Some(started) => Some(now.saturating_duration_since(started)),
+            None => self.frozen,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timer_ticks_while_active_and_freezes_on_end() {
+        let start = Instant::now();
+        let mut timer = CombatTimer::default();
+        assert_eq!(timer.elapsed(start), None);
+
+        timer.observe(true, start);
+        timer.observe(true, start + Duration::from_secs(3));
+        assert_eq!(
+            timer.elapsed(start + Duration::from_secs(5)),
+            Some(Duration::from_secs(5))
+        );
+
+        timer.observe(false, start + Duration::from_secs(7));
+        assert_eq!(
+            timer.elapsed(start + Duration::from_secs(60)),
+            Some(Duration::from_secs(7))
+        );
+
+        let next = start + Duration::from_secs(90);
+        timer.observe(true, next);
+        assert_eq!(
+            timer.elapsed(next + Duration::from_secs(1)),
+            Some(Duration::from_secs(1))
+        );
+    }
+}
@@ -1,15 +1,19 @@
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
+use crate::dungeon::DungeonCatalog;
 use crate::errors::AppError;
 use crate::history::{
-    DungeonAggregateRecord, DungeonHistoryDay, DungeonHistoryItem, EncounterRecord, HistoryDay,
-    HistoryEncounterItem,
+    DungeonAggregateRecord, DungeonHistoryDay, DungeonHistoryItem, DungeonProgress, DungeonStreak,
+    EncounterRecord, HistoryDay, HistoryEncounterItem,
 };
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+use super::{RecentKind, RoleFilter, SortKey, ViewMode};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct EncounterSummary {
     pub title: String,
     pub zone: String,
@@ -21,7 +25,7 @@ pub struct EncounterSummary {
     pub is_active: bool,
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct CombatantRow {
     pub name: String,
     pub job: String,
@@ -43,65 +47,203 @@ pub struct CombatantRow {
     pub deaths: String,
 }
 
+impl CombatantRow {
+    /// True when `query` appears in the name or job, ignoring case; an empty query matches.
+    pub fn matches_query(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.name.to_lowercase().contains(&query)
+            || self.job.to_lowercase().contains(&query)
+    }
+
+    /// False for rows with nothing in the metric `mode` ranks by: limit breaks, idle pets and
+    /// spectators show 0 damage in DPS mode, and everyone but healers is usually 0 in heal mode.
+    pub fn contributed(&self, mode: ViewMode) -> bool {
+        match mode {
+            ViewMode::Dps => self.damage != 0.0,
+            ViewMode::Heal => self.healed != 0.0,
+        }
+    }
+}
+
+/// Live rows as the combatant table shows them: narrowed by the role filter and the `/` query
+/// and, with `hide_zero`, without rows that put nothing into `mode`'s metric. Copying uses the
+/// same rows, so the clipboard matches the screen.
+pub fn visible_live_rows(
+    rows: &[CombatantRow],
+    filter: RoleFilter,
+    query: &str,
+    hide_zero: bool,
+    mode: ViewMode,
+) -> Vec<CombatantRow> {
+    let mut rows = filter.apply(rows);
+    rows.retain(|row| row.matches_query(query) && (!hide_zero || row.contributed(mode)));
+    rows
+}
+
+/// Recorded rows as a history detail table shows them: sorted by `sort` in `mode`, narrowed by
+/// `filter` and, with `hide_zero`, without rows that put nothing into `mode`'s metric.
+pub fn visible_detail_rows(
+    rows: &[CombatantRow],
+    mode: ViewMode,
+    sort: SortKey,
+    ascending: bool,
+    filter: RoleFilter,
+    hide_zero: bool,
+) -> Vec<CombatantRow> {
+    let mut sorted = rows.to_vec();
+    sort.sort_rows(mode, ascending, &mut sorted);
+    visible_live_rows(&sorted, filter, "", hide_zero, mode)
+}
+
+/// Where the websocket connection stands, as reported by the client task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ConnectionState {
+    /// First attempt since startup, before anything has succeeded or failed.
+    #[default]
+    Connecting,
+    Connected,
+    Disconnected,
+    /// Retrying after a failure; `attempt` counts from 1 since the last connection.
+    Reconnecting {
+        attempt: u32,
+    },
+}
+
+impl ConnectionState {
+    pub fn is_connected(self) -> bool {
+        self == ConnectionState::Connected
+    }
+}
+
 #[derive(Debug)]
 pub enum AppEvent {
+    /// The websocket client is making its first connection attempt.
+    Connecting,
     Connected,
     Disconnected,
+    /// The websocket client is about to retry; `attempt` counts from 1 since the last connection.
+    Reconnecting {
+        attempt: u32,
+    },
     CombatData {
         encounter: EncounterSummary,
         rows: Vec<CombatantRow>,
     },
     HistoryDatesLoaded {
+        epoch: u64,
         days: Vec<HistoryDay>,
     },
     HistoryEncountersLoaded {
+        epoch: u64,
         date_id: String,
         encounters: Vec<HistoryEncounterItem>,
     },
     HistoryEncounterLoaded {
+        epoch: u64,
         key: Vec<u8>,
         record: EncounterRecord,
     },
     DungeonDatesLoaded {
+        epoch: u64,
         days: Vec<DungeonHistoryDay>,
     },
     DungeonRunsLoaded {
+        epoch: u64,
         date_id: String,
         runs: Vec<DungeonHistoryItem>,
     },
     DungeonRunLoaded {
+        epoch: u64,
         key: Vec<u8>,
         record: DungeonAggregateRecord,
     },
     DungeonEncounterLoaded {
+        epoch: u64,
         key: Vec<u8>,
         record: EncounterRecord,
     },
     DungeonSessionUpdate {
         active_zone: Option<String>,
+        /// Totals of the open run; `None` once it ends.
+        progress: Option<DungeonProgress>,
+    },
+    /// The dungeon catalog was reloaded with `R`.
+    DungeonCatalogReloaded {
+        catalog: Arc<DungeonCatalog>,
+    },
+    /// The completion streak after a dungeon run was saved.
+    DungeonStreak {
+        streak: DungeonStreak,
+    },
+    /// Matches of a history search across all days, newest first.
+    HistorySearchLoaded {
+        epoch: u64,
+        results: Vec<HistoryEncounterItem>,
+    },
+    /// A record picked with `x` was deleted from the store.
+    HistoryEntryDeleted {
+        epoch: u64,
+        kind: RecentKind,
+        key: Vec<u8>,
     },
     HistoryError {
+        epoch: u64,
         message: String,
     },
     SystemError {
         error: AppError,
     },
+    /// Short-lived status message shown as a toast.
+    Notice {
+        message: String,
+    },
+    /// Websocket messages per second over the last rate window.
+    MessageRate {
+        per_sec: f64,
+    },
+    /// Store lookup for a recently viewed entry: `(date_id, label)`, or `None` once deleted.
+    HistoryRecentResolved {
+        kind: RecentKind,
+        key: Vec<u8>,
+        found: Option<(String, String)>,
+    },
+}
+
+impl AppEvent {
+    /// The history load epoch a store result was requested under, for dropping stale results.
+    pub fn history_epoch(&self) -> Option<u64> {
+        match self {
+            AppEvent::HistoryDatesLoaded { epoch, .. }
+            | AppEvent::HistoryEncountersLoaded { epoch, .. }
+            | AppEvent::HistoryEncounterLoaded { epoch, .. }
+            | AppEvent::DungeonDatesLoaded { epoch, .. }
+            | AppEvent::DungeonRunsLoaded { epoch, .. }
+            | AppEvent::DungeonRunLoaded { epoch, .. }
+            | AppEvent::DungeonEncounterLoaded { epoch, .. }
+            | AppEvent::HistorySearchLoaded { epoch, .. }
+            | AppEvent::HistoryEntryDeleted { epoch, .. }
+            | AppEvent::HistoryError { epoch, .. } => Some(*epoch),
+            _ => None,
+        }
+    }
 }
 
 // Known job codes for party filtering and color mapping
+#[rustfmt::skip]
 pub fn known_jobs() -> &'static HashSet<&'static str> {
     static JOBS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
         [
             // Tanks
-            "PLD", "WAR", "DRK", "GNB", 
+            "PLD", "WAR", "DRK", "GNB",
             // Healers
-            "WHM", "SCH", "AST", "SGE", 
+            "WHM", "SCH", "AST", "SGE",
             // Melee
-            "MNK", "DRG", "NIN", "SAM", "RPR", "VPR", 
+            "MNK", "DRG", "NIN", "SAM", "RPR", "VPR",
             // Ranged phys
-            "BRD", "MCH", "DNC", 
+            "BRD", "MCH", "DNC",
             // Casters
-            "BLM", "SMN", "RDM", "PCT", 
+            "BLM", "SMN", "RDM", "PCT",
             // Limited
             "BLU",
             // Pre-Jobs
@@ -112,3 +254,34 @@ pub fn known_jobs() -> &'static HashSet<&'static str> {
     });
     &JOBS
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visible_live_rows_apply_every_table_filter() {
+        let row = |name: &str, job: &str, damage: f64| CombatantRow {
+            name: name.into(),
+            job: job.into(),
+            damage,
+            ..CombatantRow::default()
+        };
+        let rows = [
+            row("Alice", "NIN", 900.0),
+            row("Bob", "WAR", 800.0),
+            row("Limit Break", "", 0.0),
+            row("Alina", "WHM", 100.0),
+        ];
+        let shown = |filter, query, hide_zero| {
+            visible_live_rows(&rows, filter, query, hide_zero, ViewMode::Dps)
+                .into_iter()
+                .map(|row| row.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(shown(RoleFilter::All, "", false).len(), 4);
+        assert_eq!(shown(RoleFilter::All, "ali", true), ["Alice", "Alina"]);
+        assert_eq!(shown(RoleFilter::Dps, " AL ", true), ["Alice"]);
+    }
+}
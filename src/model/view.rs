@@ -1,4 +1,10 @@
+use std::cmp::Ordering;
+
+use chrono::{Local, TimeZone};
 use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::CombatantRow;
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum IdleScene {
@@ -44,6 +50,22 @@ pub enum Decoration {
 }
 
 impl Decoration {
+    pub const ALL: [Decoration; 3] = [
+        Decoration::Underline,
+        Decoration::Background,
+        Decoration::None,
+    ];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Decoration::Underline => {
+                "Two-line rows with a role-colored bar under each name, scaled to the top row"
+            }
+            Decoration::Background => "Role-colored meter behind each row, scaled to the top row",
+            Decoration::None => "Compact one-line rows without meters",
+        }
+    }
+
     pub fn next(self) -> Self {
         match self {
             Decoration::Underline => Decoration::Background,
@@ -100,6 +122,81 @@ impl Decoration {
     }
 }
 
+/// Combat role derived from a job code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Role {
+    Tank,
+    Healer,
+    Dps,
+}
+
+impl Role {
+    pub fn from_job(job: &str) -> Self {
+        match job {
+            "PLD" | "WAR" | "DRK" | "GNB" | "GLD" | "MRD" => Role::Tank,
+            "WHM" | "SCH" | "AST" | "SGE" | "CNJ" => Role::Healer,
+            // Everything else is treated as DPS
+            _ => Role::Dps,
+        }
+    }
+}
+
+/// Display-only filter that narrows the combatant table to one role.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum RoleFilter {
+    #[default]
+    All,
+    Tanks,
+    Healers,
+    Dps,
+}
+
+impl RoleFilter {
+    pub fn next(self) -> Self {
+        match self {
+            RoleFilter::All => RoleFilter::Tanks,
+            RoleFilter::Tanks => RoleFilter::Healers,
+            RoleFilter::Healers => RoleFilter::Dps,
+            RoleFilter::Dps => RoleFilter::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RoleFilter::All => "All",
+            RoleFilter::Tanks => "Tanks",
+            RoleFilter::Healers => "Healers",
+            RoleFilter::Dps => "DPS",
+        }
+    }
+
+    pub fn matches(self, job: &str) -> bool {
+        match self {
+            RoleFilter::All => true,
+            RoleFilter::Tanks => Role::from_job(job) == Role::Tank,
+            RoleFilter::Healers => Role::from_job(job) == Role::Healer,
+            RoleFilter::Dps => Role::from_job(job) == Role::Dps,
+        }
+    }
+
+    /// Rows that pass the filter, keeping their order.
+    pub fn apply(self, rows: &[CombatantRow]) -> Vec<CombatantRow> {
+        rows.iter()
+            .filter(|row| self.matches(&row.job))
+            .cloned()
+            .collect()
+    }
+
+    pub fn empty_message(self) -> &'static str {
+        match self {
+            RoleFilter::All => "No combatants yet.",
+            RoleFilter::Tanks => "No tanks in this encounter. Press f to change the filter.",
+            RoleFilter::Healers => "No healers in this encounter. Press f to change the filter.",
+            RoleFilter::Dps => "No DPS in this encounter. Press f to change the filter.",
+        }
+    }
+}
+
 // High-level view mode of the table
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum ViewMode {
@@ -109,6 +206,17 @@ pub enum ViewMode {
 }
 
 impl ViewMode {
+    pub const ALL: [ViewMode; 2] = [ViewMode::Dps, ViewMode::Heal];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ViewMode::Dps => {
+                "Sorted by encounter DPS; shows damage share, crit/DH rates and deaths"
+            }
+            ViewMode::Heal => "Sorted by encounter HPS; shows heal share, overheal and deaths",
+        }
+    }
+
     pub fn next(self) -> Self {
         match self {
             ViewMode::Dps => ViewMode::Heal,
@@ -148,3 +256,331 @@ impl ViewMode {
         }
     }
 }
+
+/// What the combatant tables are ordered by. `Rate` follows the view mode (ENCDPS or ENCHPS).
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SortKey {
+    #[default]
+    Rate,
+    Damage,
+    Healed,
+    Crit,
+    Deaths,
+    Name,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 6] = [
+        SortKey::Rate,
+        SortKey::Damage,
+        SortKey::Healed,
+        SortKey::Crit,
+        SortKey::Deaths,
+        SortKey::Name,
+    ];
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|key| *key == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let idx = Self::ALL.iter().position(|key| *key == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self, mode: ViewMode) -> &'static str {
+        match (self, mode) {
+            (SortKey::Rate, ViewMode::Dps) => "ENCDPS",
+            (SortKey::Rate, ViewMode::Heal) => "ENCHPS",
+            (SortKey::Damage, _) => "damage",
+            (SortKey::Healed, _) => "healed",
+            (SortKey::Crit, _) => "crit%",
+            (SortKey::Deaths, _) => "deaths",
+            (SortKey::Name, _) => "name",
+        }
+    }
+
+    /// Direction a freshly picked key starts in: A to Z for names, highest first otherwise.
+    pub fn default_ascending(self) -> bool {
+        self == SortKey::Name
+    }
+
+    /// Table column whose header carries the sort marker; damage and healing are shown as
+    /// shares, which order the same way.
+    pub fn column(self, mode: ViewMode) -> ColumnId {
+        match (self, mode) {
+            (SortKey::Rate, ViewMode::Dps) => ColumnId::Encdps,
+            (SortKey::Rate, ViewMode::Heal) => ColumnId::Enchps,
+            (SortKey::Damage, _) => ColumnId::Share,
+            (SortKey::Healed, _) => ColumnId::HealShare,
+            (SortKey::Crit, _) => ColumnId::Crit,
+            (SortKey::Deaths, _) => ColumnId::Deaths,
+            (SortKey::Name, _) => ColumnId::Name,
+        }
+    }
+
+    /// Orders rows by this key (`Rate` uses `mode`'s rate), with ties broken by name A to Z
+    /// whichever way the key runs.
+    pub fn sort_rows(self, mode: ViewMode, ascending: bool, rows: &mut [CombatantRow]) {
+        let value = |row: &CombatantRow| match self {
+            SortKey::Rate => match mode {
+                ViewMode::Dps => row.encdps,
+                ViewMode::Heal => row.enchps,
+            },
+            SortKey::Damage => row.damage,
+            SortKey::Healed => row.healed,
+            SortKey::Crit => leading_number(&row.crit),
+            SortKey::Deaths => leading_number(&row.deaths),
+            SortKey::Name => 0.0,
+        };
+        rows.sort_by(|a, b| {
+            let order = if self == SortKey::Name {
+                a.name.to_lowercase().cmp(&b.name.to_lowercase())
+            } else {
+                value(a).partial_cmp(&value(b)).unwrap_or(Ordering::Equal)
+            };
+            let order = if ascending { order } else { order.reverse() };
+            order.then_with(|| a.name.cmp(&b.name))
+        });
+    }
+}
+
+/// Number at the start of a display value such as "23.5%" or "2"; 0 when there is none.
+fn leading_number(text: &str) -> f64 {
+    let text = text.trim();
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .unwrap_or(text.len());
+    text[..end].parse().unwrap_or(0.0)
+}
+
+/// Clock used for times of day: 24-hour ("14:05") or 12-hour ("2:05 PM").
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ClockFormat {
+    #[default]
+    H24,
+    H12,
+}
+
+impl ClockFormat {
+    pub fn config_key(self) -> &'static str {
+        match self {
+            ClockFormat::H24 => "24h",
+            ClockFormat::H12 => "12h",
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        match key.as_ref().trim().to_ascii_lowercase().as_str() {
+            "12h" | "h12" | "12" => ClockFormat::H12,
+            _ => ClockFormat::H24,
+        }
+    }
+
+    /// The chrono pattern for a time of day, with or without seconds.
+    pub fn time_pattern(self, seconds: bool) -> &'static str {
+        match (self, seconds) {
+            (ClockFormat::H24, false) => "%H:%M",
+            (ClockFormat::H24, true) => "%H:%M:%S",
+            (ClockFormat::H12, false) => "%-I:%M %p",
+            (ClockFormat::H12, true) => "%-I:%M:%S %p",
+        }
+    }
+
+    /// `ms` as a local time of day, prefixed with the date when `with_date` is set. `None` for
+    /// a missing (0) or unrepresentable timestamp.
+    pub fn format_local(self, ms: u64, with_date: bool, seconds: bool) -> Option<String> {
+        if ms == 0 {
+            return None;
+        }
+        let dt = Local
+            .timestamp_millis_opt(i64::try_from(ms).ok()?)
+            .single()?;
+        let time = dt.format(self.time_pattern(seconds));
+        Some(if with_date {
+            format!("{} {time}", dt.format("%Y-%m-%d"))
+        } else {
+            time.to_string()
+        })
+    }
+}
+
+/// Columns the combatant table can show. `DEFAULT_ORDER` reproduces the stock layout; each
+/// width variant only renders the columns that fit, in the configured order. Columns outside
+/// `DEFAULT_ORDER` are opt-in through `column_order`.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ColumnId {
+    Name,
+    Share,
+    HealShare,
+    Encdps,
+    RollingDps,
+    Enchps,
+    Job,
+    Crit,
+    DirectHit,
+    Overheal,
+    Deaths,
+    PartyDelta,
+}
+
+impl ColumnId {
+    pub const DEFAULT_ORDER: [ColumnId; 11] = [
+        ColumnId::Name,
+        ColumnId::Share,
+        ColumnId::HealShare,
+        ColumnId::Encdps,
+        ColumnId::RollingDps,
+        ColumnId::Enchps,
+        ColumnId::Job,
+        ColumnId::Crit,
+        ColumnId::DirectHit,
+        ColumnId::Overheal,
+        ColumnId::Deaths,
+    ];
+
+    pub const ALL: [ColumnId; 12] = [
+        ColumnId::Name,
+        ColumnId::Share,
+        ColumnId::HealShare,
+        ColumnId::Encdps,
+        ColumnId::RollingDps,
+        ColumnId::Enchps,
+        ColumnId::Job,
+        ColumnId::Crit,
+        ColumnId::DirectHit,
+        ColumnId::Overheal,
+        ColumnId::Deaths,
+        ColumnId::PartyDelta,
+    ];
+
+    pub fn config_key(self) -> &'static str {
+        match self {
+            ColumnId::Name => "name",
+            ColumnId::Share => "share",
+            ColumnId::HealShare => "heal_share",
+            ColumnId::Encdps => "encdps",
+            ColumnId::RollingDps => "rolling_dps",
+            ColumnId::Enchps => "enchps",
+            ColumnId::Job => "job",
+            ColumnId::Crit => "crit",
+            ColumnId::DirectHit => "dh",
+            ColumnId::Overheal => "overheal",
+            ColumnId::Deaths => "deaths",
+            ColumnId::PartyDelta => "party_delta",
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Option<Self> {
+        let key = key.as_ref().trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|column| column.config_key() == key)
+    }
+
+    /// Resolves configured column ids, skipping unknown or repeated entries. An empty or
+    /// entirely invalid list falls back to `DEFAULT_ORDER`.
+    pub fn resolve_order<S: AsRef<str>>(keys: &[S]) -> Vec<ColumnId> {
+        let mut order = Vec::with_capacity(keys.len());
+        for key in keys {
+            match Self::from_config_key(key) {
+                Some(column) if !order.contains(&column) => order.push(column),
+                Some(_) => {}
+                None => warn!(column = key.as_ref(), "ignoring unknown column id"),
+            }
+        }
+        if order.is_empty() {
+            Self::DEFAULT_ORDER.to_vec()
+        } else {
+            order
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_format_switches_between_24h_and_am_pm() {
+        let ms = Local
+            .with_ymd_and_hms(2024, 5, 1, 14, 5, 9)
+            .unwrap()
+            .timestamp_millis() as u64;
+        let h24 = ClockFormat::from_config_key("24h");
+        let h12 = ClockFormat::from_config_key(" 12H ");
+        assert_eq!(h24.format_local(ms, false, false).as_deref(), Some("14:05"));
+        assert_eq!(
+            h24.format_local(ms, true, true).as_deref(),
+            Some("2024-05-01 14:05:09")
+        );
+        assert_eq!(
+            h12.format_local(ms, false, false).as_deref(),
+            Some("2:05 PM")
+        );
+        assert_eq!(
+            h12.format_local(ms, true, true).as_deref(),
+            Some("2024-05-01 2:05:09 PM")
+        );
+        assert_eq!(h12.format_local(0, true, true), None);
+        assert_eq!(ClockFormat::from_config_key("bogus"), ClockFormat::H24);
+    }
+
+    #[test]
+    fn sort_keys_order_rows_and_break_ties_by_name() {
+        let row = |name: &str, encdps: f64, crit: &str, deaths: &str| CombatantRow {
+            name: name.into(),
+            encdps,
+            crit: crit.into(),
+            deaths: deaths.into(),
+            ..Default::default()
+        };
+        let mut rows = vec![
+            row("cat", 100.0, "30%", "0"),
+            row("Bob", 300.0, "12.5%", "2"),
+            row("Amy", 100.0, "45%", "1"),
+        ];
+        let names = |rows: &[CombatantRow]| -> Vec<String> {
+            rows.iter().map(|row| row.name.clone()).collect()
+        };
+
+        SortKey::Rate.sort_rows(ViewMode::Dps, false, &mut rows);
+        assert_eq!(names(&rows), ["Bob", "Amy", "cat"]);
+        SortKey::Rate.sort_rows(ViewMode::Dps, true, &mut rows);
+        assert_eq!(names(&rows), ["Amy", "cat", "Bob"]);
+        SortKey::Crit.sort_rows(ViewMode::Dps, false, &mut rows);
+        assert_eq!(names(&rows), ["Amy", "cat", "Bob"]);
+        SortKey::Deaths.sort_rows(ViewMode::Dps, false, &mut rows);
+        assert_eq!(names(&rows), ["Bob", "Amy", "cat"]);
+        SortKey::Name.sort_rows(ViewMode::Dps, SortKey::Name.default_ascending(), &mut rows);
+        assert_eq!(names(&rows), ["Amy", "Bob", "cat"]);
+
+        assert_eq!(SortKey::Name.next(), SortKey::Rate);
+        assert_eq!(SortKey::Rate.prev(), SortKey::Name);
+    }
+
+    #[test]
+    fn role_filter_keeps_only_matching_jobs_in_order() {
+        let rows: Vec<CombatantRow> = ["NIN", "WAR", "WHM", "PLD", "BLM"]
+            .into_iter()
+            .map(|job| CombatantRow {
+                name: job.to_lowercase(),
+                job: job.into(),
+                ..Default::default()
+            })
+            .collect();
+        let names = |filter: RoleFilter| -> Vec<String> {
+            filter
+                .apply(&rows)
+                .into_iter()
+                .map(|row| row.name)
+                .collect()
+        };
+        assert_eq!(names(RoleFilter::All).len(), 5);
+        assert_eq!(names(RoleFilter::Tanks), vec!["war", "pld"]);
+        assert_eq!(names(RoleFilter::Healers), vec!["whm"]);
+        assert_eq!(names(RoleFilter::Dps), vec!["nin", "blm"]);
+        assert_eq!(RoleFilter::Dps.next(), RoleFilter::All);
+    }
+}
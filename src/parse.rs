@@ -41,18 +41,10 @@ fn upper<S: AsRef<str>>(s: S) -> String {
 }
 
 pub fn parse_combat_data(value: &Value) -> Option<(EncounterSummary, Vec<CombatantRow>)> {
-    let root = value.as_object()?;
-    if root.get("type")?.as_str()? != "CombatData" {
-        return None;
-    }
+    let root = combat_data_root(value.as_object()?)?;
 
     let encounter = parse_encounter(root);
-
-    let combatants = root
-        .get("Combatant")
-        .and_then(|v| v.as_object())
-        .cloned()
-        .unwrap_or_default();
+    let combatants = combatant_map(root);
 
     let mut rows = combatant_rows(&combatants);
 
@@ -69,45 +61,176 @@ pub fn parse_combat_data(value: &Value) -> Option<(EncounterSummary, Vec<Combata
     Some((encounter, rows))
 }
 
-fn parse_encounter(root: &Map<String, Value>) -> EncounterSummary {
-    let enc_obj = root
-        .get("Encounter")
+/// One line of a combatant's per-skill breakdown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbilityStat {
+    pub name: String,
+    pub hits: u64,
+    pub damage: f64,
+}
+
+/// Reads `Combatant[name].abilities` from a CombatData payload, highest damage first. The
+/// breakdown may be a map keyed by skill name or a list of objects with a `name`; hits come
+/// from `hits`, `swings` or `count`. `None` when the payload carries no breakdown for `name`.
+pub fn parse_abilities(value: &Value, combatant: &str) -> Option<Vec<AbilityStat>> {
+    let root = combat_data_root(value.as_object()?)?;
+    let combatants = combatant_map(root);
+    let stats = combatants
+        .iter()
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(combatant.trim()))
+        .and_then(|(_, stats)| stats.as_object())?;
+    let entries: Vec<(String, &Map<String, Value>)> = match get_ci(stats, "abilities")? {
+        Value::Object(map) => map
+            .iter()
+            .filter_map(|(name, entry)| Some((name.clone(), entry.as_object()?)))
+            .collect(),
+        Value::Array(list) => list
+            .iter()
+            .filter_map(|entry| {
+                let entry = entry.as_object()?;
+                Some((get_ci(entry, "name").map(val_to_string)?, entry))
+            })
+            .collect(),
+        _ => return None,
+    };
+    let number = |entry: &Map<String, Value>, keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| get_ci(entry, key))
+            .map(|v| to_f64_any(val_to_string(v)))
+            .unwrap_or(0.0)
+    };
+    let mut abilities: Vec<AbilityStat> = entries
+        .into_iter()
+        .filter(|(name, _)| !name.trim().is_empty())
+        .map(|(name, entry)| AbilityStat {
+            name: name.trim().to_string(),
+            hits: number(entry, &["hits", "swings", "count"]).max(0.0) as u64,
+            damage: number(entry, &["damage"]),
+        })
+        .collect();
+    abilities.sort_by(|a, b| {
+        b.damage
+            .partial_cmp(&a.damage)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    Some(abilities)
+}
+
+/// Extracts the zone name from a `ChangeZone` (type `01`) LogLine message.
+pub fn parse_zone_change(value: &Value) -> Option<String> {
+    log_line_field(value, "01", 3)
+}
+
+/// Extracts who died from a `NetworkDeath` (type `25`) LogLine message.
+pub fn parse_death(value: &Value) -> Option<String> {
+    log_line_field(value, "25", 3)
+}
+
+/// Trimmed, non-empty field `index` of a LogLine message of the given line type.
+fn log_line_field(value: &Value, line_type: &str, index: usize) -> Option<String> {
+    let root = value.as_object()?;
+    if root.get("type")?.as_str()? != "LogLine" {
+        return None;
+    }
+    let line = root.get("line")?.as_array()?;
+    if line.first()?.as_str()? != line_type {
+        return None;
+    }
+    let field = line.get(index)?.as_str()?.trim();
+    (!field.is_empty()).then(|| field.to_string())
+}
+
+/// Locates the object carrying the CombatData payload.
+///
+/// Newer OverlayPlugin builds send `{"type":"CombatData", ...}` directly, while the legacy
+/// ACTWebSocket-compatible endpoint wraps it as `{"type":"broadcast","msgtype":"CombatData","msg":{...}}`.
+fn combat_data_root(root: &Map<String, Value>) -> Option<&Map<String, Value>> {
+    match root.get("type").and_then(|v| v.as_str()) {
+        Some("CombatData") => Some(root),
+        Some("broadcast") => {
+            if root.get("msgtype")?.as_str()? != "CombatData" {
+                return None;
+            }
+            root.get("msg")?.as_object()
+        }
+        _ => None,
+    }
+}
+
+/// Returns the encounter fields, either nested under `Encounter` or flattened onto the root.
+fn encounter_object(root: &Map<String, Value>) -> &Map<String, Value> {
+    get_ci(root, "Encounter")
         .and_then(|v| v.as_object())
-        .cloned()
-        .unwrap_or_default();
+        .unwrap_or(root)
+}
+
+/// Normalises combatants into a name-keyed map.
+///
+/// Most plugin versions key `Combatant` by name; some send a list of objects carrying a `name`
+/// field instead.
+fn combatant_map(root: &Map<String, Value>) -> Map<String, Value> {
+    match get_ci(root, "Combatant").or_else(|| get_ci(root, "Combatants")) {
+        Some(Value::Object(map)) => map.clone(),
+        Some(Value::Array(list)) => list
+            .iter()
+            .filter_map(|entry| {
+                let stats = entry.as_object()?;
+                let name = get_ci(stats, "name").map(val_to_string)?;
+                let name = name.trim();
+                if name.is_empty() {
+                    return None;
+                }
+                Some((name.to_string(), entry.clone()))
+            })
+            .collect(),
+        _ => Map::new(),
+    }
+}
+
+fn parse_active_flag(v: &Value) -> bool {
+    match v {
+        Value::Bool(b) => *b,
+        Value::String(s) => s.eq_ignore_ascii_case("true"),
+        _ => false,
+    }
+}
+
+fn parse_encounter(root: &Map<String, Value>) -> EncounterSummary {
+    let enc_obj = encounter_object(root);
 
     let title = enc_obj
         .get("title")
-        .or_else(|| get_ci(&enc_obj, "Encounter"))
+        .or_else(|| get_ci(enc_obj, "Encounter"))
+        .filter(|v| !v.is_object())
         .map(val_to_string)
         .unwrap_or_default();
-    let zone = get_ci(&enc_obj, "CurrentZoneName")
+    let zone = get_ci(enc_obj, "CurrentZoneName")
         .map(val_to_string)
         .unwrap_or_default();
-    let duration = get_ci(&enc_obj, "duration")
+    let duration = get_ci(enc_obj, "duration")
         .map(val_to_string)
         .unwrap_or_default();
-    let encdps = get_ci(&enc_obj, "encdps")
-        .or_else(|| get_ci(&enc_obj, "ENCDPS"))
-        .or_else(|| get_ci(&enc_obj, "DPS"))
+    let encdps = get_ci(enc_obj, "encdps")
+        .or_else(|| get_ci(enc_obj, "ENCDPS"))
+        .or_else(|| get_ci(enc_obj, "DPS"))
         .map(val_to_string)
         .unwrap_or_default();
-    let damage = get_ci(&enc_obj, "damage")
-        .or_else(|| get_ci(&enc_obj, "damageTotal"))
+    let damage = get_ci(enc_obj, "damage")
+        .or_else(|| get_ci(enc_obj, "damageTotal"))
         .map(val_to_string)
         .unwrap_or_default();
-    let enchps = get_ci(&enc_obj, "enchps")
-        .or_else(|| get_ci(&enc_obj, "ENCHPS"))
+    let enchps = get_ci(enc_obj, "enchps")
+        .or_else(|| get_ci(enc_obj, "ENCHPS"))
         .map(val_to_string)
         .unwrap_or_default();
-    let healed = get_ci(&enc_obj, "healed")
+    let healed = get_ci(enc_obj, "healed")
         .map(val_to_string)
         .unwrap_or_default();
 
-    let is_active = root
-        .get("isActive")
-        .and_then(|v| v.as_str())
-        .map(|s| s.eq_ignore_ascii_case("true"))
+    let is_active = get_ci(root, "isActive")
+        .or_else(|| get_ci(enc_obj, "isActive"))
+        .map(parse_active_flag)
         .unwrap_or(false);
 
     EncounterSummary {
@@ -281,6 +404,50 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn parses_ability_maps_and_lists() {
+        let payload = json!({
+            "type": "CombatData",
+            "Combatant": {
+                "Alice": {
+                    "name": "Alice",
+                    "abilities": {
+                        "Spinning Edge": { "hits": "12", "damage": "24,000" },
+                        "Bhavacakra": { "swings": 3, "damage": 60000 }
+                    }
+                },
+                "Bob": {
+                    "name": "Bob",
+                    "abilities": [{ "name": "Fast Blade", "count": "5", "damage": "9000" }]
+                },
+                "Cleo": { "name": "Cleo" }
+            }
+        });
+        let alice = parse_abilities(&payload, "alice").expect("alice abilities");
+        assert_eq!(
+            alice,
+            vec![
+                AbilityStat {
+                    name: "Bhavacakra".into(),
+                    hits: 3,
+                    damage: 60_000.0
+                },
+                AbilityStat {
+                    name: "Spinning Edge".into(),
+                    hits: 12,
+                    damage: 24_000.0
+                },
+            ]
+        );
+        let bob = parse_abilities(&payload, "Bob").expect("bob abilities");
+        assert_eq!(bob[0].name, "Fast Blade");
+        assert_eq!(bob[0].hits, 5);
+
+        assert!(parse_abilities(&payload, "Cleo").is_none());
+        assert!(parse_abilities(&payload, "Nobody").is_none());
+        assert!(parse_abilities(&json!({ "type": "LogLine" }), "Alice").is_none());
+    }
+
     #[test]
     fn parses_basic_combat_data() {
         let payload = json!({
@@ -365,4 +532,148 @@ mod tests {
         assert_eq!(rows[0].share_str, "70.0%");
         assert!((rows[1].share - 0.3).abs() < 1e-6);
     }
+
+    fn nested_fixture() -> Value {
+        json!({
+            "type": "CombatData",
+            "Encounter": {
+                "title": "Striking Dummy",
+                "duration": "01:30",
+                "encdps": "2,000",
+                "damage": "10,000",
+                "enchps": "1,000",
+                "healed": "2,000",
+                "CurrentZoneName": "Middle La Noscea"
+            },
+            "Combatant": {
+                "Alice": {
+                    "Job": "Nin",
+                    "encdps": "6,000",
+                    "damage": "6,000",
+                    "crithit%": "10%",
+                    "DirectHit%": "20%",
+                    "deaths": "0",
+                    "enchps": "100",
+                    "healed": "500",
+                    "OverHealPct": "5%"
+                },
+                "Bob": {
+                    "Job": "Whm",
+                    "encdps": "4,000",
+                    "damage": "4,000",
+                    "crithit%": "5%",
+                    "DirectHit%": "15%",
+                    "deaths": "1",
+                    "enchps": "900",
+                    "healed": "1,500",
+                    "OverHealPct": "15%"
+                }
+            },
+            "isActive": "true"
+        })
+    }
+
+    fn broadcast_fixture() -> Value {
+        json!({
+            "type": "broadcast",
+            "msgtype": "CombatData",
+            "msg": nested_fixture()
+        })
+    }
+
+    fn flat_fixture() -> Value {
+        json!({
+            "type": "CombatData",
+            "title": "Striking Dummy",
+            "duration": "01:30",
+            "encdps": "2,000",
+            "damage": "10,000",
+            "enchps": "1,000",
+            "healed": "2,000",
+            "CurrentZoneName": "Middle La Noscea",
+            "isActive": true,
+            "Combatant": [
+                {
+                    "name": "Bob",
+                    "Job": "Whm",
+                    "encdps": "4,000",
+                    "damage": "4,000",
+                    "crithit%": "5%",
+                    "DirectHit%": "15%",
+                    "deaths": "1",
+                    "enchps": "900",
+                    "healed": "1,500",
+                    "OverHealPct": "15%"
+                },
+                {
+                    "name": "Alice",
+                    "Job": "Nin",
+                    "encdps": "6,000",
+                    "damage": "6,000",
+                    "crithit%": "10%",
+                    "DirectHit%": "20%",
+                    "deaths": "0",
+                    "enchps": "100",
+                    "healed": "500",
+                    "OverHealPct": "5%"
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn envelope_shapes_produce_identical_output() {
+        let expected = parse_combat_data(&nested_fixture()).expect("nested parsed");
+        assert_eq!(expected.0.title, "Striking Dummy");
+        assert!(expected.0.is_active);
+        assert_eq!(expected.1.len(), 2);
+
+        let broadcast = parse_combat_data(&broadcast_fixture()).expect("broadcast parsed");
+        assert_eq!(broadcast, expected);
+
+        let flat = parse_combat_data(&flat_fixture()).expect("flat parsed");
+        assert_eq!(flat, expected);
+    }
+
+    #[test]
+    fn ignores_non_combat_messages() {
+        assert!(parse_combat_data(&json!({"type": "LogLine", "line": []})).is_none());
+        assert!(parse_combat_data(&json!({
+            "type": "broadcast",
+            "msgtype": "SendCharName",
+            "msg": {"charName": "Alice"}
+        }))
+        .is_none());
+    }
+
+    #[test]
+    fn parses_change_zone_log_lines() {
+        let change = json!({
+            "type": "LogLine",
+            "line": ["01", "2025-01-01T12:00:00.0000000+00:00", "3A", "Sastasha", "hash"]
+        });
+        assert_eq!(parse_zone_change(&change).as_deref(), Some("Sastasha"));
+
+        let other = json!({
+            "type": "LogLine",
+            "line": ["00", "2025-01-01T12:00:00.0000000+00:00", "0039", "", "Hello"]
+        });
+        assert!(parse_zone_change(&other).is_none());
+    }
+
+    #[test]
+    fn parses_network_death_log_lines() {
+        let death = json!({
+            "type": "LogLine",
+            "line": ["25", "2025-01-01T12:00:00.0000000+00:00", "10FF0001", "Alice Doe", "40001234", "Ifrit", "hash"]
+        });
+        assert_eq!(parse_death(&death).as_deref(), Some("Alice Doe"));
+        assert!(parse_zone_change(&death).is_none());
+
+        let change = json!({
+            "type": "LogLine",
+            "line": ["01", "2025-01-01T12:00:00.0000000+00:00", "3A", "Sastasha", "hash"]
+        });
+        assert!(parse_death(&change).is_none());
+    }
 }
@@ -0,0 +1,217 @@
+//! State files stored next to the config file: an opt-in crash-recovery copy of the live view,
+//! the small UI-state file that remembers history panel state between sessions and the
+//! dungeon completion streak.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::config_dir;
+use crate::history::DungeonStreak;
+use crate::model::{AppState, CombatantRow, Decoration, EncounterSummary, RecentEntry, ViewMode};
+
+const SNAPSHOT_FILE_NAME: &str = "live_snapshot.json";
+const UI_STATE_FILE_NAME: &str = "ui_state.json";
+const DUNGEON_STREAK_FILE_NAME: &str = "dungeon_streak.json";
+/// How often the main loop may rewrite the snapshot file.
+pub const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5);
+/// Snapshots older than this are ignored on startup.
+const SNAPSHOT_MAX_AGE_MS: u64 = 15 * 60 * 1000;
+
+/// The serializable slice of `AppState` worth restoring; connection, history and overlay
+/// state are transient and rebuilt on startup.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LiveSnapshot {
+    pub saved_ms: u64,
+    pub encounter: Option<EncounterSummary>,
+    pub rows: Vec<CombatantRow>,
+    pub decoration: Decoration,
+    pub mode: ViewMode,
+}
+
+impl LiveSnapshot {
+    pub fn capture(state: &AppState, saved_ms: u64) -> Self {
+        Self {
+            saved_ms,
+            encounter: state.encounter.clone(),
+            rows: state.rows.clone(),
+            decoration: state.decoration,
+            mode: state.mode,
+        }
+    }
+
+    /// Puts the saved view back. The encounter is marked inactive since its fight is long over.
+    pub fn restore_into(self, state: &mut AppState) {
+        state.encounter = self.encounter.map(|mut enc| {
+            enc.is_active = false;
+            enc
+        });
+        state.rows = self.rows;
+        state.decoration = self.decoration;
+        state.mode = self.mode;
+        state.resort_rows();
+    }
+
+    /// True when both snapshots would restore the same view, ignoring the save time.
+    pub fn same_view(&self, other: &LiveSnapshot) -> bool {
+        self.encounter == other.encounter
+            && self.rows == other.rows
+            && self.decoration == other.decoration
+            && self.mode == other.mode
+    }
+}
+
+pub fn snapshot_path() -> PathBuf {
+    config_dir().join(SNAPSHOT_FILE_NAME)
+}
+
+pub fn save_snapshot(path: &Path, snapshot: &LiveSnapshot) -> Result<()> {
+    write_json_atomic(path, snapshot)
+}
+
+fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Unable to create state directory {}", parent.display()))?;
+    }
+    // Write to a sibling file first so a crash mid-write never leaves a truncated file.
+    let tmp = path.with_extension("json.tmp");
+    let data = serde_json::to_vec(value)?;
+    fs::write(&tmp, data).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to move {}", path.display()))?;
+    Ok(())
+}
+
+/// Loads the snapshot at `path` if it exists and was saved within the recovery window.
+pub fn load_recent_snapshot(path: &Path, now_ms: u64) -> Result<Option<LiveSnapshot>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("Failed to read snapshot at {}", path.display()))
+        }
+    };
+    let snapshot: LiveSnapshot = serde_json::from_slice(&bytes)
+        .with_context(|| format!("Failed to parse snapshot at {}", path.display()))?;
+    if now_ms.saturating_sub(snapshot.saved_ms) > SNAPSHOT_MAX_AGE_MS {
+        return Ok(None);
+    }
+    Ok(Some(snapshot))
+}
+
+/// History panel state remembered across sessions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct UiState {
+    /// Only written when `persist_history_filter` is on.
+    #[serde(default)]
+    pub history_filter: String,
+    /// Recently viewed encounters and runs, most recent first.
+    #[serde(default)]
+    pub recent_history: Vec<RecentEntry>,
+    /// Websocket URL of the last successful connection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_ws_url: Option<String>,
+}
+
+pub fn ui_state_path() -> PathBuf {
+    config_dir().join(UI_STATE_FILE_NAME)
+}
+
+pub fn save_ui_state(path: &Path, ui_state: &UiState) -> Result<()> {
+    write_json_atomic(path, ui_state)
+}
+
+/// Loads the UI state, treating a missing file as the defaults.
+pub fn load_ui_state(path: &Path) -> Result<UiState> {
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to parse UI state at {}", path.display())),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(UiState::default()),
+        Err(err) => {
+            Err(err).with_context(|| format!("Failed to read UI state at {}", path.display()))
+        }
+    }
+}
+
+pub fn dungeon_streak_path() -> PathBuf {
+    config_dir().join(DUNGEON_STREAK_FILE_NAME)
+}
+
+pub fn save_dungeon_streak(path: &Path, streak: &DungeonStreak) -> Result<()> {
+    write_json_atomic(path, streak)
+}
+
+/// Loads the dungeon streak; no file yet means no runs so far.
+pub fn load_dungeon_streak(path: &Path) -> Result<DungeonStreak> {
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to parse dungeon streak at {}", path.display())),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(DungeonStreak::default()),
+        Err(err) => {
+            Err(err).with_context(|| format!("Failed to read dungeon streak at {}", path.display()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::RecentKind;
+
+    #[test]
+    fn snapshot_round_trips_and_expires() {
+        let dir = std::env::temp_dir().join(format!(
+            "nekomata-snapshot-{}",
+            crate::history::types::now_ms()
+        ));
+        let path = dir.join(SNAPSHOT_FILE_NAME);
+        assert!(load_recent_snapshot(&path, 0)
+            .expect("missing file")
+            .is_none());
+
+        let state = AppState {
+            encounter: Some(EncounterSummary {
+                title: "Striking Dummy".into(),
+                is_active: true,
+                ..Default::default()
+            }),
+            mode: ViewMode::Heal,
+            ..Default::default()
+        };
+        let snapshot = LiveSnapshot::capture(&state, 1_000);
+        save_snapshot(&path, &snapshot).expect("save snapshot");
+
+        let loaded = load_recent_snapshot(&path, 2_000)
+            .expect("load snapshot")
+            .expect("recent snapshot");
+        assert_eq!(loaded, snapshot);
+
+        let stale = load_recent_snapshot(&path, 1_000 + SNAPSHOT_MAX_AGE_MS + 1).expect("load");
+        assert!(stale.is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn ui_state_round_trips_and_defaults_when_missing() {
+        let dir = std::env::temp_dir().join(format!(
+            "nekomata-ui-state-{}",
+            crate::history::types::now_ms()
+        ));
+        let path = dir.join(UI_STATE_FILE_NAME);
+        assert_eq!(load_ui_state(&path).expect("missing"), UiState::default());
+
+        let ui_state = UiState {
+            history_filter: "titan".into(),
+            recent_history: vec![RecentEntry::new(RecentKind::Encounter, &[0xab, 0x01])],
+            last_ws_url: Some("ws://192.168.1.50:10501/ws".into()),
+        };
+        save_ui_state(&path, &ui_state).expect("save");
+        assert_eq!(load_ui_state(&path).expect("load"), ui_state);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,169 @@
+//! Optional outbound WebSocket feed (`--push-ws <addr>`) for third-party overlays.
+//!
+//! Every change to the live view is serialized once and handed to all connected clients.
+//! Clients that fall behind skip straight to the newest frame instead of queueing stale ones,
+//! and new clients receive the current frame as soon as they connect.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+use crate::model::{AppState, CombatantRow, EncounterSummary, ViewMode};
+
+/// The slice of the live view pushed to clients, one JSON text message per change.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PushFrame {
+    pub connected: bool,
+    pub is_idle: bool,
+    pub mode: ViewMode,
+    pub encounter: Option<EncounterSummary>,
+    pub rows: Vec<CombatantRow>,
+}
+
+impl PushFrame {
+    pub fn capture(state: &AppState, now: Instant) -> Self {
+        Self {
+            connected: state.connection.is_connected(),
+            is_idle: state.is_idle_at(now),
+            mode: state.mode,
+            encounter: state.encounter.clone(),
+            rows: state.rows.clone(),
+        }
+    }
+}
+
+pub struct PushServer {
+    latest: watch::Sender<Option<Arc<str>>>,
+    last_frame: Option<PushFrame>,
+}
+
+impl PushServer {
+    /// Binds `addr` and starts accepting overlay clients in the background.
+    pub async fn spawn(addr: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("failed to bind push server on {addr}"))?;
+        info!(%addr, "push server listening");
+        Ok(Self::serve(listener))
+    }
+
+    /// Accepts overlay clients from the already bound `listener` in the background.
+    fn serve(listener: TcpListener) -> Self {
+        let (latest, _) = watch::channel(None);
+        let accept_latest = latest.clone();
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        tokio::spawn(serve_client(stream, peer, accept_latest.subscribe()));
+                    }
+                    Err(err) => warn!(error = ?err, "push server failed to accept a client"),
+                }
+            }
+        });
+        Self {
+            latest,
+            last_frame: None,
+        }
+    }
+
+    /// Publishes `frame` unless it matches the last one sent.
+    pub fn publish(&mut self, frame: PushFrame) {
+        if self.last_frame.as_ref() == Some(&frame) {
+            return;
+        }
+        match serde_json::to_string(&frame) {
+            Ok(json) => {
+                self.latest.send_replace(Some(json.into()));
+            }
+            Err(err) => warn!(error = ?err, "failed to serialize push frame"),
+        }
+        self.last_frame = Some(frame);
+    }
+}
+
+async fn serve_client(
+    stream: TcpStream,
+    peer: SocketAddr,
+    mut latest: watch::Receiver<Option<Arc<str>>>,
+) {
+    let ws = match accept_async(stream).await {
+        Ok(ws) => ws,
+        Err(err) => {
+            debug!(%peer, error = ?err, "push client handshake failed");
+            return;
+        }
+    };
+    info!(%peer, "push client connected");
+    let (mut sink, mut incoming) = ws.split();
+    latest.mark_changed();
+
+    loop {
+        tokio::select! {
+            changed = latest.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let frame = latest.borrow_and_update().clone();
+                if let Some(json) = frame {
+                    if sink.send(Message::Text(json.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            message = incoming.next() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by tungstenite on the next write; other input is ignored.
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    let _ = sink.close().await;
+    info!(%peer, "push client disconnected");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ConnectionState;
+
+    #[tokio::test]
+    async fn clients_receive_the_latest_frame_and_changes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        let mut server = PushServer::serve(listener);
+        let mut state = AppState {
+            connection: ConnectionState::Connected,
+            ..Default::default()
+        };
+        server.publish(PushFrame::capture(&state, Instant::now()));
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{addr}"))
+            .await
+            .expect("connect");
+        let first = client.next().await.expect("frame").expect("message");
+        let value: serde_json::Value =
+            serde_json::from_str(first.to_text().expect("text")).expect("json");
+        assert_eq!(value["connected"], true);
+
+        state.rows.push(CombatantRow {
+            name: "Alice".into(),
+            ..Default::default()
+        });
+        server.publish(PushFrame::capture(&state, Instant::now()));
+        let second = client.next().await.expect("frame").expect("message");
+        let value: serde_json::Value =
+            serde_json::from_str(second.to_text().expect("text")).expect("json");
+        assert_eq!(value["rows"][0]["name"], "Alice");
+    }
+}
@@ -1,4 +1,13 @@
-use ratatui::style::{Color, Style};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::errors::AppErrorKind;
+use crate::model::{known_jobs, ConnectionState, Role};
 
 // Dark purple / cyberpunk palette (foreground-only to preserve terminal background)
 pub const ACCENT: Color = Color::Rgb(200, 60, 255); // neon purple
@@ -6,72 +15,560 @@ pub const ACCENT_2: Color = Color::Rgb(0, 255, 200); // neon cyan-green
 pub const TEXT: Color = Color::Rgb(220, 210, 230);
 pub const STATUS_IDLE: Color = Color::Rgb(205, 102, 0); // dark orange
 pub const STATUS_DISCONNECTED: Color = Color::Rgb(220, 60, 60); // bright red
+pub const CHECK_PASS: Color = Color::Rgb(80, 220, 120); // mint green
+pub const CHECK_FAIL: Color = Color::Rgb(220, 60, 60); // bright red
 
-// Simple job color suggestions tuned toward purple/cyberpunk vibe
-pub fn job_color(job: &str) -> Color {
-    match job {
+/// Named color set behind every style helper. The [`Theme`] in the settings picks one and
+/// [`set_theme`] installs it; until then the default palette is used.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    pub name: &'static str,
+    pub text: Color,
+    pub accent: Color,
+    pub value: Color,
+    /// Background of the selected row in history lists.
+    pub selection: Color,
+    pub status_idle: Color,
+    pub status_disconnected: Color,
+    pub check_pass: Color,
+    pub check_fail: Color,
+    /// Name colors for jobs missing from `jobs`.
+    pub role_text: RoleColors,
+    /// Background meter colors.
+    pub role_bar: RoleColors,
+    /// Per-job name colors; jobs not listed fall back to `role_text`.
+    pub jobs: &'static [(&'static str, Color)],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoleColors {
+    pub tank: Color,
+    pub healer: Color,
+    pub dps: Color,
+}
+
+impl RoleColors {
+    pub fn for_role(&self, role: Role) -> Color {
+        match role {
+            Role::Tank => self.tank,
+            Role::Healer => self.healer,
+            Role::Dps => self.dps,
+        }
+    }
+}
+
+impl Palette {
+    pub const ALL: [&'static Palette; 4] =
+        [&DEFAULT_PALETTE, &LIGHT, &HIGH_CONTRAST, &DEUTERANOPIA];
+
+    /// Looks up a built-in palette, ignoring case and treating `_` like `-`.
+    pub fn by_name(name: &str) -> Option<&'static Palette> {
+        let name = name.trim().to_ascii_lowercase().replace('_', "-");
+        Self::ALL.into_iter().find(|palette| palette.name == name)
+    }
+
+    /// A copy with the `[theme]` colors from the config applied on top.
+    fn with_overrides(&self, overrides: &ColorOverrides) -> Palette {
+        let mut palette = self.clone();
+        for (element, color) in &overrides.elements {
+            palette.set_element(element, *color);
+        }
+        if !overrides.jobs.is_empty() {
+            let jobs: Vec<(&'static str, Color)> = self
+                .jobs
+                .iter()
+                .filter(|(code, _)| !overrides.jobs.iter().any(|(job, _)| job == code))
+                .chain(&overrides.jobs)
+                .copied()
+                .collect();
+            palette.jobs = jobs.leak();
+        }
+        palette
+    }
+
+    /// Sets the color named `element` (see [`ColorOverrides::parse`]); false for unknown names.
+    fn set_element(&mut self, element: &str, color: Color) -> bool {
+        let slot = match element {
+            "text" => &mut self.text,
+            "accent" => &mut self.accent,
+            "value" => &mut self.value,
+            "selection" => &mut self.selection,
+            "status_idle" => &mut self.status_idle,
+            "status_disconnected" => &mut self.status_disconnected,
+            "check_pass" => &mut self.check_pass,
+            "check_fail" => &mut self.check_fail,
+            "tank" => &mut self.role_text.tank,
+            "healer" => &mut self.role_text.healer,
+            "dps" => &mut self.role_text.dps,
+            "tank_bar" => &mut self.role_bar.tank,
+            "healer_bar" => &mut self.role_bar.healer,
+            "dps_bar" => &mut self.role_bar.dps,
+            _ => return false,
+        };
+        *slot = color;
+        true
+    }
+
+    pub fn job_color(&self, job: &str) -> Color {
+        if let Some((_, color)) = self.jobs.iter().find(|(code, _)| *code == job) {
+            *color
+        } else if known_jobs().contains(job) {
+            self.role_text.for_role(Role::from_job(job))
+        } else {
+            self.accent
+        }
+    }
+}
+
+// Dark purple / cyberpunk look; job colors tuned toward the same vibe
+pub static DEFAULT_PALETTE: Palette = Palette {
+    name: "default",
+    text: TEXT,
+    accent: ACCENT,
+    value: ACCENT_2,
+    selection: Color::Cyan,
+    status_idle: STATUS_IDLE,
+    status_disconnected: STATUS_DISCONNECTED,
+    check_pass: CHECK_PASS,
+    check_fail: CHECK_FAIL,
+    role_text: RoleColors {
+        tank: Color::Rgb(180, 160, 255),
+        healer: Color::Rgb(120, 255, 230),
+        dps: Color::Rgb(255, 120, 160),
+    },
+    // Tanks → blue(75), Healers → green(41), DPS → red(124)
+    role_bar: RoleColors {
+        tank: Color::Indexed(75),
+        healer: Color::Indexed(41),
+        dps: Color::Indexed(124),
+    },
+    jobs: &[
         // Tanks
-        "PLD" => Color::Rgb(180, 160, 255),
-        "WAR" => Color::Rgb(255, 120, 120),
-        "DRK" => Color::Rgb(150, 60, 200),
-        "GNB" => Color::Rgb(200, 120, 255),
+        ("PLD", Color::Rgb(180, 160, 255)),
+        ("WAR", Color::Rgb(255, 120, 120)),
+        ("DRK", Color::Rgb(150, 60, 200)),
+        ("GNB", Color::Rgb(200, 120, 255)),
         // Healers
-        "WHM" => Color::Rgb(200, 220, 255),
-        "SCH" => Color::Rgb(120, 200, 255),
-        "AST" => Color::Rgb(255, 180, 255),
-        "SGE" => Color::Rgb(120, 255, 230),
+        ("WHM", Color::Rgb(200, 220, 255)),
+        ("SCH", Color::Rgb(120, 200, 255)),
+        ("AST", Color::Rgb(255, 180, 255)),
+        ("SGE", Color::Rgb(120, 255, 230)),
         // Melee
-        "MNK" => Color::Rgb(255, 200, 140),
-        "DRG" => Color::Rgb(140, 160, 255),
-        "NIN" => Color::Rgb(255, 100, 200),
-        "SAM" => Color::Rgb(255, 120, 160),
-        "RPR" => Color::Rgb(180, 80, 180),
-        "VPR" => Color::Rgb(220, 120, 255),
+        ("MNK", Color::Rgb(255, 200, 140)),
+        ("DRG", Color::Rgb(140, 160, 255)),
+        ("NIN", Color::Rgb(255, 100, 200)),
+        ("SAM", Color::Rgb(255, 120, 160)),
+        ("RPR", Color::Rgb(180, 80, 180)),
+        ("VPR", Color::Rgb(220, 120, 255)),
         // Ranged phys
-        "BRD" => Color::Rgb(255, 200, 255),
-        "MCH" => Color::Rgb(160, 255, 220),
-        "DNC" => Color::Rgb(255, 160, 220),
+        ("BRD", Color::Rgb(255, 200, 255)),
+        ("MCH", Color::Rgb(160, 255, 220)),
+        ("DNC", Color::Rgb(255, 160, 220)),
         // Casters
-        "BLM" => Color::Rgb(120, 120, 255),
-        "SMN" => Color::Rgb(120, 255, 160),
-        "RDM" => Color::Rgb(255, 160, 200),
-        "PCT" => Color::Rgb(180, 220, 255),
+        ("BLM", Color::Rgb(120, 120, 255)),
+        ("SMN", Color::Rgb(120, 255, 160)),
+        ("RDM", Color::Rgb(255, 160, 200)),
+        ("PCT", Color::Rgb(180, 220, 255)),
         // Limited
-        "BLU" => Color::Rgb(140, 200, 255),
+        ("BLU", Color::Rgb(140, 200, 255)),
         // Pre-Jobs
-        "GLD" => Color::Rgb(255, 200, 140),
-        "PGL" => Color::Rgb(140, 160, 255),
-        "MRD" => Color::Rgb(255, 100, 200),
-        "LNC" => Color::Rgb(255, 120, 160),
-        "ARC" => Color::Rgb(180, 80, 180),
-        "CNJ" => Color::Rgb(120, 255, 230),
-        "THM" => Color::Rgb(220, 120, 255),
-        "ROG" => Color::Rgb(120, 200, 255),
-        _ => ACCENT,
+        ("GLD", Color::Rgb(255, 200, 140)),
+        ("PGL", Color::Rgb(140, 160, 255)),
+        ("MRD", Color::Rgb(255, 100, 200)),
+        ("LNC", Color::Rgb(255, 120, 160)),
+        ("ARC", Color::Rgb(180, 80, 180)),
+        ("CNJ", Color::Rgb(120, 255, 230)),
+        ("THM", Color::Rgb(220, 120, 255)),
+        ("ROG", Color::Rgb(120, 200, 255)),
+    ],
+};
+
+// Dark text and pale meters for light terminal backgrounds; names are colored by role only
+pub static LIGHT: Palette = Palette {
+    name: "light",
+    text: Color::Rgb(40, 40, 50),
+    accent: Color::Rgb(120, 0, 170),
+    value: Color::Rgb(0, 100, 130),
+    selection: Color::Rgb(150, 200, 255),
+    status_idle: Color::Rgb(180, 90, 0),
+    status_disconnected: Color::Rgb(190, 0, 0),
+    check_pass: Color::Rgb(0, 130, 50),
+    check_fail: Color::Rgb(190, 0, 0),
+    role_text: RoleColors {
+        tank: Color::Rgb(30, 80, 200),
+        healer: Color::Rgb(0, 120, 60),
+        dps: Color::Rgb(180, 30, 60),
+    },
+    role_bar: RoleColors {
+        tank: Color::Rgb(180, 205, 255),
+        healer: Color::Rgb(180, 235, 195),
+        dps: Color::Rgb(255, 190, 200),
+    },
+    jobs: &[],
+};
+
+// Saturated, bright colors on any dark background; names are colored by role only
+pub static HIGH_CONTRAST: Palette = Palette {
+    name: "high-contrast",
+    text: Color::Rgb(255, 255, 255),
+    accent: Color::Rgb(255, 230, 0),
+    value: Color::Rgb(0, 255, 255),
+    selection: Color::Rgb(255, 230, 0),
+    status_idle: Color::Rgb(255, 160, 0),
+    status_disconnected: Color::Rgb(255, 60, 60),
+    check_pass: Color::Rgb(0, 255, 0),
+    check_fail: Color::Rgb(255, 60, 60),
+    role_text: RoleColors {
+        tank: Color::Rgb(90, 170, 255),
+        healer: Color::Rgb(0, 255, 0),
+        dps: Color::Rgb(255, 90, 90),
+    },
+    role_bar: RoleColors {
+        tank: Color::Indexed(27),
+        healer: Color::Indexed(28),
+        dps: Color::Indexed(160),
+    },
+    jobs: &[],
+};
+
+// Okabe–Ito colors: roles and pass/fail never rely on telling red from green
+pub static DEUTERANOPIA: Palette = Palette {
+    name: "deuteranopia",
+    text: Color::Rgb(230, 230, 230),
+    accent: Color::Rgb(204, 121, 167),
+    value: Color::Rgb(240, 228, 66),
+    selection: Color::Rgb(86, 180, 233),
+    status_idle: Color::Rgb(230, 159, 0),
+    status_disconnected: Color::Rgb(213, 94, 0),
+    check_pass: Color::Rgb(86, 180, 233),
+    check_fail: Color::Rgb(230, 159, 0),
+    role_text: RoleColors {
+        tank: Color::Rgb(86, 180, 233),
+        healer: Color::Rgb(240, 228, 66),
+        dps: Color::Rgb(230, 159, 0),
+    },
+    role_bar: RoleColors {
+        tank: Color::Rgb(0, 114, 178),
+        healer: Color::Rgb(0, 158, 115),
+        dps: Color::Rgb(213, 94, 0),
+    },
+    jobs: &[],
+};
+
+/// User-selectable color theme, one per built-in [`Palette`]; stored as `palette` in the config.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    Deuteranopia,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [
+        Theme::Dark,
+        Theme::Light,
+        Theme::HighContrast,
+        Theme::Deuteranopia,
+    ];
+
+    pub fn palette(self) -> &'static Palette {
+        Palette::ALL[self.index()]
+    }
+
+    fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|theme| *theme == self)
+            .unwrap_or(0)
+    }
+
+    pub fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+            Theme::HighContrast => "High contrast",
+            Theme::Deuteranopia => "Deuteranopia",
+        }
+    }
+
+    pub fn config_key(self) -> &'static str {
+        self.palette().name
     }
+
+    /// The theme whose palette is named `name` (see [`Palette::by_name`]).
+    pub fn by_name(name: &str) -> Option<Self> {
+        let palette = Palette::by_name(name)?;
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.palette().name == palette.name)
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        Self::by_name(key.as_ref()).unwrap_or_default()
+    }
+}
+
+/// Colors from the config's `[theme]` table, checked once at startup.
+#[derive(Debug, Default)]
+struct ColorOverrides {
+    /// Element names accepted by `Palette::set_element`.
+    elements: Vec<(String, Color)>,
+    /// Per-job name colors, keyed by job code.
+    jobs: Vec<(&'static str, Color)>,
+}
+
+impl ColorOverrides {
+    /// Reads `element = "#rrggbb"` pairs. Keys are palette elements (`text`, `accent`, `value`,
+    /// `selection`, `status_idle`, `status_disconnected`, `check_pass`, `check_fail`, `tank`,
+    /// `healer`, `dps` and the meter colors `tank_bar`, `healer_bar`, `dps_bar`) or job codes.
+    /// Unknown keys and invalid colors are logged and skipped, keeping the palette's color.
+    fn parse(colors: &HashMap<String, String>) -> Self {
+        let mut entries: Vec<(&String, &String)> = colors.iter().collect();
+        entries.sort();
+        let mut overrides = Self::default();
+        let mut probe = DEFAULT_PALETTE.clone();
+        for (key, value) in entries {
+            let Some(color) = parse_hex_color(value) else {
+                warn!(element = %key, value = %value, "invalid theme color, keeping the palette's");
+                continue;
+            };
+            let job = key.trim().to_ascii_uppercase();
+            if let Some(code) = known_jobs().get(job.as_str()) {
+                overrides.jobs.push((code, color));
+                continue;
+            }
+            let element = key.trim().to_ascii_lowercase().replace('-', "_");
+            if probe.set_element(&element, color) {
+                overrides.elements.push((element, color));
+            } else {
+                warn!(element = %key, "unknown theme element, ignoring it");
+            }
+        }
+        overrides
+    }
+
+    fn is_empty(&self) -> bool {
+        self.elements.is_empty() && self.jobs.is_empty()
+    }
+}
+
+/// `#rrggbb`, `rrggbb` or the short `#rgb` form.
+fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match hex.len() {
+        6 => Some(Color::Rgb(
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        )),
+        3 => {
+            let short = |i: usize| channel(&hex[i..=i]).map(|v| v * 17);
+            Some(Color::Rgb(short(0)?, short(1)?, short(2)?))
+        }
+        _ => None,
+    }
+}
+
+/// The built-in palettes with the config's `[theme]` colors applied, in `Palette::ALL` order.
+static CUSTOMIZED: OnceLock<Vec<&'static Palette>> = OnceLock::new();
+
+/// Applies the config's `[theme]` colors on top of every built-in palette, so they stick
+/// whichever theme is selected. Only the first call takes effect.
+pub fn install_overrides(colors: &HashMap<String, String>) {
+    let overrides = ColorOverrides::parse(colors);
+    if overrides.is_empty() {
+        return;
+    }
+    let palettes = Palette::ALL
+        .iter()
+        .map(|base| &*Box::leak(Box::new(base.with_overrides(&overrides))))
+        .collect();
+    let _ = CUSTOMIZED.set(palettes);
+}
+
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// Installs the palette used by every style helper. The UI calls it with the theme from the
+/// settings on every draw, so a change in the settings pane shows up on the next frame.
+pub fn set_theme(theme: Theme) {
+    ACTIVE.store(theme.index(), Ordering::Relaxed);
+}
+
+pub fn palette() -> &'static Palette {
+    let index = ACTIVE.load(Ordering::Relaxed);
+    let palette = match CUSTOMIZED.get() {
+        Some(customized) => customized.get(index),
+        None => Palette::ALL.get(index),
+    };
+    palette.copied().unwrap_or(&DEFAULT_PALETTE)
+}
+
+pub fn job_color(job: &str) -> Color {
+    palette().job_color(job)
 }
 
 pub fn header_style() -> Style {
-    Style::default().fg(TEXT)
+    Style::default().fg(palette().text)
 }
 pub fn title_style() -> Style {
-    Style::default().fg(ACCENT)
+    Style::default().fg(palette().accent)
 }
 pub fn value_style() -> Style {
-    Style::default().fg(ACCENT_2)
+    Style::default().fg(palette().value)
+}
+pub fn selection_style() -> Style {
+    Style::default()
+        .fg(Color::Black)
+        .bg(palette().selection)
+        .add_modifier(Modifier::BOLD)
 }
 
-// Role-based color for DPS bars (xterm 256-indexed colors)
-// Tanks → blue(75), Healers → green(41), DPS → red(124)
-pub fn role_bar_color(job: &str) -> Color {
-    match job {
-        // Tanks
-        "PLD" | "WAR" | "DRK" | "GNB" | "GLD" | "MRD" => Color::Indexed(75),
-        // Healers
-        "WHM" | "SCH" | "AST" | "SGE" | "CNJ" => Color::Indexed(41),
-        // Everything else treated as DPS
-        _ => Color::Indexed(124),
+// Pass/fail coloring for values compared against a configured target; neutral without one
+pub fn check_style(passed: Option<bool>) -> Style {
+    match passed {
+        Some(true) => Style::default()
+            .fg(palette().check_pass)
+            .add_modifier(Modifier::BOLD),
+        Some(false) => Style::default()
+            .fg(palette().check_fail)
+            .add_modifier(Modifier::BOLD),
+        None => value_style(),
     }
 }
 
+/// Header connection dot: green when connected, red when down, yellow while (re)connecting.
+pub fn connection_style(state: ConnectionState) -> Style {
+    let palette = palette();
+    let color = match state {
+        ConnectionState::Connected => palette.check_pass,
+        ConnectionState::Disconnected => palette.status_disconnected,
+        ConnectionState::Connecting | ConnectionState::Reconnecting { .. } => palette.status_idle,
+    };
+    Style::default().fg(color).add_modifier(Modifier::BOLD)
+}
+
+// Error log coloring per error kind
+pub fn error_kind_style(kind: AppErrorKind) -> Style {
+    let palette = palette();
+    let color = match kind {
+        AppErrorKind::Network | AppErrorKind::Clipboard => palette.status_idle,
+        AppErrorKind::Storage | AppErrorKind::History => palette.status_disconnected,
+        AppErrorKind::Unknown => palette.text,
+    };
+    Style::default().fg(color).add_modifier(Modifier::BOLD)
+}
+
+// Role-based color for DPS bars
+pub fn role_bar_color(job: &str) -> Color {
+    palette().role_bar.for_role(Role::from_job(job))
+}
+
 // Gradient helpers removed; we use solid role colors for bars.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palettes_resolve_by_name() {
+        for palette in Palette::ALL {
+            assert_eq!(
+                Palette::by_name(palette.name).map(|p| p.name),
+                Some(palette.name)
+            );
+        }
+        assert_eq!(
+            Palette::by_name(" High_Contrast ").map(|p| p.name),
+            Some("high-contrast")
+        );
+        assert!(Palette::by_name("solarized").is_none());
+    }
+
+    #[test]
+    fn themes_cycle_through_every_palette_and_round_trip() {
+        let mut theme = Theme::default();
+        let mut seen = Vec::new();
+        for _ in 0..Theme::ALL.len() {
+            seen.push(theme.palette().name);
+            assert_eq!(Theme::from_config_key(theme.config_key()), theme);
+            theme = theme.next();
+        }
+        assert_eq!(theme, Theme::default());
+        assert_eq!(seen, Palette::ALL.map(|palette| palette.name));
+        assert_eq!(Theme::Dark.prev(), Theme::Deuteranopia);
+        assert_eq!(Theme::from_config_key("solarized"), Theme::Dark);
+    }
+
+    #[test]
+    fn hex_colors_parse_in_long_and_short_form() {
+        assert_eq!(parse_hex_color("#ff8000"), Some(Color::Rgb(255, 128, 0)));
+        assert_eq!(parse_hex_color(" 0a0B0c "), Some(Color::Rgb(10, 11, 12)));
+        assert_eq!(parse_hex_color("#f80"), Some(Color::Rgb(255, 136, 0)));
+        assert_eq!(parse_hex_color("#ff80"), None);
+        assert_eq!(parse_hex_color("#gg0000"), None);
+        assert_eq!(parse_hex_color("red"), None);
+    }
+
+    #[test]
+    fn overrides_replace_valid_elements_and_keep_the_rest() {
+        let colors: HashMap<String, String> = [
+            ("accent", "#112233"),
+            ("Tank-Bar", "#445566"),
+            ("war", "#010203"),
+            ("value", "not a color"),
+            ("sparkles", "#ffffff"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+        let overrides = ColorOverrides::parse(&colors);
+        let custom = DEFAULT_PALETTE.with_overrides(&overrides);
+        assert_eq!(custom.accent, Color::Rgb(0x11, 0x22, 0x33));
+        assert_eq!(custom.role_bar.tank, Color::Rgb(0x44, 0x55, 0x66));
+        assert_eq!(custom.job_color("WAR"), Color::Rgb(1, 2, 3));
+        assert_eq!(custom.job_color("PLD"), DEFAULT_PALETTE.job_color("PLD"));
+        assert_eq!(custom.value, DEFAULT_PALETTE.value);
+        assert_eq!(custom.text, DEFAULT_PALETTE.text);
+        assert!(ColorOverrides::parse(&HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn default_palette_keeps_the_stock_job_colors() {
+        assert_eq!(DEFAULT_PALETTE.job_color("WAR"), Color::Rgb(255, 120, 120));
+        assert_eq!(DEFAULT_PALETTE.job_color("???"), ACCENT);
+        assert_eq!(DEFAULT_PALETTE.role_bar.tank, Color::Indexed(75));
+    }
+
+    #[test]
+    fn accessible_palettes_keep_roles_and_checks_apart() {
+        for palette in [&LIGHT, &HIGH_CONTRAST, &DEUTERANOPIA] {
+            let roles = [palette.role_text, palette.role_bar];
+            for colors in roles {
+                assert_ne!(colors.tank, colors.healer, "{}", palette.name);
+                assert_ne!(colors.healer, colors.dps, "{}", palette.name);
+                assert_ne!(colors.tank, colors.dps, "{}", palette.name);
+            }
+            assert_ne!(palette.check_pass, palette.check_fail, "{}", palette.name);
+            assert_eq!(palette.job_color("WHM"), palette.role_text.healer);
+            assert_eq!(palette.job_color("GLD"), palette.role_text.tank);
+        }
+        // No red/green pairs for deuteranopia: pass is blue, fail is orange.
+        assert_eq!(DEUTERANOPIA.check_pass, Color::Rgb(86, 180, 233));
+        assert_eq!(DEUTERANOPIA.check_fail, Color::Rgb(230, 159, 0));
+    }
+}
@@ -0,0 +1,47 @@
+use ratatui::layout::{Alignment, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::model::ConfirmAction;
+use crate::theme::{header_style, title_style, value_style};
+
+pub(super) fn draw(f: &mut Frame, action: &ConfirmAction) {
+    let area = prompt_rect(f.size());
+    if area.height < 3 {
+        return;
+    }
+    f.render_widget(Clear, area);
+
+    let lines = vec![
+        Line::from(Span::styled(action.prompt(), value_style())),
+        Line::default(),
+        Line::from(vec![
+            Span::styled(" y ", title_style()),
+            Span::styled("confirm", header_style()),
+            Span::raw("   "),
+            Span::styled(" any other key ", title_style()),
+            Span::styled("cancel", header_style()),
+        ]),
+    ];
+    let widget = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled("Confirm", title_style())),
+        );
+    f.render_widget(widget, area);
+}
+
+fn prompt_rect(area: Rect) -> Rect {
+    let width = area.width.min(60);
+    let height = area.height.min(6);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
@@ -0,0 +1,74 @@
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::keys::KeyAction;
+use crate::model::{AppSnapshot, ClockFormat};
+use crate::theme::{error_kind_style, header_style, title_style};
+
+pub(super) fn draw(f: &mut Frame, snapshot: &AppSnapshot) {
+    let area = panel_rect(f.size());
+    if area.height < 3 {
+        return;
+    }
+    f.render_widget(Clear, area);
+
+    let lines: Vec<Line> = if snapshot.error_log.is_empty() {
+        vec![Line::from(Span::styled(
+            "No errors recorded.",
+            header_style(),
+        ))]
+    } else {
+        snapshot
+            .error_log
+            .iter()
+            .map(|entry| {
+                let kind = entry.error.kind();
+                Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "{} ",
+                            time_label(entry.at_ms, snapshot.settings.clock_format)
+                        ),
+                        header_style(),
+                    ),
+                    Span::styled(format!("{:<8}", kind.label()), error_kind_style(kind)),
+                    Span::styled(entry.error.summary_line().into_owned(), header_style()),
+                ])
+            })
+            .collect()
+    };
+
+    let keys = &snapshot.key_bindings;
+    let title = format!(
+        "Errors · {} · {} clears · {} / {} closes",
+        snapshot.error_log.len(),
+        keys.label(KeyAction::Copy),
+        keys.label(KeyAction::ErrorLog),
+        keys.label(KeyAction::Quit)
+    );
+    let widget = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled(title, title_style())),
+    );
+    f.render_widget(widget, area);
+}
+
+fn time_label(ms: u64, clock: ClockFormat) -> String {
+    clock
+        .format_local(ms, false, true)
+        .unwrap_or_else(|| "--:--:--".to_string())
+}
+
+fn panel_rect(area: Rect) -> Rect {
+    let width = area.width.saturating_sub(4).min(120);
+    let height = area.height.saturating_sub(4).min(20);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
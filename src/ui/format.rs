@@ -0,0 +1,85 @@
+//! Formatting helpers shared by the live and history views.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+pub(crate) fn format_duration_short(total_secs: u64) -> String {
+    if total_secs == 0 {
+        return "00:00".to_string();
+    }
+    let hours = total_secs / 3600;
+    let minutes = (total_secs % 3600) / 60;
+    let seconds = total_secs % 60;
+    if hours > 0 {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// "950", "12.3k", "1.2M".
+pub(crate) fn format_compact(value: f64) -> String {
+    if value >= 1_000_000.0 {
+        format!("{:.1}M", value / 1_000_000.0)
+    } else if value >= 1_000.0 {
+        format!("{:.1}k", value / 1_000.0)
+    } else {
+        format!("{value:.0}")
+    }
+}
+
+/// Cuts `text` to at most `max` terminal columns, ending in "…" when anything was dropped.
+/// Wide (CJK) characters count as two columns and combining marks stay with their base.
+pub(crate) fn truncate_display(text: &str, max: usize) -> String {
+    if text.width() <= max {
+        return text.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let budget = max - 1;
+    let mut used = 0;
+    let mut out = String::new();
+    for ch in text.chars() {
+        let width = ch.width().unwrap_or(0);
+        if used + width > budget {
+            break;
+        }
+        used += width;
+        out.push(ch);
+    }
+    out.push('…');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_compact_abbreviates_thousands_and_millions() {
+        assert_eq!(format_compact(950.4), "950");
+        assert_eq!(format_compact(12_345.0), "12.3k");
+        assert_eq!(format_compact(1_234_567.0), "1.2M");
+    }
+
+    #[test]
+    fn truncate_display_respects_column_width() {
+        assert_eq!(truncate_display("Titan", 10), "Titan");
+        assert_eq!(truncate_display("The Navel (Extreme)", 10), "The Navel…");
+        assert_eq!(truncate_display("abc", 0), "");
+
+        // Each CJK character takes two columns; never split one across the limit.
+        let cjk = "極タイタン討滅戦";
+        assert_eq!(cjk.width(), 16);
+        assert_eq!(truncate_display(cjk, 8), "極タイ…");
+        assert_eq!(truncate_display(cjk, 7).width(), 7);
+        assert_eq!(truncate_display(cjk, 6).width(), 5);
+    }
+
+    #[test]
+    fn truncate_display_keeps_combining_marks_with_their_base() {
+        let text = "Cafe\u{301} Ruse\u{301}e Extreme";
+        assert_eq!(truncate_display(text, 5), "Cafe\u{301}…");
+        assert_eq!(truncate_display(text, 5).width(), 5);
+    }
+}
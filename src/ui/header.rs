@@ -1,18 +1,25 @@
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use ratatui::style::Style;
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
 
-use crate::model::{AppSnapshot, ViewMode};
-use crate::theme::{header_style, value_style, TEXT};
+use crate::history::util::parse_number;
+use crate::history::DungeonProgress;
+use crate::model::{AppSnapshot, EncounterSummary, ViewMode};
+use crate::theme::{check_style, connection_style, header_style, title_style, value_style};
+
+use super::format::{format_compact, format_duration_short};
 
 pub(super) fn draw(f: &mut Frame, area: Rect, snapshot: &AppSnapshot) {
     let block = Block::default().borders(Borders::NONE);
     let width = area.width as usize;
 
     let top_line = header_metrics_line(snapshot, width);
-    let bottom_line = header_title_line(snapshot, width);
+    let mut bottom_line = header_title_line(snapshot, width);
+    bottom_line
+        .spans
+        .insert(0, Span::styled("● ", connection_style(snapshot.connection)));
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -27,15 +34,107 @@ pub(super) fn draw(f: &mut Frame, area: Rect, snapshot: &AppSnapshot) {
 
     let top_widget = Paragraph::new(bottom_line)
         .block(block.clone())
-        .style(Style::default().fg(TEXT))
+        .style(header_style())
         .alignment(Alignment::Left);
     f.render_widget(top_widget, top_area);
 
     let bottom_widget = Paragraph::new(top_line)
         .block(block)
-        .style(Style::default().fg(TEXT))
+        .style(header_style())
         .alignment(Alignment::Left);
     f.render_widget(bottom_widget, bottom_area);
+
+    if let Some(timer) = combat_timer_line(snapshot, width) {
+        f.render_widget(Paragraph::new(timer).alignment(Alignment::Right), top_area);
+    }
+
+    if let Some(hud) = snapshot
+        .dungeon_progress
+        .as_ref()
+        .filter(|_| chunks[2].height > 0)
+        .and_then(|progress| dungeon_hud_line(progress, width))
+    {
+        f.render_widget(Paragraph::new(hud), chunks[2]);
+    }
+
+    if snapshot.combat_start_flash && chunks[2].height > 0 {
+        let flash = Span::styled("Combat Start! ", title_style().add_modifier(Modifier::BOLD));
+        f.render_widget(
+            Paragraph::new(Line::from(flash)).alignment(Alignment::Right),
+            chunks[2],
+        );
+    }
+}
+
+/// Running totals of the open dungeon run, e.g. "Run: Sastasha · 3 pulls · 02:15 · 1234567 dmg".
+fn dungeon_hud_line(progress: &DungeonProgress, width: usize) -> Option<Line<'static>> {
+    if width < 40 {
+        return None;
+    }
+    let pulls = match progress.pulls {
+        1 => "1 pull".to_string(),
+        n => format!("{n} pulls"),
+    };
+    let mut spans = vec![
+        Span::styled("Run:", header_style()),
+        Span::styled(format!(" {} ", progress.zone), value_style()),
+        Span::styled(format!("· {pulls} · "), header_style()),
+        Span::styled(
+            format_duration_short(progress.total_duration_secs as u64),
+            value_style(),
+        ),
+    ];
+    if width >= 72 {
+        spans.extend([
+            Span::styled(" · ", header_style()),
+            Span::styled(format_compact(progress.total_damage), value_style()),
+            Span::styled(" dmg · ", header_style()),
+            Span::styled(format_compact(progress.encdps()), value_style()),
+            Span::styled(" DPS", header_style()),
+        ]);
+    }
+    Some(Line::from(spans))
+}
+
+/// Remaining enrage time at which the countdown turns red regardless of pace.
+const ENRAGE_WARNING_SECS: u64 = 60;
+
+/// Real-time combat clock for the right edge of the title row, followed by the enrage
+/// countdown when the zone has one configured.
+fn combat_timer_line(snapshot: &AppSnapshot, width: usize) -> Option<Line<'static>> {
+    if width < 40 {
+        return None;
+    }
+    let secs = snapshot.combat_elapsed_secs?;
+    let style = if snapshot.combat_running {
+        value_style().add_modifier(Modifier::BOLD)
+    } else {
+        header_style()
+    };
+    let mut spans = vec![
+        Span::styled("⏱ ", header_style()),
+        Span::styled(format_duration_short(secs), style),
+    ];
+    if let Some((remaining, style)) = enrage_countdown(snapshot, secs).filter(|_| width >= 56) {
+        spans.push(Span::styled(" · enrage ", header_style()));
+        spans.push(Span::styled(format_duration_short(remaining), style));
+    }
+    spans.push(Span::raw(" "));
+    Some(Line::from(spans))
+}
+
+/// Time left before the zone's enrage and how to color it: red once inside the warning
+/// window, otherwise green/red by the DPS check when one is configured.
+fn enrage_countdown(snapshot: &AppSnapshot, elapsed: u64) -> Option<(u64, Style)> {
+    let enc = snapshot.encounter.as_ref()?;
+    let enrage = snapshot.settings.enrage_secs_for(&enc.zone)?;
+    let remaining = enrage.saturating_sub(elapsed);
+    let style = if remaining <= ENRAGE_WARNING_SECS {
+        check_style(Some(false))
+    } else {
+        check_style(dps_check(snapshot, enc).map(|(_, passed)| passed))
+    };
+    Some((remaining, style))
 }
 
 fn header_metrics_line(snapshot: &AppSnapshot, width: usize) -> Line<'static> {
@@ -44,39 +143,59 @@ fn header_metrics_line(snapshot: &AppSnapshot, width: usize) -> Line<'static> {
             ViewMode::Dps => ("ENCDPS", enc.encdps.as_str(), "Damage", enc.damage.as_str()),
             ViewMode::Heal => ("ENCHPS", enc.enchps.as_str(), "Healed", enc.healed.as_str()),
         };
+        let check = dps_check(snapshot, enc);
+        let metric_style = check_style(check.map(|(_, passed)| passed));
 
         if width >= 56 {
-            Line::from(vec![
+            let mut spans = vec![
                 Span::styled("Dur:", header_style()),
                 Span::styled(format!(" {} ", enc.duration), value_style()),
                 Span::raw("| "),
                 Span::styled(format!("{}:", metric_label), header_style()),
-                Span::styled(format!(" {} ", metric_val), value_style()),
+                Span::styled(format!(" {} ", metric_val), metric_style),
+            ];
+            if let Some((threshold, _)) = check.filter(|_| width >= 72) {
+                spans.push(Span::styled(
+                    format!("(target {threshold:.0}) "),
+                    header_style(),
+                ));
+            }
+            spans.extend([
                 Span::raw("| "),
                 Span::styled(format!("{}:", total_label), header_style()),
                 Span::styled(format!(" {}", total_val), value_style()),
-            ])
+            ]);
+            Line::from(spans)
         } else if width >= 40 {
             Line::from(vec![
                 Span::styled("Dur:", header_style()),
                 Span::styled(format!(" {} ", enc.duration), value_style()),
                 Span::styled(format!("{}:", metric_label), header_style()),
-                Span::styled(format!(" {}", metric_val), value_style()),
+                Span::styled(format!(" {}", metric_val), metric_style),
             ])
         } else if width >= 28 {
             Line::from(vec![
                 Span::styled(enc.duration.clone(), value_style()),
                 Span::raw("  "),
-                Span::styled(metric_val.to_string(), value_style()),
+                Span::styled(metric_val.to_string(), metric_style),
             ])
         } else {
-            Line::from(vec![Span::styled(metric_val.to_string(), value_style())])
+            Line::from(vec![Span::styled(metric_val.to_string(), metric_style)])
         }
     } else {
         Line::from(vec![Span::raw("Waiting for data...")])
     }
 }
 
+/// Party DPS against the zone's configured target, as `(threshold, passed)`.
+fn dps_check(snapshot: &AppSnapshot, enc: &EncounterSummary) -> Option<(f64, bool)> {
+    if snapshot.mode != ViewMode::Dps {
+        return None;
+    }
+    let threshold = snapshot.settings.dps_threshold_for(&enc.zone)?;
+    Some((threshold, parse_number(&enc.encdps) >= threshold))
+}
+
 fn header_title_line(snapshot: &AppSnapshot, width: usize) -> Line<'static> {
     if let Some(enc) = &snapshot.encounter {
         let display_title = if enc.title.is_empty()
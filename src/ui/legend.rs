@@ -0,0 +1,86 @@
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::keys::KeyAction;
+use crate::model::{AppSnapshot, Decoration, ViewMode};
+use crate::theme::{header_style, role_bar_color, title_style, value_style};
+
+/// Explains what the decorations, modes and role colors mean. Built from the enum metadata so
+/// new variants show up automatically.
+pub(super) fn draw(f: &mut Frame, snapshot: &AppSnapshot) {
+    let lines = legend_lines(snapshot);
+    let area = legend_rect(f.size(), lines.len() as u16 + 2);
+    if area.height < 3 {
+        return;
+    }
+    f.render_widget(Clear, area);
+    let title = format!(
+        "Legend · {} / {} to close",
+        snapshot.key_bindings.label(KeyAction::Legend),
+        snapshot.key_bindings.label(KeyAction::Quit)
+    );
+    let widget = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled(title, title_style())),
+    );
+    f.render_widget(widget, area);
+}
+
+fn legend_lines(snapshot: &AppSnapshot) -> Vec<Line<'static>> {
+    let keys = &snapshot.key_bindings;
+    let mut lines = vec![Line::from(Span::styled(
+        format!("Decorations ({})", keys.label(KeyAction::CycleDecoration)),
+        title_style(),
+    ))];
+    for decoration in Decoration::ALL {
+        lines.push(entry_line(
+            decoration.label(),
+            decoration.description(),
+            decoration == snapshot.decoration,
+        ));
+    }
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(
+        format!("Modes ({})", keys.label(KeyAction::CycleMode)),
+        title_style(),
+    )));
+    for mode in ViewMode::ALL {
+        lines.push(entry_line(
+            mode.label(),
+            mode.description(),
+            mode == snapshot.mode,
+        ));
+    }
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled("Role colors", title_style())));
+    let mut roles = vec![Span::raw("  ")];
+    for (label, job) in [("Tank", "PLD"), ("Healer", "WHM"), ("DPS", "NIN")] {
+        roles.push(Span::styled("  ", Style::default().bg(role_bar_color(job))));
+        roles.push(Span::styled(format!(" {label}   "), value_style()));
+    }
+    lines.push(Line::from(roles));
+    lines
+}
+
+fn entry_line(label: &str, description: &str, active: bool) -> Line<'static> {
+    let marker = if active { "▶ " } else { "  " };
+    Line::from(vec![
+        Span::styled(format!("{marker}{label:<11}"), value_style()),
+        Span::styled(description.to_string(), header_style()),
+    ])
+}
+
+fn legend_rect(area: Rect, height: u16) -> Rect {
+    let width = area.width.min(90);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
@@ -2,20 +2,39 @@ use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::Frame;
 
 use crate::model::AppSnapshot;
-use crate::{ui_history, ui_idle};
+use crate::{theme, ui_history, ui_idle};
 
+mod confirm;
+mod error_log;
+pub(crate) mod format;
 mod header;
+mod legend;
 mod settings;
 mod status;
 mod table;
+mod toast;
+pub(crate) use settings::{settings_click, SettingsClick};
+pub(crate) use status::{footer_target_at, FooterTarget};
 pub(crate) use table::{draw_with_context as draw_table_with_context, TableRenderContext};
 
 pub fn draw(f: &mut Frame, snapshot: &AppSnapshot) {
+    theme::set_theme(snapshot.settings.theme);
     if snapshot.history.visible {
         ui_history::draw_history(f, snapshot);
-        return;
+    } else {
+        draw_live(f, snapshot);
+    }
+
+    if let Some(message) = snapshot.notice.as_deref() {
+        toast::draw(f, message);
+    }
+
+    if let Some(action) = &snapshot.pending_confirm {
+        confirm::draw(f, action);
     }
+}
 
+fn draw_live(f: &mut Frame, snapshot: &AppSnapshot) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -34,7 +53,7 @@ pub fn draw(f: &mut Frame, snapshot: &AppSnapshot) {
     }
 
     if let Some(error) = snapshot.error.as_ref() {
-        status::draw_error(f, chunks[2], error);
+        status::draw_error(f, chunks[2], error, &snapshot.key_bindings);
     } else {
         status::draw(f, chunks[2], snapshot);
     }
@@ -42,4 +61,12 @@ pub fn draw(f: &mut Frame, snapshot: &AppSnapshot) {
     if snapshot.show_settings {
         settings::draw(f, snapshot);
     }
+
+    if snapshot.show_legend {
+        legend::draw(f, snapshot);
+    }
+
+    if snapshot.show_error_log {
+        error_log::draw(f, snapshot);
+    }
 }
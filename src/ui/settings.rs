@@ -1,47 +1,130 @@
-use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Position, Rect};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use ratatui::Frame;
 
+use crate::keys::KeyAction;
 use crate::model::{AppSnapshot, SettingsField};
 use crate::theme::{header_style, title_style, value_style};
 
+/// Arrows drawn around the selected value; clicking one steps it like ←/→.
+const ARROW_PREV: &str = "◀ ";
+const ARROW_NEXT: &str = " ▶";
+
+/// What a click inside the settings popup lands on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SettingsClick {
+    Select(SettingsField),
+    /// One of the arrows around the selected value; `true` steps forward.
+    Adjust(bool),
+}
+
 pub(super) fn draw(f: &mut Frame, snapshot: &AppSnapshot) {
     let area = centered_rect(60, 50, f.size());
     f.render_widget(Clear, area);
 
-    let idle_selected = matches!(snapshot.settings_cursor, SettingsField::IdleTimeout);
-    let decor_selected = matches!(snapshot.settings_cursor, SettingsField::DefaultDecoration);
-    let mode_selected = matches!(snapshot.settings_cursor, SettingsField::DefaultMode);
-    let dungeon_selected = matches!(snapshot.settings_cursor, SettingsField::DungeonMode);
+    let lines: Vec<Line> = settings_lines(snapshot)
+        .into_iter()
+        .map(|(line, _)| line)
+        .collect();
+    let content_area = content_area(area, lines.len());
 
-    let mut lines = Vec::new();
-    //lines.push(Line::from(vec![Span::styled("Settings", title_style())]));
-    lines.push(Line::default());
+    let block = Block::default()
+        .title(Line::from(vec![Span::styled("Settings", title_style())]))
+        .borders(Borders::ALL);
+    let widget = Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Center);
+    f.render_widget(widget, content_area);
+}
+
+/// Maps a click at (`column`, `row`) on a `screen`-sized terminal to the settings line under
+/// it; clicks on padding, hints or outside the popup give `None`.
+pub(crate) fn settings_click(
+    snapshot: &AppSnapshot,
+    screen: Rect,
+    column: u16,
+    row: u16,
+) -> Option<SettingsClick> {
+    let lines = settings_lines(snapshot);
+    let inner = Block::default()
+        .borders(Borders::ALL)
+        .inner(content_area(centered_rect(60, 50, screen), lines.len()));
+    if !inner.contains(Position::new(column, row)) {
+        return None;
+    }
+    let (line, field) = lines.get(usize::from(row - inner.y))?;
+    let field = (*field)?;
+    // Paragraph centers each line, so the first cell sits halfway into the spare width.
+    let mut start = inner.x + inner.width.saturating_sub(line.width() as u16) / 2;
+    for span in &line.spans {
+        let end = start + span.width() as u16;
+        if (start..end).contains(&column) {
+            return Some(match span.content.as_ref() {
+                ARROW_PREV => SettingsClick::Adjust(false),
+                ARROW_NEXT => SettingsClick::Adjust(true),
+                _ => SettingsClick::Select(field),
+            });
+        }
+        start = end;
+    }
+    None
+}
 
-    lines.push(setting_line(
-        idle_selected,
+/// The popup's lines, each with the setting it shows (if any).
+fn settings_lines(snapshot: &AppSnapshot) -> Vec<(Line<'static>, Option<SettingsField>)> {
+    let cursor = snapshot.settings_cursor;
+    let entering = snapshot.settings_entry.is_some();
+    let setting = |field: SettingsField, label: &str, value: String| {
+        let selected = cursor == field;
+        (
+            setting_line(selected, selected && !entering, label, value),
+            Some(field),
+        )
+    };
+    let hint = |text: String| (Line::from(vec![Span::styled(text, header_style())]), None);
+
+    let mut lines = vec![(Line::default(), None)];
+
+    let idle_value = match snapshot.settings_entry.as_deref() {
+        Some(entry) if cursor == SettingsField::IdleTimeout => format!("{entry}_ s"),
+        _ => format!("{}s", snapshot.settings.idle_seconds),
+    };
+    lines.push(setting(
+        SettingsField::IdleTimeout,
         "Idle timeout",
-        format!("{}s", snapshot.settings.idle_seconds),
+        idle_value,
+    ));
+    lines.push((
+        Line::from(vec![
+            Span::raw("   "),
+            Span::styled("Set to 0 to disable idle mode.", header_style()),
+        ]),
+        None,
+    ));
+    let redraw_value = match snapshot.settings_entry.as_deref() {
+        Some(entry) if cursor == SettingsField::RedrawRate => format!("{entry}_ ms"),
+        _ => format!("{}ms", snapshot.settings.redraw_ms),
+    };
+    lines.push(setting(
+        SettingsField::RedrawRate,
+        "Redraw interval",
+        redraw_value,
     ));
-    lines.push(Line::from(vec![
-        Span::raw("   "),
-        Span::styled("Set to 0 to disable idle mode.", header_style()),
-    ]));
-    lines.push(Line::default());
-
-    lines.push(setting_line(
-        decor_selected,
+    lines.push((Line::default(), None));
+
+    lines.push(setting(
+        SettingsField::DefaultDecoration,
         "Default decoration",
         snapshot.settings.default_decoration.label().to_string(),
     ));
-    lines.push(setting_line(
-        mode_selected,
+    lines.push(setting(
+        SettingsField::DefaultMode,
         "Default mode",
         snapshot.settings.default_mode.label().to_string(),
     ));
-    lines.push(setting_line(
-        dungeon_selected,
+    lines.push(setting(
+        SettingsField::DungeonMode,
         "Dungeon Mode",
         if snapshot.settings.dungeon_mode_enabled {
             "ON".to_string()
@@ -49,23 +132,72 @@ pub(super) fn draw(f: &mut Frame, snapshot: &AppSnapshot) {
             "OFF".to_string()
         },
     ));
-    lines.push(Line::default());
-
-    lines.push(Line::from(vec![Span::styled(
-        "Use ↑/↓ to select, ←/→ to adjust.",
-        header_style(),
-    )]));
-    lines.push(Line::from(vec![Span::styled(
-        "Press 'q' or 's' to close.",
-        header_style(),
-    )]));
-    lines.push(Line::default());
-
-    // Calculate content height (lines + block borders)
-    let content_height = lines.len() as u16 + 2; // +2 for top and bottom borders
+    lines.push(setting(
+        SettingsField::HideZeroRows,
+        "Hide 0 rows",
+        if snapshot.settings.hide_zero_rows {
+            "ON".to_string()
+        } else {
+            "OFF".to_string()
+        },
+    ));
+    lines.push(setting(
+        SettingsField::EscapeBehavior,
+        "Esc / q in history",
+        snapshot.settings.escape_behavior.label().to_string(),
+    ));
+    lines.push(setting(
+        SettingsField::Theme,
+        "Theme",
+        snapshot.settings.theme.label().to_string(),
+    ));
+    lines.push((Line::default(), None));
+
+    let key = |action| snapshot.key_bindings.label(action);
+    let select = format!(
+        "Use {}/{} to select",
+        key(KeyAction::SettingsPrev),
+        key(KeyAction::SettingsNext)
+    );
+    let adjust = format!(
+        "{}/{} to adjust",
+        key(KeyAction::SettingsDecrease),
+        key(KeyAction::SettingsIncrease)
+    );
+    let controls = match cursor.numeric_range() {
+        Some(range) if entering => format!(
+            "Type a value ({}–{}), Enter saves, Esc cancels.",
+            range.start(),
+            range.end()
+        ),
+        Some(_) => format!(
+            "{select}, {adjust}, {} to type a value.",
+            key(KeyAction::SettingsEdit)
+        ),
+        None => format!("{select}, {adjust}."),
+    };
+    lines.push(hint(controls));
+    lines.push(hint(
+        "Click a setting to select it, the arrows or the wheel to adjust.".to_string(),
+    ));
+    lines.push(hint(format!(
+        "Press '{}' for a legend of decorations and modes.",
+        key(KeyAction::Legend)
+    )));
+    lines.push(hint(format!(
+        "Press '{}' or '{}' to close.",
+        key(KeyAction::Quit),
+        key(KeyAction::OpenSettings)
+    )));
+    lines.push((Line::default(), None));
+    lines
+}
+
+/// The bordered box inside `area`, sized to `line_count` lines and centered vertically.
+fn content_area(area: Rect, line_count: usize) -> Rect {
+    let content_height = line_count as u16 + 2; // +2 for top and bottom borders
     let available_height = area.height;
-    
-    // Center the content vertically
+
     let top_padding = if available_height > content_height {
         (available_height - content_height) / 2
     } else {
@@ -76,7 +208,7 @@ pub(super) fn draw(f: &mut Frame, snapshot: &AppSnapshot) {
     } else {
         0
     };
-    
+
     let vertical_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -85,19 +217,10 @@ pub(super) fn draw(f: &mut Frame, snapshot: &AppSnapshot) {
             Constraint::Length(bottom_padding),
         ])
         .split(area);
-    
-    let content_area = vertical_layout[1];
-
-    let block = Block::default()
-        .title(Line::from(vec![Span::styled("Settings", title_style())]))
-        .borders(Borders::ALL);
-    let widget = Paragraph::new(lines)
-        .block(block)
-        .alignment(Alignment::Center);
-    f.render_widget(widget, content_area);
+    vertical_layout[1]
 }
 
-fn setting_line(selected: bool, label: &str, value: String) -> Line<'static> {
+fn setting_line(selected: bool, arrows: bool, label: &str, value: String) -> Line<'static> {
     let marker = if selected { "▶" } else { " " };
     let label_style = if selected {
         title_style()
@@ -105,11 +228,18 @@ fn setting_line(selected: bool, label: &str, value: String) -> Line<'static> {
         header_style()
     };
 
-    Line::from(vec![
+    let mut spans = vec![
         Span::styled(format!("{} {}:", marker, label), label_style),
         Span::raw(" "),
-        Span::styled(value, value_style()),
-    ])
+    ];
+    if arrows {
+        spans.push(Span::styled(ARROW_PREV, title_style()));
+    }
+    spans.push(Span::styled(value, value_style()));
+    if arrows {
+        spans.push(Span::styled(ARROW_NEXT, title_style()));
+    }
+    Line::from(spans)
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
@@ -133,3 +263,50 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
 
     vertical[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clicks_select_fields_and_arrows_adjust_the_selected_one() {
+        let snapshot = AppSnapshot {
+            settings_cursor: SettingsField::DefaultMode,
+            ..Default::default()
+        };
+        let screen = Rect::new(0, 0, 100, 40);
+        let lines = settings_lines(&snapshot);
+        let inner = Block::default()
+            .borders(Borders::ALL)
+            .inner(content_area(centered_rect(60, 50, screen), lines.len()));
+        let row_of = |field| {
+            let idx = lines
+                .iter()
+                .position(|(_, f)| *f == Some(field))
+                .expect("field line");
+            inner.y + idx as u16
+        };
+        let center = inner.x + inner.width / 2;
+
+        assert_eq!(
+            settings_click(
+                &snapshot,
+                screen,
+                center,
+                row_of(SettingsField::DungeonMode)
+            ),
+            Some(SettingsClick::Select(SettingsField::DungeonMode))
+        );
+
+        let row = row_of(SettingsField::DefaultMode);
+        let (line, _) = &lines[usize::from(row - inner.y)];
+        let right_edge = inner.x + (inner.width - line.width() as u16) / 2 + line.width() as u16;
+        assert_eq!(
+            settings_click(&snapshot, screen, right_edge - 1, row),
+            Some(SettingsClick::Adjust(true))
+        );
+
+        assert_eq!(settings_click(&snapshot, screen, center, inner.y), None);
+        assert_eq!(settings_click(&snapshot, screen, 0, 0), None);
+    }
+}
@@ -3,51 +3,53 @@ use std::borrow::Cow;
 use ratatui::layout::Alignment;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use ratatui::Frame;
 
 use crate::errors::AppError;
-use crate::model::AppSnapshot;
-use crate::theme::{header_style, title_style, value_style};
+use crate::keys::{KeyAction, KeyBindings};
+use crate::model::{AppSnapshot, ConnectionState};
+use crate::theme::{header_style, palette, title_style, value_style};
 
 pub(super) fn draw(f: &mut Frame, area: ratatui::layout::Rect, snapshot: &AppSnapshot) {
-    let (status_text, status_style) = status_label(snapshot);
-    let status_span = Span::styled(status_text.clone(), status_style);
-
-    let (dungeon_text, dungeon_style) = dungeon_label(snapshot);
-    let dungeon_span = Span::styled(dungeon_text, dungeon_style);
-
-    let decor_label = snapshot
-        .decoration
-        .short_label()
-        .trim_start_matches("decor:");
-    let mode_label = snapshot.mode.short_label().trim_start_matches("mode:");
-    let history_style = if snapshot.history.visible {
-        header_style().add_modifier(Modifier::BOLD)
-    } else {
-        header_style()
-    };
-
-    let width = area.width as usize;
-    let line = footer_line(
-        width,
-        status_span,
-        dungeon_span,
-        decor_label,
-        mode_label,
-        history_style,
+    let line = Line::from(
+        snapshot_footer_parts(snapshot, area.width as usize)
+            .into_iter()
+            .map(|(span, _)| span)
+            .collect::<Vec<_>>(),
     );
 
     let widget = Paragraph::new(line)
         .block(Block::default().borders(Borders::NONE))
         .alignment(Alignment::Left);
     f.render_widget(widget, area);
+
+    if snapshot.show_throughput {
+        let rate = match snapshot.message_rate {
+            Some(rate) => format!("{rate:.0} msg/s "),
+            None => "-- msg/s ".to_string(),
+        };
+        let width = (rate.chars().count() as u16).min(area.width);
+        let rect = ratatui::layout::Rect {
+            x: area.x + area.width - width,
+            width,
+            ..area
+        };
+        f.render_widget(Clear, rect);
+        f.render_widget(Paragraph::new(Span::styled(rate, value_style())), rect);
+    }
 }
 
-pub(super) fn draw_error(f: &mut Frame, area: ratatui::layout::Rect, error: &AppError) {
+pub(super) fn draw_error(
+    f: &mut Frame,
+    area: ratatui::layout::Rect,
+    error: &AppError,
+    bindings: &KeyBindings,
+) {
     let label = error.kind().label();
     let summary = error.summary_line();
-    let text = format!("{label} error: {summary}. Run with --debug for details.");
+    let key = bindings.label(KeyAction::ErrorLog);
+    let text = format!("{label} error: {summary}. Press {key} for the error log.");
 
     let widget = Paragraph::new(Line::from(Span::raw(text)))
         .block(Block::default().borders(Borders::NONE))
@@ -55,29 +57,44 @@ pub(super) fn draw_error(f: &mut Frame, area: ratatui::layout::Rect, error: &App
         .style(
             Style::default()
                 .fg(Color::Black)
-                .bg(crate::theme::STATUS_DISCONNECTED)
+                .bg(palette().status_disconnected)
                 .add_modifier(Modifier::BOLD),
         );
     f.render_widget(widget, area);
 }
 
 fn status_label(snapshot: &AppSnapshot) -> (Cow<'static, str>, Style) {
-    if !snapshot.connected {
-        if snapshot.is_idle {
+    if !snapshot.connection.is_connected() {
+        if snapshot.waiting_for_server {
+            (
+                Cow::Borrowed("Waiting for IINACT…"),
+                Style::default().fg(palette().status_idle),
+            )
+        } else if let ConnectionState::Reconnecting { attempt } = snapshot.connection {
+            let color = if snapshot.is_idle {
+                palette().status_idle
+            } else {
+                palette().status_disconnected
+            };
+            (
+                Cow::Owned(format!("Reconnecting (attempt {attempt})…")),
+                Style::default().fg(color),
+            )
+        } else if snapshot.is_idle {
             (
                 Cow::Borrowed("Disconnected (idle)"),
-                Style::default().fg(crate::theme::STATUS_IDLE),
+                Style::default().fg(palette().status_idle),
             )
         } else {
             (
                 Cow::Borrowed("Disconnected"),
-                Style::default().fg(crate::theme::STATUS_DISCONNECTED),
+                Style::default().fg(palette().status_disconnected),
             )
         }
     } else if snapshot.is_idle {
         (
             Cow::Borrowed("Connected (idle)"),
-            Style::default().fg(crate::theme::STATUS_IDLE),
+            Style::default().fg(palette().status_idle),
         )
     } else {
         (Cow::Borrowed("Connected"), value_style())
@@ -94,70 +111,257 @@ fn dungeon_label(snapshot: &AppSnapshot) -> (String, Style) {
     }
 }
 
+/// Whether finished pulls are being saved to history (`--no-record` turns it off).
+fn recording_label(snapshot: &AppSnapshot) -> (&'static str, Style) {
+    if snapshot.recording_disabled {
+        (
+            "Rec: Off",
+            Style::default().fg(palette().status_disconnected),
+        )
+    } else {
+        ("Rec: On", header_style())
+    }
+}
+
+/// Footer entries that act like their key when clicked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FooterTarget {
+    Mode,
+    Settings,
+    History,
+    Decoration,
+}
+
+/// The footer entry under `column` of a footer `width` cells wide, if it is clickable.
+pub(crate) fn footer_target_at(
+    snapshot: &AppSnapshot,
+    width: usize,
+    column: usize,
+) -> Option<FooterTarget> {
+    let mut start = 0;
+    for (span, target) in snapshot_footer_parts(snapshot, width) {
+        let end = start + span.width();
+        if (start..end).contains(&column) {
+            return target;
+        }
+        start = end;
+    }
+    None
+}
+
+fn snapshot_footer_parts(
+    snapshot: &AppSnapshot,
+    width: usize,
+) -> Vec<(Span<'static>, Option<FooterTarget>)> {
+    let (status_text, status_style) = status_label(snapshot);
+    let (dungeon_text, dungeon_style) = dungeon_label(snapshot);
+    let (recording_text, recording_style) = recording_label(snapshot);
+    let decor_label = snapshot
+        .decoration
+        .short_label()
+        .trim_start_matches("decor:");
+    let mode_label = snapshot.mode.short_label().trim_start_matches("mode:");
+    let history_style = if snapshot.history.visible {
+        header_style().add_modifier(Modifier::BOLD)
+    } else {
+        header_style()
+    };
+    footer_parts(
+        width,
+        &FooterKeys::from_bindings(&snapshot.key_bindings),
+        Span::styled(status_text, status_style),
+        Span::styled(dungeon_text, dungeon_style),
+        Span::styled(recording_text, recording_style),
+        decor_label,
+        mode_label,
+        history_style,
+    )
+}
+
+/// Footer key hints, as bound in the config.
+struct FooterKeys {
+    quit: String,
+    mode: String,
+    settings: String,
+    history: String,
+    decoration: String,
+}
+
+impl FooterKeys {
+    fn from_bindings(bindings: &KeyBindings) -> Self {
+        let hint = |action| format!(" {} ", bindings.label(action));
+        Self {
+            quit: hint(KeyAction::Quit),
+            mode: hint(KeyAction::CycleMode),
+            settings: hint(KeyAction::OpenSettings),
+            history: hint(KeyAction::ToggleHistory),
+            decoration: hint(KeyAction::CycleDecoration),
+        }
+    }
+}
+
+#[cfg(test)]
 fn footer_line(
     width: usize,
     status_span: Span<'static>,
     dungeon_span: Span<'static>,
+    recording_span: Span<'static>,
     decor_label: &str,
     mode_label: &str,
     history_style: Style,
 ) -> Line<'static> {
-    if width >= 90 {
-        Line::from(vec![
-            Span::styled(" q ", title_style()),
-            Span::styled("quit", header_style()),
-            Span::raw(" | "),
-            Span::styled(" m ", title_style()),
-            Span::styled(mode_label.to_string(), header_style()),
-            Span::raw(" | "),
-            Span::styled(" s ", title_style()),
-            Span::styled("settings", header_style()),
-            Span::raw(" | "),
-            Span::styled(" h ", title_style()),
-            Span::styled("history", history_style),
-            Span::raw(" | "),
-            Span::styled(" d ", title_style()),
-            Span::styled(decor_label.to_string(), header_style()),
-            Span::raw(" | "),
-            Span::styled(" view ", title_style()),
-            dungeon_span.clone(),
-            Span::raw(" | "),
-            Span::styled("status", header_style()),
-            Span::raw(" "),
-            status_span.clone(),
-        ])
-    } else if width >= 60 {
-        Line::from(vec![
-            Span::styled(" q ", title_style()),
-            Span::styled("quit", header_style()),
-            Span::raw(" | "),
-            Span::styled(" m ", title_style()),
-            Span::styled(mode_label.to_string(), header_style()),
-            Span::raw(" | "),
-            Span::styled(" s ", title_style()),
-            Span::styled("settings", header_style()),
-            Span::raw(" | "),
-            Span::styled(" h ", title_style()),
-            Span::styled("history", history_style),
-            Span::raw(" | "),
-            Span::styled(" d ", title_style()),
-            Span::styled(decor_label.to_string(), header_style()),
-            Span::raw(" | "),
-            dungeon_span.clone(),
-            Span::raw(" | "),
-            status_span,
-        ])
-    } else if width >= 36 {
-        Line::from(vec![
-            Span::styled(" q ", title_style()),
-            Span::styled(" m ", title_style()),
-            Span::styled(" s ", title_style()),
-            Span::styled(" h ", title_style()),
-            Span::styled(" d ", title_style()),
-            dungeon_span,
-            status_span,
-        ])
-    } else {
-        Line::from(vec![Span::styled("qmshd", title_style())])
+    let parts = footer_parts(
+        width,
+        &FooterKeys::from_bindings(&KeyBindings::default()),
+        status_span,
+        dungeon_span,
+        recording_span,
+        decor_label,
+        mode_label,
+        history_style,
+    );
+    Line::from(parts.into_iter().map(|(span, _)| span).collect::<Vec<_>>())
+}
+
+/// Picks the most detailed footer that fits `width`, each span tagged with what clicking it
+/// does. Every variant but the last keeps the mode, decoration, dungeon, recording and
+/// connection state visible; key hints go first.
+#[allow(clippy::too_many_arguments)]
+fn footer_parts(
+    width: usize,
+    keys: &FooterKeys,
+    status_span: Span<'static>,
+    dungeon_span: Span<'static>,
+    recording_span: Span<'static>,
+    decor_label: &str,
+    mode_label: &str,
+    history_style: Style,
+) -> Vec<(Span<'static>, Option<FooterTarget>)> {
+    use FooterTarget::{Decoration, History, Mode, Settings};
+
+    let key = |label: &String, target| (Span::styled(label.clone(), title_style()), target);
+    let text = |span: Span<'static>, target| (span, target);
+    let sep = |label: &'static str| (Span::raw(label), None);
+    let mode = Span::styled(mode_label.to_string(), header_style());
+    let decor = Span::styled(decor_label.to_string(), header_style());
+
+    let wide = vec![
+        key(&keys.quit, None),
+        text(Span::styled("quit", header_style()), None),
+        sep(" | "),
+        key(&keys.mode, Some(Mode)),
+        text(mode.clone(), Some(Mode)),
+        sep(" | "),
+        key(&keys.settings, Some(Settings)),
+        text(Span::styled("settings", header_style()), Some(Settings)),
+        sep(" | "),
+        key(&keys.history, Some(History)),
+        text(Span::styled("history", history_style), Some(History)),
+        sep(" | "),
+        key(&keys.decoration, Some(Decoration)),
+        text(decor.clone(), Some(Decoration)),
+        sep(" | "),
+        (Span::styled(" view ", title_style()), None),
+        text(dungeon_span.clone(), None),
+        sep(" | "),
+        text(recording_span.clone(), None),
+        sep(" | "),
+        text(Span::styled("status", header_style()), None),
+        sep(" "),
+        text(status_span.clone(), None),
+    ];
+    let medium = vec![
+        key(&keys.quit, None),
+        key(&keys.mode, Some(Mode)),
+        text(mode.clone(), Some(Mode)),
+        sep(" "),
+        key(&keys.settings, Some(Settings)),
+        key(&keys.history, Some(History)),
+        key(&keys.decoration, Some(Decoration)),
+        text(decor.clone(), Some(Decoration)),
+        sep(" | "),
+        text(dungeon_span.clone(), None),
+        sep(" | "),
+        text(recording_span.clone(), None),
+        sep(" | "),
+        text(status_span.clone(), None),
+    ];
+    let compact = vec![
+        sep(" "),
+        text(mode, Some(Mode)),
+        sep(" · "),
+        text(decor, Some(Decoration)),
+        sep(" · "),
+        text(dungeon_span, None),
+        sep(" · "),
+        text(recording_span, None),
+        sep(" · "),
+        text(status_span.clone(), None),
+    ];
+    [wide, medium, compact]
+        .into_iter()
+        .find(|parts| parts.iter().map(|(span, _)| span.width()).sum::<usize>() <= width)
+        .unwrap_or_else(|| vec![sep(" "), text(status_span, None)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn footer_text(snapshot: &AppSnapshot, width: usize) -> String {
+        let (status, status_style) = status_label(snapshot);
+        let (dungeon, dungeon_style) = dungeon_label(snapshot);
+        let (recording, recording_style) = recording_label(snapshot);
+        let line = footer_line(
+            width,
+            Span::styled(status, status_style),
+            Span::styled(dungeon, dungeon_style),
+            Span::styled(recording, recording_style),
+            "line",
+            "DPS",
+            header_style(),
+        );
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn footer_keeps_every_state_visible_while_it_fits() {
+        let mut snapshot = AppSnapshot {
+            connection: ConnectionState::Connected,
+            recording_disabled: true,
+            ..Default::default()
+        };
+        snapshot.settings.dungeon_mode_enabled = false;
+        for width in [120, 80, 60] {
+            let text = footer_text(&snapshot, width);
+            assert!(text.chars().count() <= width, "too wide at {width}: {text}");
+            for label in ["DPS", "line", "Dungeon: Off", "Rec: Off", "Connected"] {
+                assert!(text.contains(label), "{label:?} missing at {width}: {text}");
+            }
+        }
+        assert_eq!(footer_text(&snapshot, 20), " Connected");
+    }
+
+    #[test]
+    fn footer_clicks_land_on_the_entry_under_the_cursor() {
+        let snapshot = AppSnapshot {
+            connection: ConnectionState::Connected,
+            ..Default::default()
+        };
+        // Wide layout: " q quit |  m DPS |  s settings | ..."
+        assert_eq!(footer_target_at(&snapshot, 200, 0), None);
+        assert_eq!(
+            footer_target_at(&snapshot, 200, 11),
+            Some(FooterTarget::Mode)
+        );
+        assert_eq!(
+            footer_target_at(&snapshot, 200, 20),
+            Some(FooterTarget::Settings)
+        );
+        assert_eq!(footer_target_at(&snapshot, 200, 199), None);
+        assert_eq!(footer_target_at(&snapshot, 20, 5), None);
     }
 }
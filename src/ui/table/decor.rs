@@ -37,7 +37,13 @@ pub(super) fn draw_background_meters(
     let width = area.width as usize;
     let visible_rows = (area.height.saturating_sub(header_lines)) as usize;
 
-    for (index, row) in ctx.rows.iter().take(visible_rows).enumerate() {
+    for (index, row) in ctx
+        .rows
+        .iter()
+        .skip(ctx.scroll)
+        .take(visible_rows)
+        .enumerate()
+    {
         let ratio = (metric_for_mode(ctx.mode, row) / max_metric).clamp(0.0, 1.0);
         let filled = (ratio * width as f64).round() as usize;
         let y = area.y + header_lines + index as u16;
@@ -91,7 +97,13 @@ pub(super) fn draw_underlines(
     let visible_rows = (usable_height / 2) as usize;
     let width = area.width as usize;
 
-    for (index, row) in ctx.rows.iter().take(visible_rows).enumerate() {
+    for (index, row) in ctx
+        .rows
+        .iter()
+        .skip(ctx.scroll)
+        .take(visible_rows)
+        .enumerate()
+    {
         let ratio = (metric_for_mode(ctx.mode, row) / max_metric).clamp(0.0, 1.0);
         let filled = (ratio * width as f64).round() as usize;
         let y = area.y + header_lines + (index as u16) * 2 + 1;
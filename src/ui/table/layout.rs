@@ -1,9 +1,9 @@
 use ratatui::layout::Constraint;
-use ratatui::style::Style;
+use ratatui::style::{Modifier, Style};
 use ratatui::widgets::{Cell, Row};
 
-use crate::model::{CombatantRow, ViewMode};
-use crate::theme::{header_style, job_color};
+use crate::model::{ColumnId, CombatantRow, ViewMode};
+use crate::theme::{check_style, header_style, job_color};
 
 pub(super) struct LayoutSpec {
     columns: Vec<ColumnSpec>,
@@ -20,14 +20,40 @@ impl LayoutSpec {
         self.column_spacing
     }
 
-    pub(super) fn header_row(&self) -> Row<'static> {
-        Row::new(self.columns.iter().map(ColumnSpec::header_cell))
-            .style(header_style())
-            .height(self.header_height)
+    /// `sorted` is the column the rows are ordered by and whether ascending; its header gets
+    /// a ▲/▼ marker.
+    pub(super) fn header_row(&self, sorted: Option<(ColumnId, bool)>) -> Row<'static> {
+        Row::new(self.columns.iter().map(|col| {
+            let marker = match sorted {
+                Some((id, ascending)) if id == col.id => Some(ascending),
+                _ => None,
+            };
+            col.header_cell(marker)
+        }))
+        .style(header_style())
+        .height(self.header_height)
     }
 
-    pub(super) fn data_row(&self, row: &CombatantRow, row_height: u16) -> Row<'static> {
-        Row::new(self.columns.iter().map(|col| col.data_cell(row))).height(row_height)
+    /// `party_mean` is the mean ENCDPS of the displayed rows (see [`party_mean`]); `rolling`
+    /// is the row's DPS over the rolling window, if known; `is_self` bolds the name of the
+    /// player's own row.
+    pub(super) fn data_row(
+        &self,
+        row: &CombatantRow,
+        row_height: u16,
+        party_mean: f64,
+        rolling: Option<f64>,
+        is_self: bool,
+    ) -> Row<'static> {
+        Row::new(self.columns.iter().map(|col| {
+            let cell = col.data_cell(row, party_mean, rolling);
+            if is_self && col.id == ColumnId::Name {
+                cell.style(name_style(row).add_modifier(Modifier::BOLD))
+            } else {
+                cell
+            }
+        }))
+        .height(row_height)
     }
 
     pub(super) fn widths(&self) -> Vec<Constraint> {
@@ -48,70 +74,281 @@ impl LayoutSpec {
         self.column_spacing = spacing;
         self
     }
+
+    /// Reorders the variant's columns to follow `order`, dropping any it does not mention.
+    /// Single-column variants are left alone; a filter that would hide everything keeps the
+    /// stock columns instead.
+    fn ordered(mut self, order: &[ColumnId]) -> Self {
+        if self.columns.len() <= 1 {
+            return self;
+        }
+        let mut picked = Vec::with_capacity(self.columns.len());
+        for id in order {
+            if let Some(pos) = self.columns.iter().position(|col| col.id == *id) {
+                picked.push(self.columns.remove(pos));
+            }
+        }
+        if picked.is_empty() {
+            self.columns
+                .retain(|col| ColumnId::DEFAULT_ORDER.contains(&col.id));
+        } else {
+            self.columns = picked;
+        }
+        self
+    }
 }
 
-pub(super) fn layout_for(mode: ViewMode, width: usize) -> LayoutSpec {
+/// Narrowest table that shows DPS and HPS side by side; below it only `mode`'s rate is shown.
+const BOTH_RATES_MIN_WIDTH: usize = 100;
+
+/// Narrowest DPS table that gets the rolling DPS column.
+const ROLLING_MIN_WIDTH: usize = 72;
+
+/// `both_rates` adds the other mode's rate column (ENCHPS in DPS mode and vice versa) when the
+/// table is at least [`BOTH_RATES_MIN_WIDTH`] wide; `mode` still decides the other columns.
+/// `rolling` adds the rolling DPS column to DPS tables at least [`ROLLING_MIN_WIDTH`] wide.
+pub(super) fn layout_for(
+    mode: ViewMode,
+    width: usize,
+    order: &[ColumnId],
+    both_rates: bool,
+    rolling: bool,
+) -> LayoutSpec {
     let variant = TableVariant::from_width(width);
-    layout_for_variant(mode, variant)
+    let mut spec = layout_for_variant(mode, variant);
+    if both_rates && width >= BOTH_RATES_MIN_WIDTH {
+        spec.columns.push(match mode {
+            ViewMode::Dps => right_column(
+                ColumnId::Enchps,
+                "ENCHPS",
+                10,
+                Constraint::Length(10),
+                value_enchps,
+            ),
+            ViewMode::Heal => right_column(
+                ColumnId::Encdps,
+                "ENCDPS",
+                10,
+                Constraint::Length(10),
+                value_encdps,
+            ),
+        });
+    }
+    if rolling && mode == ViewMode::Dps && width >= ROLLING_MIN_WIDTH {
+        spec.columns.push(rolling_column(9));
+    }
+    spec.ordered(order)
 }
 
 fn layout_for_variant(mode: ViewMode, variant: TableVariant) -> LayoutSpec {
     match (mode, variant) {
         (ViewMode::Dps, TableVariant::Full) => LayoutSpec::new(vec![
             name_column(Constraint::Percentage(34)),
-            right_column("Share%", 7, Constraint::Length(7), value_share),
-            right_column("ENCDPS", 10, Constraint::Length(10), value_encdps),
-            right_column("Job", 5, Constraint::Length(5), value_job),
-            right_column("Crit%", 8, Constraint::Length(8), value_crit),
-            right_column("DH%", 8, Constraint::Length(8), value_dh),
-            right_column("Deaths", 8, Constraint::Length(8), value_deaths),
+            right_column(
+                ColumnId::Share,
+                "Share%",
+                7,
+                Constraint::Length(7),
+                value_share,
+            ),
+            right_column(
+                ColumnId::Encdps,
+                "ENCDPS",
+                10,
+                Constraint::Length(10),
+                value_encdps,
+            ),
+            right_column(ColumnId::Job, "Job", 5, Constraint::Length(5), value_job),
+            right_column(
+                ColumnId::Crit,
+                "Crit%",
+                8,
+                Constraint::Length(8),
+                value_crit,
+            ),
+            right_column(
+                ColumnId::DirectHit,
+                "DH%",
+                8,
+                Constraint::Length(8),
+                value_dh,
+            ),
+            right_column(
+                ColumnId::Deaths,
+                "Deaths",
+                8,
+                Constraint::Length(8),
+                value_deaths,
+            ),
+            party_delta_column(7),
         ]),
         (ViewMode::Heal, TableVariant::Full) => LayoutSpec::new(vec![
             name_column(Constraint::Percentage(34)),
-            right_column("Heal%", 7, Constraint::Length(7), value_heal_share),
-            right_column("ENCHPS", 10, Constraint::Length(10), value_enchps),
-            right_column("Job", 5, Constraint::Length(5), value_job),
-            right_column("Overheal%", 10, Constraint::Length(10), value_overheal),
-            right_column("Deaths", 8, Constraint::Length(8), value_deaths),
+            right_column(
+                ColumnId::HealShare,
+                "Heal%",
+                7,
+                Constraint::Length(7),
+                value_heal_share,
+            ),
+            right_column(
+                ColumnId::Enchps,
+                "ENCHPS",
+                10,
+                Constraint::Length(10),
+                value_enchps,
+            ),
+            right_column(ColumnId::Job, "Job", 5, Constraint::Length(5), value_job),
+            right_column(
+                ColumnId::Overheal,
+                "Overheal%",
+                10,
+                Constraint::Length(10),
+                value_overheal,
+            ),
+            right_column(
+                ColumnId::Deaths,
+                "Deaths",
+                8,
+                Constraint::Length(8),
+                value_deaths,
+            ),
         ]),
         (ViewMode::Dps, TableVariant::NoDeaths) => LayoutSpec::new(vec![
             name_column(Constraint::Percentage(38)),
-            right_column("Share%", 7, Constraint::Length(7), value_share),
-            right_column("ENCDPS", 9, Constraint::Length(9), value_encdps),
-            right_column("Job", 5, Constraint::Length(5), value_job),
-            right_column("Crit%", 6, Constraint::Length(6), value_crit),
-            right_column("DH%", 6, Constraint::Length(6), value_dh),
+            right_column(
+                ColumnId::Share,
+                "Share%",
+                7,
+                Constraint::Length(7),
+                value_share,
+            ),
+            right_column(
+                ColumnId::Encdps,
+                "ENCDPS",
+                9,
+                Constraint::Length(9),
+                value_encdps,
+            ),
+            right_column(ColumnId::Job, "Job", 5, Constraint::Length(5), value_job),
+            right_column(
+                ColumnId::Crit,
+                "Crit%",
+                6,
+                Constraint::Length(6),
+                value_crit,
+            ),
+            right_column(
+                ColumnId::DirectHit,
+                "DH%",
+                6,
+                Constraint::Length(6),
+                value_dh,
+            ),
         ]),
         (ViewMode::Heal, TableVariant::NoDeaths) => LayoutSpec::new(vec![
             name_column(Constraint::Percentage(44)),
-            right_column("Heal%", 7, Constraint::Length(7), value_heal_share),
-            right_column("ENCHPS", 9, Constraint::Length(9), value_enchps),
-            right_column("Job", 5, Constraint::Length(5), value_job),
-            right_column("Overheal%", 9, Constraint::Length(9), value_overheal),
+            right_column(
+                ColumnId::HealShare,
+                "Heal%",
+                7,
+                Constraint::Length(7),
+                value_heal_share,
+            ),
+            right_column(
+                ColumnId::Enchps,
+                "ENCHPS",
+                9,
+                Constraint::Length(9),
+                value_enchps,
+            ),
+            right_column(ColumnId::Job, "Job", 5, Constraint::Length(5), value_job),
+            right_column(
+                ColumnId::Overheal,
+                "Overheal%",
+                9,
+                Constraint::Length(9),
+                value_overheal,
+            ),
         ]),
         (ViewMode::Dps, TableVariant::NoDhDeaths) => LayoutSpec::new(vec![
             name_column(Constraint::Percentage(54)),
-            right_column("Share%", 7, Constraint::Length(7), value_share),
-            right_column("ENCDPS", 9, Constraint::Length(9), value_encdps),
-            right_column("Crit%", 6, Constraint::Length(6), value_crit),
+            right_column(
+                ColumnId::Share,
+                "Share%",
+                7,
+                Constraint::Length(7),
+                value_share,
+            ),
+            right_column(
+                ColumnId::Encdps,
+                "ENCDPS",
+                9,
+                Constraint::Length(9),
+                value_encdps,
+            ),
+            right_column(
+                ColumnId::Crit,
+                "Crit%",
+                6,
+                Constraint::Length(6),
+                value_crit,
+            ),
         ]),
         (ViewMode::Heal, TableVariant::NoDhDeaths) => LayoutSpec::new(vec![
             name_column(Constraint::Percentage(58)),
-            right_column("Heal%", 7, Constraint::Length(7), value_heal_share),
-            right_column("ENCHPS", 9, Constraint::Length(9), value_enchps),
-            right_column("Job", 5, Constraint::Length(5), value_job),
+            right_column(
+                ColumnId::HealShare,
+                "Heal%",
+                7,
+                Constraint::Length(7),
+                value_heal_share,
+            ),
+            right_column(
+                ColumnId::Enchps,
+                "ENCHPS",
+                9,
+                Constraint::Length(9),
+                value_enchps,
+            ),
+            right_column(ColumnId::Job, "Job", 5, Constraint::Length(5), value_job),
         ]),
         (ViewMode::Dps, TableVariant::Minimal) => LayoutSpec::new(vec![
             name_column(Constraint::Percentage(64)),
-            right_column("Share%", 6, Constraint::Length(6), value_share),
-            right_column("ENCDPS", 9, Constraint::Length(9), value_encdps),
+            right_column(
+                ColumnId::Share,
+                "Share%",
+                6,
+                Constraint::Length(6),
+                value_share,
+            ),
+            right_column(
+                ColumnId::Encdps,
+                "ENCDPS",
+                9,
+                Constraint::Length(9),
+                value_encdps,
+            ),
         ]),
         (ViewMode::Heal, TableVariant::Minimal) => LayoutSpec::new(vec![
             name_column(Constraint::Percentage(64)),
-            right_column("Heal%", 6, Constraint::Length(6), value_heal_share),
-            right_column("ENCHPS", 9, Constraint::Length(9), value_enchps),
+            right_column(
+                ColumnId::HealShare,
+                "Heal%",
+                6,
+                Constraint::Length(6),
+                value_heal_share,
+            ),
+            right_column(
+                ColumnId::Enchps,
+                "ENCHPS",
+                9,
+                Constraint::Length(9),
+                value_enchps,
+            ),
         ]),
         (ViewMode::Dps, TableVariant::NameOnly) => LayoutSpec::new(vec![left_column(
+            ColumnId::Name,
             "Name (Share%)",
             Constraint::Percentage(100),
             value_name_with_share,
@@ -119,6 +356,7 @@ fn layout_for_variant(mode: ViewMode, variant: TableVariant) -> LayoutSpec {
         )])
         .with_spacing(0),
         (ViewMode::Heal, TableVariant::NameOnly) => LayoutSpec::new(vec![left_column(
+            ColumnId::Name,
             "Name (Heal%)",
             Constraint::Percentage(100),
             value_name_with_heal_share,
@@ -167,70 +405,168 @@ impl Align {
     }
 }
 
+enum CellValue {
+    Row(fn(&CombatantRow) -> String),
+    /// Needs the whole party, so it is computed from the row and the party mean.
+    PartyDelta,
+    /// Comes from the update history rather than the row itself.
+    Rolling,
+}
+
 struct ColumnSpec {
+    id: ColumnId,
     header: &'static str,
     align: Align,
     width: Constraint,
-    value: fn(&CombatantRow) -> String,
+    value: CellValue,
     style: Option<fn(&CombatantRow) -> Style>,
 }
 
 impl ColumnSpec {
-    fn header_cell(&self) -> Cell<'static> {
-        Cell::from(self.align.format(self.header))
+    fn header_cell(&self, sort_marker: Option<bool>) -> Cell<'static> {
+        Cell::from(self.header_text(sort_marker))
     }
 
-    fn data_cell(&self, row: &CombatantRow) -> Cell<'static> {
-        let text = (self.value)(row);
-        let formatted = self.align.format(&text);
-        let mut cell = Cell::from(formatted);
-        if let Some(style_fn) = self.style {
-            cell = cell.style(style_fn(row));
+    /// Headers too wide for their column to take a marker are left bare.
+    fn header_text(&self, sort_marker: Option<bool>) -> String {
+        let fits = match self.align {
+            Align::Left => true,
+            Align::Right { width } => self.header.chars().count() < width,
+        };
+        let header = match sort_marker {
+            Some(true) if fits => format!("{}▲", self.header),
+            Some(false) if fits => format!("{}▼", self.header),
+            _ => self.header.to_string(),
+        };
+        self.align.format(&header)
+    }
+
+    fn data_cell(
+        &self,
+        row: &CombatantRow,
+        party_mean: f64,
+        rolling: Option<f64>,
+    ) -> Cell<'static> {
+        let (text, style) = match self.value {
+            CellValue::Row(value) => (value(row), self.style.map(|style_fn| style_fn(row))),
+            CellValue::PartyDelta => {
+                let delta = party_delta_pct(row.encdps, party_mean);
+                let passed = (delta != 0).then_some(delta > 0);
+                (format_party_delta(delta), Some(check_style(passed)))
+            }
+            CellValue::Rolling => (format_rolling(rolling), None),
+        };
+        let mut cell = Cell::from(self.align.format(&text));
+        if let Some(style) = style {
+            cell = cell.style(style);
         }
         cell
     }
 }
 
+/// Mean ENCDPS across `rows`, or 0 when there are none.
+pub(super) fn party_mean(rows: &[CombatantRow]) -> f64 {
+    if rows.is_empty() {
+        return 0.0;
+    }
+    rows.iter().map(|row| row.encdps).sum::<f64>() / rows.len() as f64
+}
+
+/// Whole-percent difference of `value` from `mean`. A party with no damage yet reads 0%.
+fn party_delta_pct(value: f64, mean: f64) -> i64 {
+    if !mean.is_finite() || mean <= 0.0 || !value.is_finite() {
+        return 0;
+    }
+    ((value - mean) / mean * 100.0).round() as i64
+}
+
+fn format_party_delta(delta: i64) -> String {
+    if delta == 0 {
+        "0%".to_string()
+    } else {
+        format!("{delta:+}%")
+    }
+}
+
+/// Whole DPS, or "-" until the window has enough updates.
+fn format_rolling(rolling: Option<f64>) -> String {
+    match rolling {
+        Some(dps) if dps.is_finite() => format!("{dps:.0}"),
+        _ => "-".to_string(),
+    }
+}
+
 fn name_style(row: &CombatantRow) -> Style {
     Style::default().fg(job_color(&row.job))
 }
 
 fn name_column(width: Constraint) -> ColumnSpec {
     ColumnSpec {
+        id: ColumnId::Name,
         header: "Name",
         align: Align::Left,
         width,
-        value: value_name,
+        value: CellValue::Row(value_name),
         style: Some(name_style),
     }
 }
 
 fn right_column(
+    id: ColumnId,
     header: &'static str,
     align_width: usize,
     width: Constraint,
     value: fn(&CombatantRow) -> String,
 ) -> ColumnSpec {
     ColumnSpec {
+        id,
         header,
         align: Align::Right { width: align_width },
         width,
-        value,
+        value: CellValue::Row(value),
+        style: None,
+    }
+}
+
+fn party_delta_column(width: u16) -> ColumnSpec {
+    ColumnSpec {
+        id: ColumnId::PartyDelta,
+        header: "vs Avg",
+        align: Align::Right {
+            width: width as usize,
+        },
+        width: Constraint::Length(width),
+        value: CellValue::PartyDelta,
+        style: None,
+    }
+}
+
+fn rolling_column(width: u16) -> ColumnSpec {
+    ColumnSpec {
+        id: ColumnId::RollingDps,
+        header: "Rolling",
+        align: Align::Right {
+            width: width as usize,
+        },
+        width: Constraint::Length(width),
+        value: CellValue::Rolling,
         style: None,
     }
 }
 
 fn left_column(
+    id: ColumnId,
     header: &'static str,
     width: Constraint,
     value: fn(&CombatantRow) -> String,
     style: Option<fn(&CombatantRow) -> Style>,
 ) -> ColumnSpec {
     ColumnSpec {
+        id,
         header,
         align: Align::Left,
         width,
-        value,
+        value: CellValue::Row(value),
         style,
     }
 }
@@ -284,7 +620,7 @@ fn value_name_with_heal_share(row: &CombatantRow) -> String {
 }
 
 fn right_align(text: &str, width: usize) -> String {
-    let len = text.len();
+    let len = text.chars().count();
     if len >= width {
         text.chars()
             .rev()
@@ -297,3 +633,152 @@ fn right_align(text: &str, width: usize) -> String {
         format!("{:>width$}", text, width = width)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(spec: &LayoutSpec) -> Vec<&'static str> {
+        spec.columns.iter().map(|col| col.header).collect()
+    }
+
+    #[test]
+    fn default_order_matches_stock_layouts() {
+        let dps = layout_for(ViewMode::Dps, 100, &ColumnId::DEFAULT_ORDER, false, false);
+        assert_eq!(
+            headers(&dps),
+            ["Name", "Share%", "ENCDPS", "Job", "Crit%", "DH%", "Deaths"]
+        );
+        let heal = layout_for(ViewMode::Heal, 100, &ColumnId::DEFAULT_ORDER, false, false);
+        assert_eq!(
+            headers(&heal),
+            ["Name", "Heal%", "ENCHPS", "Job", "Overheal%", "Deaths"]
+        );
+    }
+
+    #[test]
+    fn custom_order_reorders_and_hides_columns() {
+        let order = ColumnId::resolve_order(&["encdps", "name", "enchps"]);
+        let dps = layout_for(ViewMode::Dps, 100, &order, false, false);
+        assert_eq!(headers(&dps), ["ENCDPS", "Name"]);
+        assert_eq!(dps.widths().len(), 2);
+    }
+
+    #[test]
+    fn empty_or_invalid_order_falls_back_to_defaults() {
+        let empty: [&str; 0] = [];
+        assert_eq!(ColumnId::resolve_order(&empty), ColumnId::DEFAULT_ORDER);
+        assert_eq!(
+            ColumnId::resolve_order(&["bogus", "also-bogus"]),
+            ColumnId::DEFAULT_ORDER
+        );
+        assert_eq!(
+            ColumnId::resolve_order(&["DEATHS", "bogus", "deaths", "name"]),
+            [ColumnId::Deaths, ColumnId::Name]
+        );
+
+        // Heal-only columns leave nothing for DPS mode, which keeps the stock layout.
+        let heal_only = ColumnId::resolve_order(&["overheal"]);
+        let dps = layout_for(ViewMode::Dps, 100, &heal_only, false, false);
+        assert_eq!(headers(&dps).len(), 7);
+    }
+
+    #[test]
+    fn both_rates_add_the_other_metric_on_wide_tables() {
+        let order = ColumnId::DEFAULT_ORDER;
+        let dps = layout_for(ViewMode::Dps, 120, &order, true, false);
+        assert_eq!(
+            headers(&dps),
+            ["Name", "Share%", "ENCDPS", "ENCHPS", "Job", "Crit%", "DH%", "Deaths"]
+        );
+        let heal = layout_for(ViewMode::Heal, 120, &order, true, false);
+        assert_eq!(
+            headers(&heal),
+            [
+                "Name",
+                "Heal%",
+                "ENCDPS",
+                "ENCHPS",
+                "Job",
+                "Overheal%",
+                "Deaths"
+            ]
+        );
+
+        let narrow = layout_for(ViewMode::Dps, 95, &order, true, false);
+        assert_eq!(
+            headers(&narrow),
+            headers(&layout_for(ViewMode::Dps, 95, &order, false, false))
+        );
+
+        let hidden = ColumnId::resolve_order(&["name", "encdps"]);
+        assert_eq!(
+            headers(&layout_for(ViewMode::Dps, 120, &hidden, true, false)),
+            ["Name", "ENCDPS"]
+        );
+    }
+
+    #[test]
+    fn party_delta_is_opt_in_and_width_gated() {
+        let order = ColumnId::resolve_order(&["name", "encdps", "party_delta"]);
+        let wide = layout_for(ViewMode::Dps, 100, &order, false, false);
+        assert_eq!(headers(&wide), ["Name", "ENCDPS", "vs Avg"]);
+        let narrow = layout_for(ViewMode::Dps, 80, &order, false, false);
+        assert_eq!(headers(&narrow), ["Name", "ENCDPS"]);
+    }
+
+    #[test]
+    fn rolling_column_is_dps_only_and_width_gated() {
+        let order = ColumnId::DEFAULT_ORDER;
+        let dps = layout_for(ViewMode::Dps, 100, &order, false, true);
+        assert_eq!(
+            headers(&dps),
+            ["Name", "Share%", "ENCDPS", "Rolling", "Job", "Crit%", "DH%", "Deaths"]
+        );
+        let heal = layout_for(ViewMode::Heal, 100, &order, false, true);
+        assert!(!headers(&heal).contains(&"Rolling"));
+        let narrow = layout_for(ViewMode::Dps, 60, &order, false, true);
+        assert!(!headers(&narrow).contains(&"Rolling"));
+        assert_eq!(format_rolling(Some(1234.6)), "1235");
+        assert_eq!(format_rolling(None), "-");
+    }
+
+    #[test]
+    fn sorted_column_header_gets_a_marker_when_it_fits() {
+        let order = ColumnId::DEFAULT_ORDER;
+        let header = |mode: ViewMode, width: usize, id: ColumnId| {
+            let spec = layout_for(mode, width, &order, false, false);
+            let col = spec.columns.iter().find(|col| col.id == id).unwrap();
+            col.header_text(Some(id == ColumnId::Name))
+        };
+        assert_eq!(header(ViewMode::Dps, 100, ColumnId::Encdps), "   ENCDPS▼");
+        assert_eq!(header(ViewMode::Dps, 100, ColumnId::Name), "Name▲");
+        assert_eq!(header(ViewMode::Dps, 80, ColumnId::Crit), "Crit%▼");
+        // On narrower heal tables Overheal% fills its column exactly, so it stays unmarked.
+        assert_eq!(header(ViewMode::Heal, 80, ColumnId::Overheal), "Overheal%");
+    }
+
+    #[test]
+    fn party_delta_handles_single_and_zero_rows() {
+        let row = |encdps: f64| CombatantRow {
+            encdps,
+            ..Default::default()
+        };
+        let solo = [row(4200.0)];
+        let mean = party_mean(&solo);
+        assert_eq!(party_delta_pct(solo[0].encdps, mean), 0);
+
+        let party = [row(1120.0), row(920.0), row(960.0), row(0.0)];
+        let mean = party_mean(&party);
+        assert_eq!(mean, 750.0);
+        let deltas: Vec<_> = party
+            .iter()
+            .map(|r| format_party_delta(party_delta_pct(r.encdps, mean)))
+            .collect();
+        assert_eq!(deltas, ["+49%", "+23%", "+28%", "-100%"]);
+
+        let idle = [row(0.0), row(0.0)];
+        assert_eq!(party_delta_pct(0.0, party_mean(&idle)), 0);
+        assert_eq!(format_party_delta(0), "0%");
+    }
+}
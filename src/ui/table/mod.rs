@@ -1,19 +1,91 @@
-use ratatui::layout::Rect;
+use std::collections::HashMap;
+
+use ratatui::layout::{Alignment, Rect};
 use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Table};
 use ratatui::Frame;
 
-use crate::model::{AppSnapshot, CombatantRow, Decoration, ViewMode};
+use crate::keys::KeyAction;
+use crate::model::{
+    visible_live_rows, AppSnapshot, ColumnId, CombatantRow, Decoration, RoleFilter, ViewMode,
+};
+use crate::theme::{header_style, selection_style, title_style};
 
 mod decor;
 mod layout;
 
 pub(super) fn draw(f: &mut Frame, area: Rect, snapshot: &AppSnapshot) {
+    let filter = snapshot.role_filter;
+    let mut area = area;
+    if filter != RoleFilter::All && area.height > 1 {
+        let title = Line::from(vec![
+            Span::styled(format!("Role: {}", filter.label()), title_style()),
+            Span::raw(" "),
+            Span::styled(
+                format!(
+                    "({} cycles)",
+                    snapshot.key_bindings.label(KeyAction::RoleFilter)
+                ),
+                header_style(),
+            ),
+        ]);
+        f.render_widget(Paragraph::new(title), Rect { height: 1, ..area });
+        area.y += 1;
+        area.height -= 1;
+    }
+
+    let query = snapshot.live_filter.as_str();
+    if (snapshot.live_filter_editing || !query.is_empty()) && area.height > 1 {
+        let (cursor, hint) = if snapshot.live_filter_editing {
+            ("▏", "(Enter keeps · Esc clears)".to_string())
+        } else {
+            let key = snapshot.key_bindings.label(KeyAction::Filter);
+            ("", format!("({key} edits · Esc clears)"))
+        };
+        let title = Line::from(vec![
+            Span::styled(format!("Filter: {query}{cursor}"), title_style()),
+            Span::raw(" "),
+            Span::styled(hint, header_style()),
+        ]);
+        f.render_widget(Paragraph::new(title), Rect { height: 1, ..area });
+        area.y += 1;
+        area.height -= 1;
+    }
+
+    let hide_zero = snapshot.settings.hide_zero_rows;
+    let rows = visible_live_rows(&snapshot.rows, filter, query, hide_zero, snapshot.mode);
+    if rows.is_empty() && !snapshot.rows.is_empty() {
+        // Something was filtered away; say which filter did it.
+        let message = if !query.trim().is_empty() {
+            format!("No combatants match \"{}\"", query.trim())
+        } else if filter == RoleFilter::All && hide_zero {
+            "Everyone is still at 0 (\"Hide 0 rows\" is on).".to_string()
+        } else {
+            filter.empty_message().to_string()
+        };
+        f.render_widget(Clear, area);
+        let message = Paragraph::new(message)
+            .style(header_style())
+            .alignment(Alignment::Center);
+        f.render_widget(message, area);
+        return;
+    }
+
     let ctx = TableRenderContext {
-        rows: &snapshot.rows,
+        rows: &rows,
         mode: snapshot.mode,
         decoration: snapshot.decoration,
+        columns: &snapshot.settings.column_order,
+        scroll: 0,
+        selected: None,
+        self_name: snapshot.self_name.as_deref(),
+        both_rates: snapshot.show_both_rates,
+        rolling: snapshot.rolling_dps.as_ref(),
+        sorted: Some((
+            snapshot.sort_key.column(snapshot.mode),
+            snapshot.sort_ascending,
+        )),
     };
     draw_with_context(f, area, &ctx);
 }
@@ -23,25 +95,68 @@ pub(crate) struct TableRenderContext<'a> {
     pub rows: &'a [CombatantRow],
     pub mode: ViewMode,
     pub decoration: Decoration,
+    pub columns: &'a [ColumnId],
+    /// Rows skipped at the top; clamped so the last page stays full.
+    pub scroll: usize,
+    /// Row drawn highlighted; the scroll follows it so it stays on screen.
+    pub selected: Option<usize>,
+    /// Row drawn as "you" (bold name).
+    pub self_name: Option<&'a str>,
+    /// Show DPS and HPS side by side on wide tables.
+    pub both_rates: bool,
+    /// Column the rows are sorted by and whether ascending, marked in the header.
+    pub sorted: Option<(ColumnId, bool)>,
+    /// Rolling DPS by combatant name; adds the rolling column (live table only).
+    pub rolling: Option<&'a HashMap<String, f64>>,
 }
 
-pub(crate) fn draw_with_context(f: &mut Frame, area: Rect, ctx: &TableRenderContext<'_>) {
+/// Draws the table and returns how many rows fit below the header.
+pub(crate) fn draw_with_context(f: &mut Frame, area: Rect, ctx: &TableRenderContext<'_>) -> usize {
     f.render_widget(Clear, area);
 
     let width = area.width as usize;
     let row_height = ctx.decoration.row_height();
-    let layout = layout::layout_for(ctx.mode, width);
+    let layout = layout::layout_for(
+        ctx.mode,
+        width,
+        ctx.columns,
+        ctx.both_rates,
+        ctx.rolling.is_some(),
+    );
     let header_lines = layout.header_height();
+    let capacity = (area.height.saturating_sub(header_lines) / row_height.max(1)) as usize;
+    let ctx = &TableRenderContext {
+        scroll: clamp_scroll(
+            ctx.rows.len(),
+            capacity,
+            follow_selection(ctx.scroll, capacity, ctx.selected),
+        ),
+        ..*ctx
+    };
 
     if matches!(ctx.decoration, Decoration::Background) {
         decor::draw_background_meters(f, area, ctx, header_lines);
     }
 
+    let party_mean = layout::party_mean(ctx.rows);
     let table = Table::new(
-        ctx.rows.iter().map(|row| layout.data_row(row, row_height)),
+        ctx.rows
+            .iter()
+            .enumerate()
+            .skip(ctx.scroll)
+            .map(|(index, row)| {
+                let is_self = ctx.self_name == Some(row.name.as_str());
+                let rolling = ctx.rolling.and_then(|rates| rates.get(&row.name).copied());
+                let data_row = layout.data_row(row, row_height, party_mean, rolling, is_self);
+                if ctx.selected == Some(index) {
+                    data_row.style(selection_style())
+                } else {
+                    data_row
+                }
+            }),
         layout.widths(),
     )
-    .header(layout.header_row())
+    .header(layout.header_row(ctx.sorted))
     .block(Block::default().borders(Borders::NONE))
     .column_spacing(layout.column_spacing());
 
@@ -54,6 +169,47 @@ pub(crate) fn draw_with_context(f: &mut Frame, area: Rect, ctx: &TableRenderCont
     if matches!(ctx.decoration, Decoration::Underline) {
         decor::draw_underlines(f, area, ctx, header_lines);
     }
+
+    if let Some(indicator) = scroll_indicator(ctx.rows.len(), capacity, ctx.scroll) {
+        let width = (indicator.chars().count() as u16).min(area.width);
+        let rect = Rect {
+            x: area.x + area.width - width,
+            y: area.y + area.height - 1,
+            width,
+            height: 1,
+        };
+        f.render_widget(Paragraph::new(Span::styled(indicator, title_style())), rect);
+    }
+    capacity
+}
+
+/// Shifts `scroll` just enough to keep the selected row inside a window of `capacity` rows.
+fn follow_selection(scroll: usize, capacity: usize, selected: Option<usize>) -> usize {
+    match selected {
+        Some(index) if index < scroll => index,
+        Some(index) if capacity > 0 && index >= scroll + capacity => index + 1 - capacity,
+        _ => scroll,
+    }
+}
+
+fn clamp_scroll(total: usize, capacity: usize, scroll: usize) -> usize {
+    scroll.min(total.saturating_sub(capacity))
+}
+
+/// "▲ n ▼ m" hint for rows hidden above/below the visible window, if any.
+fn scroll_indicator(total: usize, capacity: usize, scroll: usize) -> Option<String> {
+    if capacity == 0 || total <= capacity {
+        return None;
+    }
+    let below = total.saturating_sub(scroll + capacity);
+    let mut parts = Vec::new();
+    if scroll > 0 {
+        parts.push(format!("▲ {scroll}"));
+    }
+    if below > 0 {
+        parts.push(format!("▼ {below} more"));
+    }
+    Some(format!(" {} ", parts.join(" ")))
 }
 
 fn draw_header_separator(f: &mut Frame, area: Rect, header_lines: u16) {
@@ -77,3 +233,34 @@ fn draw_header_separator(f: &mut Frame, area: Rect, header_lines: u16) {
     )));
     f.render_widget(separator, rect);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scroll_is_clamped_to_the_last_full_page() {
+        assert_eq!(clamp_scroll(10, 4, 3), 3);
+        assert_eq!(clamp_scroll(10, 4, 9), 6);
+        assert_eq!(clamp_scroll(3, 4, 2), 0);
+    }
+
+    #[test]
+    fn scroll_follows_the_selected_row() {
+        assert_eq!(follow_selection(0, 4, None), 0);
+        assert_eq!(follow_selection(0, 4, Some(3)), 0);
+        assert_eq!(follow_selection(0, 4, Some(6)), 3);
+        assert_eq!(follow_selection(5, 4, Some(2)), 2);
+    }
+
+    #[test]
+    fn indicator_reports_hidden_rows() {
+        assert_eq!(scroll_indicator(3, 4, 0), None);
+        assert_eq!(scroll_indicator(10, 4, 0).as_deref(), Some(" ▼ 6 more "));
+        assert_eq!(
+            scroll_indicator(10, 4, 3).as_deref(),
+            Some(" ▲ 3 ▼ 3 more ")
+        );
+        assert_eq!(scroll_indicator(10, 4, 6).as_deref(), Some(" ▲ 6 "));
+    }
+}
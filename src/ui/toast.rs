@@ -0,0 +1,28 @@
+use ratatui::layout::Rect;
+use ratatui::text::Span;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::theme::{title_style, value_style};
+
+/// Draws a one-line notice in a small box just above the footer, right-aligned.
+pub(super) fn draw(f: &mut Frame, message: &str) {
+    let area = f.size();
+    if area.height < 5 || area.width < 10 {
+        return;
+    }
+    let width = (message.chars().count() as u16 + 4).min(area.width);
+    let rect = Rect {
+        x: area.x + area.width - width,
+        y: area.y + area.height - 4,
+        width,
+        height: 3,
+    };
+    f.render_widget(Clear, rect);
+    let widget = Paragraph::new(Span::styled(message.to_string(), value_style())).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(title_style()),
+    );
+    f.render_widget(widget, rect);
+}
@@ -1,18 +1,46 @@
-use std::cmp::Ordering;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use chrono::{Local, TimeZone};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::widgets::{
+    Block, Borders, Clear, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+    ScrollbarState, Sparkline,
+};
 use ratatui::Frame;
-
+use unicode_width::UnicodeWidthStr;
+
+use crate::history::bosses::{classify_run, group_pulls, PullGroup};
+use crate::history::buffs::BuffUptime;
+use crate::history::deaths::{deaths_by_combatant, DeathEvent};
+use crate::history::types::{dps_series, EncounterFrame};
+use crate::history::util::{
+    parse_duration_millis, parse_duration_rate_secs, parse_duration_secs, parse_number,
+};
+use crate::history::{
+    local_date_id, DungeonHistoryItem, DungeonStreak, EncounterRecord, HistoryEncounterItem,
+};
 use crate::model::{
-    AppSnapshot, CombatantRow, DungeonPanelLevel, HistoryPanelLevel, HistoryView, ViewMode,
+    visible_detail_rows, AppSnapshot, ClockFormat, CombatantRow, DungeonPanelLevel,
+    EncounterSummary, HistoryPanelLevel, HistoryView, RecentKind, RecentTarget, RoleFilter,
+    ViewMode, SEARCH_RESULT_LIMIT,
 };
-use crate::theme::{header_style, title_style, value_style, TEXT};
+use crate::theme::{check_style, header_style, palette, selection_style, title_style, value_style};
+use crate::ui::format::{format_duration_short, truncate_display};
 use crate::ui::{draw_table_with_context, TableRenderContext};
 
+/// Rows taken by the day-total line above the encounter list (used for mouse hit-testing).
+pub const DAY_TOTAL_ROWS: u16 = 1;
+
+/// Rows the last drawn detail table had room for; the layout depends on which panels are open
+/// and the terminal size, so only the renderer knows it.
+static DETAIL_CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+/// Rows the combatant table of the last drawn encounter detail had room for.
+pub fn detail_table_capacity() -> usize {
+    DETAIL_CAPACITY.load(Ordering::Relaxed)
+}
+
 pub fn draw_history(f: &mut Frame, s: &AppSnapshot) {
     let area = f.size();
     f.render_widget(Clear, area);
@@ -25,6 +53,80 @@ pub fn draw_history(f: &mut Frame, s: &AppSnapshot) {
 
     draw_header(f, chunks[0], s);
     draw_body(f, chunks[1], s);
+
+    if s.history.show_recent {
+        draw_recent_overlay(f, area, s);
+    }
+}
+
+/// Quick list of recently opened encounters and runs, drawn over the history panel.
+fn draw_recent_overlay(f: &mut Frame, area: Rect, s: &AppSnapshot) {
+    let width = area.width.min(64);
+    let height = (s.history.recent.len().max(1) as u16 + 3).min(area.height);
+    if width < 20 || height < 4 {
+        return;
+    }
+    let rect = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, rect);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled("Recently viewed", title_style()))
+        .title_bottom(Line::from(Span::styled(
+            " Enter jump · v/Esc close ",
+            header_style(),
+        )));
+    if s.history.recent.is_empty() {
+        let empty = Paragraph::new("Nothing opened yet.")
+            .style(header_style())
+            .alignment(Alignment::Center)
+            .block(block);
+        f.render_widget(empty, rect);
+        return;
+    }
+
+    let items: Vec<ListItem> = s
+        .history
+        .recent
+        .iter()
+        .map(|entry| {
+            let kind = match entry.kind {
+                RecentKind::Encounter => "Pull ",
+                RecentKind::Dungeon => "Run  ",
+            };
+            let label = match &entry.target {
+                RecentTarget::Found { date_id, title } => {
+                    Span::styled(format!("{date_id} · {title}"), value_style())
+                }
+                RecentTarget::Pending => Span::styled("loading…", header_style()),
+                RecentTarget::Missing => Span::styled("no longer in history", header_style()),
+            };
+            ListItem::new(Line::from(vec![Span::styled(kind, header_style()), label]))
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(s.history.recent_selected));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(selection_style());
+    f.render_stateful_widget(list, rect, &mut state);
+}
+
+/// "   streak: 4 (best 9)" for the dungeon tab; nothing before the first complete run.
+fn streak_spans(streak: DungeonStreak) -> Vec<Span<'static>> {
+    if streak.longest == 0 {
+        return Vec::new();
+    }
+    vec![
+        Span::styled("   streak: ", header_style()),
+        Span::styled(streak.current.to_string(), value_style()),
+        Span::styled(format!(" (best {})", streak.longest), header_style()),
+    ]
 }
 
 fn draw_header(f: &mut Frame, area: Rect, s: &AppSnapshot) {
@@ -34,20 +136,37 @@ fn draw_header(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         err.as_str()
     } else {
         match (s.history.view, s.history.level, s.history.dungeon_level) {
+            (HistoryView::Encounters, _, _) if s.history.search_editing => {
+                "Type to search every day by title or zone · Enter searches · Esc cancels"
+            }
+            (HistoryView::Encounters, HistoryPanelLevel::Dates, _) if s.history.filter_restored => {
+                "Saved encounter filter active (/ on the encounter list edits it, Esc clears it)"
+            }
             (HistoryView::Encounters, HistoryPanelLevel::Dates, _) => {
-                "Enter/Click ▸ view encounters · ↑/↓ scroll · Tab switches view"
+                "Enter/Click ▸ view encounters · ↑/↓ scroll · / search · v recent · Tab switches view"
+            }
+            (HistoryView::Encounters, HistoryPanelLevel::Encounters, _)
+                if s.history.filter_editing =>
+            {
+                "Type to filter by title · Enter keeps it · Esc clears it"
             }
             (HistoryView::Encounters, HistoryPanelLevel::Encounters, _) => {
-                "← dates · ↑/↓ scroll · Enter view details · Tab switches view"
+                "← dates · ↑/↓ scroll · Enter view details · / filter · j job · x delete · Tab switches view"
             }
             (HistoryView::Encounters, HistoryPanelLevel::EncounterDetail, _) => {
-                "← encounters · ↑/↓ switch encounter · m toggles DPS/Heal · Tab switches view"
+                "← encounters · ↑/↓ switch encounter · [/] combatant · Enter abilities · m toggles DPS/Heal"
+            }
+            (HistoryView::Encounters, HistoryPanelLevel::Abilities, _) => {
+                "← combatants · ↑/↓ scroll · Tab switches view"
+            }
+            (HistoryView::Encounters, HistoryPanelLevel::SearchResults, _) => {
+                "← dates · ↑/↓ scroll · Enter view details · / new search · Tab switches view"
             }
             (HistoryView::Dungeons, _, DungeonPanelLevel::Dates) => {
-                "Enter/Click ▸ view runs · ↑/↓ scroll · Tab switches view"
+                "Enter/Click ▸ view runs · ↑/↓ scroll · v recent · Tab switches view"
             }
             (HistoryView::Dungeons, _, DungeonPanelLevel::Runs) => {
-                "← dates · ↑/↓ scroll · Enter view run · Tab switches view"
+                "← dates · ↑/↓ scroll · Enter view run · x delete · Tab switches view"
             }
             (HistoryView::Dungeons, _, DungeonPanelLevel::RunDetail) => {
                 "← runs · ↑/↓ select pull · Enter view pull · m toggles table · Tab switches view"
@@ -64,19 +183,30 @@ fn draw_header(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         (header_style(), title_style().add_modifier(Modifier::BOLD))
     };
 
-    let tabs_line = Line::from(vec![
+    let mut tabs = vec![
         Span::styled("Encounters", enc_style),
         Span::raw("  |  "),
         Span::styled("Dungeons", dun_style),
-    ]);
+    ];
+    if s.history.view == HistoryView::Dungeons {
+        tabs.extend(streak_spans(s.dungeon_streak));
+    }
+    let tabs_line = Line::from(tabs);
 
-    let title_line = Line::from(vec![Span::styled(
+    let mut title_spans = vec![Span::styled(
         "History",
         Style::default()
-            .fg(Color::Cyan)
+            .fg(palette().value)
             .add_modifier(Modifier::BOLD),
-    )]);
-    let subtitle_line = Line::from(vec![Span::styled(subtitle, Style::default().fg(TEXT))]);
+    )];
+    if s.recording_disabled {
+        title_spans.push(Span::styled(
+            " · recording disabled (--no-record)",
+            Style::default().fg(palette().status_idle),
+        ));
+    }
+    let title_line = Line::from(title_spans);
+    let subtitle_line = Line::from(vec![Span::styled(subtitle, header_style())]);
 
     let block = Paragraph::new(vec![title_line, tabs_line, subtitle_line])
         .alignment(ratatui::layout::Alignment::Left)
@@ -116,6 +246,8 @@ fn draw_body(f: &mut Frame, area: Rect, s: &AppSnapshot) {
                 HistoryPanelLevel::Dates => draw_dates(f, area, s),
                 HistoryPanelLevel::Encounters => draw_encounters(f, area, s),
                 HistoryPanelLevel::EncounterDetail => draw_encounter_detail(f, area, s),
+                HistoryPanelLevel::Abilities => draw_abilities(f, area, s),
+                HistoryPanelLevel::SearchResults => draw_search_results(f, area, s),
             }
         }
         HistoryView::Dungeons => {
@@ -172,23 +304,75 @@ fn draw_dates(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         .constraints([Constraint::Min(1), Constraint::Length(1)])
         .split(area);
 
+    let mut title = vec![Span::raw(format!(
+        "Dates · {}",
+        s.history.date_sort.label()
+    ))];
+    if s.history.search_editing {
+        title.push(Span::raw(" · "));
+        title.push(Span::styled(
+            format!("search: {}▏", s.history.search_query),
+            title_style(),
+        ));
+    }
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Dates"))
-        .highlight_style(
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        );
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Line::from(title)),
+        )
+        .highlight_style(selection_style());
 
     f.render_stateful_widget(list, chunks[0], &mut state);
+    draw_list_scrollbar(
+        f,
+        Block::default().borders(Borders::ALL).inner(chunks[0]),
+        s.history.days.len(),
+        s.history.selected_day,
+    );
 
-    let hint = Paragraph::new("Tab swaps view · Enter view encounters")
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::NONE));
+    let hint = Paragraph::new(
+        "Tab swaps view · Enter view encounters · e export day · o order · r refresh",
+    )
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::NONE));
     f.render_widget(hint, chunks[1]);
 }
 
+/// Scroll position for a list of `len` items with `selected` highlighted, or `None` when all
+/// of them fit in `rows` lines and there is nothing to scroll.
+fn list_scrollbar_state(len: usize, rows: u16, selected: usize) -> Option<ScrollbarState> {
+    if len <= rows as usize {
+        return None;
+    }
+    Some(
+        ScrollbarState::new(len)
+            .position(selected.min(len - 1))
+            .viewport_content_length(rows as usize),
+    )
+}
+
+/// Draws a scrollbar in the column just right of `rows`, which is the right border of the
+/// block the list sits in, so the labels keep their full width.
+fn draw_list_scrollbar(f: &mut Frame, rows: Rect, len: usize, selected: usize) {
+    let Some(mut state) = list_scrollbar_state(len, rows.height, selected) else {
+        return;
+    };
+    let column = Rect {
+        x: rows.x + rows.width,
+        y: rows.y,
+        width: 1,
+        height: rows.height,
+    };
+    if column.right() > f.size().right() {
+        return;
+    }
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None);
+    f.render_stateful_widget(scrollbar, column, &mut state);
+}
+
 fn draw_encounters(f: &mut Frame, area: Rect, s: &AppSnapshot) {
     let Some(day) = s.history.current_day() else {
         let block = Paragraph::new("No date selected.")
@@ -214,29 +398,621 @@ fn draw_encounters(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         return;
     }
 
-    let items: Vec<ListItem> = day
+    let visible: Vec<&HistoryEncounterItem> = day
         .encounters
+        .iter()
+        .filter(|enc| s.history.encounter_matches(enc))
+        .collect();
+
+    let mut title = vec![Span::raw(format!("Encounters · {}", day.label))];
+    if s.history.filter_editing || !s.history.filter.is_empty() {
+        let cursor = if s.history.filter_editing { "▏" } else { "" };
+        title.push(Span::raw(" · "));
+        title.push(Span::styled(
+            format!("filter: {}{cursor}", s.history.filter),
+            title_style(),
+        ));
+        if s.history.filter_restored {
+            title.push(Span::styled(" (saved)", header_style()));
+        }
+    }
+    if let Some(job) = &s.history.job_filter {
+        title.push(Span::raw(" · "));
+        title.push(Span::styled(format!("job: {job}"), title_style()));
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Line::from(title));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let selected = visible
+        .iter()
+        .position(|enc| {
+            Some(enc.key.as_slice()) == s.history.current_encounter().map(|c| c.key.as_slice())
+        })
+        .unwrap_or(0);
+    let labels: Vec<(String, String)> = visible
         .iter()
         .map(|enc| {
-            let text = format!("{}  [{}]", enc.display_title, enc.time_label);
-            ListItem::new(text)
+            let time =
+                time_of_day_label(enc.last_seen_ms, &enc.time_label, s.settings.clock_format);
+            let mut suffix = format!("  [{time}]");
+            if let Some(scale) = enc.scale {
+                suffix.push_str("  · ");
+                suffix.push_str(scale.label());
+            }
+            if enc.never_active {
+                suffix.push_str("  · never active");
+            }
+            (enc.display_title.clone(), suffix)
         })
         .collect();
+    let width = inner.width as usize;
+    let full_name = labels
+        .get(selected)
+        .filter(|(name, suffix)| name.width() + suffix.width() > width)
+        .map(|(name, _)| name.clone());
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(DAY_TOTAL_ROWS),
+            Constraint::Min(1),
+            Constraint::Length(u16::from(full_name.is_some())),
+        ])
+        .split(inner);
+
+    f.render_widget(
+        Paragraph::new(day_total_line(&visible, s.settings.subsecond_durations)),
+        chunks[0],
+    );
+
+    if visible.is_empty() {
+        let message = Paragraph::new("No encounters match the filter. Press / or j to change it.")
+            .alignment(Alignment::Center)
+            .style(header_style());
+        f.render_widget(message, chunks[1]);
+        return;
+    }
+
+    let items: Vec<ListItem> = labels
+        .iter()
+        .map(|(name, suffix)| ListItem::new(fit_label(name, suffix, width)))
+        .collect();
 
     let mut state = ListState::default();
-    state.select(Some(s.history.selected_encounter));
+    state.select(Some(selected));
 
-    let title = format!("Encounters · {}", day.label);
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title(title))
-        .highlight_style(
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        );
+    let list = List::new(items).highlight_style(selection_style());
+
+    f.render_stateful_widget(list, chunks[1], &mut state);
+    draw_list_scrollbar(f, chunks[1], visible.len(), selected);
+    if let Some(name) = full_name {
+        f.render_widget(full_name_line(&name), chunks[2]);
+    }
+}
+
+fn draw_search_results(f: &mut Frame, area: Rect, s: &AppSnapshot) {
+    let results = &s.history.search_results;
+    let cursor = if s.history.search_editing { "▏" } else { "" };
+    let mut title = vec![
+        Span::raw("Search · "),
+        Span::styled(format!("{}{cursor}", s.history.search_query), title_style()),
+    ];
+    if !s.history.loading {
+        let capped = if results.len() >= SEARCH_RESULT_LIMIT {
+            " (newest shown)"
+        } else {
+            ""
+        };
+        title.push(Span::raw(format!(" · {} matches{capped}", results.len())));
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Line::from(title));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if s.history.loading || results.is_empty() {
+        let message = if s.history.loading {
+            "Searching…"
+        } else {
+            "No encounters match. Press / to search again."
+        };
+        let message = Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .style(header_style());
+        f.render_widget(message, inner);
+        return;
+    }
+
+    let selected = s.history.search_selected.min(results.len() - 1);
+    let labels: Vec<(String, String)> = results
+        .iter()
+        .map(|enc| {
+            let time =
+                time_of_day_label(enc.last_seen_ms, &enc.time_label, s.settings.clock_format);
+            let date = local_date_id(enc.last_seen_ms);
+            let mut suffix = format!("  [{date} {time}]");
+            if !enc.duration.is_empty() {
+                suffix.push_str("  · ");
+                suffix.push_str(&enc.duration);
+            }
+            if enc.never_active {
+                suffix.push_str("  · never active");
+            }
+            (enc.display_title.clone(), suffix)
+        })
+        .collect();
+    let width = inner.width as usize;
+    let full_name = labels
+        .get(selected)
+        .filter(|(name, suffix)| name.width() + suffix.width() > width)
+        .map(|(name, _)| name.clone());
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),
+            Constraint::Length(u16::from(full_name.is_some())),
+        ])
+        .split(inner);
+
+    let items: Vec<ListItem> = labels
+        .iter()
+        .map(|(name, suffix)| ListItem::new(fit_label(name, suffix, width)))
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(selected));
+    let list = List::new(items).highlight_style(selection_style());
+    f.render_stateful_widget(list, chunks[0], &mut state);
+    draw_list_scrollbar(f, chunks[0], results.len(), selected);
+    if let Some(name) = full_name {
+        f.render_widget(full_name_line(&name), chunks[1]);
+    }
+}
+
+/// `name` followed by `suffix`, with the name shortened so the whole label fits in `width`.
+fn fit_label(name: &str, suffix: &str, width: usize) -> String {
+    let budget = width.saturating_sub(suffix.width()).max(MIN_NAME_COLUMNS);
+    format!("{}{suffix}", truncate_display(name, budget))
+}
 
+/// Untruncated name of the selected entry, shown under a list whose label had to be cut.
+fn full_name_line(name: &str) -> Paragraph<'static> {
+    Paragraph::new(Line::from(vec![
+        Span::styled("▸ ", header_style()),
+        Span::styled(name.to_string(), value_style()),
+    ]))
+}
+
+/// Summary line rendered above the encounter list; totals come from the day's index entries so
+/// they stay in step with whatever the list currently shows.
+fn day_total_line(encounters: &[&HistoryEncounterItem], subsecond: bool) -> Line<'static> {
+    let total_secs: f64 = encounters
+        .iter()
+        .filter_map(|enc| parse_duration_rate_secs(&enc.duration, subsecond))
+        .sum();
+    let total_damage: f64 = encounters.iter().map(|enc| parse_number(&enc.damage)).sum();
+    let avg_dps = if total_secs > 0.0 {
+        total_damage / total_secs
+    } else {
+        0.0
+    };
+
+    Line::from(vec![
+        Span::styled("Day total ", header_style()),
+        Span::styled(format!("{} pulls", encounters.len()), value_style()),
+        Span::styled(" · ", header_style()),
+        Span::styled(format_duration_short(total_secs as u64), value_style()),
+        Span::styled(" in combat · ", header_style()),
+        Span::styled(format_number(total_damage), value_style()),
+        Span::styled(" dmg · ", header_style()),
+        Span::styled(format_number(avg_dps), value_style()),
+        Span::styled(" dps", header_style()),
+    ])
+}
+
+fn combatants_label(record: &EncounterRecord) -> String {
+    match record.content_scale() {
+        Some(scale) => format!("{} ({})", record.combatant_count(), scale.label()),
+        None => "Unknown".to_string(),
+    }
+}
+
+fn coverage_line(record: &EncounterRecord) -> Line<'static> {
+    let value = match record.frame_coverage() {
+        Some(coverage) => Span::styled(
+            format!("● {}% ({})", coverage.score, coverage.label()),
+            check_style(coverage.verdict()),
+        ),
+        None => Span::styled("Unknown", value_style()),
+    };
+    Line::from(vec![Span::styled("Coverage: ", header_style()), value])
+}
+
+/// Names shorter than this are never truncated further, even if the label then overflows.
+const MIN_NAME_COLUMNS: usize = 12;
+
+/// "{prefix} · {name}" for a bordered block, with the name cut to fit inside the corners.
+fn block_title(prefix: &str, name: &str, block_width: u16) -> String {
+    let budget = (block_width as usize).saturating_sub(prefix.width() + 5);
+    format!("{prefix} · {}", truncate_display(name, budget))
+}
+
+/// Detail screens shorter than this collapse the summary panels into one line.
+const COMPACT_DETAIL_HEIGHT: u16 = 24;
+
+struct DetailLayout {
+    compact: bool,
+    summary: Rect,
+    table: Rect,
+    mode: Option<Rect>,
+    hint: Option<Rect>,
+}
+
+fn detail_layout(area: Rect, summary_rows: usize) -> DetailLayout {
+    if area.height < COMPACT_DETAIL_HEIGHT {
+        // One condensed summary line, the table, and the key hint when it fits.
+        let hint_rows = u16::from(area.height >= 4);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1.min(area.height)),
+                Constraint::Min(0),
+                Constraint::Length(hint_rows),
+            ])
+            .split(area);
+        return DetailLayout {
+            compact: true,
+            summary: chunks[0],
+            table: chunks[1],
+            mode: None,
+            hint: (hint_rows > 0).then_some(chunks[2]),
+        };
+    }
+
+    let summary_height = (summary_rows.saturating_add(2) as u16).clamp(3, area.height);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(summary_height),
+            Constraint::Min(6),
+            Constraint::Length(4),
+            Constraint::Length(1),
+        ])
+        .split(area);
+    DetailLayout {
+        compact: false,
+        summary: chunks[0],
+        table: chunks[1],
+        mode: Some(chunks[2]),
+        hint: Some(chunks[3]),
+    }
+}
+
+fn compact_summary_line(metrics: &[(&str, String)]) -> Line<'static> {
+    let mut spans = Vec::with_capacity(metrics.len() * 3);
+    for (idx, (label, value)) in metrics.iter().enumerate() {
+        if idx > 0 {
+            spans.push(Span::styled(" · ", header_style()));
+        }
+        spans.push(Span::styled(format!("{label} "), header_style()));
+        let value = if value.is_empty() { "—" } else { value };
+        spans.push(Span::styled(value.to_string(), value_style()));
+    }
+    Line::from(spans)
+}
+
+/// Rows reserved for the deaths panel (including borders) when it is toggled on.
+const DEATHS_PANEL_HEIGHT: u16 = 6;
+
+/// Rows reserved for the buff uptime panel (including borders) when it is toggled on.
+const BUFF_PANEL_HEIGHT: u16 = 6;
+
+/// Rows of the DPS-over-time chart (including borders) under the summary panels.
+const DPS_CHART_HEIGHT: u16 = 5;
+
+fn split_bottom_panel(table: Rect, show: bool, height: u16) -> (Rect, Option<Rect>) {
+    // Keep at least a header and a couple of rows visible in the table.
+    if !show || table.height < height + 5 {
+        return (table, None);
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(height)])
+        .split(table);
+    (chunks[0], Some(chunks[1]))
+}
+
+fn draw_dps_chart(f: &mut Frame, area: Rect, frames: &[EncounterFrame]) {
+    let series = dps_series(frames);
+    let block = Block::default().borders(Borders::ALL);
+    if series.is_empty() {
+        let message = Paragraph::new("Not enough data for chart")
+            .alignment(Alignment::Center)
+            .style(header_style())
+            .block(block.title(Span::styled("DPS over time", title_style())));
+        f.render_widget(message, area);
+        return;
+    }
+    let peak = series.iter().copied().fold(0.0, f64::max);
+    let block = block.title(Span::styled(
+        format!("DPS over time · peak {}", format_number(peak)),
+        title_style(),
+    ));
+    let data = chart_buckets(&series, block.inner(area).width as usize);
+    let chart = Sparkline::default()
+        .block(block)
+        .data(&data)
+        .style(Style::default().fg(palette().accent));
+    f.render_widget(chart, area);
+}
+
+/// Squeezes `series` into at most `width` bars by averaging neighbouring samples, so the
+/// whole encounter fits instead of the sparkline cutting off its end.
+fn chart_buckets(series: &[f64], width: usize) -> Vec<u64> {
+    if width == 0 {
+        return Vec::new();
+    }
+    let buckets = series.len().min(width);
+    (0..buckets)
+        .map(|bucket| {
+            let start = bucket * series.len() / buckets;
+            let end = ((bucket + 1) * series.len() / buckets).max(start + 1);
+            let slice = &series[start..end];
+            (slice.iter().sum::<f64>() / slice.len() as f64).round() as u64
+        })
+        .collect()
+}
+
+fn draw_buff_panel(f: &mut Frame, area: Rect, uptime: &[BuffUptime], buff_names: &[String]) {
+    let lines: Vec<Line> = if buff_names.is_empty() {
+        vec![Line::from(Span::styled(
+            "No buffs configured (uptime_buffs in the config file).",
+            header_style(),
+        ))]
+    } else if uptime.is_empty() {
+        vec![Line::from(Span::styled(
+            "No buff data in this recording.",
+            header_style(),
+        ))]
+    } else {
+        uptime
+            .iter()
+            .map(|entry| {
+                let mut spans = vec![Span::styled(format!("{}: ", entry.name), header_style())];
+                let parts: Vec<String> = entry
+                    .uptimes
+                    .iter()
+                    .filter(|(_, share)| *share > 0.0)
+                    .map(|(buff, share)| format!("{buff} {:.0}%", share * 100.0))
+                    .collect();
+                spans.push(Span::styled(parts.join(", "), value_style()));
+                Line::from(spans)
+            })
+            .collect()
+    };
+    let panel = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(
+        Line::from(vec![
+            Span::styled("Buff uptime", title_style()),
+            Span::raw(" "),
+            Span::styled("(u toggles)", header_style()),
+        ]),
+    ));
+    f.render_widget(panel, area);
+}
+
+fn draw_death_panel(f: &mut Frame, area: Rect, events: &[DeathEvent]) {
+    let grouped = deaths_by_combatant(events);
+    let total: usize = grouped.iter().map(|(_, offsets)| offsets.len()).sum();
+    let lines: Vec<Line> = if grouped.is_empty() {
+        vec![Line::from(Span::styled(
+            "No deaths recorded.",
+            header_style(),
+        ))]
+    } else {
+        grouped
+            .into_iter()
+            .map(|(name, offsets)| {
+                let times: Vec<String> = offsets.into_iter().map(format_duration_short).collect();
+                Line::from(vec![
+                    Span::styled(format!("{name}: "), header_style()),
+                    Span::styled(times.join(", "), value_style()),
+                ])
+            })
+            .collect()
+    };
+    let panel = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(
+        Line::from(vec![
+            Span::styled(format!("Deaths · {total}"), title_style()),
+            Span::raw(" "),
+            Span::styled("(D toggles)", header_style()),
+        ]),
+    ));
+    f.render_widget(panel, area);
+}
+
+/// Deaths per cell of a `width`-cell track spanning `duration_ms`; deaths past the end land in
+/// the last cell.
+fn death_strip_cells(events: &[DeathEvent], duration_ms: u64, width: usize) -> Vec<usize> {
+    let mut cells = vec![0; width];
+    if width == 0 {
+        return cells;
+    }
+    let span = duration_ms.max(1);
+    for event in events {
+        let cell = (event.ms.min(span) as f64 / span as f64 * (width - 1) as f64).round();
+        cells[cell as usize] += 1;
+    }
+    cells
+}
+
+/// One-line strip under the summary marking when the logged deaths happened, relative to the
+/// encounter duration. Records without logged deaths get a bare track.
+fn draw_death_strip(f: &mut Frame, area: Rect, record: &EncounterRecord) {
+    let label = format!("Deaths {:>2} ", record.deaths.len());
+    let width = (area.width as usize).saturating_sub(label.width());
+    let duration_ms = parse_duration_secs(&record.encounter.duration)
+        .map(|secs| secs * 1000)
+        .unwrap_or_else(|| record.last_seen_ms.saturating_sub(record.first_seen_ms));
+    let marker = Style::default().fg(palette().check_fail);
+    let mut spans = vec![Span::styled(label, header_style())];
+    spans.extend(
+        death_strip_cells(&record.deaths, duration_ms, width)
+            .into_iter()
+            .map(|count| match count {
+                0 => Span::styled("─", header_style()),
+                1 => Span::styled("✖", marker),
+                2..=9 => Span::styled(count.to_string(), marker),
+                _ => Span::styled("+", marker),
+            }),
+    );
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Rows of a detail table as shown: sorted, narrowed by the role filter and, with
+/// `hide_zero_rows` on, without rows that put nothing into `mode`'s metric.
+fn detail_table_rows(s: &AppSnapshot, rows: &[CombatantRow], mode: ViewMode) -> Vec<CombatantRow> {
+    visible_detail_rows(
+        rows,
+        mode,
+        s.sort_key,
+        s.sort_ascending,
+        s.role_filter,
+        s.settings.hide_zero_rows,
+    )
+}
+
+/// An encounter's ENCDPS or ENCHPS as saved, or "—" when there is none or the pull lasted
+/// 0 ms (ACT's rate means nothing then; see `zero_duration_policy`).
+fn encounter_rate(encounter: &EncounterSummary, mode: ViewMode) -> String {
+    let rate = match mode {
+        ViewMode::Dps => encounter.encdps.trim(),
+        ViewMode::Heal => encounter.enchps.trim(),
+    };
+    if rate.is_empty() || parse_duration_millis(&encounter.duration) == Some(0) {
+        "—".to_string()
+    } else {
+        rate.to_string()
+    }
+}
+
+/// Why a detail table with recorded rows shows none of them.
+fn empty_rows_message(s: &AppSnapshot) -> &'static str {
+    if s.role_filter == RoleFilter::All && s.settings.hide_zero_rows {
+        "Every combatant is at 0 here. Turn off \"Hide 0 rows\" in settings to show them."
+    } else {
+        s.role_filter.empty_message()
+    }
+}
+
+/// "Combatants (8) · DPS", or "(6 of 8)" while filters hide some of the recorded rows.
+fn combatants_title(
+    mode: ViewMode,
+    filter: RoleFilter,
+    shown: usize,
+    total: usize,
+) -> Line<'static> {
+    let count = if shown == total {
+        total.to_string()
+    } else {
+        format!("{shown} of {total}")
+    };
+    let mut title = format!("Combatants ({count}) · {}", mode.label());
+    if filter != RoleFilter::All {
+        title.push_str(&format!(" · {}", filter.label()));
+    }
+    Line::from(vec![
+        Span::styled(title, title_style()),
+        Span::raw(" "),
+        Span::styled("(m toggles · f role)", header_style()),
+    ])
+}
+
+/// Ability breakdown of the combatant under the detail cursor, highest damage first.
+fn draw_abilities(f: &mut Frame, area: Rect, s: &AppSnapshot) {
+    let record = s
+        .history
+        .current_encounter()
+        .and_then(|enc| enc.record.as_ref());
+    let (Some(record), Some(row)) = (
+        record,
+        s.history.detail_combatant(
+            s.role_filter,
+            s.sort_key,
+            s.sort_ascending,
+            s.settings.hide_zero_rows,
+        ),
+    ) else {
+        let block = Paragraph::new("No combatant selected.")
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(block, area);
+        return;
+    };
+
+    let title = if row.job.is_empty() {
+        format!("Abilities · {}", row.name)
+    } else {
+        format!("Abilities · {} ({})", row.name, row.job)
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(title, title_style()));
+
+    let abilities = record.abilities_for(&row.name).unwrap_or_default();
+    if abilities.is_empty() {
+        let message = Paragraph::new("No ability data captured for this combatant.")
+            .alignment(Alignment::Center)
+            .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let total = if row.damage > 0.0 {
+        row.damage
+    } else {
+        abilities.iter().map(|ability| ability.damage).sum()
+    };
+    let name_width = abilities
+        .iter()
+        .map(|ability| ability.name.width())
+        .max()
+        .unwrap_or(0)
+        .min(28);
+    let items: Vec<ListItem> = abilities
+        .iter()
+        .map(|ability| {
+            let share = if total > 0.0 {
+                ability.damage / total * 100.0
+            } else {
+                0.0
+            };
+            let name = truncate_display(&ability.name, name_width);
+            let pad = " ".repeat(name_width.saturating_sub(name.width()));
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{name}{pad}"), value_style()),
+                Span::styled(format!("  {:>5} hits", ability.hits), header_style()),
+                Span::styled(
+                    format!("  {:>10}", format_number(ability.damage)),
+                    value_style(),
+                ),
+                Span::styled(format!("  {share:>5.1}%"), header_style()),
+            ]))
+        })
+        .collect();
+
+    let selected = s.history.ability_selected.min(abilities.len() - 1);
+    let mut state = ListState::default();
+    state.select(Some(selected));
+    let rows = block.inner(area);
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(selection_style());
     f.render_stateful_widget(list, area, &mut state);
+    draw_list_scrollbar(f, rows, abilities.len(), selected);
 }
 
 fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
@@ -263,7 +1039,7 @@ fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
                 Block::default()
                     .borders(Borders::ALL)
                     .title(Line::from(vec![Span::styled(
-                        format!("Details · {}", encounter.display_title),
+                        block_title("Details", &encounter.display_title, area.width),
                         title_style(),
                     )])),
             );
@@ -289,14 +1065,24 @@ fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             },
         ),
         ("Duration", record.encounter.duration.clone()),
-        ("ENCDPS", record.encounter.encdps.clone()),
+        ("ENCDPS", encounter_rate(&record.encounter, ViewMode::Dps)),
         ("Damage", record.encounter.damage.clone()),
     ];
 
     let technical_metrics = [
         ("Snapshots", record.snapshots.to_string()),
         ("Frames", record.frames.len().to_string()),
-        ("Last seen", encounter.timestamp_label.clone()),
+        ("Combatants", combatants_label(record)),
+        (
+            "Started",
+            format_timestamp_label(record.first_seen_ms, s.settings.clock_format),
+        ),
+        (
+            "Ended",
+            format_timestamp_label(record.last_seen_ms, s.settings.clock_format),
+        ),
+        ("Wall time", wall_time_label(record)),
+        ("Version", version_label(record.app_version.as_deref())),
     ];
 
     let summary_lines: Vec<Line> = basic_metrics
@@ -309,7 +1095,7 @@ fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         })
         .collect();
 
-    let technical_lines: Vec<Line> = technical_metrics
+    let mut technical_lines: Vec<Line> = technical_metrics
         .iter()
         .map(|(label, value)| {
             Line::from(vec![
@@ -318,96 +1104,134 @@ fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             ])
         })
         .collect();
+    technical_lines.push(coverage_line(record));
 
-    let max_summary_rows = summary_lines.len().max(technical_lines.len());
-    let mut summary_height = max_summary_rows.saturating_add(2) as u16;
-    let max_height = area.height.max(1u16);
-    if summary_height > max_height {
-        summary_height = max_height;
+    let detail_mode = s.history.detail_mode;
+    let visible_rows = detail_table_rows(s, &record.rows, detail_mode);
+
+    let layout = detail_layout(area, summary_lines.len().max(technical_lines.len()));
+    let (chart_area, table_area) = if !layout.compact && layout.table.height >= DPS_CHART_HEIGHT + 8
+    {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(DPS_CHART_HEIGHT), Constraint::Min(0)])
+            .split(layout.table);
+        (Some(chunks[0]), chunks[1])
+    } else {
+        (None, layout.table)
+    };
+    if let Some(chart_area) = chart_area {
+        draw_dps_chart(f, chart_area, &record.frames);
     }
-    let min_required = 3u16.min(max_height);
-    if summary_height < min_required {
-        summary_height = min_required;
+    let (table_area, deaths_area) =
+        split_bottom_panel(table_area, s.history.show_deaths, DEATHS_PANEL_HEIGHT);
+    let (table_area, buffs_area) =
+        split_bottom_panel(table_area, s.history.show_buffs, BUFF_PANEL_HEIGHT);
+    let (strip_area, table_area) = if !layout.compact && table_area.height >= 8 {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(table_area);
+        (Some(chunks[0]), chunks[1])
+    } else {
+        (None, table_area)
+    };
+    if let Some(strip_area) = strip_area {
+        draw_death_strip(f, strip_area, record);
     }
 
-    let detail_mode = s.history.detail_mode;
-    let mut sorted_rows = record.rows.clone();
-    sort_rows_for_mode(&mut sorted_rows, detail_mode);
-
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(summary_height),
-            Constraint::Min(6),
-            Constraint::Length(4),
-            Constraint::Length(1),
-        ])
-        .split(area);
-
-    let summary_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-        .split(layout[0]);
+    if layout.compact {
+        f.render_widget(
+            Paragraph::new(compact_summary_line(&basic_metrics)),
+            layout.summary,
+        );
+    } else {
+        let summary_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(layout.summary);
 
-    let summary = Paragraph::new(summary_lines)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(Line::from(vec![Span::styled(
-                    format!("Details · {}", encounter.display_title),
-                    title_style(),
-                )])),
-        )
-        .alignment(Alignment::Left);
-    f.render_widget(summary, summary_chunks[0]);
+        let summary = Paragraph::new(summary_lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Line::from(vec![Span::styled(
+                        block_title("Details", &encounter.display_title, summary_chunks[0].width),
+                        title_style(),
+                    )])),
+            )
+            .alignment(Alignment::Left);
+        f.render_widget(summary, summary_chunks[0]);
 
-    let technical = Paragraph::new(technical_lines)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(Line::from(vec![Span::styled(
-                    "Technical Details".to_string(),
-                    title_style(),
-                )])),
-        )
-        .alignment(Alignment::Left);
-    f.render_widget(technical, summary_chunks[1]);
+        let technical = Paragraph::new(technical_lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Line::from(vec![Span::styled(
+                        "Technical Details".to_string(),
+                        title_style(),
+                    )])),
+            )
+            .alignment(Alignment::Left);
+        f.render_widget(technical, summary_chunks[1]);
+    }
 
-    if sorted_rows.is_empty() {
+    if table_area.height == 0 {
+        // Nothing left for the table on extremely short terminals.
+    } else if record.rows.is_empty() {
         let block = Paragraph::new("No combatants recorded.")
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
-        f.render_widget(block, layout[1]);
+        f.render_widget(block, table_area);
+    } else if visible_rows.is_empty() {
+        let block = Paragraph::new(empty_rows_message(s))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(block, table_area);
     } else {
-        let table_title = Line::from(vec![
-            Span::styled(
-                format!("Combatants · {}", detail_mode.label()),
-                title_style(),
-            ),
-            Span::raw(" "),
-            Span::styled("(m toggles)", Style::default().fg(TEXT)),
-        ]);
+        let table_title = combatants_title(
+            detail_mode,
+            s.role_filter,
+            visible_rows.len(),
+            record.rows.len(),
+        );
         let block = Block::default().borders(Borders::ALL).title(table_title);
-        let table_area = layout[1];
         let inner = block.inner(table_area);
         f.render_widget(block, table_area);
 
         let ctx = TableRenderContext {
-            rows: &sorted_rows,
+            rows: &visible_rows,
             mode: detail_mode,
             decoration: s.decoration,
+            columns: &s.settings.column_order,
+            scroll: s.history.detail_scroll,
+            selected: Some(s.history.detail_row.min(visible_rows.len() - 1)),
+            self_name: s.settings.resolve_self(&visible_rows),
+            both_rates: s.show_both_rates,
+            rolling: None,
+            sorted: Some((s.sort_key.column(detail_mode), s.sort_ascending)),
         };
-        draw_table_with_context(f, inner, &ctx);
+        DETAIL_CAPACITY.store(draw_table_with_context(f, inner, &ctx), Ordering::Relaxed);
+    }
+
+    if let Some(deaths_area) = deaths_area {
+        draw_death_panel(f, deaths_area, &record.death_events());
+    }
+
+    if let Some(buffs_area) = buffs_area {
+        draw_buff_panel(
+            f,
+            buffs_area,
+            &encounter.buff_uptime,
+            &s.settings.uptime_buffs,
+        );
     }
 
     let metric_label = match detail_mode {
         ViewMode::Dps => "ENCDPS",
         ViewMode::Heal => "ENCHPS",
     };
-    let metric_value = match detail_mode {
-        ViewMode::Dps => &record.encounter.encdps,
-        ViewMode::Heal => &record.encounter.enchps,
-    };
+    let metric_value = encounter_rate(&record.encounter, detail_mode);
     let total_label = match detail_mode {
         ViewMode::Dps => "Total Damage",
         ViewMode::Heal => "Total Healed",
@@ -417,11 +1241,6 @@ fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         ViewMode::Heal => &record.encounter.healed,
     };
 
-    let metric_value = if metric_value.is_empty() {
-        "—".to_string()
-    } else {
-        metric_value.clone()
-    };
     let total_value = if total_value.is_empty() {
         "—".to_string()
     } else {
@@ -432,18 +1251,18 @@ fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         Line::from(vec![
             Span::styled("Current: ", header_style()),
             Span::styled(detail_mode.label(), value_style()),
-            Span::styled(" · press m to toggle", Style::default().fg(TEXT)),
+            Span::styled(" · press m to toggle", header_style()),
         ]),
         Line::from(vec![
             Span::styled("Sorting: ", header_style()),
             Span::styled(metric_label, value_style()),
-            Span::styled(" · encounter ", Style::default().fg(TEXT)),
+            Span::styled(" · encounter ", header_style()),
             Span::styled(metric_label, value_style()),
-            Span::styled(": ", Style::default().fg(TEXT)),
+            Span::styled(": ", header_style()),
             Span::styled(metric_value, value_style()),
-            Span::styled(" · ", Style::default().fg(TEXT)),
+            Span::styled(" · ", header_style()),
             Span::styled(total_label, header_style()),
-            Span::styled(": ", Style::default().fg(TEXT)),
+            Span::styled(": ", header_style()),
             Span::styled(total_value, value_style()),
         ]),
     ];
@@ -453,12 +1272,18 @@ fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             .borders(Borders::ALL)
             .title(Line::from(vec![Span::styled("View Mode", title_style())])),
     );
-    f.render_widget(mode_paragraph, layout[2]);
+    if let Some(mode_area) = layout.mode {
+        f.render_widget(mode_paragraph, mode_area);
+    }
 
-    let hint = Paragraph::new("← back · ↑/↓ switch encounter · m toggles DPS/Heal · Enter re-open")
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(hint, layout[3]);
+    let hint = Paragraph::new(
+        "← back · ↑/↓ switch encounter · PgUp/PgDn scroll · m toggles DPS/Heal · D deaths · u buffs · Enter abilities · e/E export JSON/CSV",
+    )
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::NONE));
+    if let Some(hint_area) = layout.hint {
+        f.render_widget(hint, hint_area);
+    }
 }
 
 fn draw_dungeon_dates(f: &mut Frame, area: Rect, s: &AppSnapshot) {
@@ -481,18 +1306,19 @@ fn draw_dungeon_dates(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Dungeon Dates"),
+                .title(format!("Dungeon Dates · {}", s.history.date_sort.label())),
         )
-        .highlight_style(
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        );
+        .highlight_style(selection_style());
 
     f.render_stateful_widget(list, chunks[0], &mut state);
+    draw_list_scrollbar(
+        f,
+        Block::default().borders(Borders::ALL).inner(chunks[0]),
+        s.history.dungeon_days.len(),
+        s.history.dungeon_selected_day,
+    );
 
-    let hint = Paragraph::new("Tab swaps view · Enter view runs")
+    let hint = Paragraph::new("Tab swaps view · Enter view runs · o order · r refresh")
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::NONE));
     f.render_widget(hint, chunks[1]);
@@ -523,39 +1349,58 @@ fn draw_dungeon_runs(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         return;
     }
 
-    let items: Vec<ListItem> = day
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Dungeon Runs · {}", day.label));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let width = inner.width as usize;
+
+    let labels: Vec<(&str, String)> = day
         .runs
         .iter()
         .map(|run| {
-            let mut text = format!(
-                "{} · {} · pulls: {} · dmg {} · dps {}",
-                run.zone,
-                run.started_label,
+            let mut suffix = format!(
+                " · {} · pulls: {} · dmg {} · dps {} · deaths {}",
+                time_of_day_label(run.started_ms, &run.started_label, s.settings.clock_format),
                 run.child_count,
                 format_number(run.total_damage),
                 format_number(run.total_encdps),
+                run.total_deaths,
             );
             if run.incomplete {
-                text.push_str(" · incomplete");
+                suffix.push_str(" · incomplete");
             }
-            ListItem::new(text)
+            (run.zone.as_str(), suffix)
         })
         .collect();
+    let full_name = labels
+        .get(s.history.dungeon_selected_run)
+        .filter(|(zone, suffix)| zone.width() + suffix.width() > width)
+        .map(|(zone, _)| zone.to_string());
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),
+            Constraint::Length(u16::from(full_name.is_some())),
+        ])
+        .split(inner);
+
+    let items: Vec<ListItem> = labels
+        .iter()
+        .map(|(zone, suffix)| ListItem::new(fit_label(zone, suffix, width)))
+        .collect();
 
     let mut state = ListState::default();
     state.select(Some(s.history.dungeon_selected_run));
 
-    let title = format!("Dungeon Runs · {}", day.label);
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title(title))
-        .highlight_style(
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        );
+    let list = List::new(items).highlight_style(selection_style());
 
-    f.render_stateful_widget(list, area, &mut state);
+    f.render_stateful_widget(list, chunks[0], &mut state);
+    draw_list_scrollbar(f, chunks[0], day.runs.len(), s.history.dungeon_selected_run);
+    if let Some(name) = full_name {
+        f.render_widget(full_name_line(&name), chunks[1]);
+    }
 }
 
 fn draw_dungeon_run_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
@@ -623,6 +1468,12 @@ fn draw_dungeon_run_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             format_duration_short(record.total_duration_secs),
             value_style(),
         ),
+        Span::raw(" · "),
+        Span::styled("Deaths: ", header_style()),
+        Span::styled(record.total_deaths.to_string(), value_style()),
+        Span::raw(" · "),
+        Span::styled("Wipes: ", header_style()),
+        Span::styled(record.wipe_count.to_string(), value_style()),
     ]));
     summary_lines.push(Line::from(vec![
         Span::styled(format!("{total_label}: "), header_style()),
@@ -646,32 +1497,54 @@ fn draw_dungeon_run_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         Span::styled("Party: ", header_style()),
         Span::styled(party, value_style()),
     ]));
+    if let Some(version) = record.app_version.as_deref() {
+        summary_lines.push(Line::from(vec![
+            Span::styled("Recorded with: ", header_style()),
+            Span::styled(version_label(Some(version)), value_style()),
+        ]));
+    }
+    if let Some(stats) = pull_stats(&run.child_records, detail_mode) {
+        let mut spans = vec![
+            Span::styled("Pulls: ", header_style()),
+            Span::raw("fastest "),
+            Span::styled(format_duration_short(stats.fastest_secs), value_style()),
+            Span::raw(" · slowest "),
+            Span::styled(format_duration_short(stats.slowest_secs), value_style()),
+        ];
+        if let Some(median) = stats.median_rate {
+            spans.push(Span::raw(format!(
+                " · median {} ",
+                detail_mode_metric(detail_mode)
+            )));
+            spans.push(Span::styled(format_number(median), value_style()));
+        }
+        if stats.loaded < run.child_records.len() {
+            spans.push(Span::styled(
+                format!(
+                    " (partial: {}/{} loaded)",
+                    stats.loaded,
+                    run.child_records.len()
+                ),
+                header_style(),
+            ));
+        }
+        summary_lines.push(Line::from(spans));
+    }
     if record.incomplete {
         summary_lines.push(Line::from(vec![Span::styled(
             "Status: Incomplete",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(palette().status_idle)
                 .add_modifier(Modifier::BOLD),
         )]));
     }
 
-    let mut list_items = Vec::new();
-    let metric_label = match detail_mode {
-        ViewMode::Dps => "DPS",
-        ViewMode::Heal => "HPS",
-    };
+    let mut labels = Vec::new();
+    let metric_label = detail_mode_metric(detail_mode);
 
     for (idx, title) in record.child_titles.iter().enumerate() {
         let label = if let Some(child) = run.child_records.get(idx).and_then(|c| c.as_ref()) {
-            let metric_value = match detail_mode {
-                ViewMode::Dps => child.encounter.encdps.as_str(),
-                ViewMode::Heal => child.encounter.enchps.as_str(),
-            };
-            let metric_value = if metric_value.is_empty() {
-                "—"
-            } else {
-                metric_value
-            };
+            let metric_value = encounter_rate(&child.encounter, detail_mode);
             format!(
                 "{} · {} · {} {}",
                 title, child.encounter.duration, metric_label, metric_value,
@@ -679,12 +1552,26 @@ fn draw_dungeon_run_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         } else {
             format!("{} · (loading…)", title)
         };
-        list_items.push(ListItem::new(label));
+        labels.push(label);
     }
 
+    let selected_child = s.history.dungeon_selected_child;
+    let (list_items, selected_row) = if s.history.dungeon_group_bosses {
+        let bosses = s
+            .dungeon_catalog
+            .as_deref()
+            .map(|catalog| catalog.bosses(&record.zone))
+            .unwrap_or(&[]);
+        let kinds = classify_run(record, &run.child_records, bosses);
+        boss_group_items(&labels, &group_pulls(&kinds), run, selected_child)
+    } else {
+        let items: Vec<ListItem> = labels.into_iter().map(ListItem::new).collect();
+        (items, Some(selected_child))
+    };
+
     let mut list_state = ListState::default();
     if !list_items.is_empty() {
-        list_state.select(Some(s.history.dungeon_selected_child));
+        list_state.select(selected_row);
     }
 
     let layout = Layout::default()
@@ -702,7 +1589,7 @@ fn draw_dungeon_run_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             Block::default()
                 .borders(Borders::ALL)
                 .title(Line::from(vec![Span::styled(
-                    format!("Run · {}", run.zone),
+                    block_title("Run", &run.zone, area.width),
                     title_style(),
                 )])),
         );
@@ -714,25 +1601,121 @@ fn draw_dungeon_run_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(block, layout[1]);
     } else {
-        let title = format!("Pulls · {}", record.child_keys.len());
+        let title = if s.history.dungeon_group_bosses {
+            format!("Pulls · {} · by boss", record.child_keys.len())
+        } else {
+            format!("Pulls · {}", record.child_keys.len())
+        };
+        let len = list_items.len();
         let list = List::new(list_items)
             .block(Block::default().borders(Borders::ALL).title(title))
-            .highlight_style(
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            );
+            .highlight_style(selection_style());
         f.render_stateful_widget(list, layout[1], &mut list_state);
+        draw_list_scrollbar(
+            f,
+            Block::default().borders(Borders::ALL).inner(layout[1]),
+            len,
+            selected_row.unwrap_or(0),
+        );
     }
 
     let instructions =
-        Paragraph::new("← runs · ↑/↓ select pull · Enter view pull · m toggles DPS/Heal")
+        Paragraph::new("← runs · ↑/↓ select pull · Enter view pull · m DPS/Heal · g group by boss")
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::NONE));
     f.render_widget(instructions, layout[2]);
 }
 
+fn detail_mode_metric(mode: ViewMode) -> &'static str {
+    match mode {
+        ViewMode::Dps => "DPS",
+        ViewMode::Heal => "HPS",
+    }
+}
+
+/// Consistency stats across the loaded pulls of a dungeon run.
+#[derive(Debug, PartialEq)]
+struct PullStats {
+    fastest_secs: u64,
+    slowest_secs: u64,
+    /// Median ENCDPS (or ENCHPS in heal mode) over pulls that report one.
+    median_rate: Option<f64>,
+    /// Pulls the stats were computed from; less than the run's pull count while loading.
+    loaded: usize,
+}
+
+fn pull_stats(children: &[Option<EncounterRecord>], mode: ViewMode) -> Option<PullStats> {
+    let loaded: Vec<&EncounterRecord> = children.iter().flatten().collect();
+    let durations: Vec<u64> = loaded
+        .iter()
+        .filter_map(|child| parse_duration_secs(&child.encounter.duration))
+        .collect();
+    let mut rates: Vec<f64> = loaded
+        .iter()
+        .map(|child| encounter_rate(&child.encounter, mode))
+        .filter(|value| value != "—")
+        .map(|value| parse_number(&value))
+        .collect();
+    rates.sort_by(|a, b| a.total_cmp(b));
+    let median_rate = match rates.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(rates[len / 2]),
+        len => Some((rates[len / 2 - 1] + rates[len / 2]) / 2.0),
+    };
+    Some(PullStats {
+        fastest_secs: *durations.iter().min()?,
+        slowest_secs: *durations.iter().max()?,
+        median_rate,
+        loaded: loaded.len(),
+    })
+}
+
+/// List rows for the boss-grouped run view: each checkpoint's trash pulls collapse into one
+/// summary line (expanded while the selection is inside it) followed by its boss.
+/// Returns the rows and the row holding `selected`.
+fn boss_group_items(
+    labels: &[String],
+    groups: &[PullGroup],
+    run: &DungeonHistoryItem,
+    selected: usize,
+) -> (Vec<ListItem<'static>>, Option<usize>) {
+    let mut items = Vec::new();
+    let mut selected_row = None;
+    for group in groups {
+        if group.trash.contains(&selected) {
+            for &idx in &group.trash {
+                if idx == selected {
+                    selected_row = Some(items.len());
+                }
+                items.push(ListItem::new(format!("  {}", labels[idx])));
+            }
+        } else if !group.trash.is_empty() {
+            let secs: u64 = group
+                .trash
+                .iter()
+                .filter_map(|&idx| run.child_records.get(idx).and_then(Option::as_ref))
+                .filter_map(|child| parse_duration_secs(&child.encounter.duration))
+                .sum();
+            let count = group.trash.len();
+            let noun = if count == 1 { "pull" } else { "pulls" };
+            items.push(
+                ListItem::new(format!(
+                    "▸ {count} trash {noun} · {}",
+                    format_duration_short(secs)
+                ))
+                .style(header_style()),
+            );
+        }
+        if let Some(idx) = group.boss {
+            if idx == selected {
+                selected_row = Some(items.len());
+            }
+            items.push(ListItem::new(format!("★ {}", labels[idx])).style(title_style()));
+        }
+    }
+    (items, selected_row)
+}
+
 fn draw_dungeon_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
     let Some(run) = s.history.current_dungeon_run() else {
         let block = Paragraph::new("No run selected.")
@@ -783,8 +1766,7 @@ fn draw_dungeon_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         .unwrap_or_else(|| "Encounter".to_string());
 
     let detail_mode = s.history.detail_mode;
-    let mut sorted_rows = encounter_record.rows.clone();
-    sort_rows_for_mode(&mut sorted_rows, detail_mode);
+    let visible_rows = detail_table_rows(s, &encounter_record.rows, detail_mode);
 
     let basic_metrics = [
         (
@@ -804,7 +1786,10 @@ fn draw_dungeon_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             },
         ),
         ("Duration", encounter_record.encounter.duration.clone()),
-        ("ENCDPS", encounter_record.encounter.encdps.clone()),
+        (
+            "ENCDPS",
+            encounter_rate(&encounter_record.encounter, ViewMode::Dps),
+        ),
         ("Damage", encounter_record.encounter.damage.clone()),
     ];
 
@@ -812,8 +1797,17 @@ fn draw_dungeon_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         ("Snapshots", encounter_record.snapshots.to_string()),
         ("Frames", encounter_record.frames.len().to_string()),
         (
-            "Last seen",
-            format_timestamp_label(encounter_record.last_seen_ms),
+            "Started",
+            format_timestamp_label(encounter_record.first_seen_ms, s.settings.clock_format),
+        ),
+        (
+            "Ended",
+            format_timestamp_label(encounter_record.last_seen_ms, s.settings.clock_format),
+        ),
+        ("Wall time", wall_time_label(encounter_record)),
+        (
+            "Version",
+            version_label(encounter_record.app_version.as_deref()),
         ),
     ];
 
@@ -827,7 +1821,7 @@ fn draw_dungeon_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         })
         .collect();
 
-    let technical_lines: Vec<Line> = technical_metrics
+    let mut technical_lines: Vec<Line> = technical_metrics
         .iter()
         .map(|(label, value)| {
             Line::from(vec![
@@ -836,92 +1830,105 @@ fn draw_dungeon_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             ])
         })
         .collect();
+    technical_lines.push(coverage_line(encounter_record));
+
+    let layout = detail_layout(area, summary_lines.len().max(technical_lines.len()));
+    let (table_area, deaths_area) =
+        split_bottom_panel(layout.table, s.history.show_deaths, DEATHS_PANEL_HEIGHT);
+    let (table_area, buffs_area) =
+        split_bottom_panel(table_area, s.history.show_buffs, BUFF_PANEL_HEIGHT);
+
+    if layout.compact {
+        f.render_widget(
+            Paragraph::new(compact_summary_line(&basic_metrics)),
+            layout.summary,
+        );
+    } else {
+        let summary_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(layout.summary);
 
-    let max_summary_rows = summary_lines.len().max(technical_lines.len());
-    let mut summary_height = max_summary_rows.saturating_add(2) as u16;
-    let max_height = area.height.max(1u16);
-    if summary_height > max_height {
-        summary_height = max_height;
-    }
-    let min_required = 3u16.min(max_height);
-    if summary_height < min_required {
-        summary_height = min_required;
-    }
-
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(summary_height),
-            Constraint::Min(6),
-            Constraint::Length(4),
-            Constraint::Length(1),
-        ])
-        .split(area);
-
-    let summary_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-        .split(layout[0]);
-
-    let summary = Paragraph::new(summary_lines)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(Line::from(vec![Span::styled(
-                    format!("Details · {title}"),
-                    title_style(),
-                )])),
-        )
-        .alignment(Alignment::Left);
-    f.render_widget(summary, summary_chunks[0]);
+        let summary = Paragraph::new(summary_lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Line::from(vec![Span::styled(
+                        block_title("Details", &title, summary_chunks[0].width),
+                        title_style(),
+                    )])),
+            )
+            .alignment(Alignment::Left);
+        f.render_widget(summary, summary_chunks[0]);
 
-    let technical = Paragraph::new(technical_lines)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(Line::from(vec![Span::styled(
-                    "Technical Details".to_string(),
-                    title_style(),
-                )])),
-        )
-        .alignment(Alignment::Left);
-    f.render_widget(technical, summary_chunks[1]);
+        let technical = Paragraph::new(technical_lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Line::from(vec![Span::styled(
+                        "Technical Details".to_string(),
+                        title_style(),
+                    )])),
+            )
+            .alignment(Alignment::Left);
+        f.render_widget(technical, summary_chunks[1]);
+    }
 
-    if sorted_rows.is_empty() {
+    if table_area.height == 0 {
+        // Nothing left for the table on extremely short terminals.
+    } else if encounter_record.rows.is_empty() {
         let block = Paragraph::new("No combatants recorded.")
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
-        f.render_widget(block, layout[1]);
+        f.render_widget(block, table_area);
+    } else if visible_rows.is_empty() {
+        let block = Paragraph::new(empty_rows_message(s))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(block, table_area);
     } else {
-        let table_title = Line::from(vec![
-            Span::styled(
-                format!("Combatants · {}", detail_mode.label()),
-                title_style(),
-            ),
-            Span::raw(" "),
-            Span::styled("(m toggles)", Style::default().fg(TEXT)),
-        ]);
+        let table_title = combatants_title(
+            detail_mode,
+            s.role_filter,
+            visible_rows.len(),
+            encounter_record.rows.len(),
+        );
         let block = Block::default().borders(Borders::ALL).title(table_title);
-        let table_area = layout[1];
         let inner = block.inner(table_area);
         f.render_widget(block, table_area);
 
         let ctx = TableRenderContext {
-            rows: &sorted_rows,
+            rows: &visible_rows,
             mode: detail_mode,
             decoration: s.decoration,
+            columns: &s.settings.column_order,
+            scroll: s.history.detail_scroll,
+            selected: None,
+            self_name: s.settings.resolve_self(&visible_rows),
+            both_rates: s.show_both_rates,
+            rolling: None,
+            sorted: Some((s.sort_key.column(detail_mode), s.sort_ascending)),
         };
-        draw_table_with_context(f, inner, &ctx);
+        DETAIL_CAPACITY.store(draw_table_with_context(f, inner, &ctx), Ordering::Relaxed);
+    }
+
+    if let Some(deaths_area) = deaths_area {
+        draw_death_panel(f, deaths_area, &encounter_record.death_events());
+    }
+
+    if let Some(buffs_area) = buffs_area {
+        let uptime = run
+            .child_buff_uptime
+            .get(idx)
+            .map_or(&[][..], Vec::as_slice);
+        draw_buff_panel(f, buffs_area, uptime, &s.settings.uptime_buffs);
     }
 
     let metric_label = match detail_mode {
         ViewMode::Dps => "ENCDPS",
         ViewMode::Heal => "ENCHPS",
     };
-    let metric_value = match detail_mode {
-        ViewMode::Dps => &encounter_record.encounter.encdps,
-        ViewMode::Heal => &encounter_record.encounter.enchps,
-    };
+    let metric_value = encounter_rate(&encounter_record.encounter, detail_mode);
     let total_label = match detail_mode {
         ViewMode::Dps => "Total Damage",
         ViewMode::Heal => "Total Healed",
@@ -931,11 +1938,6 @@ fn draw_dungeon_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         ViewMode::Heal => &encounter_record.encounter.healed,
     };
 
-    let metric_value = if metric_value.is_empty() {
-        "—".to_string()
-    } else {
-        metric_value.clone()
-    };
     let total_value = if total_value.is_empty() {
         "—".to_string()
     } else {
@@ -946,18 +1948,18 @@ fn draw_dungeon_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         Line::from(vec![
             Span::styled("Current: ", header_style()),
             Span::styled(detail_mode.label(), value_style()),
-            Span::styled(" · press m to toggle", Style::default().fg(TEXT)),
+            Span::styled(" · press m to toggle", header_style()),
         ]),
         Line::from(vec![
             Span::styled("Sorting: ", header_style()),
             Span::styled(metric_label, value_style()),
-            Span::styled(" · encounter ", Style::default().fg(TEXT)),
+            Span::styled(" · encounter ", header_style()),
             Span::styled(metric_label, value_style()),
-            Span::styled(": ", Style::default().fg(TEXT)),
+            Span::styled(": ", header_style()),
             Span::styled(metric_value, value_style()),
-            Span::styled(" · ", Style::default().fg(TEXT)),
+            Span::styled(" · ", header_style()),
             Span::styled(total_label, header_style()),
-            Span::styled(": ", Style::default().fg(TEXT)),
+            Span::styled(": ", header_style()),
             Span::styled(total_value, value_style()),
         ]),
     ];
@@ -967,29 +1969,17 @@ fn draw_dungeon_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             .borders(Borders::ALL)
             .title(Line::from(vec![Span::styled("View Mode", title_style())])),
     );
-    f.render_widget(mode_paragraph, layout[2]);
-
-    let hint =
-        Paragraph::new("← run detail · ↑/↓ switch pull · m toggles DPS/Heal · Enter re-open")
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::NONE));
-    f.render_widget(hint, layout[3]);
-}
+    if let Some(mode_area) = layout.mode {
+        f.render_widget(mode_paragraph, mode_area);
+    }
 
-fn sort_rows_for_mode(rows: &mut [CombatantRow], mode: ViewMode) {
-    match mode {
-        ViewMode::Dps => rows.sort_by(|a, b| {
-            b.encdps
-                .partial_cmp(&a.encdps)
-                .unwrap_or(Ordering::Equal)
-                .then_with(|| a.name.cmp(&b.name))
-        }),
-        ViewMode::Heal => rows.sort_by(|a, b| {
-            b.enchps
-                .partial_cmp(&a.enchps)
-                .unwrap_or(Ordering::Equal)
-                .then_with(|| a.name.cmp(&b.name))
-        }),
+    let hint = Paragraph::new(
+        "← run detail · ↑/↓ switch pull · PgUp/PgDn scroll · m toggles DPS/Heal · D deaths · u buffs · Enter re-open",
+    )
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::NONE));
+    if let Some(hint_area) = layout.hint {
+        f.render_widget(hint, hint_area);
     }
 }
 
@@ -1015,17 +2005,10 @@ fn render_loading_overlay(f: &mut Frame, area: Rect, message: &str) {
     f.render_widget(block, overlay);
 }
 
-fn format_duration_short(total_secs: u64) -> String {
-    if total_secs == 0 {
-        return "00:00".to_string();
-    }
-    let hours = total_secs / 3600;
-    let minutes = (total_secs % 3600) / 60;
-    let seconds = total_secs % 60;
-    if hours > 0 {
-        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
-    } else {
-        format!("{:02}:{:02}", minutes, seconds)
+fn version_label(version: Option<&str>) -> String {
+    match version {
+        Some(version) => format!("v{version}"),
+        None => "unknown".to_string(),
     }
 }
 
@@ -1037,18 +2020,225 @@ fn format_number(value: f64) -> String {
     }
 }
 
-fn format_timestamp_label(ms: u64) -> String {
-    if let Ok(ms_i64) = i64::try_from(ms) {
-        if let Some(dt) = Local.timestamp_millis_opt(ms_i64).single() {
-            return dt.format("%Y-%m-%d %H:%M:%S").to_string();
-        }
+/// Time of day of `ms` on the configured clock; falls back to the label stored in the summary
+/// index when the timestamp is missing.
+fn time_of_day_label(ms: u64, stored: &str, clock: ClockFormat) -> String {
+    clock
+        .format_local(ms, false, false)
+        .unwrap_or_else(|| stored.to_string())
+}
+
+/// Local date and time of `ms`; records without a timestamp (0) read "unknown".
+fn format_timestamp_label(ms: u64, clock: ClockFormat) -> String {
+    clock
+        .format_local(ms, true, true)
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Real time from the first to the last snapshot, which can exceed the ACT duration when
+/// the pull had downtime; "unknown" when either timestamp is missing or they are reversed.
+fn wall_time_label(record: &EncounterRecord) -> String {
+    let (first, last) = (record.first_seen_ms, record.last_seen_ms);
+    if first == 0 || last < first {
+        return "unknown".to_string();
     }
-    "unknown".to_string()
+    format_duration_short((last - first) / 1000)
 }
 
 fn format_party_signature(sig: &[String]) -> String {
     if sig.is_empty() {
         return "Unknown".to_string();
     }
-    sig.iter().cloned().collect::<Vec<_>>().join(", ")
+    sig.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_length_pulls_show_a_dash_for_their_rates() {
+        let encounter = |duration: &str| EncounterSummary {
+            duration: duration.into(),
+            encdps: "inf".into(),
+            enchps: "".into(),
+            ..EncounterSummary::default()
+        };
+        assert_eq!(encounter_rate(&encounter("00:00"), ViewMode::Dps), "—");
+        assert_eq!(encounter_rate(&encounter("00:00.4"), ViewMode::Dps), "inf");
+        assert_eq!(encounter_rate(&encounter("00:12"), ViewMode::Heal), "—");
+    }
+
+    #[test]
+    fn scrollbar_only_shows_when_the_list_overflows() {
+        assert!(list_scrollbar_state(5, 5, 2).is_none());
+        assert!(list_scrollbar_state(0, 0, 0).is_none());
+        assert_eq!(
+            list_scrollbar_state(20, 5, 7),
+            Some(
+                ScrollbarState::new(20)
+                    .position(7)
+                    .viewport_content_length(5)
+            )
+        );
+        // A stale selection past a shrunken (filtered) list clamps to the last item.
+        assert_eq!(
+            list_scrollbar_state(8, 3, 12),
+            Some(
+                ScrollbarState::new(8)
+                    .position(7)
+                    .viewport_content_length(3)
+            )
+        );
+    }
+
+    #[test]
+    fn death_strip_places_markers_by_offset() {
+        let death = |ms: u64| DeathEvent {
+            ms,
+            name: "Alice".into(),
+        };
+        assert_eq!(death_strip_cells(&[], 60_000, 5), [0, 0, 0, 0, 0]);
+        let cells = death_strip_cells(
+            &[death(0), death(30_000), death(31_000), death(90_000)],
+            60_000,
+            5,
+        );
+        assert_eq!(cells, [1, 0, 2, 0, 1]);
+        assert!(death_strip_cells(&[death(10)], 0, 0).is_empty());
+    }
+
+    #[test]
+    fn hidden_zero_rows_follow_the_mode_and_show_in_the_title_count() {
+        let row = |name: &str, damage: f64, healed: f64| CombatantRow {
+            name: name.into(),
+            job: "WHM".into(),
+            damage,
+            encdps: damage / 10.0,
+            healed,
+            enchps: healed / 10.0,
+            ..Default::default()
+        };
+        let rows = [
+            row("Limit Break", 0.0, 0.0),
+            row("Alice", 1_000.0, 0.0),
+            row("Bob", 3_000.0, 500.0),
+        ];
+        let mut s = AppSnapshot::default();
+        let names = |s: &AppSnapshot, mode| {
+            detail_table_rows(s, &rows, mode)
+                .into_iter()
+                .map(|row| row.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&s, ViewMode::Dps).len(), 3);
+
+        s.settings.hide_zero_rows = true;
+        assert_eq!(names(&s, ViewMode::Dps), ["Bob", "Alice"]);
+        assert_eq!(names(&s, ViewMode::Heal), ["Bob"]);
+
+        let title = combatants_title(ViewMode::Dps, RoleFilter::All, 2, 3);
+        assert_eq!(title.spans[0].content, "Combatants (2 of 3) · DPS");
+        let title = combatants_title(ViewMode::Dps, RoleFilter::All, 3, 3);
+        assert_eq!(title.spans[0].content, "Combatants (3) · DPS");
+    }
+
+    #[test]
+    fn chart_buckets_average_long_series_into_the_width() {
+        assert_eq!(chart_buckets(&[1.0, 2.6], 10), vec![1, 3]);
+        assert_eq!(
+            chart_buckets(&[0.0, 2.0, 4.0, 6.0, 8.0, 10.0], 3),
+            vec![1, 5, 9]
+        );
+        assert_eq!(chart_buckets(&[1.0, 2.0, 3.0], 2), vec![1, 3]);
+        assert!(chart_buckets(&[1.0], 0).is_empty());
+    }
+
+    #[test]
+    fn detail_layout_collapses_summary_on_short_terminals() {
+        let tall = detail_layout(Rect::new(0, 0, 100, 40), 5);
+        assert!(!tall.compact);
+        assert_eq!(tall.summary.height, 7);
+        assert!(tall.mode.is_some());
+
+        let short = detail_layout(Rect::new(0, 0, 100, 16), 5);
+        assert!(short.compact);
+        assert_eq!(short.summary.height, 1);
+        assert_eq!(short.table.height, 14);
+        assert!(short.mode.is_none());
+
+        for height in 0..COMPACT_DETAIL_HEIGHT {
+            let layout = detail_layout(Rect::new(0, 0, 100, height), 5);
+            let used = layout.summary.height
+                + layout.table.height
+                + layout.hint.map_or(0, |hint| hint.height);
+            assert_eq!(used, height);
+        }
+    }
+
+    fn pull(duration: &str, encdps: &str) -> Option<EncounterRecord> {
+        Some(EncounterRecord::fixture(
+            0,
+            crate::model::EncounterSummary {
+                duration: duration.into(),
+                encdps: encdps.into(),
+                ..Default::default()
+            },
+        ))
+    }
+
+    #[test]
+    fn pull_stats_cover_loaded_pulls_only() {
+        let children = vec![
+            pull("01:30", "1000"),
+            None,
+            pull("00:45", "3000"),
+            pull("03:10", "2000"),
+            pull("02:00", ""),
+        ];
+        let stats = pull_stats(&children, ViewMode::Dps).expect("stats");
+        assert_eq!(
+            stats,
+            PullStats {
+                fastest_secs: 45,
+                slowest_secs: 190,
+                median_rate: Some(2000.0),
+                loaded: 4,
+            }
+        );
+
+        let even = pull_stats(&children[..3], ViewMode::Dps).expect("stats");
+        assert_eq!(even.median_rate, Some(2000.0));
+        assert!(pull_stats(&[None, None], ViewMode::Dps).is_none());
+    }
+
+    #[test]
+    fn wall_time_spans_first_to_last_snapshot() {
+        let mut record = pull("01:00", "1000").expect("record");
+        assert_eq!(wall_time_label(&record), "unknown");
+        assert_eq!(
+            format_timestamp_label(record.first_seen_ms, ClockFormat::H24),
+            "unknown"
+        );
+
+        record.first_seen_ms = 1_000;
+        record.last_seen_ms = 91_000;
+        assert_eq!(wall_time_label(&record), "01:30");
+
+        record.last_seen_ms = 500;
+        assert_eq!(wall_time_label(&record), "unknown");
+    }
+
+    #[test]
+    fn long_names_are_cut_to_keep_labels_and_titles_in_bounds() {
+        let name = "The Minstrel's Ballad: Endsinger's Aria";
+        let label = fit_label(name, "  [21:04]", 30);
+        assert_eq!(label.width(), 30);
+        assert!(label.ends_with("…  [21:04]"));
+        assert_eq!(fit_label("Titan", "  [21:04]", 30), "Titan  [21:04]");
+
+        let title = block_title("Details", name, 30);
+        assert!(title.starts_with("Details · "));
+        assert_eq!(title.width(), 28);
+    }
 }
@@ -1,11 +1,12 @@
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use ratatui::style::{Modifier, Style};
+use ratatui::style::Modifier;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use ratatui::Frame;
 
+use crate::keys::KeyAction;
 use crate::model::{AppSnapshot, IdleScene};
-use crate::theme::{header_style, title_style, value_style, TEXT};
+use crate::theme::{header_style, title_style, value_style};
 
 /// Default order new idle widgets should rotate through once rotation logic lands.
 #[allow(dead_code)]
@@ -42,15 +43,18 @@ fn render_header(f: &mut Frame, area: Rect, snapshot: &AppSnapshot) {
 
     let description = Line::from(vec![Span::styled(
         snapshot.idle_scene.description(),
-        Style::default().fg(TEXT).add_modifier(Modifier::DIM),
+        header_style().add_modifier(Modifier::DIM),
     )]);
 
     let block = Block::default().borders(Borders::NONE);
     let mut lines = vec![title, description];
     if snapshot.idle_scene == IdleScene::Status {
         lines.push(Line::from(vec![Span::styled(
-            "press 'i' to toggle idle window",
-            Style::default().fg(TEXT).add_modifier(Modifier::DIM),
+            format!(
+                "press '{}' to toggle idle window",
+                snapshot.key_bindings.label(KeyAction::ToggleIdle)
+            ),
+            header_style().add_modifier(Modifier::DIM),
         )]));
     }
 
@@ -100,12 +104,14 @@ fn scene_lines(snapshot: &AppSnapshot) -> Vec<Line<'static>> {
 }
 
 fn status_lines(snapshot: &AppSnapshot) -> Vec<Line<'static>> {
-    let connection = if snapshot.connected {
+    let connection = if snapshot.connection.is_connected() {
         if snapshot.is_idle {
             "Connected (idle)"
         } else {
             "Connected"
         }
+    } else if snapshot.waiting_for_server {
+        "Waiting for IINACT…"
     } else {
         if snapshot.is_idle {
             "Disconnected (idle)"
@@ -139,7 +145,7 @@ fn placeholder(title: &str, caption: &str) -> Vec<Line<'static>> {
         Line::from(vec![Span::styled(caption.to_string(), header_style())]),
         Line::from(vec![Span::styled(
             "Rotate scenes via DEFAULT_ROTATION or update AppState::idle_scene.",
-            Style::default().fg(TEXT).add_modifier(Modifier::DIM),
+            header_style().add_modifier(Modifier::DIM),
         )]),
     ]
 }
@@ -1,85 +1,279 @@
-use std::time::Duration;
+use std::io::Read;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use flate2::read::{GzDecoder, ZlibDecoder};
 
 use futures_util::{SinkExt, StreamExt};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
 use serde_json::Value;
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::{Receiver, UnboundedSender};
 use tokio::time::sleep;
-use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::http::Uri;
 use tokio_tungstenite::tungstenite::protocol::frame::CloseFrame;
+use tokio_tungstenite::tungstenite::Error as WsError;
 use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async_tls_with_config, Connector};
 use tracing::{debug, info, warn};
 
+use crate::config::AppConfig;
+use crate::errors::{AppError, AppErrorKind};
 use crate::history::RecorderHandle;
-use crate::model::AppEvent;
-use crate::parse::parse_combat_data;
+use crate::model::{AppEvent, RateCounter, WS_URL_DEFAULT};
+use crate::parse::{parse_combat_data, parse_death, parse_zone_change};
+
+/// Handshake sent after every (re)connect: ask for the language, then subscribe to events.
+const DEFAULT_SUBSCRIBE_MESSAGES: [&str; 2] = [
+    r#"{"call":"getLanguage"}"#,
+    r#"{"call":"subscribe","events":["CombatData","LogLine"]}"#,
+];
+
+/// Largest decompressed binary frame accepted. Real CombatData payloads stay well under a
+/// megabyte; the cap keeps a tiny compressed frame from expanding without bound.
+const MAX_INFLATED: u64 = 16 * 1024 * 1024;
+
+/// Connection settings for the IINACT websocket client.
+#[derive(Clone, Debug)]
+pub struct ClientOptions {
+    pub url: String,
+    /// Grace period before the first attempt so IINACT can finish starting up.
+    pub startup_delay: Duration,
+    /// Text frames sent, in order, right after each successful connection.
+    pub subscribe_messages: Vec<String>,
+    /// Accept any server certificate on `wss://` URLs.
+    pub accept_invalid_certs: bool,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            url: WS_URL_DEFAULT.to_string(),
+            startup_delay: Duration::ZERO,
+            subscribe_messages: default_subscribe_messages(),
+            accept_invalid_certs: false,
+        }
+    }
+}
+
+impl From<&AppConfig> for ClientOptions {
+    fn from(cfg: &AppConfig) -> Self {
+        Self {
+            startup_delay: Duration::from_secs(cfg.startup_delay_secs),
+            subscribe_messages: resolve_subscribe_messages(cfg.subscribe_messages.as_ref()),
+            accept_invalid_certs: cfg.ws_accept_invalid_certs,
+            ..Self::default()
+        }
+    }
+}
+
+/// Where the websocket URL in use came from, in order of precedence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UrlSource {
+    Cli,
+    Config,
+    LastGood,
+    Default,
+}
+
+impl UrlSource {
+    /// Where to go to fix a bad URL, for error messages.
+    pub fn description(self) -> &'static str {
+        match self {
+            UrlSource::Cli => "--ws-url",
+            UrlSource::Config => "\"ws_url\" in the config",
+            UrlSource::LastGood => "the remembered last_ws_url in ui_state.json",
+            UrlSource::Default => "the built-in default",
+        }
+    }
+}
+
+/// Checks that `url` is a `ws://` or `wss://` URL with a host, so a typo fails at startup
+/// instead of showing up as an endless reconnect loop.
+pub fn validate_url(url: &str) -> Result<()> {
+    let uri: Uri = url
+        .parse()
+        .with_context(|| format!("`{url}` is not a valid URL"))?;
+    match uri.scheme_str() {
+        Some("ws" | "wss") => {}
+        Some(scheme) => bail!("`{url}` uses the {scheme}:// scheme; expected ws:// or wss://"),
+        None => bail!("`{url}` has no scheme; expected something like ws://127.0.0.1:10501/ws"),
+    }
+    if uri.host().map_or(true, str::is_empty) {
+        bail!("`{url}` has no host");
+    }
+    Ok(())
+}
+
+/// Picks the websocket URL: `--ws-url`, then `ws_url` in the config, then the last URL that
+/// connected, then [`WS_URL_DEFAULT`]. Blank values are ignored.
+pub fn resolve_url(
+    cli: Option<&str>,
+    config: Option<&str>,
+    last_good: Option<&str>,
+) -> (String, UrlSource) {
+    [
+        (cli, UrlSource::Cli),
+        (config, UrlSource::Config),
+        (last_good, UrlSource::LastGood),
+    ]
+    .into_iter()
+    .find_map(|(url, source)| {
+        let url = url.map(str::trim).filter(|url| !url.is_empty())?;
+        Some((url.to_string(), source))
+    })
+    .unwrap_or_else(|| (WS_URL_DEFAULT.to_string(), UrlSource::Default))
+}
+
+fn default_subscribe_messages() -> Vec<String> {
+    DEFAULT_SUBSCRIBE_MESSAGES
+        .iter()
+        .map(|msg| msg.to_string())
+        .collect()
+}
+
+/// Turns the configured override into text frames. The override must be a non-empty JSON
+/// array whose entries are objects (sent as JSON) or strings (sent verbatim, but only if they
+/// parse as JSON); anything else logs a warning and keeps the built-in handshake.
+fn resolve_subscribe_messages(raw: Option<&Value>) -> Vec<String> {
+    let Some(raw) = raw else {
+        return default_subscribe_messages();
+    };
+    let Some(entries) = raw.as_array().filter(|entries| !entries.is_empty()) else {
+        warn!("subscribe_messages must be a non-empty JSON array; using the default handshake");
+        return default_subscribe_messages();
+    };
+    let mut messages = Vec::with_capacity(entries.len());
+    for (idx, entry) in entries.iter().enumerate() {
+        let text = match entry {
+            Value::Object(_) => entry.to_string(),
+            Value::String(text) if serde_json::from_str::<Value>(text).is_ok() => text.clone(),
+            _ => {
+                warn!(
+                    index = idx,
+                    "subscribe_messages entry is not a JSON object; using the default handshake"
+                );
+                return default_subscribe_messages();
+            }
+        };
+        messages.push(text);
+    }
+    messages
+}
+
+/// Connects and keeps reconnecting until the app drops `tx`. A message on `reconnect` drops
+/// the current connection (or cuts the backoff wait short) and connects again right away.
+pub async fn run(
+    options: ClientOptions,
+    tx: UnboundedSender<AppEvent>,
+    history: RecorderHandle,
+    mut reconnect: Receiver<()>,
+) {
+    let ws_url = options.url.clone();
+    if !options.startup_delay.is_zero() {
+        debug!(delay = ?options.startup_delay, "delaying first websocket connect");
+        sleep(options.startup_delay).await;
+    }
+
+    let needs_tls = ws_url.starts_with("wss://");
+    let mut connector: Option<Connector> = None;
+    // Only a change in the TLS failure is reported, not every retry of the same one.
+    let mut last_tls_error: Option<String> = None;
+
+    // Until the first successful connection, failures usually mean IINACT is still starting.
+    let mut connected_once = false;
+    let mut backoff = Backoff::default();
+    let mut attempt: u32 = 0;
 
-pub async fn run(ws_url: String, tx: UnboundedSender<AppEvent>, history: RecorderHandle) {
-    // Simple reconnect loop
+    // Reconnect loop; ends once the app drops its event receiver.
     loop {
-        debug!(%ws_url, "websocket connect attempt");
-        match connect_async(&ws_url).await {
-            Ok((ws_stream, resp)) => {
+        let event = if attempt == 0 {
+            AppEvent::Connecting
+        } else {
+            AppEvent::Reconnecting { attempt }
+        };
+        if tx.send(event).is_err() {
+            break;
+        }
+        let mut reconnect_requested = false;
+        // A TLS setup failure is retried like a refused connection rather than ending the task.
+        if needs_tls && connector.is_none() {
+            match tls_connector(options.accept_invalid_certs) {
+                Ok(built) => connector = Some(built),
+                Err(err) => report_tls_error(&tx, &mut last_tls_error, format!("{err:#}")),
+            }
+        }
+        let result = if needs_tls && connector.is_none() {
+            None
+        } else {
+            debug!(%ws_url, attempt, "websocket connect attempt");
+            Some(connect_async_tls_with_config(&ws_url, None, false, connector.clone()).await)
+        };
+        match result {
+            Some(Ok((ws_stream, resp))) => {
+                connected_once = true;
+                last_tls_error = None;
+                backoff.reset();
+                attempt = 0;
+                // Requests made while this attempt was in flight are already satisfied.
+                while reconnect.try_recv().is_ok() {}
                 let (mut write, mut read) = ws_stream.split();
                 info!(status = ?resp.status(), "websocket connected");
                 let _ = tx.send(AppEvent::Connected);
 
-                // Perform handshake: getLanguage, then subscribe
-                if let Err(err) = write
-                    .send(Message::Text("{\"call\":\"getLanguage\"}".to_string()))
-                    .await
-                {
-                    warn!(error = ?err, "failed to send getLanguage call");
-                }
-                if let Err(err) = write
-                    .send(Message::Text(
-                        "{\"call\":\"subscribe\",\"events\":[\"CombatData\",\"LogLine\"]}"
-                            .to_string(),
-                    ))
-                    .await
-                {
-                    warn!(error = ?err, "failed to send subscribe call");
+                // Perform handshake (by default getLanguage, then subscribe)
+                for message in &options.subscribe_messages {
+                    if let Err(err) = write.send(Message::Text(message.clone())).await {
+                        warn!(error = ?err, %message, "failed to send handshake message");
+                    }
                 }
 
                 // Reader loop
-                while let Some(msg) = read.next().await {
-                    match msg {
+                let mut rate = RateCounter::default();
+                loop {
+                    let msg = tokio::select! {
+                        msg = read.next() => msg,
+                        Some(()) = reconnect.recv() => {
+                            info!("manual reconnect requested");
+                            reconnect_requested = true;
+                            let _ = write.send(Message::Close(None)).await;
+                            None
+                        }
+                    };
+                    let Some(msg) = msg else {
+                        break;
+                    };
+                    if let Some(per_sec) = rate.record(Instant::now()) {
+                        let _ = tx.send(AppEvent::MessageRate { per_sec });
+                    }
+                    let val = match msg {
                         Ok(Message::Text(txt)) => match serde_json::from_str::<Value>(&txt) {
-                            Ok(val) => {
-                                if let Some((enc, rows)) = parse_combat_data(&val) {
-                                    history.record_components(enc.clone(), rows.clone(), val);
-                                    if tx
-                                        .send(AppEvent::CombatData {
-                                            encounter: enc,
-                                            rows,
-                                        })
-                                        .is_err()
-                                    {
-                                        warn!("receiver dropped websocket updates");
-                                        break;
-                                    }
-                                } else {
-                                    let event_type = val
-                                        .get("type")
-                                        .and_then(|t| t.as_str())
-                                        .unwrap_or("unknown");
-                                    debug!(%event_type, "ignored websocket message");
-                                }
-                            }
+                            Ok(val) => val,
                             Err(err) => {
                                 let snippet: String = txt.chars().take(128).collect();
                                 warn!(error = ?err, snippet, "failed to parse websocket text frame as JSON");
+                                continue;
+                            }
+                        },
+                        Ok(Message::Binary(bytes)) => match decode_binary_frame(&bytes) {
+                            Ok(val) => val,
+                            Err(err) => {
+                                warn!(error = %err, len = bytes.len(), "skipped undecodable binary websocket frame");
+                                continue;
                             }
                         },
-                        Ok(Message::Binary(_)) => {
-                            debug!("ignored binary websocket frame");
-                        }
                         Ok(Message::Ping(_)) => {
                             debug!("received websocket ping");
+                            continue;
                         }
                         Ok(Message::Pong(_)) => {
                             debug!("received websocket pong");
+                            continue;
                         }
-                        Ok(Message::Frame(_)) => {}
+                        Ok(Message::Frame(_)) => continue,
                         Ok(Message::Close(frame)) => {
                             log_close_frame(frame.as_ref());
                             break;
@@ -88,26 +282,242 @@ pub async fn run(ws_url: String, tx: UnboundedSender<AppEvent>, history: Recorde
                             warn!(error = ?err, "websocket read error");
                             break;
                         }
+                    };
+                    if let Some((enc, rows)) = parse_combat_data(&val) {
+                        history.record_components(enc.clone(), rows.clone(), val);
+                        if tx
+                            .send(AppEvent::CombatData {
+                                encounter: enc,
+                                rows,
+                            })
+                            .is_err()
+                        {
+                            warn!("receiver dropped websocket updates");
+                            break;
+                        }
+                    } else if let Some(zone) = parse_zone_change(&val) {
+                        debug!(%zone, "zone change");
+                        history.zone_changed(zone);
+                    } else if let Some(name) = parse_death(&val) {
+                        debug!(%name, "combatant died");
+                        history.combatant_died(name);
+                    } else {
+                        let event_type = val
+                            .get("type")
+                            .and_then(|t| t.as_str())
+                            .unwrap_or("unknown");
+                        debug!(%event_type, "ignored websocket message");
                     }
                 }
-                history.flush();
+                // A deliberate reconnect is not a failed run; only the current pull is saved.
+                if reconnect_requested {
+                    history.flush_encounter();
+                } else {
+                    history.flush();
+                }
                 if tx.send(AppEvent::Disconnected).is_err() {
                     debug!("receiver dropped disconnected event");
+                    break;
                 }
                 info!("websocket loop exited, scheduling reconnect");
             }
-            Err(err) => {
-                warn!(error = ?err, "websocket connection failed");
+            Some(Err(err)) => {
+                if let Some(tls) = tls_failure(&err) {
+                    let message = format!("TLS handshake with {ws_url} failed: {tls}");
+                    report_tls_error(&tx, &mut last_tls_error, message);
+                } else if connected_once {
+                    warn!(error = ?err, "websocket connection failed");
+                } else {
+                    info!(error = %err, "waiting for IINACT websocket");
+                }
                 history.flush();
                 if tx.send(AppEvent::Disconnected).is_err() {
                     debug!("receiver dropped disconnected event");
+                    break;
                 }
             }
+            None => {
+                if tx.send(AppEvent::Disconnected).is_err() {
+                    debug!("receiver dropped disconnected event");
+                    break;
+                }
+            }
+        }
+
+        if reconnect_requested {
+            continue;
+        }
+        attempt = attempt.saturating_add(1);
+        let delay = backoff.next_delay();
+        debug!(?delay, attempt, "waiting before reconnecting");
+        tokio::select! {
+            _ = sleep(delay) => {}
+            _ = tx.closed() => break,
+            Some(()) = reconnect.recv() => {
+                info!("manual reconnect requested; skipping the backoff wait");
+                backoff.reset();
+                attempt = 0;
+            }
+        }
+    }
+    debug!("app shut down; websocket client stopped");
+}
+
+fn report_network_error(tx: &UnboundedSender<AppEvent>, message: String) {
+    let error = AppError::new(AppErrorKind::Network, message);
+    let _ = tx.send(AppEvent::SystemError { error });
+}
+
+/// Logs a TLS failure and reports it as a system error unless it repeats the last one.
+fn report_tls_error(
+    tx: &UnboundedSender<AppEvent>,
+    last_tls_error: &mut Option<String>,
+    message: String,
+) {
+    warn!(%message);
+    if last_tls_error.as_deref() != Some(message.as_str()) {
+        report_network_error(tx, message.clone());
+        *last_tls_error = Some(message);
+    }
+}
+
+/// The rustls error behind a failed connect, if TLS is what failed. tokio-tungstenite hands
+/// handshake and certificate errors back as `Error::Io` wrapping the `rustls::Error`.
+fn tls_failure(err: &WsError) -> Option<String> {
+    match err {
+        WsError::Tls(tls) => Some(tls.to_string()),
+        WsError::Io(io) => io
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<rustls::Error>())
+            .map(|tls| tls.to_string()),
+        _ => None,
+    }
+}
+
+/// rustls setup for `wss://`: the bundled webpki roots, or no certificate checks at all when
+/// `accept_invalid_certs` is set.
+fn tls_connector(accept_invalid_certs: bool) -> Result<Connector> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .context("TLS setup failed")?;
+    let config = if accept_invalid_certs {
+        warn!("accepting any TLS certificate for the websocket connection");
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyCert(provider)))
+            .with_no_client_auth()
+    } else {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        builder.with_root_certificates(roots).with_no_client_auth()
+    };
+    Ok(Connector::Rustls(Arc::new(config)))
+}
+
+/// Trusts whatever certificate the server presents (self-signed proxies). Handshake signatures
+/// are still checked, so the connection is encrypted, just not authenticated.
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// Reconnect delay: 1s, doubling after every failed attempt up to 30s.
+#[derive(Debug)]
+struct Backoff {
+    next: Duration,
+}
+
+impl Backoff {
+    const INITIAL: Duration = Duration::from_secs(1);
+    const MAX: Duration = Duration::from_secs(30);
+
+    /// The delay to wait now; the one after it is twice as long, up to the cap.
+    fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(Self::MAX);
+        delay
+    }
+
+    /// Starts over from the initial delay after a successful connection.
+    fn reset(&mut self) {
+        self.next = Self::INITIAL;
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            next: Self::INITIAL,
         }
+    }
+}
 
-        // Backoff before reconnect
-        sleep(Duration::from_secs(1)).await;
+/// Decodes a binary frame as JSON. Some OverlayPlugin transports send the same payload as a
+/// text frame would carry, either as raw UTF-8 or gzip/zlib-compressed; the compression is
+/// recognised by its magic bytes.
+fn decode_binary_frame(bytes: &[u8]) -> Result<Value> {
+    let text = match bytes {
+        [0x1f, 0x8b, ..] => inflate(GzDecoder::new(bytes)).context("invalid gzip payload")?,
+        [0x78, 0x01 | 0x5e | 0x9c | 0xda, ..] => {
+            inflate(ZlibDecoder::new(bytes)).context("invalid zlib payload")?
+        }
+        _ => String::from_utf8(bytes.to_vec()).context("payload is not UTF-8")?,
+    };
+    serde_json::from_str(&text).context("payload is not JSON")
+}
+
+fn inflate(decoder: impl Read) -> Result<String> {
+    let mut text = String::new();
+    decoder.take(MAX_INFLATED + 1).read_to_string(&mut text)?;
+    if text.len() as u64 > MAX_INFLATED {
+        bail!("payload inflates past {} MiB", MAX_INFLATED / (1024 * 1024));
     }
+    Ok(text)
 }
 
 fn log_close_frame(frame: Option<&CloseFrame<'_>>) {
@@ -121,3 +531,158 @@ fn log_close_frame(frame: Option<&CloseFrame<'_>>) {
         info!("websocket closed without frame");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn subscribe_override_replaces_default_and_falls_back_when_invalid() {
+        assert_eq!(
+            resolve_subscribe_messages(None),
+            default_subscribe_messages()
+        );
+
+        let custom = json!([
+            { "call": "subscribe", "events": ["CombatData"] },
+            "{\"call\":\"getLanguage\"}"
+        ]);
+        assert_eq!(
+            resolve_subscribe_messages(Some(&custom)),
+            vec![
+                r#"{"call":"subscribe","events":["CombatData"]}"#.to_string(),
+                r#"{"call":"getLanguage"}"#.to_string(),
+            ]
+        );
+
+        for invalid in [
+            json!([]),
+            json!({ "call": "subscribe" }),
+            json!(["not json", 3]),
+        ] {
+            assert_eq!(
+                resolve_subscribe_messages(Some(&invalid)),
+                default_subscribe_messages()
+            );
+        }
+    }
+
+    #[test]
+    fn binary_frames_decode_like_text_frames() {
+        use std::io::Write;
+
+        use flate2::write::{GzEncoder, ZlibEncoder};
+        use flate2::Compression;
+
+        let payload = r#"{"type":"LogLine","line":["01","2024-05-01","3D","The Aurum Vale"]}"#;
+        let expected: Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(decode_binary_frame(payload.as_bytes()).unwrap(), expected);
+        assert_eq!(
+            parse_zone_change(&decode_binary_frame(payload.as_bytes()).unwrap()).as_deref(),
+            Some("The Aurum Vale")
+        );
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(payload.as_bytes()).unwrap();
+        assert_eq!(
+            decode_binary_frame(&gzip.finish().unwrap()).unwrap(),
+            expected
+        );
+
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(payload.as_bytes()).unwrap();
+        assert_eq!(
+            decode_binary_frame(&zlib.finish().unwrap()).unwrap(),
+            expected
+        );
+
+        assert!(decode_binary_frame(&[0xff, 0xfe, 0x00]).is_err());
+        assert!(decode_binary_frame(&[0x1f, 0x8b, 0x00]).is_err());
+        assert!(decode_binary_frame(b"not json").is_err());
+
+        let mut bomb = GzEncoder::new(Vec::new(), Compression::best());
+        let zeros = vec![b' '; 1024 * 1024];
+        for _ in 0..=MAX_INFLATED / zeros.len() as u64 {
+            bomb.write_all(&zeros).unwrap();
+        }
+        let err = decode_binary_frame(&bomb.finish().unwrap()).unwrap_err();
+        assert!(format!("{err:#}").contains("inflates past"), "{err:#}");
+    }
+
+    #[test]
+    fn validate_url_requires_a_websocket_scheme_and_host() {
+        assert!(validate_url(WS_URL_DEFAULT).is_ok());
+        assert!(validate_url("ws://192.168.1.50:10501/ws").is_ok());
+        assert!(validate_url("wss://iinact.example/ws").is_ok());
+        for invalid in [
+            "",
+            "127.0.0.1:10501",
+            "http://127.0.0.1:10501/ws",
+            "ws:///ws",
+            "ws://bad host/",
+        ] {
+            assert!(validate_url(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_to_the_cap_and_resets() {
+        let mut backoff = Backoff::default();
+        let delays: Vec<u64> = (0..7).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 30, 30]);
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn certificate_errors_wrapped_in_io_errors_count_as_tls_failures() {
+        use std::io;
+
+        let bad_cert = rustls::Error::InvalidCertificate(rustls::CertificateError::UnknownIssuer);
+        let err = WsError::Io(io::Error::new(io::ErrorKind::InvalidData, bad_cert));
+        let message = tls_failure(&err).expect("tls failure");
+        assert!(message.contains("certificate"), "{message}");
+
+        let refused = WsError::Io(io::Error::from(io::ErrorKind::ConnectionRefused));
+        assert!(tls_failure(&refused).is_none());
+        assert!(tls_failure(&WsError::ConnectionClosed).is_none());
+    }
+
+    #[test]
+    fn tls_connector_builds_and_can_skip_certificate_checks() {
+        assert!(matches!(tls_connector(false), Ok(Connector::Rustls(_))));
+        assert!(matches!(tls_connector(true), Ok(Connector::Rustls(_))));
+
+        let verifier = AcceptAnyCert(Arc::new(rustls::crypto::ring::default_provider()));
+        let name = ServerName::try_from("iinact.lan").unwrap();
+        let self_signed = CertificateDer::from(vec![0x30, 0x03, 0x02, 0x01, 0x01]);
+        assert!(verifier
+            .verify_server_cert(&self_signed, &[], &name, &[], UnixTime::now())
+            .is_ok());
+    }
+
+    #[test]
+    fn url_precedence_is_cli_config_last_good_default() {
+        let cli = Some("ws://cli:1/ws");
+        let cfg = Some("ws://config:2/ws");
+        let last = Some("ws://last:3/ws");
+        assert_eq!(
+            resolve_url(cli, cfg, last),
+            ("ws://cli:1/ws".to_string(), UrlSource::Cli)
+        );
+        assert_eq!(
+            resolve_url(None, cfg, last),
+            ("ws://config:2/ws".to_string(), UrlSource::Config)
+        );
+        assert_eq!(
+            resolve_url(None, Some("  "), last),
+            ("ws://last:3/ws".to_string(), UrlSource::LastGood)
+        );
+        assert_eq!(
+            resolve_url(None, None, None),
+            (WS_URL_DEFAULT.to_string(), UrlSource::Default)
+        );
+    }
+}